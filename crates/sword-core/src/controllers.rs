@@ -14,6 +14,7 @@ pub enum Controller {
     Web,
     SocketIo,
     Grpc,
+    WebSocket,
 }
 
 /// A trait for defining controllers in the application.
@@ -0,0 +1,12 @@
+use crate::{Build, DependencyInjectionError, Interceptor, State};
+use sword_layers::csrf::CsrfProtection;
+
+/// `CsrfProtection` is built from `[web.csrf]` and stored by the web router
+/// before controllers are mounted, so building it resolves that instance.
+impl Build for CsrfProtection {
+    fn build(state: &State) -> Result<Self, DependencyInjectionError> {
+        state.get::<Self>()
+    }
+}
+
+impl Interceptor for CsrfProtection {}
@@ -0,0 +1,56 @@
+use crate::{
+    Build, ConfigRegistrar, DependencyInjectionError, Interceptor, InterceptorRegistrar, State,
+    inventory_submit, sword_error,
+};
+use sword_layers::{
+    DisplayConfig,
+    jwt::{AuthConfig, JwtAuth},
+};
+
+/// `JwtAuth` is built once from `[auth.jwt]` by `Interceptor::register`,
+/// so building it resolves the registered instance.
+impl Build for JwtAuth {
+    fn build(state: &State) -> Result<Self, DependencyInjectionError> {
+        state.get::<Self>()
+    }
+}
+
+impl Interceptor for JwtAuth {
+    fn register(state: &State) {
+        if state.get::<Self>().is_ok() {
+            return;
+        }
+
+        let Some(config) = state.get::<AuthConfig>().ok().and_then(|auth| auth.jwt) else {
+            return;
+        };
+
+        config.display();
+
+        let auth = JwtAuth::new(&config).unwrap_or_else(|err| {
+            sword_error! {
+                title: "Failed to load JWT verification keys",
+                reason: err,
+                context: {
+                    "algorithms" => format!("{:?}", config.algorithms),
+                    "source" => "JwtAuth::register",
+                },
+                hints: [
+                    "Set `auth.jwt.secret` for HS* algorithms",
+                    "Set `auth.jwt.public-key` or `auth.jwt.jwks` for RS*, PS*, ES* and EdDSA algorithms",
+                ],
+            }
+        });
+
+        state.insert(auth);
+    }
+}
+
+inventory_submit! {[
+    ConfigRegistrar::new(|state, config| {
+        state.insert(config.get_or_default::<AuthConfig>());
+    }),
+    InterceptorRegistrar {
+        register: <JwtAuth as Interceptor>::register,
+    }
+]}
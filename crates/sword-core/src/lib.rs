@@ -1,9 +1,15 @@
 mod application;
 mod config;
 mod controllers;
+#[cfg(feature = "csrf")]
+mod csrf;
 mod injectables;
 mod interceptor;
+#[cfg(feature = "jwt")]
+mod jwt;
 mod module;
+#[cfg(feature = "rate-limit")]
+mod rate_limit;
 mod shutdown;
 mod state;
 
@@ -21,6 +27,8 @@ pub use controllers::*;
 pub use injectables::*;
 pub use interceptor::*;
 pub use module::*;
+#[cfg(feature = "rate-limit")]
+pub use rate_limit::*;
 pub use shutdown::*;
 pub use state::*;
 
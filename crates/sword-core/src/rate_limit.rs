@@ -0,0 +1,136 @@
+use crate::{
+    Build, ConfigRegistrar, DependencyInjectionError, Interceptor, InterceptorRegistrar, Provider,
+    State, inventory_submit, sword_error,
+};
+use std::sync::Arc;
+use sword_layers::{
+    DisplayConfig,
+    rate_limit::{
+        MemoryRateLimitStore, RateLimitConfig, RateLimitKeyExtractor, RateLimitStore, RateLimiter,
+    },
+};
+
+/// Replaces the in-memory rate limit store, e.g. with one shared between instances.
+///
+/// ### Example
+/// ```rust,ignore
+/// Application::builder()
+///     .with_provider(RateLimitStoreProvider::new(RedisRateLimitStore::new(client)))
+///     .with_module::<ApiModule>()
+///     .build();
+/// ```
+#[derive(Clone)]
+pub struct RateLimitStoreProvider(Arc<dyn RateLimitStore>);
+
+impl RateLimitStoreProvider {
+    pub fn new(store: impl RateLimitStore) -> Self {
+        Self(Arc::new(store))
+    }
+}
+
+impl Provider for RateLimitStoreProvider {}
+
+/// Registers a [`RateLimitKeyExtractor`] used by policies with `key = "custom:<name>"`.
+pub struct RateLimitKeyRegistrar {
+    pub name: &'static str,
+    pub extractor: &'static dyn RateLimitKeyExtractor,
+}
+
+impl RateLimitKeyRegistrar {
+    pub const fn new(name: &'static str, extractor: &'static dyn RateLimitKeyExtractor) -> Self {
+        Self { name, extractor }
+    }
+}
+
+inventory::collect!(RateLimitKeyRegistrar);
+
+/// Registers a [`RateLimitKeyExtractor`] under the name used by
+/// `key = "custom:<name>"` in `[rate-limit]` policies.
+///
+/// ```rust,ignore
+/// register_rate_limit_key!("tenant", TenantKey);
+/// ```
+#[macro_export]
+macro_rules! register_rate_limit_key {
+    ($name:literal, $extractor:expr) => {
+        const _: () = {
+            ::sword::internal::inventory::submit! {
+                ::sword::internal::core::RateLimitKeyRegistrar::new($name, &$extractor)
+            }
+        };
+    };
+}
+
+/// `RateLimiter` is built once from `[rate-limit]` by `Interceptor::register`,
+/// so building it resolves the registered instance.
+impl Build for RateLimiter {
+    fn build(state: &State) -> Result<Self, DependencyInjectionError> {
+        state.get::<Self>()
+    }
+}
+
+impl Interceptor for RateLimiter {
+    fn register(state: &State) {
+        let Ok(config) = state.get::<RateLimitConfig>() else {
+            return;
+        };
+
+        config.display();
+
+        if !config.enabled {
+            return;
+        }
+
+        let store = match state.get::<RateLimitStoreProvider>() {
+            Ok(provider) => provider.0,
+            Err(_) => Arc::new(MemoryRateLimitStore::new()),
+        };
+
+        let limiter = inventory::iter::<RateLimitKeyRegistrar>()
+            .fold(RateLimiter::new(&config, store), |limiter, key| {
+                limiter.with_extractor(key.name, key.extractor)
+            });
+
+        #[cfg(feature = "jwt")]
+        let limiter = with_jwt(state, limiter);
+
+        limiter.validate().unwrap_or_else(|err| {
+            sword_error! {
+                title: "Failed to initialize rate limiting",
+                reason: err,
+                context: {
+                    "source" => "RateLimiter::register",
+                },
+                hints: [
+                    "Register custom keys with `register_rate_limit_key!`",
+                    "The \"subject\" key requires the `jwt` feature and `[auth.jwt]`",
+                ],
+            }
+        });
+
+        state.insert(limiter);
+    }
+}
+
+/// Lets "subject" keys verify bearer tokens with `[auth.jwt]`.
+#[cfg(feature = "jwt")]
+fn with_jwt(state: &State, limiter: RateLimiter) -> RateLimiter {
+    use sword_layers::jwt::JwtAuth;
+
+    // Interceptors register in no particular order.
+    <JwtAuth as Interceptor>::register(state);
+
+    match state.get::<JwtAuth>() {
+        Ok(auth) => limiter.with_jwt(auth),
+        Err(_) => limiter,
+    }
+}
+
+inventory_submit! {[
+    ConfigRegistrar::new(|state, config| {
+        state.insert(config.get_or_default::<RateLimitConfig>());
+    }),
+    InterceptorRegistrar {
+        register: <RateLimiter as Interceptor>::register,
+    }
+]}
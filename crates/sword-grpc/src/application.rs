@@ -3,19 +3,66 @@ use crate::controller::GrpcControllerRegistrar;
 use crate::registry::GrpcServiceRegistry;
 
 use std::collections::HashMap;
+use std::fmt;
 use std::net::SocketAddr;
 use sword_core::{
     ApplicationConfig, Controller, ControllerMap, EngineBuildContext, State, shutdown_signal,
     sword_error,
 };
 
-use sword_layers::{DisplayConfig, body_limit::GrpcBodyLimitValue};
+use sword_layers::{DisplayConfig, body_limit::GrpcBodyLimitValue, tracing::DispatchLayer};
+use tracing::Dispatch;
+
+#[cfg(feature = "testing")]
+use crate::testing::InProcessIncoming;
+
+#[cfg(feature = "tls")]
+use crate::tls::{ALPN_PROTOCOLS, TlsIncoming, insert_connect_info};
+
+#[cfg(feature = "tls")]
+use sword_layers::tls::{TlsAcceptor, TlsListener};
+
+#[cfg(feature = "metrics")]
+use sword_layers::metrics::{GrpcMetricsLayer, MetricsConfig, with_metrics_route};
+
+#[cfg(feature = "opentelemetry")]
+use sword_layers::opentelemetry::{GrpcTraceLayer, OpenTelemetryConfig, Telemetry};
+
+enum GrpcListener {
+    Tcp(SocketAddr),
+
+    #[cfg(feature = "tls")]
+    Tls(TlsListener),
+
+    #[cfg(feature = "testing")]
+    InProcess(InProcessIncoming),
+}
+
+impl fmt::Display for GrpcListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "{addr}"),
+
+            #[cfg(feature = "tls")]
+            Self::Tls(listener) => write!(f, "{}", listener.local_addr()),
+
+            #[cfg(feature = "testing")]
+            Self::InProcess(_) => f.write_str("in-process"),
+        }
+    }
+}
 
 pub struct GrpcApplication {
     pub state: State,
     pub config: GrpcApplicationConfig,
     pub graceful_shutdown: bool,
     pub controllers: ControllerMap,
+
+    #[cfg(feature = "metrics")]
+    pub metrics_config: MetricsConfig,
+
+    #[cfg(feature = "opentelemetry")]
+    pub opentelemetry_config: OpenTelemetryConfig,
 }
 
 impl GrpcApplication {
@@ -29,6 +76,86 @@ impl GrpcApplication {
             "Starting gRPC application listener"
         );
 
+        let bind_addr: SocketAddr = bind.parse::<SocketAddr>().unwrap_or_else(|err| {
+            sword_error! {
+                title: "Invalid gRPC bind address",
+                reason: err,
+                context: {
+                    "bind" => bind,
+                    "source" => "GrpcApplication::start",
+                },
+                hints: ["Ensure host and port values are valid"],
+            }
+        });
+
+        let listener = self.listener(bind_addr).await;
+
+        self.serve(listener, None).await;
+    }
+
+    #[cfg(not(feature = "tls"))]
+    async fn listener(&self, bind_addr: SocketAddr) -> GrpcListener {
+        GrpcListener::Tcp(bind_addr)
+    }
+
+    /// Binds a TLS listener when `[grpc.tls]` is enabled.
+    #[cfg(feature = "tls")]
+    async fn listener(&self, bind_addr: SocketAddr) -> GrpcListener {
+        let config = &self.config.tls;
+
+        config.display();
+
+        if !config.enabled {
+            return GrpcListener::Tcp(bind_addr);
+        }
+
+        let listener = tokio::net::TcpListener::bind(bind_addr)
+            .await
+            .unwrap_or_else(|err| {
+                sword_error! {
+                    title: "Failed to bind gRPC listener",
+                    reason: err,
+                    context: {
+                        "host" => self.config.host.clone(),
+                        "port" => self.config.port.to_string(),
+                    },
+                    hints: ["Ensure the host/port is available and not already in use"],
+                }
+            });
+
+        let listener = TlsAcceptor::new(config, ALPN_PROTOCOLS)
+            .and_then(|acceptor| {
+                acceptor.watch();
+                acceptor.bind(listener)
+            })
+            .unwrap_or_else(|err| {
+                sword_error! {
+                    title: "Failed to initialize the TLS listener",
+                    reason: err,
+                    context: {
+                        "cert" => config.cert.clone(),
+                        "key" => config.key.clone(),
+                        "source" => "GrpcApplication::listener",
+                    },
+                    hints: ["Ensure `grpc.tls.cert` and `grpc.tls.key` point to readable PEM files"],
+                }
+            });
+
+        GrpcListener::Tls(listener)
+    }
+
+    /// Serve the registered controllers over in-memory connections.
+    ///
+    /// Every request is handled with `dispatch` as the default tracing subscriber.
+    #[cfg(feature = "testing")]
+    pub async fn serve_in_process(&self, incoming: InProcessIncoming, dispatch: Dispatch) {
+        self.serve(GrpcListener::InProcess(incoming), Some(dispatch))
+            .await;
+    }
+
+    async fn serve(&self, listener: GrpcListener, dispatch: Option<Dispatch>) {
+        let bind = listener.to_string();
+
         let grpc_ids = self
             .controllers
             .get(&Controller::Grpc)
@@ -39,24 +166,12 @@ impl GrpcApplication {
                     reason: "At least one gRPC controller must be registered before starting the server",
                     context: {
                         "bind" => bind,
-                        "source" => "GrpcApplication::start",
+                        "source" => "GrpcApplication::serve",
                     },
                     hints: ["Register a controller with `controllers.register::<MyGrpcController>()` in your module"],
                 }
             });
 
-        let bind_addr: SocketAddr = bind.parse::<SocketAddr>().unwrap_or_else(|err| {
-            sword_error! {
-                title: "Invalid gRPC bind address",
-                reason: err,
-                context: {
-                    "bind" => bind,
-                    "source" => "GrpcApplication::start",
-                },
-                hints: ["Ensure host and port values are valid"],
-            }
-        });
-
         let mut registrars: HashMap<_, _> = HashMap::new();
 
         for registrar in inventory::iter::<GrpcControllerRegistrar>() {
@@ -81,7 +196,7 @@ impl GrpcApplication {
                     reason: "No GrpcControllerRegistrar entry was found for controller",
                     context: {
                         "controller_id" => format!("{controller_id:?}"),
-                        "source" => "GrpcApplication::start",
+                        "source" => "GrpcApplication::serve",
                     },
                     hints: ["This usually indicates a controller macro expansion issue"],
                 }
@@ -102,7 +217,7 @@ impl GrpcApplication {
                 reason: "Controllers were discovered but no tonic services were added to routes",
                 context: {
                     "controllers_count" => grpc_ids.len().to_string(),
-                    "source" => "GrpcApplication::start",
+                    "source" => "GrpcApplication::serve",
                 },
                 hints: ["Implement generated register hooks to add tonic services into GrpcServiceRegistry"],
             }
@@ -111,6 +226,18 @@ impl GrpcApplication {
         let service_names: Vec<&'static str> = grpc_registry.service_names().collect();
         let routes = grpc_registry.into_routes();
 
+        #[cfg(feature = "metrics")]
+        let routes = if self.metrics_config.enabled {
+            self.metrics_config.display();
+
+            tonic::service::Routes::from(with_metrics_route(
+                routes.into_axum_router(),
+                &self.metrics_config,
+            ))
+        } else {
+            routes
+        };
+
         let (health_reporter, health_service) = tonic_health::server::health_reporter();
 
         health_reporter
@@ -138,15 +265,37 @@ impl GrpcApplication {
                     title: "Failed to build tonic reflection service",
                     reason: err,
                     context: {
-                        "source" => "GrpcApplication::start",
+                        "source" => "GrpcApplication::serve",
                     },
                     hints: ["Ensure build.rs generates `sword_descriptor_set.bin` when reflection support is enabled"],
                 }
             }))
         };
 
-        let server = tonic::transport::Server::builder().add_routes(routes);
-        let router = server.add_service(health_service);
+        let server = tonic::transport::Server::builder();
+
+        #[cfg(feature = "metrics")]
+        let server =
+            server
+                .accept_http1(self.metrics_config.enabled)
+                .layer(tower::util::option_layer(
+                    self.metrics_config.enabled.then_some(GrpcMetricsLayer),
+                ));
+
+        #[cfg(feature = "opentelemetry")]
+        let server = server.layer(tower::util::option_layer(
+            self.init_telemetry().map(GrpcTraceLayer::new),
+        ));
+
+        let server = server.layer(tower::util::option_layer(dispatch.map(DispatchLayer::new)));
+
+        #[cfg(feature = "tls")]
+        let server = server.layer(tower::util::MapRequestLayer::new(
+            insert_connect_info as fn(_) -> _,
+        ));
+
+        let mut server = server;
+        let router = server.add_routes(routes).add_service(health_service);
 
         #[cfg(feature = "reflection")]
         let router = if let Some(reflection_service) = reflection_service {
@@ -155,6 +304,51 @@ impl GrpcApplication {
             router
         };
 
+        let bind_addr = match listener {
+            GrpcListener::Tcp(bind_addr) => bind_addr,
+
+            #[cfg(feature = "tls")]
+            GrpcListener::Tls(listener) => {
+                let incoming = TlsIncoming::new(listener);
+
+                let result = if self.graceful_shutdown {
+                    router
+                        .serve_with_incoming_shutdown(incoming, shutdown_signal())
+                        .await
+                } else {
+                    router.serve_with_incoming(incoming).await
+                };
+
+                return result.unwrap_or_else(|err| {
+                    sword_error! {
+                        title: "gRPC server stopped with an internal error",
+                        reason: err,
+                        context: {
+                            "mode" => "tls",
+                            "host" => self.config.host.clone(),
+                            "port" => self.config.port.to_string(),
+                        },
+                    }
+                });
+            }
+
+            #[cfg(feature = "testing")]
+            GrpcListener::InProcess(incoming) => {
+                return router
+                    .serve_with_incoming(incoming)
+                    .await
+                    .unwrap_or_else(|err| {
+                        sword_error! {
+                            title: "gRPC server stopped with an internal error",
+                            reason: err,
+                            context: {
+                                "mode" => "in_process",
+                            },
+                        }
+                    });
+            }
+        };
+
         if self.graceful_shutdown {
             router
                 .serve_with_shutdown(bind_addr, shutdown_signal())
@@ -188,6 +382,36 @@ impl GrpcApplication {
     }
 }
 
+#[cfg(feature = "opentelemetry")]
+impl GrpcApplication {
+    /// Build the OTLP exporter and store the shared `Telemetry` handle in state.
+    fn init_telemetry(&self) -> Option<Telemetry> {
+        let config = &self.opentelemetry_config;
+
+        config.display();
+
+        if !config.enabled {
+            return None;
+        }
+
+        let telemetry = Telemetry::from_config(config).unwrap_or_else(|err| {
+            sword_error! {
+                title: "Failed to initialize OpenTelemetry exporter",
+                reason: err,
+                context: {
+                    "endpoint" => config.endpoint.clone(),
+                    "source" => "GrpcApplication::init_telemetry",
+                },
+                hints: ["Ensure the `opentelemetry.endpoint` value is a valid URI"],
+            }
+        });
+
+        self.state.insert(telemetry.clone());
+
+        Some(telemetry)
+    }
+}
+
 impl From<EngineBuildContext> for GrpcApplication {
     fn from(ctx: EngineBuildContext) -> Self {
         let EngineBuildContext {
@@ -205,6 +429,10 @@ impl From<EngineBuildContext> for GrpcApplication {
             config: grpc_config,
             graceful_shutdown: app_config.graceful_shutdown,
             controllers: controllers.snapshot(),
+            #[cfg(feature = "metrics")]
+            metrics_config: config.get_or_default::<MetricsConfig>(),
+            #[cfg(feature = "opentelemetry")]
+            opentelemetry_config: config.get_or_default::<OpenTelemetryConfig>(),
         }
     }
 }
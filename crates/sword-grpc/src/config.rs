@@ -13,6 +13,11 @@ pub struct GrpcApplicationConfig {
     /// Message size limits for gRPC requests/responses.
     #[serde(rename = "body-limit")]
     pub body_limit: GrpcBodyLimitConfig,
+
+    /// TLS termination for the listener, with optional client certificates.
+    #[cfg(feature = "tls")]
+    #[serde(default)]
+    pub tls: sword_layers::tls::TlsConfig,
 }
 
 impl Default for GrpcApplicationConfig {
@@ -21,6 +26,8 @@ impl Default for GrpcApplicationConfig {
             host: "0.0.0.0".to_string(),
             port: 50051,
             body_limit: GrpcBodyLimitConfig::default(),
+            #[cfg(feature = "tls")]
+            tls: Default::default(),
         }
     }
 }
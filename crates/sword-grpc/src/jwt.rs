@@ -0,0 +1,24 @@
+//! gRPC interceptor implementation of the built-in [`JwtAuth`] interceptor.
+
+use crate::interceptor::{GrpcInterceptorResult, OnRequest};
+
+use sword_layers::jwt::JwtAuth;
+use tonic::{Request, Status};
+
+impl OnRequest for JwtAuth {
+    async fn on_request(&self, mut req: Request<()>) -> GrpcInterceptorResult {
+        let authorization = req
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok());
+
+        let claims = self.verify_authorization(authorization).map_err(|err| {
+            tracing::debug!(target: "sword.grpc.jwt", error = ?err, "Rejected bearer token");
+            Status::unauthenticated(err.to_string())
+        })?;
+
+        req.extensions_mut().insert(claims);
+
+        Ok(req)
+    }
+}
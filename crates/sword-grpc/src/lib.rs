@@ -5,6 +5,18 @@ pub mod interceptor;
 pub mod registry;
 pub mod response;
 
+#[cfg(feature = "rate-limit")]
+mod rate_limit;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "jwt")]
+mod jwt;
+
+#[cfg(feature = "tls")]
+mod tls;
+
 pub mod prelude {
     pub use crate::config::GrpcApplicationConfig;
     pub use crate::controller::{GrpcResult, GrpcStream};
@@ -13,6 +25,22 @@ pub mod prelude {
     pub use tonic::{
         Code, Extensions, Request, Response, Status, Streaming, async_trait, include_proto,
     };
+
+    #[cfg(feature = "tls")]
+    pub use sword_layers::tls::PeerIdentity;
+
+    #[cfg(feature = "jwt")]
+    pub use sword_layers::jwt::{JwtAuth, JwtClaims};
+
+    #[cfg(feature = "rate-limit")]
+    pub use sword_core::{RateLimitStoreProvider, register_rate_limit_key};
+
+    #[cfg(feature = "rate-limit")]
+    pub use sword_layers::rate_limit::{
+        MemoryRateLimitStore, RateLimitDecision, RateLimitError, RateLimitFuture,
+        RateLimitKeyExtractor, RateLimitPolicy, RateLimitRequest, RateLimitState, RateLimitStore,
+        RateLimiter,
+    };
 }
 
 #[doc(hidden)]
@@ -0,0 +1,60 @@
+//! gRPC interceptor implementations of the built-in [`RateLimiter`].
+
+use crate::interceptor::{GrpcInterceptorResult, OnRequest, OnRequestWithConfig};
+
+use sword_layers::rate_limit::{DEFAULT_POLICY, RateLimitError, RateLimitRequest, RateLimiter};
+use tonic::{Request, Status, metadata::MetadataValue};
+
+/// Counts calls against the default policy.
+impl OnRequest for RateLimiter {
+    async fn on_request(&self, req: Request<()>) -> GrpcInterceptorResult {
+        limit(self, DEFAULT_POLICY, req).await
+    }
+}
+
+/// Counts calls against the policy named by `config`.
+impl OnRequestWithConfig<&'static str> for RateLimiter {
+    async fn on_request(&self, policy: &'static str, req: Request<()>) -> GrpcInterceptorResult {
+        limit(self, policy, req).await
+    }
+}
+
+/// Rejects calls over the limit with `ResourceExhausted`, carrying the
+/// `ratelimit-*` and `retry-after` entries as trailing metadata.
+async fn limit(limiter: &RateLimiter, policy: &str, req: Request<()>) -> GrpcInterceptorResult {
+    let headers = req.metadata().clone().into_headers();
+    let ip = req.remote_addr().map(|addr| addr.ip());
+    let request = RateLimitRequest::new(&headers, req.extensions(), ip);
+
+    let decision = match limiter.check(policy, &request).await {
+        Ok(decision) => decision,
+        Err(RateLimitError::Config(reason)) => {
+            tracing::error!(target: "sword.grpc.rate_limit", policy, reason, "Invalid rate limit policy");
+            return Err(Status::internal("Invalid rate limit policy"));
+        }
+        Err(err) => {
+            tracing::warn!(
+                target: "sword.grpc.rate_limit",
+                policy,
+                error = %err,
+                "Rate limit check failed, letting the call through"
+            );
+
+            return Ok(req);
+        }
+    };
+
+    if decision.allowed {
+        return Ok(req);
+    }
+
+    let mut status = Status::resource_exhausted("Too many requests");
+
+    for (name, value) in decision.headers() {
+        if let Ok(value) = MetadataValue::try_from(value) {
+            status.metadata_mut().insert(name, value);
+        }
+    }
+
+    Err(status)
+}
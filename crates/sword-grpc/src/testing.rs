@@ -0,0 +1,39 @@
+//! In-memory transport for exercising gRPC controllers without binding a port.
+
+use hyper_util::rt::TokioIo;
+use std::io;
+use tokio::io::DuplexStream;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tonic::transport::{Channel, Endpoint, Uri};
+
+const DUPLEX_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Server side of an in-process connection pair, consumed by
+/// [`GrpcApplication::serve_in_process`](crate::application::GrpcApplication::serve_in_process).
+pub type InProcessIncoming = UnboundedReceiverStream<io::Result<DuplexStream>>;
+
+/// Creates a lazily connected tonic `Channel` together with the stream of server
+/// connections it opens. Every connection the channel makes is an in-memory duplex pipe.
+pub fn in_process_channel() -> (Channel, InProcessIncoming) {
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    let connector = tower::service_fn(move |_: Uri| {
+        let tx = tx.clone();
+
+        async move {
+            let (client, server) = tokio::io::duplex(DUPLEX_BUFFER_SIZE);
+
+            tx.send(Ok(server)).map_err(|_| {
+                io::Error::new(io::ErrorKind::BrokenPipe, "in-process server is gone")
+            })?;
+
+            Ok::<_, io::Error>(TokioIo::new(client))
+        }
+    });
+
+    let channel =
+        Endpoint::from_static("http://in-process.sword").connect_with_connector_lazy(connector);
+
+    (channel, UnboundedReceiverStream::new(rx))
+}
@@ -0,0 +1,133 @@
+//! TLS transport for the gRPC server, built on the shared `sword_layers::tls` acceptor.
+
+use std::{
+    io,
+    net::SocketAddr,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use sword_layers::tls::{PeerIdentity, TlsListener};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::TcpStream,
+};
+use tokio_rustls::server::TlsStream;
+use tokio_stream::Stream;
+use tonic::{
+    body::Body,
+    codegen::http::Request,
+    transport::server::{Connected, TcpConnectInfo},
+};
+
+/// ALPN protocols advertised by the gRPC TLS listener.
+pub(crate) const ALPN_PROTOCOLS: &[&[u8]] = &[b"h2"];
+
+/// Established TLS connections, as consumed by tonic's `serve_with_incoming`.
+pub(crate) struct TlsIncoming {
+    listener: TlsListener,
+}
+
+impl TlsIncoming {
+    pub(crate) fn new(listener: TlsListener) -> Self {
+        Self { listener }
+    }
+}
+
+impl Stream for TlsIncoming {
+    type Item = io::Result<GrpcTlsStream>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let local_addr = self.listener.local_addr();
+
+        self.get_mut().listener.poll_accept(cx).map(|accepted| {
+            accepted.map(|(stream, remote_addr)| {
+                Ok(GrpcTlsStream {
+                    stream: Box::new(stream),
+                    local_addr,
+                    remote_addr,
+                })
+            })
+        })
+    }
+}
+
+pub(crate) struct GrpcTlsStream {
+    stream: Box<TlsStream<TcpStream>>,
+    local_addr: SocketAddr,
+    remote_addr: SocketAddr,
+}
+
+/// Connection info that tonic inserts into each request of a TLS connection.
+#[derive(Clone)]
+pub(crate) struct TlsConnectInfo {
+    tcp: TcpConnectInfo,
+    peer_identity: Option<PeerIdentity>,
+}
+
+impl Connected for GrpcTlsStream {
+    type ConnectInfo = TlsConnectInfo;
+
+    fn connect_info(&self) -> Self::ConnectInfo {
+        TlsConnectInfo {
+            tcp: TcpConnectInfo {
+                local_addr: Some(self.local_addr),
+                remote_addr: Some(self.remote_addr),
+            },
+            peer_identity: PeerIdentity::from_stream(&self.stream),
+        }
+    }
+}
+
+/// Splits [`TlsConnectInfo`] into the extensions interceptors and handlers read:
+/// `TcpConnectInfo`, so `Request::remote_addr` keeps working, and `PeerIdentity`.
+pub(crate) fn insert_connect_info(mut req: Request<Body>) -> Request<Body> {
+    if let Some(info) = req.extensions_mut().remove::<TlsConnectInfo>() {
+        req.extensions_mut().insert(info.tcp);
+
+        if let Some(identity) = info.peer_identity {
+            req.extensions_mut().insert(identity);
+        }
+    }
+
+    req
+}
+
+impl AsyncRead for GrpcTlsStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for GrpcTlsStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().stream).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().stream).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.stream.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_shutdown(cx)
+    }
+}
@@ -30,13 +30,12 @@ impl<'de> Deserialize<'de> for BodyLimitConfig {
     }
 }
 
-use crate::{MapResponseLayer, ResponseFnMapper, ServiceLayer};
+use crate::{LayerError, MapResponseLayer, ResponseFnMapper, ServiceLayer};
 
 use axum::{
     body::Body,
     response::{IntoResponse, Response},
 };
-use axum_responses::JsonResponse;
 use tower::ServiceBuilder;
 use tower_http::limit::RequestBodyLimitLayer;
 
@@ -44,18 +43,21 @@ pub struct BodyLimitLayer;
 
 impl BodyLimitLayer {
     pub fn new(config: &BodyLimitConfig) -> ServiceLayer<MapResponseLayer, RequestBodyLimitLayer> {
+        Self::with_max_size(config.max_size.parsed)
+    }
+
+    /// Same as [`BodyLimitLayer::new`], with a limit in bytes instead of a config.
+    pub fn with_max_size(max_size: usize) -> ServiceLayer<MapResponseLayer, RequestBodyLimitLayer> {
         fn map_body_limit_response(r: Response<Body>) -> Response<Body> {
             if r.status().as_u16() != 413 {
                 return r;
             }
 
-            JsonResponse::PayloadTooLarge()
-                .message("The request body exceeds the maximum allowed size by the server")
-                .into_response()
+            LayerError::BodyTooLarge.into_response()
         }
 
         ServiceBuilder::new()
-            .layer(RequestBodyLimitLayer::new(config.max_size.parsed))
+            .layer(RequestBodyLimitLayer::new(max_size))
             .map_response(map_body_limit_response as ResponseFnMapper)
     }
 }
@@ -0,0 +1,452 @@
+//! Cross-site request forgery protection.
+//!
+//! Every request gets a [`CsrfToken`], which handlers embed in forms or hand
+//! to scripts. Requests with an unsafe method must send it back in a header or
+//! a form field. The expected token is kept in a cookie the client echoes
+//! (double-submit mode) or in the server-side session (synchronizer mode).
+
+use crate::DisplayConfig;
+
+use axum::{
+    body::{Body, to_bytes},
+    extract::OriginalUri,
+    http::{HeaderMap, Method, Request, header},
+    response::{IntoResponse, Response},
+};
+use axum_responses::JsonResponse;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use thiserror::Error;
+use tower::{Layer, Service};
+use tower_cookies::{Cookie, Cookies, cookie::SameSite};
+
+/// Session key of the token in synchronizer mode.
+#[cfg(feature = "sessions")]
+const SESSION_KEY: &str = "_csrf";
+
+/// Configuration of the `[web.csrf]` key.
+///
+/// ```toml
+/// [web.csrf]
+/// mode = "synchronizer"
+/// exempt-routes = ["/webhooks"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsrfConfig {
+    /// Where the expected token is kept: "double-submit" (default) or "synchronizer".
+    pub mode: CsrfMode,
+
+    /// Whether every route is protected. When false, only routes with
+    /// `#[interceptor(CsrfProtection)]` are. Defaults to true.
+    pub global: bool,
+
+    /// Methods that don't require a token. Defaults to GET, HEAD, OPTIONS and TRACE.
+    #[serde(rename = "safe-methods")]
+    pub safe_methods: Vec<String>,
+
+    /// Path prefixes that don't require a token, e.g. webhooks called by other servers.
+    #[serde(rename = "exempt-routes")]
+    pub exempt_routes: Vec<String>,
+
+    /// Header the token is read from. Defaults to "X-CSRF-Token".
+    pub header: String,
+
+    /// Field of `application/x-www-form-urlencoded` bodies the token is read
+    /// from when the header is absent. Defaults to "_csrf".
+    #[serde(rename = "form-field")]
+    pub form_field: String,
+
+    /// Name of the double-submit cookie. Defaults to "sword.csrf".
+    #[serde(rename = "cookie-name")]
+    pub cookie_name: String,
+
+    /// Whether the double-submit cookie is only sent over HTTPS. Defaults to true.
+    pub secure: bool,
+
+    /// Whether to display the configuration details. Defaults to false.
+    pub display: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CsrfMode {
+    /// The token is stored in a cookie readable by scripts, and requests must
+    /// repeat it in the header or form field.
+    #[default]
+    DoubleSubmit,
+
+    /// The token is stored in the server-side session. Requires `[web.session]`.
+    Synchronizer,
+}
+
+impl Default for CsrfConfig {
+    fn default() -> Self {
+        Self {
+            mode: CsrfMode::DoubleSubmit,
+            global: true,
+            safe_methods: ["GET", "HEAD", "OPTIONS", "TRACE"]
+                .map(String::from)
+                .to_vec(),
+            exempt_routes: Vec::new(),
+            header: "X-CSRF-Token".to_string(),
+            form_field: "_csrf".to_string(),
+            cookie_name: "sword.csrf".to_string(),
+            secure: true,
+            display: false,
+        }
+    }
+}
+
+impl DisplayConfig for CsrfConfig {
+    fn display(&self) {
+        if !self.display {
+            return;
+        }
+
+        tracing::info!(
+            target: "sword.layers.csrf",
+            mode = ?self.mode,
+            global = self.global,
+            safe_methods = ?self.safe_methods,
+            exempt_routes = ?self.exempt_routes,
+            header = self.header,
+            form_field = self.form_field,
+        );
+    }
+}
+
+/// The CSRF token of the current request, inserted as a request extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrfToken(Arc<str>);
+
+impl CsrfToken {
+    fn generate() -> Self {
+        let token = format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+
+        Self(token.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for CsrfToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CsrfError {
+    #[error("Missing CSRF token")]
+    Missing,
+
+    #[error("Invalid CSRF token")]
+    Mismatch,
+
+    #[error("The CSRF token can't be read: {0}")]
+    Unavailable(&'static str),
+
+    #[error("Invalid CSRF configuration: {0}")]
+    Config(String),
+}
+
+impl From<CsrfError> for JsonResponse {
+    fn from(error: CsrfError) -> Self {
+        match error {
+            CsrfError::Missing | CsrfError::Mismatch => {
+                tracing::debug!(target: "sword.layers.csrf", error = %error, "Rejected request");
+                JsonResponse::Forbidden().message(error.to_string())
+            }
+            CsrfError::Unavailable(_) | CsrfError::Config(_) => {
+                tracing::error!(target: "sword.layers.csrf", error = %error, "CSRF error");
+                JsonResponse::InternalServerError().message("Failed to verify the CSRF token")
+            }
+        }
+    }
+}
+
+/// Checks the CSRF token of requests with the settings of a [`CsrfConfig`].
+///
+/// Used by `CsrfLayer` on every route when `global` is enabled, and as the
+/// `#[interceptor(CsrfProtection)]` of routes protected one by one.
+#[derive(Clone)]
+pub struct CsrfProtection {
+    inner: Arc<CsrfSettings>,
+}
+
+struct CsrfSettings {
+    mode: CsrfMode,
+    global: bool,
+    safe_methods: Vec<Method>,
+    exempt_routes: Vec<String>,
+    header: String,
+    form_field: String,
+    cookie_name: String,
+    secure: bool,
+}
+
+impl CsrfProtection {
+    pub fn new(config: &CsrfConfig) -> Result<Self, CsrfError> {
+        #[cfg(not(feature = "sessions"))]
+        if config.mode == CsrfMode::Synchronizer {
+            return Err(CsrfError::Config(
+                "the \"synchronizer\" mode requires the `sessions` feature".to_string(),
+            ));
+        }
+
+        let safe_methods = config
+            .safe_methods
+            .iter()
+            .map(|method| {
+                Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                    .map_err(|_| CsrfError::Config(format!("invalid safe method '{method}'")))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let settings = CsrfSettings {
+            mode: config.mode,
+            global: config.global,
+            safe_methods,
+            exempt_routes: config
+                .exempt_routes
+                .iter()
+                .map(|route| route.trim_end_matches('/').to_string())
+                .collect(),
+            header: config.header.clone(),
+            form_field: config.form_field.clone(),
+            cookie_name: config.cookie_name.clone(),
+            secure: config.secure,
+        };
+
+        Ok(Self {
+            inner: Arc::new(settings),
+        })
+    }
+
+    /// Whether a request with `method` to `path` must carry the token.
+    pub fn requires_token(&self, method: &Method, path: &str) -> bool {
+        let settings = &self.inner;
+
+        !settings.safe_methods.contains(method)
+            && !settings.exempt_routes.iter().any(|route| {
+                path.strip_prefix(route.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+    }
+
+    /// Compares the token sent in the header, or in the form field of a
+    /// url-encoded `body`, with the expected one.
+    pub fn verify(
+        &self,
+        expected: Option<&CsrfToken>,
+        headers: &HeaderMap,
+        body: Option<&[u8]>,
+    ) -> Result<(), CsrfError> {
+        let expected = expected.ok_or(CsrfError::Unavailable(
+            "no `CsrfToken` extension, is `[web.csrf]` configured?",
+        ))?;
+
+        let from_header = headers
+            .get(self.inner.header.as_str())
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        let submitted = from_header.or_else(|| {
+            let body = body.filter(|_| is_form(headers))?;
+
+            form_urlencoded::parse(body)
+                .find(|(key, _)| key == self.inner.form_field.as_str())
+                .map(|(_, value)| value.into_owned())
+        });
+
+        match submitted {
+            None => Err(CsrfError::Missing),
+            Some(token) if constant_time_eq(token.as_bytes(), expected.as_str().as_bytes()) => {
+                Ok(())
+            }
+            Some(_) => Err(CsrfError::Mismatch),
+        }
+    }
+
+    /// The token of the request, creating and storing one on its first visit.
+    fn issue<B>(&self, req: &Request<B>) -> Result<CsrfToken, CsrfError> {
+        match self.inner.mode {
+            CsrfMode::DoubleSubmit => {
+                let cookies = req
+                    .extensions()
+                    .get::<Cookies>()
+                    .ok_or(CsrfError::Unavailable(
+                        "`CookieManagerLayer` is not applied",
+                    ))?;
+
+                if let Some(cookie) = cookies.get(&self.inner.cookie_name)
+                    && !cookie.value().is_empty()
+                {
+                    return Ok(CsrfToken(cookie.value().into()));
+                }
+
+                let token = CsrfToken::generate();
+                let mut cookie = Cookie::new(self.inner.cookie_name.clone(), token.to_string());
+
+                // Readable by scripts, which send it back in the header.
+                cookie.set_path("/");
+                cookie.set_http_only(false);
+                cookie.set_secure(self.inner.secure);
+                cookie.set_same_site(SameSite::Strict);
+
+                cookies.add(cookie);
+
+                Ok(token)
+            }
+
+            #[cfg(feature = "sessions")]
+            CsrfMode::Synchronizer => {
+                let session = req.extensions().get::<crate::sessions::Session>().ok_or(
+                    CsrfError::Unavailable("the \"synchronizer\" mode requires `[web.session]`"),
+                )?;
+
+                if let Ok(Some(token)) = session.get::<String>(SESSION_KEY) {
+                    return Ok(CsrfToken(token.into()));
+                }
+
+                let token = CsrfToken::generate();
+
+                session
+                    .insert(SESSION_KEY, token.as_str())
+                    .map_err(|_| CsrfError::Unavailable("the session can't be written"))?;
+
+                Ok(token)
+            }
+
+            #[cfg(not(feature = "sessions"))]
+            CsrfMode::Synchronizer => Err(CsrfError::Unavailable(
+                "the \"synchronizer\" mode requires the `sessions` feature",
+            )),
+        }
+    }
+}
+
+/// Issues the [`CsrfToken`] of each request and, when `global` is enabled,
+/// rejects unsafe requests without a valid one with a `403 Forbidden`.
+///
+/// Must be applied inside `CookieManagerLayer`, and inside `SessionLayer` in
+/// synchronizer mode. Form fields are read from bodies within the limit
+/// applied by outer layers.
+#[derive(Clone)]
+pub struct CsrfLayer {
+    protection: CsrfProtection,
+}
+
+impl CsrfLayer {
+    pub fn new(protection: CsrfProtection) -> Self {
+        Self { protection }
+    }
+}
+
+impl<S> Layer<S> for CsrfLayer {
+    type Service = CsrfService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CsrfService {
+            inner,
+            protection: self.protection.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CsrfService<S> {
+    inner: S,
+    protection: CsrfProtection,
+}
+
+type BoxFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'static>>;
+
+impl<S> Service<Request<Body>> for CsrfService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let protection = self.protection.clone();
+
+        Box::pin(async move {
+            let token = match protection.issue(&req) {
+                Ok(token) => token,
+                Err(err) => return Ok(JsonResponse::from(err).into_response()),
+            };
+
+            req.extensions_mut().insert(token.clone());
+
+            let path = req
+                .extensions()
+                .get::<OriginalUri>()
+                .map_or_else(|| req.uri().path(), |uri| uri.path())
+                .to_string();
+
+            if !protection.inner.global || !protection.requires_token(req.method(), &path) {
+                return inner.call(req).await;
+            }
+
+            let (parts, body) = req.into_parts();
+
+            // The body is only buffered when the token may be in a form field.
+            let buffer = !parts.headers.contains_key(protection.inner.header.as_str())
+                && is_form(&parts.headers);
+
+            let (body, bytes) = match buffer {
+                false => (body, None),
+                true => {
+                    match to_bytes(body, usize::MAX).await {
+                        Ok(bytes) => (Body::from(bytes.clone()), Some(bytes)),
+                        Err(_) => {
+                            return Ok(JsonResponse::PayloadTooLarge()
+                            .message("The request body exceeds the maximum allowed size by the server")
+                            .into_response());
+                        }
+                    }
+                }
+            };
+
+            if let Err(err) = protection.verify(Some(&token), &parts.headers, bytes.as_deref()) {
+                return Ok(JsonResponse::from(err).into_response());
+            }
+
+            inner.call(Request::from_parts(parts, body)).await
+        })
+    }
+}
+
+fn is_form(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/x-www-form-urlencoded"))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
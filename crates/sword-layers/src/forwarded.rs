@@ -0,0 +1,326 @@
+//! Trusted-proxy aware client address resolution.
+//!
+//! This module resolves the client IP, scheme and host of each request from the
+//! socket peer address and either the RFC 7239 `Forwarded` header or the
+//! `X-Forwarded-*` headers, whichever family the proxies are configured to set.
+//! Forwarding headers are only honoured for hops added by a trusted proxy, so a
+//! client connecting directly can't spoof its address.
+
+use crate::DisplayConfig;
+
+use axum::{
+    extract::{ConnectInfo, connect_info::MockConnectInfo},
+    http::{HeaderMap, Request, Uri, header},
+};
+use ipnet::IpNet;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower::{Layer, Service};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrustedProxiesConfig {
+    /// CIDR ranges of the proxies allowed to set forwarding headers
+    /// (e.g. `["10.0.0.0/8", "127.0.0.1"]`). A bare address is a single host.
+    #[serde(deserialize_with = "deserialize_cidrs")]
+    pub cidrs: Vec<IpNet>,
+
+    /// Header family set by the trusted proxies. The other family is ignored,
+    /// since a proxy that doesn't set it passes client-sent values through.
+    /// Defaults to `x-forwarded`.
+    pub header: ForwardedHeader,
+
+    /// Whether to display the configuration details.
+    pub display: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ForwardedHeader {
+    /// The RFC 7239 `Forwarded` header.
+    Forwarded,
+
+    /// `X-Forwarded-For`, `X-Forwarded-Proto` and `X-Forwarded-Host`.
+    #[default]
+    XForwarded,
+}
+
+impl TrustedProxiesConfig {
+    pub fn is_trusted(&self, addr: IpAddr) -> bool {
+        let addr = addr.to_canonical();
+        self.cidrs.iter().any(|cidr| cidr.contains(&addr))
+    }
+}
+
+impl DisplayConfig for TrustedProxiesConfig {
+    fn display(&self) {
+        if !self.display {
+            return;
+        }
+
+        tracing::info!(
+            target: "sword.layers.trusted-proxies",
+            cidrs = ?self.cidrs.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            header = ?self.header,
+        );
+    }
+}
+
+fn deserialize_cidrs<'de, D>(deserializer: D) -> Result<Vec<IpNet>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .into_iter()
+        .map(|value| {
+            value
+                .parse::<IpNet>()
+                .or_else(|_| value.parse::<IpAddr>().map(IpNet::from))
+                .map_err(|_| serde::de::Error::custom(format!("invalid CIDR range '{value}'")))
+        })
+        .collect()
+}
+
+/// The resolved origin of a request, inserted as a request extension by [`ClientInfoLayer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientInfo {
+    /// Address of the socket peer. `None` when the server doesn't provide `ConnectInfo`.
+    pub peer: Option<SocketAddr>,
+
+    /// Address of the client. `None` when it is unknown or obfuscated by a trusted proxy.
+    pub ip: Option<IpAddr>,
+
+    /// Addresses from the client to the socket peer, client first. Only hops
+    /// reported by trusted proxies are included.
+    pub ips: Vec<IpAddr>,
+
+    /// Scheme used by the client, `http` unless a trusted proxy reports otherwise.
+    pub protocol: String,
+
+    /// Host requested by the client.
+    pub host: Option<String>,
+}
+
+/// A single forwarding hop, as reported by the proxy that received it.
+#[derive(Debug, Default)]
+struct Hop {
+    /// `None` for `unknown`, obfuscated or malformed identifiers.
+    client: Option<IpAddr>,
+    proto: Option<String>,
+    host: Option<String>,
+}
+
+impl ClientInfo {
+    /// Resolves the client of a request given its peer address, URI and headers.
+    ///
+    /// Hops are walked from the nearest one: each hop is accepted while the
+    /// address that reported it is a trusted proxy. Only the header family
+    /// selected by [`TrustedProxiesConfig::header`] is read.
+    pub fn resolve(
+        peer: Option<SocketAddr>,
+        uri: &Uri,
+        headers: &HeaderMap,
+        config: &TrustedProxiesConfig,
+    ) -> Self {
+        let host = header_str(headers, header::HOST.as_str())
+            .or_else(|| uri.authority().map(|authority| authority.as_str()));
+
+        let mut info = Self {
+            peer,
+            ip: peer.map(|addr| addr.ip().to_canonical()),
+            ips: peer
+                .map(|addr| addr.ip().to_canonical())
+                .into_iter()
+                .collect(),
+            protocol: uri.scheme_str().unwrap_or("http").to_string(),
+            host: host.map(ToString::to_string),
+        };
+
+        let Some(mut reporter) = info.ip else {
+            return info;
+        };
+
+        let hops = match config.header {
+            ForwardedHeader::Forwarded => forwarded_hops(headers),
+            ForwardedHeader::XForwarded => x_forwarded_hops(headers),
+        };
+
+        for hop in hops.into_iter().rev() {
+            if !config.is_trusted(reporter) {
+                break;
+            }
+
+            if let Some(proto) = hop.proto {
+                info.protocol = proto.to_ascii_lowercase();
+            }
+
+            if let Some(host) = hop.host {
+                info.host = Some(host);
+            }
+
+            info.ip = hop.client;
+
+            let Some(client) = hop.client else {
+                break;
+            };
+
+            info.ips.insert(0, client);
+            reporter = client;
+        }
+
+        info
+    }
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn header_list<'a>(headers: &'a HeaderMap, name: &str) -> Vec<&'a str> {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect()
+}
+
+/// Parses the RFC 7239 `Forwarded` header. Each element is appended by one
+/// proxy, so its `proto` and `host` belong to the same hop as its `for`.
+fn forwarded_hops(headers: &HeaderMap) -> Vec<Hop> {
+    header_list(headers, header::FORWARDED.as_str())
+        .into_iter()
+        .map(|element| {
+            let mut hop = Hop::default();
+
+            for pair in element.split(';') {
+                let Some((key, value)) = pair.split_once('=') else {
+                    continue;
+                };
+
+                let value = value.trim().trim_matches('"');
+
+                match key.trim().to_ascii_lowercase().as_str() {
+                    "for" => hop.client = parse_node(value),
+                    "proto" => hop.proto = Some(value.to_string()),
+                    "host" => hop.host = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+
+            hop
+        })
+        .collect()
+}
+
+/// Builds hops from `X-Forwarded-For`.
+///
+/// Proxies usually overwrite `X-Forwarded-Proto` and `X-Forwarded-Host` rather
+/// than append to them, so their entries can't be matched to hops. Only the
+/// last entry is used, and only for the nearest hop: any earlier entry may
+/// have been sent by the client.
+fn x_forwarded_hops(headers: &HeaderMap) -> Vec<Hop> {
+    let mut hops = header_list(headers, "x-forwarded-for")
+        .into_iter()
+        .map(|client| Hop {
+            client: parse_node(client),
+            ..Hop::default()
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(hop) = hops.last_mut() {
+        let last = |name| header_list(headers, name).pop().map(ToString::to_string);
+
+        hop.proto = last("x-forwarded-proto");
+        hop.host = last("x-forwarded-host");
+    }
+
+    hops
+}
+
+/// Parses a node identifier: `192.0.2.1`, `192.0.2.1:8080`, `[2001:db8::1]:8080`
+/// or `2001:db8::1`. `unknown` and obfuscated identifiers (`_hidden`) yield `None`.
+fn parse_node(value: &str) -> Option<IpAddr> {
+    let value = value.trim();
+
+    if let Some(rest) = value.strip_prefix('[') {
+        return rest.split_once(']')?.0.parse::<IpAddr>().ok();
+    }
+
+    value
+        .parse::<IpAddr>()
+        .or_else(|_| value.parse::<SocketAddr>().map(|addr| addr.ip()))
+        .ok()
+        .map(|addr| addr.to_canonical())
+}
+
+/// Resolves [`ClientInfo`] for every request and stores it as a request extension.
+///
+/// The peer address is read from `ConnectInfo<SocketAddr>` (or `MockConnectInfo`
+/// in tests). Without it, forwarding headers are never honoured.
+#[derive(Clone)]
+pub struct ClientInfoLayer {
+    config: Arc<TrustedProxiesConfig>,
+}
+
+impl ClientInfoLayer {
+    pub fn new(config: &TrustedProxiesConfig) -> Self {
+        Self {
+            config: Arc::new(config.clone()),
+        }
+    }
+}
+
+impl<S> Layer<S> for ClientInfoLayer {
+    type Service = ClientInfoService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ClientInfoService {
+            inner,
+            config: Arc::clone(&self.config),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ClientInfoService<S> {
+    inner: S,
+    config: Arc<TrustedProxiesConfig>,
+}
+
+impl<S, B> Service<Request<B>> for ClientInfoService<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<B>) -> Self::Future {
+        let extensions = req.extensions();
+
+        let peer = extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|info| info.0)
+            .or_else(|| {
+                extensions
+                    .get::<MockConnectInfo<SocketAddr>>()
+                    .map(|info| info.0)
+            });
+
+        let info = ClientInfo::resolve(peer, req.uri(), req.headers(), &self.config);
+
+        req.extensions_mut().insert(info);
+
+        self.inner.call(req)
+    }
+}
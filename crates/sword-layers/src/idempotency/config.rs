@@ -0,0 +1,68 @@
+use crate::DisplayConfig;
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thisconfig::{ByteConfig, TimeConfig};
+
+/// Configuration of the `[web.idempotency]` key.
+///
+/// ```toml
+/// [web.idempotency]
+/// ttl = "24h"
+/// methods = ["POST", "PATCH"]
+/// max-body-size = "1MB"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdempotencyConfig {
+    /// Header carrying the key. Defaults to "Idempotency-Key".
+    pub header: String,
+
+    /// Methods whose requests honour the header. Defaults to POST and PATCH.
+    pub methods: Vec<String>,
+
+    /// How long a key is remembered after its first request. Defaults to "24h".
+    pub ttl: TimeConfig,
+
+    /// Largest request or response body kept for a key. Larger requests are
+    /// rejected, and larger responses are not stored. Defaults to "1MB".
+    #[serde(rename = "max-body-size")]
+    pub max_body_size: ByteConfig,
+
+    /// Whether to display the configuration details. Defaults to false.
+    pub display: bool,
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            header: "Idempotency-Key".to_string(),
+            methods: vec!["POST".to_string(), "PATCH".to_string()],
+            ttl: TimeConfig {
+                parsed: Duration::from_secs(24 * 60 * 60),
+                raw: "24h".to_string(),
+            },
+            max_body_size: ByteConfig {
+                parsed: 1_000_000,
+                raw: "1MB".to_string(),
+            },
+            display: false,
+        }
+    }
+}
+
+impl DisplayConfig for IdempotencyConfig {
+    fn display(&self) {
+        if !self.display {
+            return;
+        }
+
+        tracing::info!(
+            target: "sword.layers.idempotency",
+            header = self.header,
+            methods = ?self.methods,
+            ttl = self.ttl.raw,
+            max_body_size = self.max_body_size.raw,
+        );
+    }
+}
@@ -0,0 +1,391 @@
+use super::{
+    IdempotencyConfig, IdempotencyError, IdempotencyRecord, IdempotencyStore, StoredResponse,
+};
+
+use axum::{
+    body::{Body, HttpBody, to_bytes},
+    http::{HeaderName, HeaderValue, Request, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use axum_responses::JsonResponse;
+use sha2::{Digest, Sha256};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, SystemTime},
+};
+use tower::{Layer, Service};
+
+#[cfg(feature = "jwt")]
+use crate::jwt::{JwtAuth, JwtClaims};
+
+#[cfg(feature = "sessions")]
+use crate::sessions::Session;
+
+type BoxFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'static>>;
+
+/// Longest accepted idempotency key.
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Runs requests with an idempotency key once, replaying their response for
+/// repeats.
+///
+/// Keys are scoped to the identity of the client, so a response is never
+/// replayed to another one: the subject of its bearer token, its session id,
+/// or else its `Authorization` and `Cookie` headers. Server errors and responses larger than
+/// `max-body-size` are not kept, which lets the request be retried.
+#[derive(Clone)]
+pub struct IdempotencyLayer {
+    inner: Arc<IdempotencyManager>,
+}
+
+#[derive(Clone)]
+struct IdempotencyManager {
+    store: Arc<dyn IdempotencyStore>,
+    header: HeaderName,
+    methods: Vec<String>,
+    ttl: Duration,
+    max_body_size: usize,
+
+    #[cfg(feature = "jwt")]
+    jwt: Option<JwtAuth>,
+}
+
+impl IdempotencyLayer {
+    /// Creates the layer with the settings of `config`, keeping records in `store`.
+    pub fn new(
+        config: &IdempotencyConfig,
+        store: Arc<dyn IdempotencyStore>,
+    ) -> Result<Self, IdempotencyError> {
+        let header = HeaderName::try_from(config.header.as_str()).map_err(|_| {
+            IdempotencyError::Config(format!(
+                "`header` is not a valid header name: {}",
+                config.header
+            ))
+        })?;
+
+        let manager = IdempotencyManager {
+            store,
+            header,
+            methods: config.methods.clone(),
+            ttl: config.ttl.parsed,
+            max_body_size: config.max_body_size.parsed,
+
+            #[cfg(feature = "jwt")]
+            jwt: None,
+        };
+
+        Ok(Self {
+            inner: Arc::new(manager),
+        })
+    }
+
+    /// Verifies bearer tokens to scope keys by subject when no earlier layer
+    /// has inserted the `JwtClaims`.
+    #[cfg(feature = "jwt")]
+    pub fn with_jwt(mut self, auth: JwtAuth) -> Self {
+        Arc::make_mut(&mut self.inner).jwt = Some(auth);
+        self
+    }
+}
+
+impl<S> Layer<S> for IdempotencyLayer {
+    type Service = IdempotencyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IdempotencyService {
+            inner,
+            manager: Arc::clone(&self.inner),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct IdempotencyService<S> {
+    inner: S,
+    manager: Arc<IdempotencyManager>,
+}
+
+impl<S> Service<Request<Body>> for IdempotencyService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let manager = Arc::clone(&self.manager);
+
+        Box::pin(async move {
+            let applies = manager
+                .methods
+                .iter()
+                .any(|method| method.eq_ignore_ascii_case(req.method().as_str()));
+
+            let Some(key) = req.headers().get(&manager.header).filter(|_| applies) else {
+                return inner.call(req).await;
+            };
+
+            let Some(key) = key
+                .to_str()
+                .ok()
+                .filter(|key| !key.is_empty() && key.len() <= MAX_KEY_LENGTH)
+            else {
+                return Ok(JsonResponse::BadRequest()
+                    .message("Invalid idempotency key")
+                    .into_response());
+            };
+
+            let key = digest(&[key.as_bytes(), &manager.scope(&req)]);
+
+            let (parts, body) = req.into_parts();
+
+            let Ok(body) = to_bytes(body, manager.max_body_size).await else {
+                return Ok(JsonResponse::PayloadTooLarge()
+                    .message("The request body exceeds the maximum size for idempotent requests")
+                    .into_response());
+            };
+
+            let path = parts.uri.path_and_query().map_or("", |path| path.as_str());
+            let value = |name| {
+                parts
+                    .headers
+                    .get(name)
+                    .map_or(&[][..], HeaderValue::as_bytes)
+            };
+
+            let mut record = IdempotencyRecord {
+                fingerprint: digest(&[
+                    parts.method.as_str().as_bytes(),
+                    path.as_bytes(),
+                    value(header::ACCEPT),
+                    value(header::CONTENT_TYPE),
+                    &body,
+                ]),
+                response: None,
+                expires_at: SystemTime::now() + manager.ttl,
+            };
+
+            let req = Request::from_parts(parts, Body::from(body));
+
+            match manager.store.begin(&key, &record).await {
+                Ok(None) => {}
+                Ok(Some(existing)) => return Ok(replay(existing, &record.fingerprint)),
+                Err(err) => {
+                    tracing::warn!(
+                        target: "sword.layers.idempotency",
+                        error = %err,
+                        "Idempotency check failed, running the request"
+                    );
+
+                    return inner.call(req).await;
+                }
+            }
+
+            // Releases the key when the request is dropped before completing.
+            let mut guard = ReleaseGuard {
+                store: Arc::clone(&manager.store),
+                key: Some(key),
+            };
+
+            let response = inner.call(req).await?;
+            let key = guard.key.take().unwrap_or_default();
+
+            match manager.stored(response).await {
+                Ok((stored, response)) => {
+                    record.response = Some(stored);
+                    manager.complete(&key, &record).await;
+
+                    Ok(response)
+                }
+                Err(response) => {
+                    manager.release(&key).await;
+                    Ok(response)
+                }
+            }
+        })
+    }
+}
+
+impl IdempotencyManager {
+    /// The identity of the client keys are scoped to.
+    fn scope(&self, req: &Request<Body>) -> Vec<u8> {
+        if let Some(subject) = self.subject(req) {
+            return format!("sub:{subject}").into_bytes();
+        }
+
+        #[cfg(feature = "sessions")]
+        if let Some(id) = req.extensions().get::<Session>().and_then(Session::id) {
+            return format!("session:{id}").into_bytes();
+        }
+
+        let header = |name| {
+            req.headers()
+                .get(name)
+                .map_or(&[][..], HeaderValue::as_bytes)
+        };
+
+        [
+            b"credentials:",
+            header(header::AUTHORIZATION),
+            b"\n",
+            header(header::COOKIE),
+        ]
+        .concat()
+    }
+
+    #[cfg(feature = "jwt")]
+    fn subject(&self, req: &Request<Body>) -> Option<String> {
+        if let Some(claims) = req.extensions().get::<JwtClaims>() {
+            return claims.subject().map(str::to_string);
+        }
+
+        let authorization = req.headers().get(header::AUTHORIZATION)?.to_str().ok();
+        let claims = self
+            .jwt
+            .as_ref()?
+            .verify_authorization(authorization)
+            .ok()?;
+
+        claims.subject().map(str::to_string)
+    }
+
+    #[cfg(not(feature = "jwt"))]
+    fn subject(&self, _: &Request<Body>) -> Option<String> {
+        None
+    }
+
+    /// Buffers a response to keep it, or gives it back when it can't be kept.
+    async fn stored(&self, response: Response) -> Result<(StoredResponse, Response), Response> {
+        let fits = response
+            .body()
+            .size_hint()
+            .exact()
+            .is_some_and(|size| size <= self.max_body_size as u64);
+
+        if response.status().is_server_error() || !fits {
+            return Err(response);
+        }
+
+        let (parts, body) = response.into_parts();
+
+        let Ok(body) = to_bytes(body, self.max_body_size).await else {
+            return Err(JsonResponse::InternalServerError()
+                .message("Failed to read the response body")
+                .into_response());
+        };
+
+        let stored = StoredResponse {
+            status: parts.status.as_u16(),
+            headers: parts
+                .headers
+                .iter()
+                .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.into())))
+                .collect(),
+            body: body.to_vec(),
+        };
+
+        Ok((stored, Response::from_parts(parts, Body::from(body))))
+    }
+
+    async fn complete(&self, key: &str, record: &IdempotencyRecord) {
+        if let Err(err) = self.store.complete(key, record).await {
+            tracing::warn!(
+                target: "sword.layers.idempotency",
+                error = %err,
+                "Failed to store the idempotent response"
+            );
+
+            self.release(key).await;
+        }
+    }
+
+    async fn release(&self, key: &str) {
+        if let Err(err) = self.store.release(key).await {
+            tracing::warn!(
+                target: "sword.layers.idempotency",
+                error = %err,
+                "Failed to release the idempotency key"
+            );
+        }
+    }
+}
+
+/// The response to a repeat of a known key.
+fn replay(existing: IdempotencyRecord, fingerprint: &str) -> Response {
+    if existing.fingerprint != fingerprint {
+        return JsonResponse::UnprocessableEntity()
+            .message("The idempotency key was used for a different request")
+            .into_response();
+    }
+
+    let Some(stored) = existing.response else {
+        return JsonResponse::Conflict()
+            .message("A request with this idempotency key is in progress")
+            .into_response();
+    };
+
+    let mut response = Response::new(Body::from(stored.body));
+
+    *response.status_mut() = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+
+    let headers = response.headers_mut();
+
+    for (name, value) in stored.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+            headers.append(name, value);
+        }
+    }
+
+    headers.insert(
+        HeaderName::from_static("idempotent-replayed"),
+        HeaderValue::from_static("true"),
+    );
+
+    response
+}
+
+/// Hex SHA-256 of length-prefixed parts.
+fn digest(parts: &[&[u8]]) -> String {
+    let hash = parts
+        .iter()
+        .fold(Sha256::new(), |hasher, part| {
+            hasher
+                .chain_update((part.len() as u64).to_be_bytes())
+                .chain_update(part)
+        })
+        .finalize();
+
+    hash.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+struct ReleaseGuard {
+    store: Arc<dyn IdempotencyStore>,
+    key: Option<String>,
+}
+
+impl Drop for ReleaseGuard {
+    fn drop(&mut self) {
+        let Some(key) = self.key.take() else {
+            return;
+        };
+
+        let store = Arc::clone(&self.store);
+
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                let _ = store.release(&key).await;
+            });
+        }
+    }
+}
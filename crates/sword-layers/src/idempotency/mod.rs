@@ -0,0 +1,15 @@
+//! Idempotent retries of unsafe requests.
+//!
+//! Requests carrying an `Idempotency-Key` header are run once: the first
+//! response is kept in an [`IdempotencyStore`] and replayed for repeats of
+//! the same request until the key expires. A key reused for a different
+//! request is rejected, and so is a repeat sent while the first request is
+//! still running.
+
+mod config;
+mod layer;
+mod store;
+
+pub use config::*;
+pub use layer::*;
+pub use store::*;
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    error::Error as StdError,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+use thiserror::Error;
+
+pub type IdempotencyFuture<'a, T> =
+    Pin<Box<dyn Future<Output = Result<T, IdempotencyError>> + Send + 'a>>;
+
+/// Backend that keeps the requests and responses of idempotency keys.
+///
+/// Implement it to share keys between instances, and register the store
+/// with `IdempotencyStoreProvider` to use it instead of the in-memory one.
+///
+/// ### Example
+/// ```rust,ignore
+/// impl IdempotencyStore for RedisStore {
+///     fn begin<'a>(
+///         &'a self,
+///         key: &'a str,
+///         record: &'a IdempotencyRecord,
+///     ) -> IdempotencyFuture<'a, Option<IdempotencyRecord>> {
+///         Box::pin(async move { self.set_json_nx(key, record, record.expires_at).await })
+///     }
+///     // ...
+/// }
+/// ```
+pub trait IdempotencyStore: Send + Sync + 'static {
+    /// Saves `record` unless an unexpired record exists for `key`, which is
+    /// returned instead. Must be atomic, so only one request starts per key.
+    fn begin<'a>(
+        &'a self,
+        key: &'a str,
+        record: &'a IdempotencyRecord,
+    ) -> IdempotencyFuture<'a, Option<IdempotencyRecord>>;
+
+    /// Replaces the record of `key` with its completed version.
+    fn complete<'a>(
+        &'a self,
+        key: &'a str,
+        record: &'a IdempotencyRecord,
+    ) -> IdempotencyFuture<'a, ()>;
+
+    /// Deletes the record of `key`, so the request can be retried. Deleting
+    /// an unknown key is not an error.
+    fn release<'a>(&'a self, key: &'a str) -> IdempotencyFuture<'a, ()>;
+}
+
+#[derive(Debug, Error)]
+pub enum IdempotencyError {
+    #[error("Idempotency store failed: {0}")]
+    Store(Box<dyn StdError + Send + Sync>),
+
+    #[error("{0}")]
+    Config(String),
+}
+
+impl IdempotencyError {
+    /// Wraps an error of a custom store.
+    pub fn store(err: impl Into<Box<dyn StdError + Send + Sync>>) -> Self {
+        Self::Store(err.into())
+    }
+}
+
+/// The request seen for a key and, once it completed, its response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdempotencyRecord {
+    /// Digest of the method, path, query and body of the request.
+    pub fingerprint: String,
+
+    /// The response to replay. `None` while the request is running.
+    pub response: Option<StoredResponse>,
+
+    /// When the key is forgotten.
+    pub expires_at: SystemTime,
+}
+
+impl IdempotencyRecord {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= SystemTime::now()
+    }
+}
+
+/// A response kept for replay.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+/// Interval between sweeps of expired keys in [`MemoryIdempotencyStore`].
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Keeps records in process memory. Keys are not shared between instances.
+#[derive(Clone)]
+pub struct MemoryIdempotencyStore {
+    inner: Arc<Mutex<MemoryRecords>>,
+}
+
+struct MemoryRecords {
+    records: HashMap<String, IdempotencyRecord>,
+    pruned_at: SystemTime,
+}
+
+impl MemoryIdempotencyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Default for MemoryIdempotencyStore {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(MemoryRecords {
+                records: HashMap::new(),
+                pruned_at: SystemTime::now(),
+            })),
+        }
+    }
+}
+
+impl IdempotencyStore for MemoryIdempotencyStore {
+    fn begin<'a>(
+        &'a self,
+        key: &'a str,
+        record: &'a IdempotencyRecord,
+    ) -> IdempotencyFuture<'a, Option<IdempotencyRecord>> {
+        Box::pin(async move {
+            let now = SystemTime::now();
+            let mut inner = self.inner.lock().unwrap_or_else(|err| err.into_inner());
+
+            if now
+                .duration_since(inner.pruned_at)
+                .is_ok_and(|elapsed| elapsed >= PRUNE_INTERVAL)
+            {
+                inner.records.retain(|_, record| !record.is_expired());
+                inner.pruned_at = now;
+            }
+
+            match inner.records.get(key) {
+                Some(existing) if !existing.is_expired() => Ok(Some(existing.clone())),
+                _ => {
+                    inner.records.insert(key.to_string(), record.clone());
+                    Ok(None)
+                }
+            }
+        })
+    }
+
+    fn complete<'a>(
+        &'a self,
+        key: &'a str,
+        record: &'a IdempotencyRecord,
+    ) -> IdempotencyFuture<'a, ()> {
+        Box::pin(async move {
+            let mut inner = self.inner.lock().unwrap_or_else(|err| err.into_inner());
+            inner.records.insert(key.to_string(), record.clone());
+
+            Ok(())
+        })
+    }
+
+    fn release<'a>(&'a self, key: &'a str) -> IdempotencyFuture<'a, ()> {
+        Box::pin(async move {
+            let mut inner = self.inner.lock().unwrap_or_else(|err| err.into_inner());
+            inner.records.remove(key);
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,432 @@
+//! JSON Web Token authentication.
+//!
+//! This module loads the verification keys of the `[auth.jwt]` configuration
+//! (an HMAC secret, a PEM public key or a JWKS file), verifies bearer tokens
+//! against them and exposes the verified payload as [`JwtClaims`].
+//!
+//! The web, gRPC and Socket.IO crates implement their interceptor traits for
+//! [`JwtAuth`], so the same verifier protects every kind of controller.
+
+use crate::DisplayConfig;
+
+use jsonwebtoken::{
+    Algorithm, DecodingKey, Validation, decode, decode_header,
+    errors::{Error as JsonWebTokenError, ErrorKind},
+    jwk::JwkSet,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use std::{io, sync::Arc, time::Duration};
+use thisconfig::{ConfigItem, TimeConfig};
+use thiserror::Error;
+
+/// Configuration of the `[auth]` key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// Bearer token authentication. `JwtAuth` is only available when the
+    /// `[auth.jwt]` section is present.
+    pub jwt: Option<JwtConfig>,
+}
+
+impl ConfigItem for AuthConfig {
+    fn key() -> &'static str {
+        "auth"
+    }
+}
+
+/// Configuration of the `[auth.jwt]` key.
+///
+/// ```toml
+/// [auth.jwt]
+/// algorithms = ["RS256"]
+/// jwks = "keys/jwks.json"
+/// issuer = "https://auth.example.com"
+/// audience = ["orders-api"]
+/// leeway = "30s"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JwtConfig {
+    /// Accepted signing algorithms. Defaults to ["HS256"].
+    pub algorithms: Vec<Algorithm>,
+
+    /// Shared secret of the HMAC algorithms (HS256, HS384, HS512).
+    pub secret: Option<String>,
+
+    /// Path to the PEM public key of the RSA, ECDSA or EdDSA algorithms.
+    #[serde(rename = "public-key")]
+    pub public_key: Option<String>,
+
+    /// Path to a JWKS file. Keys are selected by the `kid` of the token header.
+    pub jwks: Option<String>,
+
+    /// Accepted `iss` values. When set, tokens must carry one of them.
+    #[serde(deserialize_with = "one_or_many")]
+    pub issuer: Vec<String>,
+
+    /// Accepted `aud` values. When set, tokens must carry one of them.
+    #[serde(deserialize_with = "one_or_many")]
+    pub audience: Vec<String>,
+
+    /// Clock skew tolerated when checking `exp` and `nbf`. Defaults to "60s".
+    pub leeway: TimeConfig,
+
+    /// Whether to display the configuration details. Defaults to false.
+    pub display: bool,
+}
+
+impl Default for JwtConfig {
+    fn default() -> Self {
+        Self {
+            algorithms: vec![Algorithm::HS256],
+            secret: None,
+            public_key: None,
+            jwks: None,
+            issuer: Vec::new(),
+            audience: Vec::new(),
+            leeway: TimeConfig {
+                parsed: Duration::from_secs(60),
+                raw: "60s".to_string(),
+            },
+            display: false,
+        }
+    }
+}
+
+impl DisplayConfig for JwtConfig {
+    fn display(&self) {
+        if !self.display {
+            return;
+        }
+
+        tracing::info!(
+            target: "sword.layers.jwt",
+            algorithms = ?self.algorithms,
+            secret = if self.secret.is_some() { "set" } else { "none" },
+            public_key = self.public_key.as_deref().unwrap_or("none"),
+            jwks = self.jwks.as_deref().unwrap_or("none"),
+            issuer = ?self.issuer,
+            audience = ?self.audience,
+            leeway = self.leeway.raw,
+        );
+    }
+}
+
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+/// Errors of loading the verification keys or verifying a token.
+///
+/// The messages of the verification errors are safe to send to clients;
+/// the underlying cause of [`JwtError::Invalid`] is available as its source.
+#[derive(Debug, Error)]
+pub enum JwtError {
+    #[error("Missing bearer token")]
+    MissingToken,
+
+    #[error("Token has expired")]
+    Expired,
+
+    #[error("Invalid token")]
+    Invalid(#[source] JsonWebTokenError),
+
+    #[error("No key matches the token")]
+    UnknownKey,
+
+    #[error("Failed to read '{path}': {source}")]
+    Read { path: String, source: io::Error },
+
+    #[error("Invalid key in '{path}': {source}")]
+    Key {
+        path: String,
+        source: JsonWebTokenError,
+    },
+
+    #[error("Invalid JWKS in '{path}': {source}")]
+    Jwks {
+        path: String,
+        source: serde_json::Error,
+    },
+
+    #[error("Invalid JWT configuration: {0}")]
+    Config(String),
+}
+
+impl JwtError {
+    /// Whether the error comes from the request rather than from the configuration.
+    pub fn is_unauthenticated(&self) -> bool {
+        matches!(
+            self,
+            Self::MissingToken | Self::Expired | Self::Invalid(_) | Self::UnknownKey
+        )
+    }
+}
+
+impl From<JsonWebTokenError> for JwtError {
+    fn from(err: JsonWebTokenError) -> Self {
+        match err.kind() {
+            ErrorKind::ExpiredSignature => Self::Expired,
+            _ => Self::Invalid(err),
+        }
+    }
+}
+
+/// The verified payload of a token.
+///
+/// Inserted as a request (or socket) extension by [`JwtAuth`]. Cloning is cheap.
+#[derive(Debug, Clone)]
+pub struct JwtClaims(Arc<Value>);
+
+impl JwtClaims {
+    pub fn new(claims: Value) -> Self {
+        Self(Arc::new(claims))
+    }
+
+    /// Deserializes the claims into `T`.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        T::deserialize(&*self.0)
+    }
+
+    /// The raw claims object.
+    pub fn as_value(&self) -> &Value {
+        &self.0
+    }
+
+    /// The `sub` claim, if present.
+    pub fn subject(&self) -> Option<&str> {
+        self.0.get("sub").and_then(Value::as_str)
+    }
+}
+
+/// Extracts the token of a `Bearer` authorization value.
+pub fn bearer_token(authorization: &str) -> Option<&str> {
+    let (scheme, token) = authorization.trim().split_once(' ')?;
+    let token = token.trim();
+
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+}
+
+/// Verifies bearer tokens with the keys of a [`JwtConfig`].
+///
+/// Cloning is cheap; clones share the loaded keys.
+#[derive(Clone)]
+pub struct JwtAuth {
+    inner: Arc<JwtVerifier>,
+}
+
+struct JwtVerifier {
+    validation: Validation,
+    secret: Option<DecodingKey>,
+    public_key: Option<DecodingKey>,
+    jwks: Vec<(Option<String>, DecodingKey)>,
+}
+
+impl JwtAuth {
+    /// Loads the verification keys of `config`.
+    pub fn new(config: &JwtConfig) -> Result<Self, JwtError> {
+        let Some(first) = config.algorithms.first() else {
+            return Err(JwtError::Config("`algorithms` can't be empty".to_string()));
+        };
+
+        let mut validation = Validation::new(*first);
+
+        validation.algorithms = config.algorithms.clone();
+        validation.leeway = config.leeway.parsed.as_secs();
+        validation.validate_nbf = true;
+
+        let mut required = vec!["exp"];
+
+        if !config.issuer.is_empty() {
+            validation.set_issuer(&config.issuer);
+            required.push("iss");
+        }
+
+        if config.audience.is_empty() {
+            validation.validate_aud = false;
+        } else {
+            validation.set_audience(&config.audience);
+            required.push("aud");
+        }
+
+        validation.set_required_spec_claims(&required);
+
+        let verifier = JwtVerifier {
+            validation,
+            secret: config
+                .secret
+                .as_ref()
+                .map(|secret| DecodingKey::from_secret(secret.as_bytes())),
+            public_key: config
+                .public_key
+                .as_deref()
+                .map(|path| load_public_key(path, &config.algorithms))
+                .transpose()?,
+            jwks: config
+                .jwks
+                .as_deref()
+                .map(load_jwks)
+                .transpose()?
+                .unwrap_or_default(),
+        };
+
+        for algorithm in &config.algorithms {
+            let has_key = if is_hmac(*algorithm) {
+                verifier.secret.is_some()
+            } else {
+                verifier.public_key.is_some() || !verifier.jwks.is_empty()
+            };
+
+            if !has_key {
+                return Err(JwtError::Config(format!(
+                    "no key is configured for {algorithm:?}"
+                )));
+            }
+        }
+
+        Ok(Self {
+            inner: Arc::new(verifier),
+        })
+    }
+
+    /// Verifies the token of an `Authorization: Bearer <token>` header value.
+    pub fn verify_authorization(&self, authorization: Option<&str>) -> Result<JwtClaims, JwtError> {
+        let token = authorization
+            .and_then(bearer_token)
+            .ok_or(JwtError::MissingToken)?;
+
+        self.verify(token)
+    }
+
+    /// Verifies the signature and the registered claims of `token`.
+    pub fn verify(&self, token: &str) -> Result<JwtClaims, JwtError> {
+        let verifier = &self.inner;
+        let header = decode_header(token)?;
+
+        if !verifier.validation.algorithms.contains(&header.alg) {
+            return Err(JwtError::Invalid(ErrorKind::InvalidAlgorithm.into()));
+        }
+
+        let key = verifier.key_for(header.alg, header.kid.as_deref())?;
+
+        // Keys are bound to one algorithm family, so only the algorithm of
+        // this token is checked against the selected key.
+        let mut validation = verifier.validation.clone();
+        validation.algorithms = vec![header.alg];
+
+        let data = decode::<Value>(token, key, &validation)?;
+
+        Ok(JwtClaims::new(data.claims))
+    }
+}
+
+impl JwtVerifier {
+    fn key_for(&self, algorithm: Algorithm, kid: Option<&str>) -> Result<&DecodingKey, JwtError> {
+        if is_hmac(algorithm) {
+            return self.secret.as_ref().ok_or(JwtError::UnknownKey);
+        }
+
+        if !self.jwks.is_empty() {
+            let key = match kid {
+                Some(kid) => self
+                    .jwks
+                    .iter()
+                    .find(|(key_id, _)| key_id.as_deref() == Some(kid)),
+                None if self.jwks.len() == 1 => self.jwks.first(),
+                None => None,
+            };
+
+            if let Some((_, key)) = key {
+                return Ok(key);
+            }
+        }
+
+        self.public_key.as_ref().ok_or(JwtError::UnknownKey)
+    }
+}
+
+fn is_hmac(algorithm: Algorithm) -> bool {
+    matches!(
+        algorithm,
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+    )
+}
+
+fn read(path: &str) -> Result<Vec<u8>, JwtError> {
+    std::fs::read(path).map_err(|source| JwtError::Read {
+        path: path.to_string(),
+        source,
+    })
+}
+
+/// Parses a PEM public key for the asymmetric algorithm family in `algorithms`.
+fn load_public_key(path: &str, algorithms: &[Algorithm]) -> Result<DecodingKey, JwtError> {
+    let pem = read(path)?;
+
+    let families = algorithms
+        .iter()
+        .filter(|algorithm| !is_hmac(**algorithm))
+        .map(|algorithm| match algorithm {
+            Algorithm::ES256 | Algorithm::ES384 => "ec",
+            Algorithm::EdDSA => "ed",
+            _ => "rsa",
+        })
+        .collect::<std::collections::BTreeSet<_>>();
+
+    let key = match families.into_iter().collect::<Vec<_>>().as_slice() {
+        ["ec"] => DecodingKey::from_ec_pem(&pem),
+        ["ed"] => DecodingKey::from_ed_pem(&pem),
+        ["rsa"] => DecodingKey::from_rsa_pem(&pem),
+        [] => {
+            return Err(JwtError::Config(
+                "`public-key` needs an RSA, ECDSA or EdDSA algorithm".to_string(),
+            ));
+        }
+        _ => {
+            return Err(JwtError::Config(
+                "`public-key` can't verify algorithms of different key types, use `jwks`"
+                    .to_string(),
+            ));
+        }
+    };
+
+    key.map_err(|source| JwtError::Key {
+        path: path.to_string(),
+        source,
+    })
+}
+
+fn load_jwks(path: &str) -> Result<Vec<(Option<String>, DecodingKey)>, JwtError> {
+    let set: JwkSet = serde_json::from_slice(&read(path)?).map_err(|source| JwtError::Jwks {
+        path: path.to_string(),
+        source,
+    })?;
+
+    set.keys
+        .iter()
+        .filter(|jwk| jwk.is_supported())
+        .map(|jwk| {
+            let key = DecodingKey::from_jwk(jwk).map_err(|source| JwtError::Key {
+                path: path.to_string(),
+                source,
+            })?;
+
+            Ok((jwk.common.key_id.clone(), key))
+        })
+        .collect()
+}
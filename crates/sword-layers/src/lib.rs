@@ -22,14 +22,38 @@ pub mod cookies {
 #[cfg(feature = "cors")]
 pub mod cors;
 
+#[cfg(feature = "csrf")]
+pub mod csrf;
+
+#[cfg(feature = "forwarded")]
+pub mod forwarded;
+
 #[cfg(feature = "helmet")]
 pub mod helmet;
 
+#[cfg(feature = "idempotency")]
+pub mod idempotency;
+
+#[cfg(feature = "jwt")]
+pub mod jwt;
+
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
 #[cfg(feature = "not-found")]
 pub mod not_found;
 
+#[cfg(feature = "opentelemetry")]
+pub mod opentelemetry;
+
 pub mod prelude;
 
+#[cfg(feature = "rate-limit")]
+pub mod rate_limit;
+
+#[cfg(feature = "sessions")]
+pub mod sessions;
+
 #[cfg(feature = "body-limit")]
 pub(crate) type ServiceLayer<Inner, Outer> = tower::ServiceBuilder<
     tower_layer::Stack<Inner, tower_layer::Stack<Outer, tower_layer::Identity>>,
@@ -53,6 +77,50 @@ pub mod tracing;
 #[cfg(feature = "req-timeout")]
 pub mod timeout;
 
+#[cfg(feature = "tls")]
+pub mod tls;
+
+/// A framework error response produced by a layer. Stored in the response
+/// extensions, so the web application can render it again with its own error
+/// format without reading the body back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerError {
+    NotFound,
+    RequestTimeout,
+    BodyTooLarge,
+}
+
+impl LayerError {
+    pub const fn status(self) -> axum::http::StatusCode {
+        match self {
+            Self::NotFound => axum::http::StatusCode::NOT_FOUND,
+            Self::RequestTimeout => axum::http::StatusCode::REQUEST_TIMEOUT,
+            Self::BodyTooLarge => axum::http::StatusCode::PAYLOAD_TOO_LARGE,
+        }
+    }
+
+    /// The message of the default `JsonResponse` envelope.
+    pub const fn message(self) -> &'static str {
+        match self {
+            Self::NotFound => "The requested resource was not found.",
+            Self::RequestTimeout => "Request Timeout",
+            Self::BodyTooLarge => "The request body exceeds the maximum allowed size by the server",
+        }
+    }
+}
+
+#[cfg(any(feature = "body-limit", feature = "not-found", feature = "req-timeout"))]
+impl axum::response::IntoResponse for LayerError {
+    fn into_response(self) -> axum::response::Response {
+        let mut response = axum_responses::JsonResponse::status(self.status())
+            .message(self.message())
+            .into_response();
+
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
 pub trait DisplayConfig {
     fn display(&self);
 }
@@ -0,0 +1,572 @@
+//! Prometheus metrics collection.
+//!
+//! This module provides the metrics configuration, a process-wide registry
+//! holding the web, gRPC and Socket.IO metric families, the tower layers that
+//! record them, and the router that exposes them in the Prometheus text format.
+//!
+//! Web metrics are labelled with the route template registered by the
+//! controller macros (e.g. `/users/{id}`), never with the raw request URI.
+//! Requests that do not match any controller route are not recorded.
+
+use crate::DisplayConfig;
+
+use axum::{
+    Router,
+    body::HttpBody,
+    http::{HeaderMap, Request, Response, StatusCode, header},
+    response::IntoResponse,
+    routing::get,
+};
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+
+use serde::{Deserialize, Serialize};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, OnceLock, RwLock},
+    task::{Context, Poll},
+    time::Instant,
+};
+use thisconfig::ConfigItem;
+use tower::{Layer, Service};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// Whether the metrics endpoint and recording layers are enabled. Defaults to false.
+    pub enabled: bool,
+
+    /// Path where the Prometheus text exposition is served. Defaults to "/metrics".
+    pub path: String,
+
+    /// Whether to display the configuration details. Defaults to false.
+    pub display: bool,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "/metrics".to_string(),
+            display: false,
+        }
+    }
+}
+
+impl ConfigItem for MetricsConfig {
+    fn key() -> &'static str {
+        "metrics"
+    }
+}
+
+impl DisplayConfig for MetricsConfig {
+    fn display(&self) {
+        if !self.display {
+            return;
+        }
+
+        tracing::info!(
+            target: "sword.layers.metrics",
+            enabled = self.enabled,
+            path = self.path,
+        );
+    }
+}
+
+type MetricsCollector = Box<dyn Fn(&SwordMetrics) + Send + Sync>;
+
+/// Process-wide registry with every metric family recorded by Sword.
+pub struct SwordMetrics {
+    registry: Registry,
+    collectors: RwLock<Vec<MetricsCollector>>,
+
+    pub http_requests_total: IntCounterVec,
+    pub http_request_duration_seconds: HistogramVec,
+    pub http_requests_in_flight: IntGaugeVec,
+    pub http_request_size_bytes: HistogramVec,
+    pub http_response_size_bytes: HistogramVec,
+
+    pub grpc_requests_total: IntCounterVec,
+    pub grpc_request_duration_seconds: HistogramVec,
+    pub grpc_requests_in_flight: IntGaugeVec,
+
+    pub socketio_events_total: IntCounterVec,
+    pub socketio_connected_sockets: IntGaugeVec,
+}
+
+const HTTP_LABELS: &[&str] = &["controller", "route", "method", "status"];
+const HTTP_IN_FLIGHT_LABELS: &[&str] = &["controller", "route", "method"];
+const GRPC_LABELS: &[&str] = &["service", "method", "code"];
+const GRPC_IN_FLIGHT_LABELS: &[&str] = &["service", "method"];
+
+const SIZE_BUCKETS: &[f64] = &[
+    64.0, 256.0, 1024.0, 4096.0, 16384.0, 65536.0, 262144.0, 1048576.0, 4194304.0,
+];
+
+impl SwordMetrics {
+    /// Returns the process-wide metrics registry, creating it on first use.
+    pub fn global() -> &'static SwordMetrics {
+        static METRICS: OnceLock<SwordMetrics> = OnceLock::new();
+        METRICS.get_or_init(SwordMetrics::new)
+    }
+
+    fn new() -> Self {
+        let registry = Registry::new();
+
+        let counter = |name: &str, help: &str, labels: &[&str]| {
+            let metric = IntCounterVec::new(Opts::new(name, help), labels)
+                .expect("valid counter definition");
+
+            registry
+                .register(Box::new(metric.clone()))
+                .expect("unique counter name");
+
+            metric
+        };
+
+        let gauge = |name: &str, help: &str, labels: &[&str]| {
+            let metric =
+                IntGaugeVec::new(Opts::new(name, help), labels).expect("valid gauge definition");
+
+            registry
+                .register(Box::new(metric.clone()))
+                .expect("unique gauge name");
+
+            metric
+        };
+
+        let histogram = |name: &str, help: &str, labels: &[&str], buckets: Option<&[f64]>| {
+            let mut opts = HistogramOpts::new(name, help);
+
+            if let Some(buckets) = buckets {
+                opts = opts.buckets(buckets.to_vec());
+            }
+
+            let metric = HistogramVec::new(opts, labels).expect("valid histogram definition");
+
+            registry
+                .register(Box::new(metric.clone()))
+                .expect("unique histogram name");
+
+            metric
+        };
+
+        Self {
+            http_requests_total: counter(
+                "http_requests_total",
+                "Total number of HTTP requests handled by web controllers",
+                HTTP_LABELS,
+            ),
+            http_request_duration_seconds: histogram(
+                "http_request_duration_seconds",
+                "HTTP request latency in seconds",
+                HTTP_LABELS,
+                None,
+            ),
+            http_requests_in_flight: gauge(
+                "http_requests_in_flight",
+                "Number of HTTP requests currently being handled",
+                HTTP_IN_FLIGHT_LABELS,
+            ),
+            http_request_size_bytes: histogram(
+                "http_request_size_bytes",
+                "HTTP request body size in bytes",
+                HTTP_IN_FLIGHT_LABELS,
+                Some(SIZE_BUCKETS),
+            ),
+            http_response_size_bytes: histogram(
+                "http_response_size_bytes",
+                "HTTP response body size in bytes",
+                HTTP_LABELS,
+                Some(SIZE_BUCKETS),
+            ),
+            grpc_requests_total: counter(
+                "grpc_requests_total",
+                "Total number of gRPC calls handled",
+                GRPC_LABELS,
+            ),
+            grpc_request_duration_seconds: histogram(
+                "grpc_request_duration_seconds",
+                "gRPC call latency in seconds",
+                GRPC_LABELS,
+                None,
+            ),
+            grpc_requests_in_flight: gauge(
+                "grpc_requests_in_flight",
+                "Number of gRPC calls currently being handled",
+                GRPC_IN_FLIGHT_LABELS,
+            ),
+            socketio_events_total: counter(
+                "socketio_events_total",
+                "Total number of Socket.IO events received",
+                &["namespace", "event"],
+            ),
+            socketio_connected_sockets: gauge(
+                "socketio_connected_sockets",
+                "Number of sockets currently connected",
+                &["namespace"],
+            ),
+            collectors: RwLock::new(Vec::new()),
+            registry,
+        }
+    }
+
+    /// Registers a callback executed right before each scrape.
+    ///
+    /// Used for values that are cheaper to read on demand than to track,
+    /// such as the number of connected Socket.IO sockets.
+    pub fn register_collector(&self, collector: impl Fn(&SwordMetrics) + Send + Sync + 'static) {
+        if let Ok(mut collectors) = self.collectors.write() {
+            collectors.push(Box::new(collector));
+        }
+    }
+
+    /// Records a received Socket.IO event.
+    pub fn record_socketio_event(&self, namespace: &str, event: &str) {
+        self.socketio_events_total
+            .with_label_values(&[namespace, event])
+            .inc();
+    }
+
+    /// Renders every registered metric family in the Prometheus text format.
+    pub fn render(&self) -> String {
+        if let Ok(collectors) = self.collectors.read() {
+            for collector in collectors.iter() {
+                collector(self);
+            }
+        }
+
+        let mut buffer = Vec::new();
+
+        if let Err(err) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            tracing::error!(target: "sword.layers.metrics", error = %err, "Failed to encode metrics");
+        }
+
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+/// Adds the metrics exposition route at the configured path.
+pub fn with_metrics_route<S>(router: Router<S>, config: &MetricsConfig) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    router.route(&config.path, get(metrics_handler))
+}
+
+async fn metrics_handler() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)],
+        SwordMetrics::global().render(),
+    )
+}
+
+type BoxFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'static>>;
+
+#[derive(Clone, Debug)]
+struct RouteLabels {
+    controller: &'static str,
+    route: Arc<str>,
+}
+
+/// Slot shared between `HttpMetricsLayer` and `RouteMetricsLayer`.
+///
+/// The outer layer inserts an empty slot into the request extensions and the
+/// per-route layer fills it once axum has matched a controller route.
+#[derive(Clone, Default)]
+struct RouteLabelsSlot(Arc<OnceLock<RouteLabels>>);
+
+/// Records request counts, latency and body sizes for matched web routes.
+///
+/// This layer must wrap the whole controller router so that responses produced
+/// by outer layers (timeouts, body limits) are recorded as well.
+#[derive(Clone)]
+pub struct HttpMetricsLayer;
+
+impl<S> Layer<S> for HttpMetricsLayer {
+    type Service = HttpMetricsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HttpMetricsService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct HttpMetricsService<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for HttpMetricsService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: HttpBody + Send + 'static,
+    ResBody: HttpBody,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
+        let slot = RouteLabelsSlot::default();
+        let method = req.method().as_str().to_owned();
+        let request_size = content_length(req.headers()).or_else(|| req.body().size_hint().exact());
+        let started_at = Instant::now();
+
+        req.extensions_mut().insert(slot.clone());
+
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let response = future.await?;
+
+            let Some(labels) = slot.0.get() else {
+                return Ok(response);
+            };
+
+            let metrics = SwordMetrics::global();
+            let status = response.status().as_u16().to_string();
+            let values = [labels.controller, &labels.route, &method, &status];
+
+            metrics.http_requests_total.with_label_values(&values).inc();
+            metrics
+                .http_request_duration_seconds
+                .with_label_values(&values)
+                .observe(started_at.elapsed().as_secs_f64());
+
+            if let Some(size) = request_size {
+                metrics
+                    .http_request_size_bytes
+                    .with_label_values(&values[..3])
+                    .observe(size as f64);
+            }
+
+            let response_size = response
+                .body()
+                .size_hint()
+                .exact()
+                .or_else(|| content_length(response.headers()));
+
+            if let Some(size) = response_size {
+                metrics
+                    .http_response_size_bytes
+                    .with_label_values(&values)
+                    .observe(size as f64);
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+/// Attaches the controller name and route template to the request metrics.
+///
+/// Applied by the web router to every route registered through
+/// `RouteRegistrar`, it also tracks the in-flight gauge for the route.
+#[derive(Clone)]
+pub struct RouteMetricsLayer {
+    labels: RouteLabels,
+}
+
+impl RouteMetricsLayer {
+    pub fn new(controller: &'static str, route: impl Into<Arc<str>>) -> Self {
+        Self {
+            labels: RouteLabels {
+                controller,
+                route: route.into(),
+            },
+        }
+    }
+}
+
+impl<S> Layer<S> for RouteMetricsLayer {
+    type Service = RouteMetricsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RouteMetricsService {
+            inner,
+            labels: self.labels.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RouteMetricsService<S> {
+    inner: S,
+    labels: RouteLabels,
+}
+
+impl<S, B> Service<Request<B>> for RouteMetricsService<S>
+where
+    S: Service<Request<B>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    B: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        if let Some(slot) = req.extensions().get::<RouteLabelsSlot>() {
+            let _ = slot.0.set(self.labels.clone());
+        }
+
+        let in_flight = InFlightGuard::new(
+            SwordMetrics::global()
+                .http_requests_in_flight
+                .with_label_values(&[
+                    self.labels.controller,
+                    &self.labels.route,
+                    req.method().as_str(),
+                ]),
+        );
+
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let _in_flight = in_flight;
+            future.await
+        })
+    }
+}
+
+/// Records call counts, latency and in-flight calls for gRPC services.
+///
+/// Labels are taken from the `/package.Service/Method` request path. The status
+/// code is read from the `grpc-status` response header, which tonic sets for
+/// trailers-only error responses; calls without it are recorded as `OK`.
+#[derive(Clone)]
+pub struct GrpcMetricsLayer;
+
+impl<S> Layer<S> for GrpcMetricsLayer {
+    type Service = GrpcMetricsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcMetricsService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct GrpcMetricsService<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for GrpcMetricsService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let Some((service, method)) = parse_grpc_path(req.uri().path()) else {
+            return Box::pin(self.inner.call(req));
+        };
+
+        let metrics = SwordMetrics::global();
+        let started_at = Instant::now();
+        let in_flight = InFlightGuard::new(
+            metrics
+                .grpc_requests_in_flight
+                .with_label_values(&[&service, &method]),
+        );
+
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let _in_flight = in_flight;
+            let response = future.await?;
+
+            let code = response
+                .headers()
+                .get("grpc-status")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u8>().ok())
+                .map_or("OK", grpc_code_name);
+
+            let values = [service.as_str(), method.as_str(), code];
+
+            metrics.grpc_requests_total.with_label_values(&values).inc();
+            metrics
+                .grpc_request_duration_seconds
+                .with_label_values(&values)
+                .observe(started_at.elapsed().as_secs_f64());
+
+            Ok(response)
+        })
+    }
+}
+
+struct InFlightGuard(prometheus::IntGauge);
+
+impl InFlightGuard {
+    fn new(gauge: prometheus::IntGauge) -> Self {
+        gauge.inc();
+        Self(gauge)
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+fn content_length(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+}
+
+fn parse_grpc_path(path: &str) -> Option<(String, String)> {
+    let (service, method) = path.strip_prefix('/')?.split_once('/')?;
+
+    if service.is_empty() || method.is_empty() || method.contains('/') {
+        return None;
+    }
+
+    Some((service.to_owned(), method.to_owned()))
+}
+
+fn grpc_code_name(code: u8) -> &'static str {
+    match code {
+        0 => "OK",
+        1 => "CANCELLED",
+        2 => "UNKNOWN",
+        3 => "INVALID_ARGUMENT",
+        4 => "DEADLINE_EXCEEDED",
+        5 => "NOT_FOUND",
+        6 => "ALREADY_EXISTS",
+        7 => "PERMISSION_DENIED",
+        8 => "RESOURCE_EXHAUSTED",
+        9 => "FAILED_PRECONDITION",
+        10 => "ABORTED",
+        11 => "OUT_OF_RANGE",
+        12 => "UNIMPLEMENTED",
+        13 => "INTERNAL",
+        14 => "UNAVAILABLE",
+        15 => "DATA_LOSS",
+        16 => "UNAUTHENTICATED",
+        _ => "UNKNOWN",
+    }
+}
@@ -9,18 +9,23 @@ use std::{
     task::{Context, Poll},
 };
 
+use crate::LayerError;
 use axum::{
-    body::{Body, to_bytes},
-    http::{Request, StatusCode},
+    body::Body,
+    http::{Request, StatusCode, header::CONTENT_TYPE},
     response::{IntoResponse, Response},
 };
-use axum_responses::JsonResponse;
-use serde_json::Value;
+
 use tower::{Layer, Service};
 
 #[derive(Clone)]
 pub struct NotFoundLayer;
 
+/// Request extension that makes `NotFoundLayer` answer the request as an
+/// unknown route, without calling the inner service.
+#[derive(Debug, Clone, Copy)]
+pub struct ForceNotFound;
+
 impl<S> Layer<S> for NotFoundLayer {
     type Service = NotFoundService<S>;
 
@@ -50,6 +55,10 @@ where
     }
 
     fn call(&mut self, req: Request<Body>) -> Self::Future {
+        if req.extensions().get::<ForceNotFound>().is_some() {
+            return Box::pin(async { Ok(not_found()) });
+        }
+
         let mut inner = self.inner.clone();
 
         Box::pin(async move {
@@ -59,27 +68,24 @@ where
                 return Ok(response);
             }
 
-            let (parts, body) = response.into_parts();
-
-            let body_bytes = match to_bytes(body, usize::MAX).await {
-                Ok(bytes) => bytes,
-                Err(_) => {
-                    return Ok(Response::from_parts(parts, Body::from("body read error")));
-                }
-            };
+            // JSON bodies were produced by handlers or the framework, and bodies
+            // in other formats (e.g. an HTML page) on purpose, so both are kept.
+            // Only the empty or plain text 404 of unmatched routes is replaced.
+            let is_mappable = response
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .is_none_or(|content_type| content_type.starts_with("text/plain"));
 
-            let already_has_message = match serde_json::from_slice::<Value>(&body_bytes) {
-                Ok(json) => json.get("message").is_some(),
-                Err(_) => false,
-            };
-
-            if already_has_message {
-                return Ok(Response::from_parts(parts, Body::from(body_bytes)));
+            if !is_mappable {
+                return Ok(response);
             }
 
-            Ok(JsonResponse::NotFound()
-                .message("The requested resource was not found.")
-                .into_response())
+            Ok(not_found())
         })
     }
 }
+
+fn not_found() -> Response {
+    LayerError::NotFound.into_response()
+}
@@ -0,0 +1,455 @@
+//! OpenTelemetry server spans with W3C trace context propagation.
+//!
+//! This module provides the exporter configuration, a per-application
+//! `Telemetry` handle wrapping an OTLP tracer provider, and the tower layers
+//! that open server spans for web routes and gRPC methods. Incoming
+//! `traceparent`/`tracestate` headers are used as the parent context, and the
+//! resulting context is injected back into the response headers.
+//!
+//! Every span records the `x-request-id` of the request (when present) as the
+//! `http.request.header.x-request-id` attribute, so exported traces can be
+//! joined with application logs.
+
+use crate::DisplayConfig;
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Request, Response};
+use opentelemetry::{
+    Context, KeyValue,
+    propagation::{Extractor, Injector, TextMapPropagator},
+    trace::{SpanKind, Status, Tracer, TracerProvider as _},
+};
+use opentelemetry_otlp::{ExporterBuildError, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    Resource,
+    propagation::TraceContextPropagator,
+    trace::{BatchConfigBuilder, BatchSpanProcessor, SdkTracer, SdkTracerProvider},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context as TaskContext, Poll},
+    time::Duration,
+};
+use thisconfig::{ConfigItem, TimeConfig};
+use tower::{Layer, Service};
+use tracing::Instrument;
+
+pub use opentelemetry::{Context as OtelContext, KeyValue as OtelKeyValue, trace::TraceContextExt};
+
+const REQUEST_ID_HEADER: &str = "x-request-id";
+const REQUEST_ID_ATTRIBUTE: &str = "http.request.header.x-request-id";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpenTelemetryConfig {
+    /// Whether spans are created and exported. Defaults to false.
+    pub enabled: bool,
+
+    /// OTLP/gRPC collector endpoint. Defaults to "http://localhost:4317".
+    pub endpoint: String,
+
+    /// Value of the `service.name` resource attribute. Defaults to "sword".
+    #[serde(rename = "service-name")]
+    pub service_name: String,
+
+    /// Delay between two consecutive batch exports. Defaults to "5s".
+    #[serde(rename = "export-interval")]
+    pub export_interval: TimeConfig,
+
+    /// Maximum duration of a single export call. Defaults to "10s".
+    #[serde(rename = "export-timeout")]
+    pub export_timeout: TimeConfig,
+
+    /// Whether to display the configuration details. Defaults to false.
+    pub display: bool,
+}
+
+impl Default for OpenTelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://localhost:4317".to_string(),
+            service_name: "sword".to_string(),
+            export_interval: TimeConfig {
+                parsed: Duration::from_secs(5),
+                raw: "5s".to_string(),
+            },
+            export_timeout: TimeConfig {
+                parsed: Duration::from_secs(10),
+                raw: "10s".to_string(),
+            },
+            display: false,
+        }
+    }
+}
+
+impl ConfigItem for OpenTelemetryConfig {
+    fn key() -> &'static str {
+        "opentelemetry"
+    }
+}
+
+impl DisplayConfig for OpenTelemetryConfig {
+    fn display(&self) {
+        if !self.display {
+            return;
+        }
+
+        tracing::info!(
+            target: "sword.layers.opentelemetry",
+            enabled = self.enabled,
+            endpoint = self.endpoint,
+            service_name = self.service_name,
+            export_interval = self.export_interval.raw,
+            export_timeout = self.export_timeout.raw,
+        );
+    }
+}
+
+/// Per-application tracer and W3C trace context propagator.
+///
+/// Cloning is cheap; all clones share the same tracer provider, which flushes
+/// the pending spans when the last clone is dropped.
+#[derive(Clone, Debug)]
+pub struct Telemetry {
+    provider: SdkTracerProvider,
+    tracer: SdkTracer,
+    propagator: Arc<TraceContextPropagator>,
+}
+
+impl Telemetry {
+    /// Builds the OTLP exporter and tracer provider described by `config`.
+    ///
+    /// Must be called from within a tokio runtime, which drives the exporter
+    /// connection.
+    pub fn from_config(config: &OpenTelemetryConfig) -> Result<Self, ExporterBuildError> {
+        let exporter = SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(config.endpoint.clone())
+            .with_timeout(config.export_timeout.parsed)
+            .build()?;
+
+        let batch_config = BatchConfigBuilder::default()
+            .with_scheduled_delay(config.export_interval.parsed)
+            .build();
+
+        let processor = BatchSpanProcessor::builder(exporter)
+            .with_batch_config(batch_config)
+            .build();
+
+        let provider = SdkTracerProvider::builder()
+            .with_span_processor(processor)
+            .with_resource(
+                Resource::builder()
+                    .with_service_name(config.service_name.clone())
+                    .build(),
+            )
+            .build();
+
+        Ok(Self {
+            tracer: provider.tracer("sword"),
+            provider,
+            propagator: Arc::new(TraceContextPropagator::new()),
+        })
+    }
+
+    /// Extracts the remote parent context from `traceparent`/`tracestate` headers.
+    pub fn extract(&self, headers: &HeaderMap) -> Context {
+        self.propagator
+            .extract_with_context(&Context::new(), &HeaderExtractor(headers))
+    }
+
+    /// Injects the given context as `traceparent`/`tracestate` headers.
+    pub fn inject(&self, cx: &Context, headers: &mut HeaderMap) {
+        self.propagator
+            .inject_context(cx, &mut HeaderInjector(headers));
+    }
+
+    /// Starts a server span as a child of `parent` and returns its context.
+    pub fn start_server_span(
+        &self,
+        name: impl Into<String>,
+        parent: &Context,
+        attributes: Vec<KeyValue>,
+    ) -> Context {
+        let span = self
+            .tracer
+            .span_builder(name.into())
+            .with_kind(SpanKind::Server)
+            .with_attributes(attributes)
+            .start_with_context(&self.tracer, parent);
+
+        parent.with_span(span)
+    }
+
+    /// Exports every finished span that is still buffered.
+    pub fn force_flush(&self) {
+        if let Err(err) = self.provider.force_flush() {
+            tracing::warn!(target: "sword.layers.opentelemetry", error = %err, "Failed to flush spans");
+        }
+    }
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(HeaderName::as_str).collect()
+    }
+}
+
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(key), HeaderValue::try_from(value)) {
+            self.0.insert(name, value);
+        }
+    }
+}
+
+fn request_id(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned)
+}
+
+type BoxFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'static>>;
+
+/// Opens a server span around a single web route.
+///
+/// Applied by the web router to every route registered through
+/// `RouteRegistrar`, so the span name uses the route template
+/// (e.g. `GET /users/{id}`) instead of the raw URI.
+#[derive(Clone)]
+pub struct HttpTraceLayer {
+    telemetry: Telemetry,
+    controller: &'static str,
+    route: Arc<str>,
+}
+
+impl HttpTraceLayer {
+    pub fn new(telemetry: Telemetry, controller: &'static str, route: impl Into<Arc<str>>) -> Self {
+        Self {
+            telemetry,
+            controller,
+            route: route.into(),
+        }
+    }
+}
+
+impl<S> Layer<S> for HttpTraceLayer {
+    type Service = HttpTraceService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HttpTraceService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct HttpTraceService<S> {
+    inner: S,
+    layer: HttpTraceLayer,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for HttpTraceService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
+        let HttpTraceLayer {
+            telemetry,
+            controller,
+            route,
+        } = self.layer.clone();
+
+        let method = req.method().as_str().to_owned();
+        let request_id = request_id(req.headers());
+        let parent = telemetry.extract(req.headers());
+
+        let mut attributes = vec![
+            KeyValue::new("http.request.method", method.clone()),
+            KeyValue::new("http.route", route.to_string()),
+            KeyValue::new("url.path", req.uri().path().to_owned()),
+            KeyValue::new("sword.controller", controller),
+        ];
+
+        if let Some(request_id) = &request_id {
+            attributes.push(KeyValue::new(REQUEST_ID_ATTRIBUTE, request_id.clone()));
+        }
+
+        let cx = telemetry.start_server_span(format!("{method} {route}"), &parent, attributes);
+        let trace_id = cx.span().span_context().trace_id().to_string();
+
+        let span = tracing::info_span!(
+            target: "sword.http",
+            "request",
+            method,
+            route = %route,
+            trace_id,
+            request_id = request_id.as_deref().unwrap_or_default(),
+        );
+
+        req.extensions_mut().insert(cx.clone());
+
+        let future = self.inner.call(req);
+
+        Box::pin(
+            async move {
+                let mut response = future.await?;
+                let status = response.status();
+
+                cx.span().set_attribute(KeyValue::new(
+                    "http.response.status_code",
+                    i64::from(status.as_u16()),
+                ));
+
+                if status.is_server_error() {
+                    cx.span().set_status(Status::error(status.to_string()));
+                }
+
+                telemetry.inject(&cx, response.headers_mut());
+                cx.span().end();
+
+                Ok(response)
+            }
+            .instrument(span),
+        )
+    }
+}
+
+/// Opens a server span around every gRPC call.
+///
+/// The span is named after the `/package.Service/Method` request path and
+/// records the `grpc-status` of trailers-only responses.
+#[derive(Clone)]
+pub struct GrpcTraceLayer {
+    telemetry: Telemetry,
+}
+
+impl GrpcTraceLayer {
+    pub fn new(telemetry: Telemetry) -> Self {
+        Self { telemetry }
+    }
+}
+
+impl<S> Layer<S> for GrpcTraceLayer {
+    type Service = GrpcTraceService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcTraceService {
+            inner,
+            telemetry: self.telemetry.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct GrpcTraceService<S> {
+    inner: S,
+    telemetry: Telemetry,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for GrpcTraceService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
+        let Some((service, method)) = req
+            .uri()
+            .path()
+            .strip_prefix('/')
+            .and_then(|path| path.split_once('/'))
+            .map(|(service, method)| (service.to_owned(), method.to_owned()))
+        else {
+            return Box::pin(self.inner.call(req));
+        };
+
+        let telemetry = self.telemetry.clone();
+        let request_id = request_id(req.headers());
+        let parent = telemetry.extract(req.headers());
+
+        let mut attributes = vec![
+            KeyValue::new("rpc.system", "grpc"),
+            KeyValue::new("rpc.service", service.clone()),
+            KeyValue::new("rpc.method", method.clone()),
+        ];
+
+        if let Some(request_id) = &request_id {
+            attributes.push(KeyValue::new(REQUEST_ID_ATTRIBUTE, request_id.clone()));
+        }
+
+        let cx = telemetry.start_server_span(format!("{service}/{method}"), &parent, attributes);
+        let trace_id = cx.span().span_context().trace_id().to_string();
+
+        let span = tracing::info_span!(
+            target: "sword.grpc",
+            "request",
+            service,
+            method,
+            trace_id,
+            request_id = request_id.as_deref().unwrap_or_default(),
+        );
+
+        req.extensions_mut().insert(cx.clone());
+
+        let future = self.inner.call(req);
+
+        Box::pin(
+            async move {
+                let mut response = future.await?;
+
+                let code = response
+                    .headers()
+                    .get("grpc-status")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.parse::<i64>().ok())
+                    .unwrap_or(0);
+
+                cx.span()
+                    .set_attribute(KeyValue::new("rpc.grpc.status_code", code));
+
+                if code != 0 {
+                    cx.span()
+                        .set_status(Status::error(format!("grpc-status {code}")));
+                }
+
+                telemetry.inject(&cx, response.headers_mut());
+                cx.span().end();
+
+                Ok(response)
+            }
+            .instrument(span),
+        )
+    }
+}
@@ -12,12 +12,21 @@ pub use crate::cookies::*;
 #[cfg(feature = "cors")]
 pub use crate::cors::*;
 
+#[cfg(feature = "forwarded")]
+pub use crate::forwarded::*;
+
 #[cfg(feature = "helmet")]
 pub use crate::helmet;
 
+#[cfg(feature = "metrics")]
+pub use crate::metrics::*;
+
 #[cfg(feature = "not-found")]
 pub use crate::not_found::*;
 
+#[cfg(feature = "opentelemetry")]
+pub use crate::opentelemetry::*;
+
 #[cfg(feature = "request-id")]
 pub use crate::request_id::*;
 
@@ -0,0 +1,199 @@
+use crate::DisplayConfig;
+
+use axum::http::HeaderName;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, str::FromStr, time::Duration};
+use thisconfig::{ConfigItem, TimeConfig};
+
+/// Configuration of the `[rate-limit]` key.
+///
+/// The top-level algorithm, limit, window and key form the default policy.
+/// Named policies are applied per controller or route with
+/// `rate_limit = "<name>"`, or to gRPC services with
+/// `#[interceptor(RateLimiter, config = "<name>")]`.
+///
+/// ```toml
+/// [rate-limit]
+/// enabled = true
+/// algorithm = "token-bucket"
+/// limit = 100
+/// window = "1m"
+/// key = "ip"
+///
+/// [rate-limit.policies.login]
+/// algorithm = "sliding-window"
+/// limit = 5
+/// window = "15m"
+/// key = "header:X-Forwarded-User"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// Whether rate limiting is enabled. Defaults to false.
+    pub enabled: bool,
+
+    /// Whether the default policy applies to every web route without its
+    /// own `rate_limit` option. Defaults to true.
+    pub global: bool,
+
+    /// The default policy.
+    #[serde(flatten)]
+    pub default: RateLimitPolicy,
+
+    /// Named policies, by name.
+    pub policies: HashMap<String, RateLimitPolicy>,
+
+    /// Whether to display the configuration details. Defaults to false.
+    pub display: bool,
+}
+
+/// How many requests a key can make, and over which window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitPolicy {
+    /// "token-bucket" (default) or "sliding-window".
+    pub algorithm: RateLimitAlgorithm,
+
+    /// Requests allowed per window. Defaults to 100.
+    pub limit: u64,
+
+    /// Length of the window. Defaults to "1m".
+    pub window: TimeConfig,
+
+    /// What requests are counted by: "ip" (default), "subject",
+    /// "header:<name>" or "custom:<name>".
+    pub key: RateLimitKey,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RateLimitAlgorithm {
+    /// Allows bursts of up to `limit` requests, refilled evenly over the window.
+    #[default]
+    TokenBucket,
+
+    /// Allows `limit` requests in any window, weighting the previous window
+    /// by how much of it still overlaps.
+    SlidingWindow,
+}
+
+/// What requests are counted by.
+///
+/// Requests without the selected key, such as anonymous requests under
+/// "subject", are counted by client IP instead.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum RateLimitKey {
+    /// The client IP resolved from `web.trusted-proxies`, or the gRPC peer address.
+    #[default]
+    Ip,
+
+    /// The `sub` claim of a valid bearer token. Requires the `jwt` feature
+    /// and `[auth.jwt]`.
+    Subject,
+
+    /// The value of a request header, or gRPC metadata entry.
+    Header(HeaderName),
+
+    /// A `RateLimitKeyExtractor` registered under this name.
+    Custom(String),
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            global: true,
+            default: RateLimitPolicy::default(),
+            policies: HashMap::new(),
+            display: false,
+        }
+    }
+}
+
+impl Default for RateLimitPolicy {
+    fn default() -> Self {
+        Self {
+            algorithm: RateLimitAlgorithm::TokenBucket,
+            limit: 100,
+            window: TimeConfig {
+                parsed: Duration::from_secs(60),
+                raw: "1m".to_string(),
+            },
+            key: RateLimitKey::Ip,
+        }
+    }
+}
+
+impl ConfigItem for RateLimitConfig {
+    fn key() -> &'static str {
+        "rate-limit"
+    }
+}
+
+impl DisplayConfig for RateLimitConfig {
+    fn display(&self) {
+        if !self.display {
+            return;
+        }
+
+        let mut policies = self.policies.keys().map(String::as_str).collect::<Vec<_>>();
+        policies.sort_unstable();
+
+        tracing::info!(
+            target: "sword.layers.rate_limit",
+            enabled = self.enabled,
+            global = self.global,
+            algorithm = ?self.default.algorithm,
+            limit = self.default.limit,
+            window = self.default.window.raw,
+            key = %self.default.key,
+            policies = policies.join(", "),
+        );
+    }
+}
+
+impl FromStr for RateLimitKey {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once(':') {
+            None if value == "ip" => Ok(Self::Ip),
+            None if value == "subject" => Ok(Self::Subject),
+            Some(("header", name)) => HeaderName::from_str(name.trim())
+                .map(Self::Header)
+                .map_err(|_| format!("Invalid header name in rate limit key '{value}'")),
+            Some(("custom", name)) if !name.trim().is_empty() => {
+                Ok(Self::Custom(name.trim().to_string()))
+            }
+            _ => Err(format!(
+                "Invalid rate limit key '{value}', expected \"ip\", \"subject\", \"header:<name>\" or \"custom:<name>\""
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for RateLimitKey {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<RateLimitKey> for String {
+    fn from(key: RateLimitKey) -> Self {
+        key.to_string()
+    }
+}
+
+impl fmt::Display for RateLimitKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ip => f.write_str("ip"),
+            Self::Subject => f.write_str("subject"),
+            Self::Header(name) => write!(f, "header:{name}"),
+            Self::Custom(name) => write!(f, "custom:{name}"),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use super::{RateLimitDecision, RateLimitRequest, RateLimiter};
+use crate::forwarded::ClientInfo;
+
+use axum::{
+    body::Body,
+    http::{HeaderName, HeaderValue, Request},
+    response::{IntoResponse, Response},
+};
+use axum_responses::JsonResponse;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower::{Layer, Service};
+
+type BoxFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'static>>;
+
+/// Counts every request against one policy of a [`RateLimiter`].
+///
+/// Rejected requests get a 429 response. Every response carries the
+/// `RateLimit-*` headers of its decision. When the store fails, the request
+/// is let through and the error is logged.
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    limiter: RateLimiter,
+    policy: Arc<str>,
+}
+
+impl RateLimitLayer {
+    pub fn new(limiter: RateLimiter, policy: &str) -> Self {
+        Self {
+            limiter,
+            policy: Arc::from(policy),
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            limiter: self.limiter.clone(),
+            policy: self.policy.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limiter: RateLimiter,
+    policy: Arc<str>,
+}
+
+impl<S> Service<Request<Body>> for RateLimitService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let limiter = self.limiter.clone();
+        let policy = self.policy.clone();
+
+        Box::pin(async move {
+            let ip = req
+                .extensions()
+                .get::<ClientInfo>()
+                .and_then(|client| client.ip);
+
+            let request = RateLimitRequest::new(req.headers(), req.extensions(), ip);
+
+            let decision = match limiter.check(&policy, &request).await {
+                Ok(decision) => decision,
+                Err(err) => {
+                    tracing::warn!(
+                        target: "sword.layers.rate_limit",
+                        policy = %policy,
+                        error = %err,
+                        "Rate limit check failed, letting the request through"
+                    );
+
+                    return inner.call(req).await;
+                }
+            };
+
+            if !decision.allowed {
+                return Ok(rejection(&decision));
+            }
+
+            let mut response = inner.call(req).await?;
+            let headers = response.headers_mut();
+
+            for (name, value) in decision.headers() {
+                if let Ok(value) = HeaderValue::from_str(&value) {
+                    headers.insert(HeaderName::from_static(name), value);
+                }
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+/// The 429 response of a rejected request.
+fn rejection(decision: &RateLimitDecision) -> Response {
+    decision
+        .headers()
+        .iter()
+        .fold(
+            JsonResponse::TooManyRequests().message("Too many requests"),
+            |response, (name, value)| response.header(name, value),
+        )
+        .into_response()
+}
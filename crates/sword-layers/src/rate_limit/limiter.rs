@@ -0,0 +1,221 @@
+use super::{
+    RateLimitConfig, RateLimitDecision, RateLimitError, RateLimitKey, RateLimitPolicy,
+    RateLimitStore,
+};
+
+use axum::http::{Extensions, HeaderMap};
+use std::{collections::HashMap, net::IpAddr, sync::Arc};
+
+#[cfg(feature = "jwt")]
+use crate::jwt::{JwtAuth, JwtClaims};
+
+/// Name of the policy formed by the top-level `[rate-limit]` values.
+pub const DEFAULT_POLICY: &str = "default";
+
+/// Derives the key of a request for policies with `key = "custom:<name>"`.
+///
+/// Returning `None` counts the request by client IP instead.
+///
+/// ### Example
+/// ```rust,ignore
+/// struct TenantKey;
+///
+/// impl RateLimitKeyExtractor for TenantKey {
+///     fn extract(&self, req: &RateLimitRequest<'_>) -> Option<String> {
+///         let tenant = req.headers().get("x-tenant-id")?.to_str().ok()?;
+///         Some(tenant.to_string())
+///     }
+/// }
+///
+/// register_rate_limit_key!("tenant", TenantKey);
+/// ```
+pub trait RateLimitKeyExtractor: Send + Sync + 'static {
+    fn extract(&self, req: &RateLimitRequest<'_>) -> Option<String>;
+}
+
+/// The parts of a web request or gRPC call a rate limit key is derived from.
+pub struct RateLimitRequest<'a> {
+    headers: &'a HeaderMap,
+    extensions: &'a Extensions,
+    ip: Option<IpAddr>,
+}
+
+impl<'a> RateLimitRequest<'a> {
+    pub fn new(headers: &'a HeaderMap, extensions: &'a Extensions, ip: Option<IpAddr>) -> Self {
+        Self {
+            headers,
+            extensions,
+            ip,
+        }
+    }
+
+    /// Request headers, or gRPC metadata.
+    pub fn headers(&self) -> &HeaderMap {
+        self.headers
+    }
+
+    pub fn extensions(&self) -> &Extensions {
+        self.extensions
+    }
+
+    /// The client IP, if known.
+    pub fn ip(&self) -> Option<IpAddr> {
+        self.ip
+    }
+}
+
+/// Counts requests against the policies of a [`RateLimitConfig`].
+///
+/// Each policy and key has its own counter, so routes sharing a policy share
+/// its quota. Cloning is cheap; clones share the store.
+#[derive(Clone)]
+pub struct RateLimiter {
+    default: Arc<RateLimitPolicy>,
+    policies: Arc<HashMap<String, RateLimitPolicy>>,
+    store: Arc<dyn RateLimitStore>,
+    extractors: HashMap<String, &'static dyn RateLimitKeyExtractor>,
+
+    #[cfg(feature = "jwt")]
+    jwt: Option<JwtAuth>,
+}
+
+impl RateLimiter {
+    pub fn new(config: &RateLimitConfig, store: Arc<dyn RateLimitStore>) -> Self {
+        Self {
+            default: Arc::new(config.default.clone()),
+            policies: Arc::new(config.policies.clone()),
+            store,
+            extractors: HashMap::new(),
+
+            #[cfg(feature = "jwt")]
+            jwt: None,
+        }
+    }
+
+    /// Registers the extractor of `key = "custom:<name>"`.
+    pub fn with_extractor(
+        mut self,
+        name: impl Into<String>,
+        extractor: &'static dyn RateLimitKeyExtractor,
+    ) -> Self {
+        self.extractors.insert(name.into(), extractor);
+        self
+    }
+
+    /// Verifies bearer tokens for `key = "subject"` when no earlier
+    /// interceptor has inserted the `JwtClaims`.
+    #[cfg(feature = "jwt")]
+    pub fn with_jwt(mut self, auth: JwtAuth) -> Self {
+        self.jwt = Some(auth);
+        self
+    }
+
+    /// Checks that every policy can derive its key.
+    pub fn validate(&self) -> Result<(), RateLimitError> {
+        if self.policies.contains_key(DEFAULT_POLICY) {
+            return Err(RateLimitError::Config(format!(
+                "the \"{DEFAULT_POLICY}\" policy name is reserved for the top-level values"
+            )));
+        }
+
+        let policies = std::iter::once((DEFAULT_POLICY, &*self.default)).chain(
+            self.policies
+                .iter()
+                .map(|(name, policy)| (name.as_str(), policy)),
+        );
+
+        for (name, policy) in policies {
+            match &policy.key {
+                RateLimitKey::Custom(key) if !self.extractors.contains_key(key) => {
+                    return Err(RateLimitError::Config(format!(
+                        "policy \"{name}\" uses the unregistered key extractor \"{key}\""
+                    )));
+                }
+                RateLimitKey::Subject if !self.verifies_tokens() => {
+                    return Err(RateLimitError::Config(format!(
+                        "policy \"{name}\" is keyed by subject, which requires `[auth.jwt]`"
+                    )));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The policy named `name`, or the default one for [`DEFAULT_POLICY`].
+    pub fn policy(&self, name: &str) -> Option<&RateLimitPolicy> {
+        match name {
+            DEFAULT_POLICY => Some(&self.default),
+            name => self.policies.get(name),
+        }
+    }
+
+    /// Counts a request against the policy named `policy`.
+    pub async fn check(
+        &self,
+        policy: &str,
+        req: &RateLimitRequest<'_>,
+    ) -> Result<RateLimitDecision, RateLimitError> {
+        let config = self
+            .policy(policy)
+            .ok_or_else(|| RateLimitError::Config(format!("unknown policy \"{policy}\"")))?;
+
+        let key = format!("{policy}:{}", self.key(&config.key, req));
+
+        self.store.hit(&key, config).await
+    }
+
+    fn key(&self, key: &RateLimitKey, req: &RateLimitRequest<'_>) -> String {
+        let value = match key {
+            RateLimitKey::Ip => None,
+            RateLimitKey::Subject => self.subject(req).map(|subject| format!("sub:{subject}")),
+            RateLimitKey::Header(name) => req
+                .headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| format!("header:{value}")),
+            RateLimitKey::Custom(name) => self
+                .extractors
+                .get(name)
+                .and_then(|extractor| extractor.extract(req))
+                .map(|value| format!("custom:{value}")),
+        };
+
+        value.unwrap_or_else(|| match req.ip {
+            Some(ip) => format!("ip:{ip}"),
+            None => "ip:unknown".to_string(),
+        })
+    }
+
+    #[cfg(feature = "jwt")]
+    fn subject(&self, req: &RateLimitRequest<'_>) -> Option<String> {
+        if let Some(claims) = req.extensions.get::<JwtClaims>() {
+            return claims.subject().map(str::to_string);
+        }
+
+        let authorization = req.headers.get("authorization")?.to_str().ok();
+        let claims = self
+            .jwt
+            .as_ref()?
+            .verify_authorization(authorization)
+            .ok()?;
+
+        claims.subject().map(str::to_string)
+    }
+
+    #[cfg(not(feature = "jwt"))]
+    fn subject(&self, _: &RateLimitRequest<'_>) -> Option<String> {
+        None
+    }
+
+    #[cfg(feature = "jwt")]
+    fn verifies_tokens(&self) -> bool {
+        self.jwt.is_some()
+    }
+
+    #[cfg(not(feature = "jwt"))]
+    fn verifies_tokens(&self) -> bool {
+        false
+    }
+}
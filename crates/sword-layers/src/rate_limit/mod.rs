@@ -0,0 +1,17 @@
+//! Request rate limiting.
+//!
+//! A [`RateLimiter`] counts requests by key (client IP, token subject,
+//! header or a custom extractor) against named policies, using a token
+//! bucket or a sliding window. Counters live in a [`RateLimitStore`], which
+//! is in memory by default and can be replaced to share limits between
+//! instances.
+
+mod config;
+mod layer;
+mod limiter;
+mod store;
+
+pub use config::*;
+pub use layer::*;
+pub use limiter::*;
+pub use store::*;
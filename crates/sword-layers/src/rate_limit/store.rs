@@ -0,0 +1,318 @@
+use super::{RateLimitAlgorithm, RateLimitPolicy};
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    error::Error as StdError,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
+
+pub type RateLimitFuture<'a, T> =
+    Pin<Box<dyn Future<Output = Result<T, RateLimitError>> + Send + 'a>>;
+
+/// Backend that counts requests by key.
+///
+/// Implement it to share limits between instances, and register the store
+/// with `RateLimitStoreProvider` to use it instead of the in-memory one.
+/// [`RateLimitState`] implements both algorithms over serializable state, so
+/// a store only has to load, update and save it atomically.
+///
+/// ### Example
+/// ```rust,ignore
+/// impl RateLimitStore for RedisStore {
+///     fn hit<'a>(
+///         &'a self,
+///         key: &'a str,
+///         policy: &'a RateLimitPolicy,
+///     ) -> RateLimitFuture<'a, RateLimitDecision> {
+///         Box::pin(async move { self.update_json(key, |state| state.hit(policy, now_millis())).await })
+///     }
+/// }
+/// ```
+pub trait RateLimitStore: Send + Sync + 'static {
+    /// Counts a request against `key` and decides whether it is allowed.
+    fn hit<'a>(
+        &'a self,
+        key: &'a str,
+        policy: &'a RateLimitPolicy,
+    ) -> RateLimitFuture<'a, RateLimitDecision>;
+}
+
+#[derive(Debug, Error)]
+pub enum RateLimitError {
+    #[error("Rate limit store failed: {0}")]
+    Store(Box<dyn StdError + Send + Sync>),
+
+    #[error("{0}")]
+    Config(String),
+}
+
+impl RateLimitError {
+    /// Wraps an error of a custom store.
+    pub fn store(err: impl Into<Box<dyn StdError + Send + Sync>>) -> Self {
+        Self::Store(err.into())
+    }
+}
+
+/// The outcome of counting a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    /// Whether the request is within the limit.
+    pub allowed: bool,
+
+    /// Requests allowed per window.
+    pub limit: u64,
+
+    /// Requests left before the limit is reached.
+    pub remaining: u64,
+
+    /// Length of the window.
+    pub window: Duration,
+
+    /// Time until the quota is fully available again.
+    pub reset: Duration,
+
+    /// Time until a rejected request can be retried.
+    pub retry_after: Option<Duration>,
+}
+
+impl RateLimitDecision {
+    /// The `RateLimit-*` headers describing the decision, plus `Retry-After`
+    /// when the request is rejected. Durations are rounded up to seconds.
+    pub fn headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = vec![
+            (
+                "ratelimit-policy",
+                format!("{};w={}", self.limit, seconds(self.window)),
+            ),
+            ("ratelimit-limit", self.limit.to_string()),
+            ("ratelimit-remaining", self.remaining.to_string()),
+            ("ratelimit-reset", seconds(self.reset).to_string()),
+        ];
+
+        if let Some(retry_after) = self.retry_after {
+            headers.push(("retry-after", seconds(retry_after).max(1).to_string()));
+        }
+
+        headers
+    }
+}
+
+fn seconds(duration: Duration) -> u64 {
+    duration.as_millis().div_ceil(1000) as u64
+}
+
+/// The counters of one key, for either algorithm. Timestamps are
+/// milliseconds since the Unix epoch, so the state can be shared between
+/// instances.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "algorithm", rename_all = "kebab-case")]
+pub enum RateLimitState {
+    TokenBucket {
+        tokens: f64,
+        updated_at: u64,
+    },
+    SlidingWindow {
+        window_start: u64,
+        current: u64,
+        previous: u64,
+    },
+}
+
+impl RateLimitState {
+    /// The state of a key without requests.
+    pub fn new(policy: &RateLimitPolicy, now: u64) -> Self {
+        match policy.algorithm {
+            RateLimitAlgorithm::TokenBucket => Self::TokenBucket {
+                tokens: policy.limit as f64,
+                updated_at: now,
+            },
+            RateLimitAlgorithm::SlidingWindow => Self::SlidingWindow {
+                window_start: now - now % window_millis(policy),
+                current: 0,
+                previous: 0,
+            },
+        }
+    }
+
+    /// Counts a request at `now`. State of another algorithm is reset first.
+    pub fn hit(&mut self, policy: &RateLimitPolicy, now: u64) -> RateLimitDecision {
+        let matches = matches!(
+            (&*self, policy.algorithm),
+            (Self::TokenBucket { .. }, RateLimitAlgorithm::TokenBucket)
+                | (
+                    Self::SlidingWindow { .. },
+                    RateLimitAlgorithm::SlidingWindow
+                )
+        );
+
+        if !matches {
+            *self = Self::new(policy, now);
+        }
+
+        let window = window_millis(policy) as f64;
+        let limit = policy.limit as f64;
+
+        let mut decision = RateLimitDecision {
+            allowed: false,
+            limit: policy.limit,
+            remaining: 0,
+            window: policy.window.parsed,
+            reset: policy.window.parsed,
+            retry_after: Some(policy.window.parsed),
+        };
+
+        if policy.limit == 0 {
+            return decision;
+        }
+
+        match self {
+            Self::TokenBucket { tokens, updated_at } => {
+                let rate = limit / window;
+                let elapsed = now.saturating_sub(*updated_at) as f64;
+
+                *tokens = (*tokens + elapsed * rate).min(limit);
+                *updated_at = now.max(*updated_at);
+
+                decision.allowed = *tokens >= 1.0;
+
+                if decision.allowed {
+                    *tokens -= 1.0;
+                    decision.retry_after = None;
+                } else {
+                    decision.retry_after = Some(millis((1.0 - *tokens) / rate));
+                }
+
+                decision.remaining = tokens.floor() as u64;
+                decision.reset = millis((limit - *tokens) / rate);
+            }
+            Self::SlidingWindow {
+                window_start,
+                current,
+                previous,
+            } => {
+                let elapsed = now.saturating_sub(*window_start) / window as u64;
+
+                if elapsed > 0 {
+                    *previous = if elapsed == 1 { *current } else { 0 };
+                    *current = 0;
+                    *window_start += elapsed * window as u64;
+                }
+
+                let offset = now.saturating_sub(*window_start) as f64;
+                let weight = (window - offset) / window;
+                let estimated = *previous as f64 * weight + *current as f64;
+
+                decision.allowed = estimated + 1.0 <= limit;
+
+                if decision.allowed {
+                    *current += 1;
+                    decision.remaining = (limit - estimated - 1.0).floor() as u64;
+                    decision.retry_after = None;
+                } else {
+                    decision.retry_after = Some(millis(sliding_retry_after(
+                        *previous as f64,
+                        *current as f64,
+                        limit,
+                        window,
+                        offset,
+                    )));
+                }
+
+                decision.reset = millis(window - offset);
+            }
+        }
+
+        decision
+    }
+
+    /// When the state becomes equivalent to a new one, and can be dropped.
+    pub fn expires_at(&self, policy: &RateLimitPolicy) -> u64 {
+        let window = window_millis(policy);
+
+        match self {
+            Self::TokenBucket { updated_at, .. } => updated_at + window,
+            Self::SlidingWindow { window_start, .. } => window_start + 2 * window,
+        }
+    }
+}
+
+/// Milliseconds until the estimated count of a sliding window drops below
+/// the limit, either later in the current window or during the next one.
+fn sliding_retry_after(previous: f64, current: f64, limit: f64, window: f64, offset: f64) -> f64 {
+    if previous > 0.0 && current + 1.0 <= limit {
+        return (window - offset) - window * (limit - current - 1.0) / previous;
+    }
+
+    (window - offset) + window * (1.0 - (limit - 1.0) / current)
+}
+
+fn window_millis(policy: &RateLimitPolicy) -> u64 {
+    (policy.window.parsed.as_millis() as u64).max(1)
+}
+
+fn millis(value: f64) -> Duration {
+    Duration::from_millis(value.max(0.0).ceil() as u64)
+}
+
+/// The current time in milliseconds since the Unix epoch.
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_millis() as u64)
+}
+
+/// Interval between sweeps of idle keys in [`MemoryRateLimitStore`].
+const PRUNE_INTERVAL: u64 = 60_000;
+
+/// Keeps counters in process memory. Limits are not shared between instances.
+#[derive(Clone, Default)]
+pub struct MemoryRateLimitStore {
+    inner: Arc<Mutex<MemoryCounters>>,
+}
+
+#[derive(Default)]
+struct MemoryCounters {
+    states: HashMap<String, (RateLimitState, u64)>,
+    pruned_at: u64,
+}
+
+impl MemoryRateLimitStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl RateLimitStore for MemoryRateLimitStore {
+    fn hit<'a>(
+        &'a self,
+        key: &'a str,
+        policy: &'a RateLimitPolicy,
+    ) -> RateLimitFuture<'a, RateLimitDecision> {
+        Box::pin(async move {
+            let now = now_millis();
+            let mut counters = self.inner.lock().unwrap_or_else(|err| err.into_inner());
+
+            if now.saturating_sub(counters.pruned_at) >= PRUNE_INTERVAL {
+                counters
+                    .states
+                    .retain(|_, (_, expires_at)| *expires_at > now);
+                counters.pruned_at = now;
+            }
+
+            let (state, expires_at) = counters
+                .states
+                .entry(key.to_string())
+                .or_insert_with(|| (RateLimitState::new(policy, now), now));
+
+            let decision = state.hit(policy, now);
+            *expires_at = state.expires_at(policy);
+
+            Ok(decision)
+        })
+    }
+}
@@ -0,0 +1,125 @@
+use crate::DisplayConfig;
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thisconfig::TimeConfig;
+use tower_cookies::cookie::SameSite;
+
+/// Configuration of the `[web.session]` key.
+///
+/// ```toml
+/// [web.session]
+/// secret = "a-secret-of-at-least-32-bytes-long"
+/// idle-timeout = "30m"
+/// absolute-timeout = "12h"
+/// store = "file"
+/// path = "var/sessions"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// Key the session cookie is signed with. Must be at least 32 bytes long.
+    pub secret: String,
+
+    /// Name of the cookie holding the session id. Defaults to "sword.sid".
+    #[serde(rename = "cookie-name")]
+    pub cookie_name: String,
+
+    /// Time without requests after which a session expires. Defaults to "30m".
+    #[serde(rename = "idle-timeout")]
+    pub idle_timeout: TimeConfig,
+
+    /// Time after its creation at which a session expires, even if it is
+    /// still in use. Defaults to "24h".
+    #[serde(rename = "absolute-timeout")]
+    pub absolute_timeout: TimeConfig,
+
+    /// Where sessions are kept: "memory" (default), "file" or "sql".
+    pub store: SessionStoreKind,
+
+    /// Directory of the "file" store. Defaults to "sessions".
+    pub path: String,
+
+    /// Database URL of the "sql" store, e.g. "sqlite://sessions.db?mode=rwc".
+    pub url: Option<String>,
+
+    /// Whether the cookie is only sent over HTTPS. Defaults to true.
+    pub secure: bool,
+
+    /// `SameSite` attribute of the cookie: "strict", "lax" (default) or "none".
+    #[serde(rename = "same-site")]
+    pub same_site: SessionSameSite,
+
+    /// Whether to display the configuration details. Defaults to false.
+    pub display: bool,
+}
+
+/// Built-in session stores selectable from the configuration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SessionStoreKind {
+    #[default]
+    Memory,
+    File,
+    Sql,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SessionSameSite {
+    Strict,
+    #[default]
+    Lax,
+    None,
+}
+
+impl From<SessionSameSite> for SameSite {
+    fn from(value: SessionSameSite) -> Self {
+        match value {
+            SessionSameSite::Strict => SameSite::Strict,
+            SessionSameSite::Lax => SameSite::Lax,
+            SessionSameSite::None => SameSite::None,
+        }
+    }
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            secret: String::new(),
+            cookie_name: "sword.sid".to_string(),
+            idle_timeout: TimeConfig {
+                parsed: Duration::from_secs(30 * 60),
+                raw: "30m".to_string(),
+            },
+            absolute_timeout: TimeConfig {
+                parsed: Duration::from_secs(24 * 60 * 60),
+                raw: "24h".to_string(),
+            },
+            store: SessionStoreKind::Memory,
+            path: "sessions".to_string(),
+            url: None,
+            secure: true,
+            same_site: SessionSameSite::Lax,
+            display: false,
+        }
+    }
+}
+
+impl DisplayConfig for SessionConfig {
+    fn display(&self) {
+        if !self.display {
+            return;
+        }
+
+        tracing::info!(
+            target: "sword.layers.sessions",
+            cookie_name = self.cookie_name,
+            idle_timeout = self.idle_timeout.raw,
+            absolute_timeout = self.absolute_timeout.raw,
+            store = ?self.store,
+            secure = self.secure,
+            same_site = ?self.same_site,
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use super::{Session, SessionConfig, SessionError, SessionRecord, SessionStore};
+
+use axum::{
+    http::Request,
+    response::{IntoResponse, Response},
+};
+use axum_responses::JsonResponse;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, SystemTime},
+};
+use tower::{Layer, Service};
+use tower_cookies::{
+    Cookie, Cookies, Key,
+    cookie::{SameSite, time},
+};
+
+/// Loads the session of each request and saves it once the response is ready.
+///
+/// The session id is read from a signed cookie, so it must be applied inside
+/// `CookieManagerLayer`. Handlers access the session through the [`Session`]
+/// request extension.
+#[derive(Clone)]
+pub struct SessionLayer {
+    inner: Arc<SessionManager>,
+}
+
+struct SessionManager {
+    store: Arc<dyn SessionStore>,
+    key: Key,
+    cookie_name: String,
+    idle_timeout: Duration,
+    absolute_timeout: Duration,
+    secure: bool,
+    same_site: SameSite,
+}
+
+impl SessionLayer {
+    /// Creates the layer with the cookie and expiry settings of `config`,
+    /// keeping sessions in `store`.
+    pub fn new(config: &SessionConfig, store: Arc<dyn SessionStore>) -> Result<Self, SessionError> {
+        if config.secret.len() < 32 {
+            return Err(SessionError::Config(
+                "`secret` must be at least 32 bytes long".to_string(),
+            ));
+        }
+
+        let manager = SessionManager {
+            store,
+            key: Key::derive_from(config.secret.as_bytes()),
+            cookie_name: config.cookie_name.clone(),
+            idle_timeout: config.idle_timeout.parsed,
+            absolute_timeout: config.absolute_timeout.parsed,
+            secure: config.secure,
+            same_site: config.same_site.into(),
+        };
+
+        Ok(Self {
+            inner: Arc::new(manager),
+        })
+    }
+}
+
+impl<S> Layer<S> for SessionLayer {
+    type Service = SessionService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SessionService {
+            inner,
+            manager: Arc::clone(&self.inner),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SessionService<S> {
+    inner: S,
+    manager: Arc<SessionManager>,
+}
+
+type BoxFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'static>>;
+
+impl<S, B> Service<Request<B>> for SessionService<S>
+where
+    S: Service<Request<B>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    B: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<B>) -> Self::Future {
+        // The ready service is taken, leaving a fresh clone for the next call.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let manager = Arc::clone(&self.manager);
+
+        Box::pin(async move {
+            let Some(cookies) = req.extensions().get::<Cookies>().cloned() else {
+                return inner.call(req).await;
+            };
+
+            let cookie_id = cookies
+                .signed(&manager.key)
+                .get(&manager.cookie_name)
+                .map(|cookie| cookie.value().to_string());
+
+            let session = match manager.load(cookie_id.as_deref()).await {
+                Ok(session) => session,
+                Err(err) => return Ok(JsonResponse::from(err).into_response()),
+            };
+
+            req.extensions_mut().insert(session.clone());
+
+            let response = inner.call(req).await?;
+
+            match manager.commit(&session, cookie_id, &cookies).await {
+                Ok(()) => Ok(response),
+                Err(err) => Ok(JsonResponse::from(err).into_response()),
+            }
+        })
+    }
+}
+
+impl SessionManager {
+    async fn load(&self, id: Option<&str>) -> Result<Session, SessionError> {
+        let Some(id) = id else {
+            return Ok(Session::new(None, Default::default(), None));
+        };
+
+        match self.store.load(id).await? {
+            Some(record) if !record.is_expired() => Ok(Session::new(
+                Some(id.to_string()),
+                record.data,
+                Some(record.created_at),
+            )),
+            Some(_) => {
+                self.store.delete(id).await?;
+                Ok(Session::new(None, Default::default(), None))
+            }
+            None => Ok(Session::new(None, Default::default(), None)),
+        }
+    }
+
+    /// Saves the session, refreshing its idle expiry, and updates the cookie
+    /// when the id changed. Sessions without data are deleted.
+    async fn commit(
+        &self,
+        session: &Session,
+        cookie_id: Option<String>,
+        cookies: &Cookies,
+    ) -> Result<(), SessionError> {
+        let state = session.take_state();
+
+        for id in &state.stale {
+            self.store.delete(id).await?;
+        }
+
+        if state.data.is_empty() {
+            if let Some(id) = &state.id {
+                self.store.delete(id).await?;
+            }
+
+            if cookie_id.is_some() {
+                cookies.signed(&self.key).remove(self.cookie(String::new()));
+            }
+
+            return Ok(());
+        }
+
+        let now = SystemTime::now();
+        let created_at = state.created_at.unwrap_or(now);
+
+        let record = SessionRecord {
+            data: state.data,
+            created_at,
+            expires_at: (now + self.idle_timeout).min(created_at + self.absolute_timeout),
+        };
+
+        let id = state
+            .id
+            .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string());
+
+        self.store.save(&id, &record).await?;
+
+        if cookie_id.as_deref() != Some(id.as_str()) {
+            cookies.signed(&self.key).add(self.cookie(id));
+        }
+
+        Ok(())
+    }
+
+    fn cookie(&self, id: String) -> Cookie<'static> {
+        let mut cookie = Cookie::new(self.cookie_name.clone(), id);
+
+        cookie.set_path("/");
+        cookie.set_http_only(true);
+        cookie.set_secure(self.secure);
+        cookie.set_same_site(self.same_site);
+
+        if let Ok(max_age) = time::Duration::try_from(self.absolute_timeout) {
+            cookie.set_max_age(max_age);
+        }
+
+        cookie
+    }
+}
@@ -0,0 +1,22 @@
+//! Server-side sessions.
+//!
+//! Session data is kept in a [`SessionStore`] and only its id is sent to the
+//! client, in a signed cookie. Sessions expire after a period without
+//! requests (`idle-timeout`) and at the latest a fixed time after they were
+//! created (`absolute-timeout`).
+
+mod config;
+mod layer;
+mod session;
+mod store;
+
+#[cfg(feature = "sessions-sql")]
+mod sql;
+
+pub use config::*;
+pub use layer::*;
+pub use session::Session;
+pub use store::*;
+
+#[cfg(feature = "sessions-sql")]
+pub use sql::SqlStore;
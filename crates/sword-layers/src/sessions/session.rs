@@ -0,0 +1,131 @@
+use super::SessionError;
+
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+use std::{
+    mem,
+    sync::{Arc, Mutex, MutexGuard},
+    time::SystemTime,
+};
+
+/// The session of the current request.
+///
+/// Inserted as a request extension by `SessionLayer`. Changes are saved to the
+/// store once the handler returns. Clones share the same session.
+#[derive(Clone)]
+pub struct Session {
+    inner: Arc<Mutex<SessionState>>,
+}
+
+#[derive(Default)]
+pub(crate) struct SessionState {
+    /// Id of the stored session. `None` until a new session is first saved.
+    pub(crate) id: Option<String>,
+    pub(crate) data: Map<String, Value>,
+    pub(crate) created_at: Option<SystemTime>,
+
+    /// Ids whose records must be deleted, after `rotate_id` or `destroy`.
+    pub(crate) stale: Vec<String>,
+}
+
+impl Session {
+    pub(crate) fn new(
+        id: Option<String>,
+        data: Map<String, Value>,
+        created_at: Option<SystemTime>,
+    ) -> Self {
+        let state = SessionState {
+            id,
+            data,
+            created_at,
+            ..Default::default()
+        };
+
+        Self {
+            inner: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, SessionState> {
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    pub(crate) fn take_state(&self) -> SessionState {
+        mem::take(&mut *self.state())
+    }
+
+    /// Id of the session, or `None` if it hasn't been saved yet.
+    pub fn id(&self) -> Option<String> {
+        self.state().id.clone()
+    }
+
+    /// Deserializes the value stored under `key`.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, SessionError> {
+        let Some(value) = self.state().data.get(key).cloned() else {
+            return Ok(None);
+        };
+
+        serde_json::from_value(value)
+            .map(Some)
+            .map_err(|source| SessionError::Deserialize {
+                key: key.to_string(),
+                source,
+            })
+    }
+
+    /// Stores `value` under `key`, replacing any previous value.
+    pub fn insert<T: Serialize>(&self, key: &str, value: T) -> Result<(), SessionError> {
+        let value = serde_json::to_value(value).map_err(|source| SessionError::Serialize {
+            key: key.to_string(),
+            source,
+        })?;
+
+        self.state().data.insert(key.to_string(), value);
+
+        Ok(())
+    }
+
+    /// Removes the value stored under `key` and returns it.
+    pub fn remove<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, SessionError> {
+        let value = self.state().data.remove(key);
+
+        value
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|source| SessionError::Deserialize {
+                key: key.to_string(),
+                source,
+            })
+    }
+
+    /// Whether `key` holds a value.
+    pub fn contains(&self, key: &str) -> bool {
+        self.state().data.contains_key(key)
+    }
+
+    /// Moves the data to a new session id and deletes the old one.
+    ///
+    /// Call it when the privilege level changes, e.g. on login, so an id
+    /// known before authentication can't be used to hijack the session.
+    pub fn rotate_id(&self) {
+        let mut state = self.state();
+
+        if let Some(id) = state.id.take() {
+            state.stale.push(id);
+        }
+    }
+
+    /// Deletes the session and clears its data, e.g. on logout.
+    ///
+    /// Values inserted afterwards start a new session with a new id.
+    pub fn destroy(&self) {
+        let mut state = self.state();
+
+        if let Some(id) = state.id.take() {
+            state.stale.push(id);
+        }
+
+        state.data.clear();
+        state.created_at = None;
+    }
+}
@@ -0,0 +1,191 @@
+use super::{SessionError, SessionFuture, SessionRecord, SessionStore};
+
+use sqlx::{
+    AnyPool, Row,
+    any::{AnyPoolOptions, install_default_drivers},
+};
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::OnceCell;
+
+/// Keeps sessions in the `sword_sessions` table of a SQLite, PostgreSQL or
+/// MySQL database. The table is created on first use.
+#[derive(Clone)]
+pub struct SqlStore {
+    pool: AnyPool,
+    dialect: Dialect,
+    migrated: Arc<OnceCell<()>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Dialect {
+    Sqlite,
+    Postgres,
+    MySql,
+}
+
+impl SqlStore {
+    /// Creates a store for `url`. Connections are opened when the store is first used.
+    pub fn new(url: &str) -> Result<Self, SessionError> {
+        let dialect = match url.split_once(':').map(|(scheme, _)| scheme) {
+            Some("sqlite") => Dialect::Sqlite,
+            Some("postgres" | "postgresql") => Dialect::Postgres,
+            Some("mysql" | "mariadb") => Dialect::MySql,
+            _ => {
+                return Err(SessionError::Config(format!(
+                    "unsupported session database URL '{url}'"
+                )));
+            }
+        };
+
+        install_default_drivers();
+
+        Ok(Self {
+            pool: AnyPoolOptions::new().connect_lazy(url)?,
+            dialect,
+            migrated: Arc::new(OnceCell::new()),
+        })
+    }
+
+    /// Uses an existing pool, e.g. the one of the application database.
+    pub fn from_pool(pool: AnyPool) -> Result<Self, SessionError> {
+        let dialect = match pool.connect_options().database_url.scheme() {
+            "sqlite" => Dialect::Sqlite,
+            "postgres" | "postgresql" => Dialect::Postgres,
+            "mysql" | "mariadb" => Dialect::MySql,
+            scheme => {
+                return Err(SessionError::Config(format!(
+                    "unsupported session database '{scheme}'"
+                )));
+            }
+        };
+
+        Ok(Self {
+            pool,
+            dialect,
+            migrated: Arc::new(OnceCell::new()),
+        })
+    }
+
+    async fn migrate(&self) -> Result<(), SessionError> {
+        self.migrated
+            .get_or_try_init(|| async {
+                sqlx::query(
+                    "CREATE TABLE IF NOT EXISTS sword_sessions (
+                        id VARCHAR(64) PRIMARY KEY,
+                        data TEXT NOT NULL,
+                        created_at BIGINT NOT NULL,
+                        expires_at BIGINT NOT NULL
+                    )",
+                )
+                .execute(&self.pool)
+                .await
+                .map(|_| ())
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    /// `$n` placeholders, or `?` for MySQL.
+    fn sql(&self, query: &str) -> String {
+        match self.dialect {
+            Dialect::MySql => (1..=4).fold(query.to_string(), |query, n| {
+                query.replace(&format!("${n}"), "?")
+            }),
+            Dialect::Sqlite | Dialect::Postgres => query.to_string(),
+        }
+    }
+}
+
+impl SessionStore for SqlStore {
+    fn load<'a>(&'a self, id: &'a str) -> SessionFuture<'a, Option<SessionRecord>> {
+        Box::pin(async move {
+            self.migrate().await?;
+
+            let query =
+                self.sql("SELECT data, created_at, expires_at FROM sword_sessions WHERE id = $1");
+
+            let Some(row) = sqlx::query(&query)
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?
+            else {
+                return Ok(None);
+            };
+
+            let data: String = row.try_get("data")?;
+
+            Ok(Some(SessionRecord {
+                data: serde_json::from_str(&data).map_err(|source| SessionError::Corrupt {
+                    path: format!("sword_sessions/{id}"),
+                    source,
+                })?,
+                created_at: from_millis(row.try_get("created_at")?),
+                expires_at: from_millis(row.try_get("expires_at")?),
+            }))
+        })
+    }
+
+    fn save<'a>(&'a self, id: &'a str, record: &'a SessionRecord) -> SessionFuture<'a, ()> {
+        Box::pin(async move {
+            self.migrate().await?;
+
+            let upsert = match self.dialect {
+                Dialect::MySql => {
+                    "ON DUPLICATE KEY UPDATE data = VALUES(data), expires_at = VALUES(expires_at)"
+                }
+                Dialect::Sqlite | Dialect::Postgres => {
+                    "ON CONFLICT (id) DO UPDATE SET data = excluded.data, expires_at = excluded.expires_at"
+                }
+            };
+
+            let query = self.sql(&format!(
+                "INSERT INTO sword_sessions (id, data, created_at, expires_at) VALUES ($1, $2, $3, $4) {upsert}"
+            ));
+
+            let data = serde_json::Value::Object(record.data.clone()).to_string();
+
+            sqlx::query(&query)
+                .bind(id)
+                .bind(data)
+                .bind(to_millis(record.created_at))
+                .bind(to_millis(record.expires_at))
+                .execute(&self.pool)
+                .await?;
+
+            // Expired rows of abandoned sessions are removed as sessions are written.
+            let query = self.sql("DELETE FROM sword_sessions WHERE expires_at <= $1");
+
+            sqlx::query(&query)
+                .bind(to_millis(SystemTime::now()))
+                .execute(&self.pool)
+                .await?;
+
+            Ok(())
+        })
+    }
+
+    fn delete<'a>(&'a self, id: &'a str) -> SessionFuture<'a, ()> {
+        Box::pin(async move {
+            self.migrate().await?;
+
+            let query = self.sql("DELETE FROM sword_sessions WHERE id = $1");
+            sqlx::query(&query).bind(id).execute(&self.pool).await?;
+
+            Ok(())
+        })
+    }
+}
+
+fn to_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+fn from_millis(millis: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(millis.max(0) as u64)
+}
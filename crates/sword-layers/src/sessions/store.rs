@@ -0,0 +1,252 @@
+use super::{SessionConfig, SessionStoreKind};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{
+    collections::HashMap,
+    future::Future,
+    io,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+use thiserror::Error;
+
+pub type SessionFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, SessionError>> + Send + 'a>>;
+
+/// The persisted state of a session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    /// Values stored by handlers, by key.
+    pub data: Map<String, Value>,
+
+    /// When the session was created. Used for the absolute timeout.
+    pub created_at: SystemTime,
+
+    /// When the session expires unless it is used again.
+    pub expires_at: SystemTime,
+}
+
+impl SessionRecord {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= SystemTime::now()
+    }
+}
+
+/// Backend that persists session records by id.
+///
+/// Implement it to keep sessions in another backend, and register the store
+/// with `SessionStoreProvider` to use it instead of the configured one.
+///
+/// ### Example
+/// ```rust,ignore
+/// impl SessionStore for RedisStore {
+///     fn load<'a>(&'a self, id: &'a str) -> SessionFuture<'a, Option<SessionRecord>> {
+///         Box::pin(async move { self.get_json(id).await })
+///     }
+///     // ...
+/// }
+/// ```
+pub trait SessionStore: Send + Sync + 'static {
+    /// Loads a record. Expired records may be returned; they are discarded by the caller.
+    fn load<'a>(&'a self, id: &'a str) -> SessionFuture<'a, Option<SessionRecord>>;
+
+    /// Creates or replaces a record.
+    fn save<'a>(&'a self, id: &'a str, record: &'a SessionRecord) -> SessionFuture<'a, ()>;
+
+    /// Deletes a record. Deleting an unknown id is not an error.
+    fn delete<'a>(&'a self, id: &'a str) -> SessionFuture<'a, ()>;
+}
+
+#[derive(Debug, Error)]
+pub enum SessionError {
+    #[error("Failed to serialize the session value of '{key}': {source}")]
+    Serialize {
+        key: String,
+        source: serde_json::Error,
+    },
+
+    #[error("Invalid session value for '{key}': {source}")]
+    Deserialize {
+        key: String,
+        source: serde_json::Error,
+    },
+
+    #[error("Failed to access '{path}': {source}")]
+    Io { path: String, source: io::Error },
+
+    #[error("Invalid session file '{path}': {source}")]
+    Corrupt {
+        path: String,
+        source: serde_json::Error,
+    },
+
+    #[cfg(feature = "sessions-sql")]
+    #[error("Session database error: {0}")]
+    Sql(#[from] sqlx::Error),
+
+    #[error("Invalid session configuration: {0}")]
+    Config(String),
+}
+
+impl From<SessionError> for axum_responses::JsonResponse {
+    fn from(error: SessionError) -> Self {
+        tracing::error!(target: "sword.layers.sessions", error = %error, "Session error");
+
+        axum_responses::JsonResponse::InternalServerError().message("Failed to access the session")
+    }
+}
+
+impl SessionConfig {
+    /// Builds the store selected by `store`.
+    pub fn open_store(&self) -> Result<Arc<dyn SessionStore>, SessionError> {
+        match self.store {
+            SessionStoreKind::Memory => Ok(Arc::new(MemoryStore::new())),
+            SessionStoreKind::File => Ok(Arc::new(FileStore::new(&self.path)?)),
+
+            #[cfg(feature = "sessions-sql")]
+            SessionStoreKind::Sql => {
+                let url = self.url.as_deref().ok_or_else(|| {
+                    SessionError::Config("the \"sql\" store requires `url`".to_string())
+                })?;
+
+                Ok(Arc::new(super::SqlStore::new(url)?))
+            }
+
+            #[cfg(not(feature = "sessions-sql"))]
+            SessionStoreKind::Sql => Err(SessionError::Config(
+                "the \"sql\" store requires the `sessions-sql` feature".to_string(),
+            )),
+        }
+    }
+}
+
+/// Keeps sessions in the process memory. They are lost when the application stops.
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    records: Arc<Mutex<HashMap<String, SessionRecord>>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SessionStore for MemoryStore {
+    fn load<'a>(&'a self, id: &'a str) -> SessionFuture<'a, Option<SessionRecord>> {
+        let record = self.records.lock().unwrap().get(id).cloned();
+        Box::pin(async move { Ok(record) })
+    }
+
+    fn save<'a>(&'a self, id: &'a str, record: &'a SessionRecord) -> SessionFuture<'a, ()> {
+        let mut records = self.records.lock().unwrap();
+
+        records.retain(|_, record| !record.is_expired());
+        records.insert(id.to_string(), record.clone());
+
+        Box::pin(async { Ok(()) })
+    }
+
+    fn delete<'a>(&'a self, id: &'a str) -> SessionFuture<'a, ()> {
+        self.records.lock().unwrap().remove(id);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Keeps each session in a JSON file named after its id.
+#[derive(Clone)]
+pub struct FileStore {
+    dir: Arc<PathBuf>,
+}
+
+impl FileStore {
+    /// Uses `dir` as the session directory, creating it if needed.
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self, SessionError> {
+        let dir = dir.as_ref();
+
+        std::fs::create_dir_all(dir).map_err(|source| SessionError::Io {
+            path: dir.display().to_string(),
+            source,
+        })?;
+
+        Ok(Self {
+            dir: Arc::new(dir.to_path_buf()),
+        })
+    }
+
+    /// The file of a session. `None` for ids that aren't generated by Sword,
+    /// so they can't point outside the directory.
+    fn file(&self, id: &str) -> Option<PathBuf> {
+        let valid = !id.is_empty() && id.bytes().all(|byte| byte.is_ascii_alphanumeric());
+        valid.then(|| self.dir.join(format!("{id}.json")))
+    }
+}
+
+impl SessionStore for FileStore {
+    fn load<'a>(&'a self, id: &'a str) -> SessionFuture<'a, Option<SessionRecord>> {
+        Box::pin(async move {
+            let Some(path) = self.file(id) else {
+                return Ok(None);
+            };
+
+            let contents = match tokio::fs::read(&path).await {
+                Ok(contents) => contents,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+                Err(source) => return Err(io_error(&path, source)),
+            };
+
+            serde_json::from_slice(&contents)
+                .map(Some)
+                .map_err(|source| SessionError::Corrupt {
+                    path: path.display().to_string(),
+                    source,
+                })
+        })
+    }
+
+    fn save<'a>(&'a self, id: &'a str, record: &'a SessionRecord) -> SessionFuture<'a, ()> {
+        Box::pin(async move {
+            let Some(path) = self.file(id) else {
+                return Err(SessionError::Config(format!("invalid session id '{id}'")));
+            };
+
+            let contents = serde_json::to_vec(record).map_err(|source| SessionError::Corrupt {
+                path: path.display().to_string(),
+                source,
+            })?;
+
+            // Written next to the target and renamed, so readers never see a partial file.
+            let temp = path.with_extension("json.tmp");
+
+            tokio::fs::write(&temp, contents)
+                .await
+                .map_err(|source| io_error(&temp, source))?;
+
+            tokio::fs::rename(&temp, &path)
+                .await
+                .map_err(|source| io_error(&path, source))
+        })
+    }
+
+    fn delete<'a>(&'a self, id: &'a str) -> SessionFuture<'a, ()> {
+        Box::pin(async move {
+            let Some(path) = self.file(id) else {
+                return Ok(());
+            };
+
+            match tokio::fs::remove_file(&path).await {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => Err(io_error(&path, err)),
+                _ => Ok(()),
+            }
+        })
+    }
+}
+
+fn io_error(path: &Path, source: io::Error) -> SessionError {
+    SessionError::Io {
+        path: path.display().to_string(),
+        source,
+    }
+}
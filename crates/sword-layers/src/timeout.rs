@@ -4,14 +4,13 @@
 //! `tower_http::timeout::TimeoutLayer`, and a response mapper that rewrites
 //! timeout responses into Sword's standardized JSON format.
 
-use crate::{MapResponseLayer, ResponseFnMapper};
+use crate::{LayerError, MapResponseLayer, ResponseFnMapper};
 
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
 };
 
-use axum_responses::JsonResponse;
 use tower::ServiceBuilder;
 pub use tower_http::timeout::TimeoutLayer;
 use tower_layer::{Identity, Stack};
@@ -45,7 +44,7 @@ impl RequestTimeoutResponseLayer {
     pub fn new() -> ServiceBuilder<Stack<MapResponseLayer, Identity>> {
         fn timeout_mapper(response: Response) -> Response {
             if response.status().as_u16() == 408 {
-                return JsonResponse::RequestTimeout().into_response();
+                return LayerError::RequestTimeout.into_response();
             }
 
             response
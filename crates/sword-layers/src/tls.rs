@@ -0,0 +1,498 @@
+//! TLS termination with optional client certificate authentication.
+//!
+//! This module loads PEM certificates into a rustls `ServerConfig`, reloads it
+//! when the files change on disk and accepts TLS connections on a TCP listener.
+//! The identity of an authenticated client is exposed as [`PeerIdentity`].
+
+use crate::DisplayConfig;
+
+use rustls::{
+    RootCertStore, ServerConfig,
+    crypto::{CryptoProvider, ring},
+    server::{WebPkiClientVerifier, danger::ClientCertVerifier},
+};
+use rustls_pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject};
+use serde::{Deserialize, Serialize};
+use std::{
+    io,
+    net::SocketAddr,
+    path::Path,
+    sync::{Arc, RwLock, Weak},
+    task::{Context, Poll},
+    time::{Duration, SystemTime},
+};
+use thisconfig::TimeConfig;
+use thiserror::Error;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_rustls::server::TlsStream;
+use x509_parser::prelude::{FromDer, GeneralName, X509Certificate};
+
+/// Number of completed handshakes buffered before the accept loop waits for the server.
+const ACCEPT_BACKLOG: usize = 128;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsConfig {
+    /// Whether the listener terminates TLS. Defaults to false.
+    pub enabled: bool,
+
+    /// Path to the PEM certificate chain, leaf certificate first.
+    pub cert: String,
+
+    /// Path to the PEM private key (PKCS#8, PKCS#1 or SEC1).
+    pub key: String,
+
+    /// Path to the PEM bundle of CAs trusted to sign client certificates.
+    /// Setting it enables mutual TLS.
+    #[serde(rename = "client-ca")]
+    pub client_ca: Option<String>,
+
+    /// Whether clients must present a certificate when `client-ca` is set.
+    /// Defaults to "required".
+    #[serde(rename = "client-auth")]
+    pub client_auth: ClientAuth,
+
+    /// How often the certificate files are checked for changes. Defaults to "30s".
+    #[serde(rename = "reload-interval")]
+    pub reload_interval: TimeConfig,
+
+    /// Maximum time a client has to complete the handshake. Defaults to "10s".
+    #[serde(rename = "handshake-timeout")]
+    pub handshake_timeout: TimeConfig,
+
+    /// Whether to display the configuration details. Defaults to false.
+    pub display: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClientAuth {
+    /// Reject clients without a certificate signed by `client-ca`.
+    #[default]
+    Required,
+
+    /// Accept anonymous clients, but verify the certificates that are presented.
+    Optional,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cert: String::new(),
+            key: String::new(),
+            client_ca: None,
+            client_auth: ClientAuth::default(),
+            reload_interval: TimeConfig {
+                parsed: Duration::from_secs(30),
+                raw: "30s".to_string(),
+            },
+            handshake_timeout: TimeConfig {
+                parsed: Duration::from_secs(10),
+                raw: "10s".to_string(),
+            },
+            display: false,
+        }
+    }
+}
+
+impl DisplayConfig for TlsConfig {
+    fn display(&self) {
+        if !self.display {
+            return;
+        }
+
+        tracing::info!(
+            target: "sword.layers.tls",
+            enabled = self.enabled,
+            cert = self.cert,
+            key = self.key,
+            client_ca = self.client_ca.as_deref().unwrap_or("none"),
+            client_auth = ?self.client_auth,
+            reload_interval = self.reload_interval.raw,
+            handshake_timeout = self.handshake_timeout.raw,
+        );
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TlsError {
+    #[error("Failed to read '{path}': {source}")]
+    Read { path: String, source: io::Error },
+
+    #[error("Invalid PEM in '{path}': {source}")]
+    Pem {
+        path: String,
+        source: rustls_pki_types::pem::Error,
+    },
+
+    #[error("No certificates found in '{path}'")]
+    NoCertificates { path: String },
+
+    #[error("Invalid client CA: {0}")]
+    ClientCa(#[from] rustls::server::VerifierBuilderError),
+
+    #[error("Invalid TLS configuration: {0}")]
+    Rustls(#[from] rustls::Error),
+
+    #[error("Listener error: {0}")]
+    Listener(#[from] io::Error),
+}
+
+/// Accepts TLS connections with a server configuration that follows the
+/// certificate files on disk.
+///
+/// Cloning is cheap; clones share the current configuration.
+#[derive(Clone)]
+pub struct TlsAcceptor {
+    config: Arc<TlsConfig>,
+    alpn: Arc<[Vec<u8>]>,
+    current: Arc<RwLock<Arc<ServerConfig>>>,
+}
+
+impl TlsAcceptor {
+    /// Loads the certificates of `config`, advertising `alpn` protocols in
+    /// order of preference (e.g. `[b"h2", b"http/1.1"]`).
+    pub fn new(config: &TlsConfig, alpn: &[&[u8]]) -> Result<Self, TlsError> {
+        let alpn: Arc<[Vec<u8>]> = alpn.iter().map(|protocol| protocol.to_vec()).collect();
+        let server_config = build_server_config(config, &alpn)?;
+
+        Ok(Self {
+            config: Arc::new(config.clone()),
+            alpn,
+            current: Arc::new(RwLock::new(Arc::new(server_config))),
+        })
+    }
+
+    /// The configuration used for new handshakes.
+    pub fn server_config(&self) -> Arc<ServerConfig> {
+        self.current
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Reloads the certificate files. On error the previous configuration is kept.
+    pub fn reload(&self) -> Result<(), TlsError> {
+        let server_config = build_server_config(&self.config, &self.alpn)?;
+
+        *self
+            .current
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(server_config);
+
+        Ok(())
+    }
+
+    /// Runs the server side of a handshake within the configured timeout.
+    pub async fn accept<IO>(&self, stream: IO) -> io::Result<TlsStream<IO>>
+    where
+        IO: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    {
+        let acceptor = tokio_rustls::TlsAcceptor::from(self.server_config());
+        let timeout = self.config.handshake_timeout.parsed;
+
+        tokio::time::timeout(timeout, acceptor.accept(stream))
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "TLS handshake timed out"))?
+    }
+
+    /// Spawns a task that reloads the certificates when their modification time
+    /// changes. It stops once every clone of the acceptor is dropped.
+    pub fn watch(&self) {
+        let current = Arc::downgrade(&self.current);
+        let config = self.config.clone();
+        let alpn = self.alpn.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(config.reload_interval.parsed);
+            let mut modified = files_modified(&config);
+
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+
+                let Some(current) = Weak::upgrade(&current) else {
+                    break;
+                };
+
+                let latest = files_modified(&config);
+
+                if latest == modified {
+                    continue;
+                }
+
+                modified = latest;
+
+                let acceptor = TlsAcceptor {
+                    config: config.clone(),
+                    alpn: alpn.clone(),
+                    current,
+                };
+
+                match acceptor.reload() {
+                    Ok(()) => tracing::info!(
+                        target: "sword.layers.tls",
+                        cert = config.cert,
+                        "Reloaded TLS certificates"
+                    ),
+                    Err(error) => tracing::warn!(
+                        target: "sword.layers.tls",
+                        cert = config.cert,
+                        error = %error,
+                        "Failed to reload TLS certificates, keeping the previous ones"
+                    ),
+                }
+            }
+        });
+    }
+
+    /// Accepts TCP connections on `listener` and completes their handshakes in
+    /// the background, so a slow client can't hold back the others.
+    ///
+    /// Listeners bound from clones of one acceptor share its configuration, so
+    /// a single [`TlsAcceptor::watch`] call reloads all of them.
+    pub fn bind(self, listener: TcpListener) -> Result<TlsListener, TlsError> {
+        let local_addr = listener.local_addr()?;
+        let (sender, receiver) = mpsc::channel(ACCEPT_BACKLOG);
+
+        tokio::spawn(async move {
+            loop {
+                let accepted = tokio::select! {
+                    accepted = listener.accept() => accepted,
+                    _ = sender.closed() => break,
+                };
+
+                let (stream, addr) = match accepted {
+                    Ok(accepted) => accepted,
+                    Err(error) => {
+                        tracing::error!(target: "sword.layers.tls", error = %error, "Failed to accept TCP connection");
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        continue;
+                    }
+                };
+
+                let acceptor = self.clone();
+                let sender = sender.clone();
+
+                tokio::spawn(async move {
+                    match acceptor.accept(stream).await {
+                        Ok(stream) => {
+                            let _ = sender.send((stream, addr)).await;
+                        }
+                        Err(error) => tracing::debug!(
+                            target: "sword.layers.tls",
+                            peer = %addr,
+                            error = %error,
+                            "TLS handshake failed"
+                        ),
+                    }
+                });
+            }
+        });
+
+        Ok(TlsListener {
+            receiver,
+            local_addr,
+        })
+    }
+}
+
+/// Connections that completed a TLS handshake, produced by [`TlsAcceptor::bind`].
+pub struct TlsListener {
+    receiver: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+    local_addr: SocketAddr,
+}
+
+impl TlsListener {
+    /// Waits for the next established connection. Returns `None` once the
+    /// accept loop has stopped.
+    pub async fn accept(&mut self) -> Option<(TlsStream<TcpStream>, SocketAddr)> {
+        self.receiver.recv().await
+    }
+
+    /// Polls for the next established connection, for use in `Stream` adapters.
+    pub fn poll_accept(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<(TlsStream<TcpStream>, SocketAddr)>> {
+        self.receiver.poll_recv(cx)
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+/// The certificate chain a client authenticated with.
+///
+/// Inserted as a request extension on connections that presented a client
+/// certificate, so interceptors can authorize callers by identity.
+#[derive(Debug, Clone)]
+pub struct PeerIdentity {
+    certificates: Arc<[CertificateDer<'static>]>,
+    subject: String,
+    common_name: Option<String>,
+    dns_names: Vec<String>,
+}
+
+impl PeerIdentity {
+    /// Reads the client certificates of an established connection.
+    pub fn from_stream<IO>(stream: &TlsStream<IO>) -> Option<Self> {
+        let certificates = stream.get_ref().1.peer_certificates()?;
+        Self::from_certificates(certificates)
+    }
+
+    /// Builds an identity from a certificate chain, leaf certificate first.
+    pub fn from_certificates(certificates: &[CertificateDer<'_>]) -> Option<Self> {
+        let (_, leaf) = X509Certificate::from_der(certificates.first()?).ok()?;
+
+        let common_name = leaf
+            .subject()
+            .iter_common_name()
+            .next()
+            .and_then(|name| name.as_str().ok())
+            .map(ToString::to_string);
+
+        let dns_names = leaf
+            .subject_alternative_name()
+            .ok()
+            .flatten()
+            .map(|extension| {
+                extension
+                    .value
+                    .general_names
+                    .iter()
+                    .filter_map(|name| match name {
+                        GeneralName::DNSName(name) => Some(name.to_string()),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            subject: leaf.subject().to_string(),
+            common_name,
+            dns_names,
+            certificates: certificates
+                .iter()
+                .map(|certificate| certificate.clone().into_owned())
+                .collect(),
+        })
+    }
+
+    /// The presented chain in DER, leaf certificate first.
+    pub fn certificates(&self) -> &[CertificateDer<'static>] {
+        &self.certificates
+    }
+
+    /// Distinguished name of the leaf certificate (e.g. `CN=worker, O=Acme`).
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// Common name of the leaf certificate subject.
+    pub fn common_name(&self) -> Option<&str> {
+        self.common_name.as_deref()
+    }
+
+    /// DNS names of the leaf certificate subject alternative name extension.
+    pub fn dns_names(&self) -> &[String] {
+        &self.dns_names
+    }
+}
+
+fn build_server_config(config: &TlsConfig, alpn: &[Vec<u8>]) -> Result<ServerConfig, TlsError> {
+    let provider = Arc::new(ring::default_provider());
+
+    let certificates = read_certificates(&config.cert)?;
+    let key = PrivateKeyDer::from_pem_slice(&read_file(&config.key)?).map_err(|source| {
+        TlsError::Pem {
+            path: config.key.clone(),
+            source,
+        }
+    })?;
+
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()?;
+
+    let mut server_config = match &config.client_ca {
+        Some(client_ca) => builder
+            .with_client_cert_verifier(client_verifier(client_ca, config.client_auth, provider)?)
+            .with_single_cert(certificates, key)?,
+        None => builder
+            .with_no_client_auth()
+            .with_single_cert(certificates, key)?,
+    };
+
+    server_config.alpn_protocols = alpn.to_vec();
+
+    Ok(server_config)
+}
+
+fn client_verifier(
+    path: &str,
+    client_auth: ClientAuth,
+    provider: Arc<CryptoProvider>,
+) -> Result<Arc<dyn ClientCertVerifier>, TlsError> {
+    let mut roots = RootCertStore::empty();
+
+    for certificate in read_certificates(path)? {
+        roots.add(certificate)?;
+    }
+
+    let builder = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider);
+
+    let verifier = match client_auth {
+        ClientAuth::Required => builder.build()?,
+        ClientAuth::Optional => builder.allow_unauthenticated().build()?,
+    };
+
+    Ok(verifier)
+}
+
+fn read_certificates(path: &str) -> Result<Vec<CertificateDer<'static>>, TlsError> {
+    let certificates = CertificateDer::pem_slice_iter(&read_file(path)?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|source| TlsError::Pem {
+            path: path.to_string(),
+            source,
+        })?;
+
+    if certificates.is_empty() {
+        return Err(TlsError::NoCertificates {
+            path: path.to_string(),
+        });
+    }
+
+    Ok(certificates)
+}
+
+fn read_file(path: &str) -> Result<Vec<u8>, TlsError> {
+    std::fs::read(path).map_err(|source| TlsError::Read {
+        path: path.to_string(),
+        source,
+    })
+}
+
+fn files_modified(config: &TlsConfig) -> Vec<Option<SystemTime>> {
+    [
+        Some(&config.cert),
+        Some(&config.key),
+        config.client_ca.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|path| {
+        Path::new(path)
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    })
+    .collect()
+}
@@ -0,0 +1,181 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll},
+};
+
+use tower::{Layer, Service};
+use tracing::{
+    Dispatch, Event, Level, Subscriber,
+    field::{Field, Visit},
+    instrument::{WithDispatch, WithSubscriber},
+};
+use tracing_subscriber::{EnvFilter, Registry, layer::Context as LayerContext, prelude::*};
+
+/// A single event recorded by a [`TracingCapture`].
+#[derive(Debug, Clone)]
+pub struct CapturedEvent {
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+    pub fields: BTreeMap<String, String>,
+}
+
+/// In-memory tracing sink scoped to a single `Dispatch`.
+///
+/// The capture is never installed as the global subscriber. Code that should be
+/// recorded must run with [`TracingCapture::dispatch`] as its default, either through
+/// `tracing::dispatcher::with_default` or by wrapping a service with [`DispatchLayer`].
+#[derive(Clone)]
+pub struct TracingCapture {
+    events: Arc<Mutex<Vec<CapturedEvent>>>,
+    dispatch: Dispatch,
+}
+
+impl TracingCapture {
+    pub fn new(filter: EnvFilter) -> Self {
+        let events = Arc::new(Mutex::new(Vec::new()));
+
+        let subscriber = Registry::default().with(
+            CaptureLayer {
+                events: Arc::clone(&events),
+            }
+            .with_filter(filter),
+        );
+
+        Self {
+            events,
+            dispatch: Dispatch::new(subscriber),
+        }
+    }
+
+    pub fn dispatch(&self) -> &Dispatch {
+        &self.dispatch
+    }
+
+    /// Returns a snapshot of every event recorded so far.
+    pub fn events(&self) -> Vec<CapturedEvent> {
+        self.lock().clone()
+    }
+
+    /// Returns `true` if any recorded event message contains `message`.
+    pub fn contains(&self, message: &str) -> bool {
+        self.lock()
+            .iter()
+            .any(|event| event.message.contains(message))
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<CapturedEvent>> {
+        self.events.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl fmt::Debug for TracingCapture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TracingCapture")
+            .field("events", &self.lock().len())
+            .finish()
+    }
+}
+
+struct CaptureLayer {
+    events: Arc<Mutex<Vec<CapturedEvent>>>,
+}
+
+impl<S: Subscriber> tracing_subscriber::Layer<S> for CaptureLayer {
+    fn on_event(&self, event: &Event<'_>, _: LayerContext<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+
+        self.events
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(CapturedEvent {
+                level: *metadata.level(),
+                target: metadata.target().to_owned(),
+                message: visitor.message,
+                fields: visitor.fields,
+            });
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: BTreeMap<String, String>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_owned();
+        } else {
+            self.fields
+                .insert(field.name().to_owned(), value.to_owned());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        } else {
+            self.fields
+                .insert(field.name().to_owned(), format!("{value:?}"));
+        }
+    }
+}
+
+/// Runs every request future of the wrapped service with the given `Dispatch`
+/// as its default subscriber.
+#[derive(Clone)]
+pub struct DispatchLayer {
+    dispatch: Dispatch,
+}
+
+impl DispatchLayer {
+    pub fn new(dispatch: Dispatch) -> Self {
+        Self { dispatch }
+    }
+}
+
+impl<S> Layer<S> for DispatchLayer {
+    type Service = DispatchService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DispatchService {
+            inner,
+            dispatch: self.dispatch.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct DispatchService<S> {
+    inner: S,
+    dispatch: Dispatch,
+}
+
+impl<S, R> Service<R> for DispatchService<S>
+where
+    S: Service<R>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = WithDispatch<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        tracing::dispatcher::with_default(&self.dispatch, || self.inner.call(req))
+            .with_subscriber(self.dispatch.clone())
+    }
+}
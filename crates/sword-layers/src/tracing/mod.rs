@@ -1,3 +1,4 @@
+mod capture;
 mod config;
 mod dev;
 mod time;
@@ -10,6 +11,7 @@ use tracing_subscriber::{
     layer::{Layer, SubscriberExt},
 };
 
+pub use capture::*;
 pub use config::*;
 
 type BoxLayer = Box<dyn Layer<Registry> + Send + Sync + 'static>;
@@ -6,8 +6,14 @@ pub mod grpc;
 #[cfg(feature = "socketio-controllers")]
 pub mod socketio;
 
-#[cfg(feature = "socketio-controllers")]
-pub use socketio::expand_on_handler;
+#[cfg(feature = "websocket-controllers")]
+pub mod websocket;
+
+#[cfg(any(feature = "socketio-controllers", feature = "websocket-controllers"))]
+mod on_handler;
+
+#[cfg(any(feature = "socketio-controllers", feature = "websocket-controllers"))]
+pub use on_handler::expand_on_handler;
 
 #[cfg(feature = "web-controllers")]
 pub mod web;
@@ -34,6 +40,11 @@ pub fn expand_controller(attr: TokenStream, item: TokenStream) -> syn::Result<To
 
         #[cfg(feature = "grpc-controllers")]
         ParsedControllerKind::Grpc { .. } => grpc::expand_grpc_controller(&parsed_input)?,
+
+        #[cfg(feature = "websocket-controllers")]
+        ParsedControllerKind::WebSocket { .. } => {
+            websocket::expand_websocket_controller(&parsed_input)?
+        }
     };
 
     let builder = proc_macro2::TokenStream::from(builder);
@@ -0,0 +1,19 @@
+use super::shared::CMetaStack;
+use proc_macro::TokenStream;
+
+/// Expands `#[on(...)]` for the controller kind of the enclosing impl block.
+pub fn expand_on_handler(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    match CMetaStack::get("controller_kind").as_deref() {
+        #[cfg(feature = "websocket-controllers")]
+        Some("websocket") => super::websocket::expand_websocket_on_handler(attr, item),
+
+        #[cfg(feature = "socketio-controllers")]
+        _ => super::socketio::expand_socketio_on_handler(attr, item),
+
+        #[cfg(not(feature = "socketio-controllers"))]
+        _ => Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "#[on] must be used inside an impl block for a struct with #[controller(kind = Controller::WebSocket, ...)]",
+        )),
+    }
+}
@@ -1,4 +1,8 @@
-#[cfg(any(feature = "web-controllers", feature = "socketio-controllers"))]
+#[cfg(any(
+    feature = "web-controllers",
+    feature = "socketio-controllers",
+    feature = "websocket-controllers"
+))]
 mod cmeta;
 mod parse;
 
@@ -9,14 +13,24 @@ use syn::{
     spanned::Spanned,
 };
 
-#[cfg(any(feature = "web-controllers", feature = "socketio-controllers"))]
+#[cfg(any(
+    feature = "web-controllers",
+    feature = "socketio-controllers",
+    feature = "websocket-controllers"
+))]
 pub(crate) use cmeta::CMetaStack;
 pub(crate) use parse::ControllerStruct;
 
+#[cfg(feature = "web-controllers")]
+use crate::controllers::web::RouteOption;
+#[cfg(feature = "web-controllers")]
+use std::time::Duration;
+
 pub enum ControllerKind {
     Web,
     SocketIo,
     Grpc,
+    WebSocket,
 }
 
 #[derive(Default)]
@@ -25,17 +39,30 @@ pub struct ControllerArgs {
     pub path: Option<LitStr>,
     pub namespace: Option<LitStr>,
     pub service: Option<Path>,
+    pub body_limit: Option<LitStr>,
+    pub timeout: Option<LitStr>,
+    pub rate_limit: Option<LitStr>,
+    pub version: Option<LitStr>,
 }
 
 pub enum ParsedControllerKind {
     #[cfg(feature = "web-controllers")]
-    Web { path: String },
+    Web {
+        path: String,
+        body_limit: Option<RouteOption<usize>>,
+        timeout: Option<RouteOption<Duration>>,
+        rate_limit: Option<String>,
+        version: Option<String>,
+    },
 
     #[cfg(feature = "socketio-controllers")]
     SocketIo { namespace: String },
 
     #[cfg(feature = "grpc-controllers")]
     Grpc { service: Path },
+
+    #[cfg(feature = "websocket-controllers")]
+    WebSocket { path: String },
 }
 
 // Try to parse from a path like `Controller::Web` or `Controller::SocketIo`.
@@ -56,9 +83,10 @@ impl Parse for ControllerKind {
             "Web" => Ok(Self::Web),
             "SocketIo" => Ok(Self::SocketIo),
             "Grpc" => Ok(Self::Grpc),
+            "WebSocket" => Ok(Self::WebSocket),
             _ => Err(Error::new(
                 path.span(),
-                "Invalid controller kind. Expected `Web`, `SocketIo`, or `Grpc`",
+                "Invalid controller kind. Expected `Web`, `SocketIo`, `Grpc` or `WebSocket`",
             )),
         }
     }
@@ -103,6 +131,30 @@ impl Parse for ControllerArgs {
                     }
                     out.service = Some(input.parse()?);
                 }
+                "body_limit" => {
+                    if out.body_limit.is_some() {
+                        return Err(Error::new(key_span, "Duplicate argument `body_limit`"));
+                    }
+                    out.body_limit = Some(input.parse()?);
+                }
+                "timeout" => {
+                    if out.timeout.is_some() {
+                        return Err(Error::new(key_span, "Duplicate argument `timeout`"));
+                    }
+                    out.timeout = Some(input.parse()?);
+                }
+                "rate_limit" => {
+                    if out.rate_limit.is_some() {
+                        return Err(Error::new(key_span, "Duplicate argument `rate_limit`"));
+                    }
+                    out.rate_limit = Some(input.parse()?);
+                }
+                "version" => {
+                    if out.version.is_some() {
+                        return Err(Error::new(key_span, "Duplicate argument `version`"));
+                    }
+                    out.version = Some(input.parse()?);
+                }
                 _ => {
                     return Err(Error::new(key_span, "Unknown controller argument"));
                 }
@@ -131,6 +183,47 @@ impl TryFrom<ControllerArgs> for ParsedControllerKind {
             .kind
             .ok_or_else(|| Error::new(Span::call_site(), "Missing required argument `kind`"))?;
 
+        if !matches!(kind, ControllerKind::Web) {
+            if let Some(body_limit) = &args.body_limit {
+                return Err(Error::new(
+                    body_limit.span(),
+                    "`body_limit` is only valid for Web controllers",
+                ));
+            }
+
+            if let Some(timeout) = &args.timeout {
+                return Err(Error::new(
+                    timeout.span(),
+                    "`timeout` is only valid for Web controllers",
+                ));
+            }
+
+            if let Some(rate_limit) = &args.rate_limit {
+                return Err(Error::new(
+                    rate_limit.span(),
+                    "`rate_limit` is only valid for Web controllers",
+                ));
+            }
+
+            if let Some(version) = &args.version {
+                return Err(Error::new(
+                    version.span(),
+                    "`version` is only valid for Web controllers",
+                ));
+            }
+        }
+
+        if let Some(version) = &args.version {
+            let value = version.value();
+
+            if value.is_empty() || value.contains(['/', ' ']) {
+                return Err(Error::new(
+                    version.span(),
+                    "`version` must be a non-empty value without '/' or spaces, e.g. \"2\"",
+                ));
+            }
+        }
+
         match kind {
             ControllerKind::Web => {
                 if let Some(service) = args.service {
@@ -159,6 +252,7 @@ impl TryFrom<ControllerArgs> for ParsedControllerKind {
 
                 #[cfg(not(feature = "web-controllers"))]
                 {
+                    let _ = (args.body_limit, args.timeout, args.rate_limit, args.version);
                     Err(Error::new(
                         Span::call_site(),
                         "Web controllers require enabling the `web-controllers` feature",
@@ -166,7 +260,13 @@ impl TryFrom<ControllerArgs> for ParsedControllerKind {
                 }
 
                 #[cfg(feature = "web-controllers")]
-                Ok(ParsedControllerKind::Web { path })
+                Ok(ParsedControllerKind::Web {
+                    path,
+                    body_limit: args.body_limit.map(RouteOption::body_limit).transpose()?,
+                    timeout: args.timeout.map(RouteOption::timeout).transpose()?,
+                    rate_limit: args.rate_limit.as_ref().map(LitStr::value),
+                    version: args.version.as_ref().map(LitStr::value),
+                })
             }
 
             ControllerKind::SocketIo => {
@@ -235,6 +335,42 @@ impl TryFrom<ControllerArgs> for ParsedControllerKind {
                 #[cfg(feature = "grpc-controllers")]
                 Ok(ParsedControllerKind::Grpc { service })
             }
+
+            ControllerKind::WebSocket => {
+                if let Some(service) = args.service {
+                    return Err(Error::new(
+                        service.span(),
+                        "`service` is not valid for WebSocket",
+                    ));
+                }
+
+                if let Some(namespace) = args.namespace {
+                    return Err(Error::new(
+                        namespace.span(),
+                        "`namespace` is not valid for WebSocket",
+                    ));
+                }
+
+                let path = args
+                    .path
+                    .ok_or_else(|| Error::new(Span::call_site(), "WebSocket requires `path`"))?
+                    .value();
+
+                if !path.starts_with('/') {
+                    return Err(Error::new(Span::call_site(), "Path must start with '/'"));
+                }
+
+                #[cfg(not(feature = "websocket-controllers"))]
+                {
+                    Err(Error::new(
+                        Span::call_site(),
+                        "WebSocket controllers require enabling the `websocket-controllers` feature",
+                    ))
+                }
+
+                #[cfg(feature = "websocket-controllers")]
+                Ok(ParsedControllerKind::WebSocket { path })
+            }
         }
     }
 }
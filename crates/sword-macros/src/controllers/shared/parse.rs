@@ -2,13 +2,20 @@ use proc_macro::TokenStream;
 use syn::{Ident, ItemStruct, Type};
 
 use super::{ControllerArgs, ParsedControllerKind};
-use crate::{interceptor::InterceptorArgs, shared::StructFields};
+use crate::{
+    interceptor::InterceptorArgs,
+    shared::{StructFields, doc_comments},
+};
 
 pub struct ControllerStruct {
     pub name: Ident,
     pub kind: ParsedControllerKind,
     pub fields: Vec<(Ident, Type)>,
     pub interceptors: Vec<InterceptorArgs>,
+
+    /// Doc comments of the struct, used as the OpenAPI tag description.
+    #[cfg_attr(not(feature = "web-controllers"), allow(dead_code))]
+    pub docs: String,
 }
 
 impl ControllerStruct {
@@ -27,11 +34,17 @@ impl ControllerStruct {
             }
         }
 
+        let docs = match doc_comments(&input.attrs) {
+            (summary, description) if description.is_empty() => summary,
+            (summary, description) => format!("{summary}\n\n{description}"),
+        };
+
         Ok(Self {
             name: input.ident,
             kind,
             fields,
             interceptors,
+            docs,
         })
     }
 }
@@ -6,7 +6,7 @@ use generation::generate_socketio_controller_builder;
 use proc_macro::TokenStream;
 use syn::{Error, Path};
 
-pub use on_handler::expand_on_handler;
+pub use on_handler::expand_socketio_on_handler;
 
 pub fn expand_socketio_controller(input: &ControllerStruct) -> syn::Result<TokenStream> {
     let ParsedControllerKind::SocketIo { namespace } = &input.kind else {
@@ -3,7 +3,10 @@ use proc_macro::TokenStream;
 use quote::{format_ident, quote};
 use syn::{ItemFn, LitStr};
 
-pub fn expand_on_handler(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+pub fn expand_socketio_on_handler(
+    attr: TokenStream,
+    item: TokenStream,
+) -> syn::Result<TokenStream> {
     let event_lit = syn::parse::<LitStr>(attr)?;
     let event_name = event_lit.value();
     let input_fn = syn::parse::<ItemFn>(item)?;
@@ -88,8 +91,11 @@ pub fn expand_on_handler(attr: TokenStream, item: TokenStream) -> syn::Result<To
 
             socket.on(#event_name, move |ctx: ::sword::socketio::SocketContext| {
                 let controller = ::std::sync::Arc::clone(&controller);
+                let observation = ::sword::internal::socketio::observe_event(#namespace, #event_name, &ctx);
+
                 async move {
                     controller.#fn_name(ctx).await;
+                    drop(observation);
                 }
             });
         }
@@ -1,9 +1,11 @@
+use super::openapi::expand_route_openapi;
 use super::parsing::ParsedRouteAttribute;
-use crate::controllers::web::expand_web_interceptor_args;
+use crate::controllers::web::{expand_web_interceptor_args, option_tokens, option_value_tokens};
 
 use proc_macro::TokenStream as TokenStream1;
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
+use syn::LitStr;
 
 pub struct WebRouteGenerator {
     route: ParsedRouteAttribute,
@@ -106,6 +108,16 @@ impl WebRouteGenerator {
         let controller_name = &self.route.context.controller_name;
         let route_path = &self.route.path;
         let method = self.route.method.as_str();
+        let streaming = self.route.streaming;
+        let body_limit = option_value_tokens(self.route.body_limit.as_ref());
+        let timeout = option_value_tokens(self.route.timeout.as_ref());
+        let rate_limit =
+            option_tokens(self.route.rate_limit.as_ref().map(LitStr::value).as_deref());
+        let etag = match &self.route.etag {
+            Some(etag) => quote! { ::std::option::Option::Some(#etag) },
+            None => quote! { ::std::option::Option::None },
+        };
+        let openapi_registration = expand_route_openapi(&self.route);
 
         quote! {
             #[allow(non_upper_case_globals)]
@@ -115,6 +127,11 @@ impl WebRouteGenerator {
                     ::sword::internal::web::RouteRegistrar {
                         controller_id: ::std::any::TypeId::of::<#controller_ident>(),
                         path: #route_path,
+                        streaming: #streaming,
+                        body_limit: #body_limit,
+                        timeout: #timeout,
+                        rate_limit: #rate_limit,
+                        etag: #etag,
                         handler: |state: ::sword::internal::core::State| -> ::sword::internal::web::MethodRouter<::sword::internal::core::State> {
                             let controller =
                                 state.borrow::<#controller_ident>().unwrap_or_else(|err| {
@@ -133,6 +150,8 @@ impl WebRouteGenerator {
                         },
                     }
                 }
+
+                #openapi_registration
             };
         }
     }
@@ -166,14 +185,14 @@ impl HandlerCallParts {
             .enumerate()
             .map(|(i, ty)| {
                 let param_name = format_ident!("p{}", i);
-                quote! { #param_name: #ty }
+                quote! { #param_name: ::sword::internal::web::Extract<#ty> }
             })
             .collect();
 
         let call_args = (0..params.len())
             .map(|i| {
                 let param_name = format_ident!("p{}", i);
-                quote! { #param_name }
+                quote! { #param_name.0 }
             })
             .collect();
 
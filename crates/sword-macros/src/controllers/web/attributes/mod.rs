@@ -1,4 +1,5 @@
 mod generation;
+mod openapi;
 pub(crate) mod parsing;
 
 use proc_macro::TokenStream;
@@ -14,3 +15,24 @@ pub fn attribute(attribute_str: &str, attr: TokenStream, item: TokenStream) -> T
 
     WebRouteGenerator::new(parsed).expand()
 }
+
+/// Expands `#[sse(...)]`: a `GET` route that streams a long-lived response.
+pub fn sse_attribute(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut parsed = match ParsedRouteAttribute::parse("GET", attr, item) {
+        Ok(p) => p,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    if let Some(timeout) = &parsed.timeout {
+        return syn::Error::new(
+            timeout.lit.span(),
+            "`#[sse]` routes are exempt from the request timeout and don't accept `timeout`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    parsed.streaming = true;
+
+    WebRouteGenerator::new(parsed).expand()
+}
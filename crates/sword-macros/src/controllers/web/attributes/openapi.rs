@@ -0,0 +1,127 @@
+use super::parsing::ParsedRouteAttribute;
+use crate::shared::doc_comments;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, GenericArgument, LitInt, PathArguments, ReturnType, Type};
+
+/// Arguments of the `#[openapi(...)]` helper attribute on a route handler.
+#[derive(Default)]
+pub struct OpenApiRouteArgs {
+    /// Schema type of the `data` field of the success response.
+    pub response: Option<Type>,
+
+    /// Status code of the success response. Defaults to 200.
+    pub status: Option<u16>,
+
+    /// Leaves the route out of the document.
+    pub skip: bool,
+}
+
+impl OpenApiRouteArgs {
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut args = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("openapi")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("response") {
+                    args.response = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("status") {
+                    args.status = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                } else if meta.path.is_ident("skip") {
+                    args.skip = true;
+                } else {
+                    return Err(meta.error(
+                        "unknown `openapi` argument, expected `response`, `status` or `skip`",
+                    ));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(args)
+    }
+}
+
+/// Expands the OpenAPI metadata registration of a route. The expansion goes through
+/// `openapi_operation!`, which is empty unless the `openapi` feature is enabled.
+pub fn expand_route_openapi(route: &ParsedRouteAttribute) -> TokenStream {
+    if route.openapi.skip {
+        return quote! {};
+    }
+
+    let controller = syn::parse_str::<Type>(&route.context.controller_name)
+        .expect("controller name must be a valid type");
+
+    let method = route.method.as_str().to_lowercase();
+    let path = &route.path;
+    let handler = route.function.sig.ident.to_string();
+    let (summary, description) = doc_comments(&route.function.attrs);
+    let status = route.openapi.status.unwrap_or(200);
+    let response = route.openapi.response.iter();
+
+    let inputs = route
+        .function
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            syn::FnArg::Typed(pat_type) => Some(&pat_type.ty),
+            syn::FnArg::Receiver(_) => None,
+        });
+
+    let errors = error_type(&route.function.sig.output).into_iter();
+
+    let security = route
+        .context
+        .controller_interceptors
+        .iter()
+        .chain(route.interceptors.iter())
+        .filter_map(|interceptor| interceptor.sword_path());
+
+    quote! {
+        ::sword::internal::web::openapi_operation! {
+            controller: #controller,
+            method: #method,
+            path: #path,
+            handler: #handler,
+            summary: #summary,
+            description: #description,
+            status: #status,
+            response: [#(#response)*],
+            inputs: [#(#inputs),*],
+            errors: [#(#errors)*],
+            security: [#(#security),*],
+        }
+    }
+}
+
+/// Returns `E` for handlers returning `Result<T, E>` or `WebResult<T, E>`.
+fn error_type(output: &ReturnType) -> Option<&Type> {
+    let ReturnType::Type(_, ty) = output else {
+        return None;
+    };
+
+    let Type::Path(type_path) = ty.as_ref() else {
+        return None;
+    };
+
+    let segment = type_path.path.segments.last()?;
+
+    if segment.ident != "Result" && segment.ident != "WebResult" {
+        return None;
+    }
+
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+
+    args.args
+        .iter()
+        .filter_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        })
+        .nth(1)
+}
@@ -1,9 +1,15 @@
-use crate::{controllers::shared::CMetaStack, interceptor::InterceptorArgs};
+use super::openapi::OpenApiRouteArgs;
+use crate::{
+    controllers::{shared::CMetaStack, web::RouteOption},
+    interceptor::InterceptorArgs,
+};
 use proc_macro::TokenStream;
 use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::quote;
+use std::time::Duration;
+use syn::parse::{Parse, ParseStream};
 use syn::spanned::Spanned;
-use syn::{Attribute, ItemFn, LitStr, Type};
+use syn::{Attribute, Ident, ItemFn, LitBool, LitStr, Token, Type};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum RequestMode {
@@ -126,31 +132,142 @@ pub struct ParsedRouteAttribute {
 
     /// Controller metadata from CMetaStack
     pub context: WebRouteContext,
+
+    /// Arguments of the `#[openapi(...)]` helper attribute
+    pub openapi: OpenApiRouteArgs,
+
+    /// Whether the route streams a long-lived response (`#[sse]`)
+    pub streaming: bool,
+
+    /// `body_limit = "..."` option, overriding the controller and global body limit
+    pub body_limit: Option<RouteOption<usize>>,
+
+    /// `timeout = "..."` option, overriding the controller and global request timeout
+    pub timeout: Option<RouteOption<Duration>>,
+
+    /// `rate_limit = "..."` option, overriding the controller and default rate limit policy
+    pub rate_limit: Option<LitStr>,
+
+    /// `etag = true|false` option, overriding `web.etag.enabled`
+    pub etag: Option<LitBool>,
+}
+
+/// Arguments of a route attribute: `"/path"` followed by optional
+/// `body_limit = "500MB"`, `timeout = "5m"`, `rate_limit = "login"` and
+/// `etag = true` options.
+struct RouteArgs {
+    path: LitStr,
+    body_limit: Option<RouteOption<usize>>,
+    timeout: Option<RouteOption<Duration>>,
+    rate_limit: Option<LitStr>,
+    etag: Option<LitBool>,
+}
+
+impl Parse for RouteArgs {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let path = input.parse::<LitStr>()?;
+        let mut body_limit = None;
+        let mut timeout = None;
+        let mut rate_limit = None;
+        let mut etag = None;
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+
+            if input.is_empty() {
+                break;
+            }
+
+            let key = input.parse::<Ident>()?;
+            input.parse::<Token![=]>()?;
+
+            if key == "etag" {
+                if etag.is_some() {
+                    return Err(syn::Error::new(key.span(), "Duplicate argument `etag`"));
+                }
+
+                etag = Some(input.parse::<LitBool>()?);
+                continue;
+            }
+
+            let is_duplicate = match key.to_string().as_str() {
+                "body_limit" => body_limit.is_some(),
+                "timeout" => timeout.is_some(),
+                "rate_limit" => rate_limit.is_some(),
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        "Unknown route argument, expected `body_limit`, `timeout`, `rate_limit` or `etag`",
+                    ));
+                }
+            };
+
+            if is_duplicate {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("Duplicate argument `{key}`"),
+                ));
+            }
+
+            let value = input.parse::<LitStr>()?;
+
+            match key.to_string().as_str() {
+                "body_limit" => body_limit = Some(RouteOption::body_limit(value)?),
+                "timeout" => timeout = Some(RouteOption::timeout(value)?),
+                _ => rate_limit = Some(value),
+            }
+        }
+
+        Ok(Self {
+            path,
+            body_limit,
+            timeout,
+            rate_limit,
+            etag,
+        })
+    }
 }
 
 impl ParsedRouteAttribute {
     pub fn parse(method: &str, attr: TokenStream, item: TokenStream) -> syn::Result<Self> {
         let method = HttpMethod::from_attr_name(method)?;
-        let path = Self::parse_path(attr)?;
+        let RouteArgs {
+            path,
+            body_limit,
+            timeout,
+            rate_limit,
+            etag,
+        } = Self::parse_args(attr)?;
         let mut input_fn = Self::parse_function(item)?;
         let request_mode = Self::infer_request_mode(&input_fn)?;
 
         let (interceptors, retained_attrs) = Self::extract_interceptors(&input_fn)?;
-        input_fn.attrs = retained_attrs;
+        let openapi = OpenApiRouteArgs::from_attrs(&retained_attrs)?;
+
+        input_fn.attrs = retained_attrs
+            .into_iter()
+            .filter(|attr| !attr.path().is_ident("openapi"))
+            .collect();
 
         let context = Self::resolve_context(method.as_str())?;
 
         Ok(Self {
             method,
-            path,
+            path: path.value(),
             function: input_fn,
             interceptors,
             request_mode,
             context,
+            openapi,
+            streaming: false,
+            body_limit,
+            timeout,
+            rate_limit,
+            etag,
         })
     }
 
-    fn parse_path(attr: TokenStream) -> syn::Result<String> {
+    fn parse_args(attr: TokenStream) -> syn::Result<RouteArgs> {
         if attr.is_empty() {
             return Err(syn::Error::new(
                 Span::call_site(),
@@ -158,7 +275,7 @@ impl ParsedRouteAttribute {
             ));
         }
 
-        Ok(syn::parse::<LitStr>(attr)?.value())
+        syn::parse::<RouteArgs>(attr)
     }
 
     fn parse_function(item: TokenStream) -> syn::Result<ItemFn> {
@@ -188,18 +305,30 @@ impl ParsedRouteAttribute {
 
     fn infer_request_mode(input_fn: &ItemFn) -> syn::Result<RequestMode> {
         let mut mode = RequestMode::None;
-
-        for arg in &input_fn.sig.inputs {
-            let syn::FnArg::Typed(pat_type) = arg else {
-                continue;
-            };
-
+        let typed_args = input_fn
+            .sig
+            .inputs
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::FnArg::Typed(pat_type) => Some(pat_type),
+                syn::FnArg::Receiver(_) => None,
+            })
+            .collect::<Vec<_>>();
+
+        for (index, pat_type) in typed_args.iter().enumerate() {
             let arg_mode = Self::request_mode_from_type(&pat_type.ty);
 
             if arg_mode == RequestMode::None {
                 continue;
             }
 
+            if index + 1 != typed_args.len() {
+                return Err(syn::Error::new(
+                    pat_type.ty.span(),
+                    "`Request` and `StreamRequest` consume the request body and must be the last handler parameter",
+                ));
+            }
+
             if mode != RequestMode::None && mode != arg_mode {
                 return Err(syn::Error::new(
                     pat_type.ty.span(),
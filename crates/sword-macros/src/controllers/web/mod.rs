@@ -1,5 +1,6 @@
 pub mod attributes;
 mod interceptor;
+mod options;
 
 use super::shared::{CMetaStack, ControllerStruct};
 use crate::controllers::shared::ParsedControllerKind;
@@ -10,9 +11,17 @@ use quote::quote;
 use syn::Error;
 
 pub use interceptor::*;
+pub(crate) use options::{RouteOption, option_value_tokens};
 
 pub fn expand_web_controller(input: &ControllerStruct) -> syn::Result<TokenStream> {
-    let ParsedControllerKind::Web { path } = &input.kind else {
+    let ParsedControllerKind::Web {
+        path,
+        body_limit,
+        timeout,
+        rate_limit,
+        version,
+    } = &input.kind
+    else {
         return Err(Error::new(input.name.span(), "Expected a web controller"));
     };
 
@@ -20,6 +29,7 @@ pub fn expand_web_controller(input: &ControllerStruct) -> syn::Result<TokenStrea
         name: self_name,
         fields: self_fields,
         interceptors: controller_interceptors,
+        docs,
         ..
     } = input;
 
@@ -36,6 +46,11 @@ pub fn expand_web_controller(input: &ControllerStruct) -> syn::Result<TokenStrea
         serialized_controller_interceptors,
     );
 
+    let controller_name = self_name.to_string();
+    let body_limit = option_value_tokens(body_limit.as_ref());
+    let timeout = option_value_tokens(timeout.as_ref());
+    let rate_limit = option_tokens(rate_limit.as_deref());
+    let version = option_tokens(version.as_deref());
     let deps_impl = gen_deps(self_name, self_fields);
     let build_impl = gen_build(self_name, self_fields);
     let clone_impl = gen_clone(self_name, self_fields);
@@ -48,7 +63,12 @@ pub fn expand_web_controller(input: &ControllerStruct) -> syn::Result<TokenStrea
         ::sword::internal::inventory::submit! {
             ::sword::internal::web::WebControllerRegistrar {
                 controller_id: ::std::any::TypeId::of::<#self_name>(),
+                controller_name: stringify!(#self_name),
                 controller_path: #path,
+                body_limit: #body_limit,
+                timeout: #timeout,
+                rate_limit: #rate_limit,
+                version: #version,
                 build: |state: &::sword::internal::core::State| {
                     state.insert::<#self_name>(#self_name::build(state).unwrap_or_else(|e| {
                         ::sword::internal::core::sword_error! {
@@ -66,6 +86,12 @@ pub fn expand_web_controller(input: &ControllerStruct) -> syn::Result<TokenStrea
             }
         }
 
+        ::sword::internal::web::openapi_tag! {
+            controller: #self_name,
+            name: #controller_name,
+            description: #docs,
+        }
+
         impl ::sword::internal::web::WebController for #self_name {
             fn base_path() -> &'static str {
                 #path
@@ -89,3 +115,11 @@ pub fn expand_web_controller(input: &ControllerStruct) -> syn::Result<TokenStrea
 
     Ok(TokenStream::from(expanded))
 }
+
+/// Expands an optional string argument into an `Option<&'static str>` literal.
+pub(crate) fn option_tokens(value: Option<&str>) -> proc_macro2::TokenStream {
+    match value {
+        Some(value) => quote! { ::std::option::Option::Some(#value) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
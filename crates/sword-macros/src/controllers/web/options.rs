@@ -0,0 +1,64 @@
+use byte_unit::Byte;
+use proc_macro2::TokenStream;
+use quote::{ToTokens, quote};
+use std::{str::FromStr, time::Duration};
+use syn::{Error, LitStr};
+
+/// A `body_limit = "..."` or `timeout = "..."` argument, parsed when the macro
+/// expands with the same rules as `web.body-limit` and `web.request-timeout`.
+pub struct RouteOption<T> {
+    pub lit: LitStr,
+    pub value: T,
+}
+
+impl RouteOption<usize> {
+    pub fn body_limit(lit: LitStr) -> syn::Result<Self> {
+        let value = Byte::from_str(&lit.value())
+            .map_err(|err| invalid(&lit, "body_limit", err, "sizes like \"500MB\""))?
+            .as_u64() as usize;
+
+        Ok(Self { lit, value })
+    }
+}
+
+impl RouteOption<Duration> {
+    pub fn timeout(lit: LitStr) -> syn::Result<Self> {
+        let value = duration_str::parse(lit.value())
+            .map_err(|err| invalid(&lit, "timeout", err, "durations like \"5m\""))?;
+
+        Ok(Self { lit, value })
+    }
+}
+
+fn invalid(lit: &LitStr, option: &str, err: impl std::fmt::Display, hint: &str) -> Error {
+    Error::new(
+        lit.span(),
+        format!("Invalid `{option}` \"{}\", use {hint}: {err}", lit.value()),
+    )
+}
+
+impl ToTokens for RouteOption<usize> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let value = self.value;
+        tokens.extend(quote! { #value });
+    }
+}
+
+impl ToTokens for RouteOption<Duration> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let secs = self.value.as_secs();
+        let nanos = self.value.subsec_nanos();
+        tokens.extend(quote! { ::std::time::Duration::new(#secs, #nanos) });
+    }
+}
+
+/// Expands an optional route option into an `Option<T>` expression.
+pub(crate) fn option_value_tokens<T>(value: Option<&RouteOption<T>>) -> TokenStream
+where
+    RouteOption<T>: ToTokens,
+{
+    match value {
+        Some(value) => quote! { ::std::option::Option::Some(#value) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
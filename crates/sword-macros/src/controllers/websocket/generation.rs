@@ -0,0 +1,121 @@
+use crate::{
+    controllers::shared::{ControllerStruct, ParsedControllerKind},
+    shared::{gen_build, gen_clone, gen_deps},
+};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, Path};
+
+pub fn generate_websocket_controller_builder(
+    input: &ControllerStruct,
+    interceptors: &[Path],
+) -> syn::Result<TokenStream> {
+    let ParsedControllerKind::WebSocket { path } = &input.kind else {
+        return Err(Error::new_spanned(
+            &input.name,
+            "Expected a WebSocket controller struct",
+        ));
+    };
+
+    let self_name = &input.name;
+    let self_fields = &input.fields;
+    let controller_name_str = self_name.to_string();
+
+    let deps_impl = gen_deps(self_name, self_fields);
+    let build_impl = gen_build(self_name, self_fields);
+    let clone_impl = gen_clone(self_name, self_fields);
+
+    let interceptor_applications = interceptors.iter().map(|interceptor_path| {
+        quote! {
+            let interceptor = state.borrow::<#interceptor_path>()
+                .unwrap_or_else(|err| {
+                    ::sword::internal::core::sword_error!(
+                        title: "Failed to retrieve WebSocket interceptor from State",
+                        reason: err,
+                        context: {
+                            "interceptor" => stringify!(#interceptor_path),
+                        },
+                        hints: ["Ensure the interceptor is registered and built before controller setup"],
+                    )
+                });
+
+            interceptors.push(::std::sync::Arc::new(move |ctx: ::sword::websocket::WebSocketContext| {
+                let interceptor = ::std::sync::Arc::clone(&interceptor);
+
+                ::std::boxed::Box::pin(async move {
+                    <#interceptor_path as ::sword::websocket::OnUpgrade>::on_upgrade(&*interceptor, ctx)
+                        .await
+                        .map_err(::sword::internal::websocket::IntoResponse::into_response)
+                }) as ::sword::internal::websocket::UpgradeFuture
+            }));
+        }
+    });
+
+    let setup_impl = quote! {
+        #[doc(hidden)]
+        pub fn __websocket_setup(
+            state: &::sword::internal::core::State,
+        ) -> ::sword::internal::websocket::WebSocketSetup {
+            let controller = <#self_name as ::sword::internal::core::Build>::build(state).unwrap_or_else(|err| {
+                ::sword::internal::core::sword_error! {
+                    title: "Failed to build WebSocket controller",
+                    reason: err,
+                    context: {
+                        "controller" => #controller_name_str,
+                    },
+                    hints: ["Ensure all controller dependencies are registered as providers or components"],
+                }
+            });
+
+            let mut interceptors: ::std::vec::Vec<::sword::internal::websocket::UpgradeInterceptor> =
+                ::std::vec::Vec::new();
+
+            #(#interceptor_applications)*
+
+            ::sword::internal::websocket::WebSocketSetup {
+                controller: ::std::sync::Arc::new(controller),
+                interceptors,
+            }
+        }
+    };
+
+    let expanded = quote! {
+        #build_impl
+        #deps_impl
+        #clone_impl
+
+        impl ::sword::internal::websocket::WebSocketController for #self_name {
+            fn path() -> &'static str {
+                #path
+            }
+        }
+
+        impl ::sword::internal::core::ControllerSpec for #self_name {
+            fn kind() -> ::sword::internal::core::Controller {
+                ::sword::internal::core::Controller::WebSocket
+            }
+
+            fn type_id() -> ::std::any::TypeId {
+                ::std::any::TypeId::of::<Self>()
+            }
+        }
+
+        impl #self_name {
+            #setup_impl
+        }
+
+        const _: () = {
+            ::sword::internal::inventory::submit! {
+                ::sword::internal::websocket::WebSocketControllerRegistrar {
+                    controller_id: ::std::any::TypeId::of::<#self_name>(),
+                    controller_name: #controller_name_str,
+                    path: #path,
+                    setup_fn: #self_name::__websocket_setup,
+                }
+            }
+        };
+    };
+
+    Ok(expanded)
+}
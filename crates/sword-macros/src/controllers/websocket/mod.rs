@@ -0,0 +1,36 @@
+mod generation;
+mod on_handler;
+
+use super::shared::{CMetaStack, ControllerStruct, ParsedControllerKind};
+use generation::generate_websocket_controller_builder;
+use proc_macro::TokenStream;
+use syn::{Error, Path};
+
+pub use on_handler::expand_websocket_on_handler;
+
+pub fn expand_websocket_controller(input: &ControllerStruct) -> syn::Result<TokenStream> {
+    let ParsedControllerKind::WebSocket { path } = &input.kind else {
+        return Err(Error::new(
+            input.name.span(),
+            "Invalid WebSocket controller",
+        ));
+    };
+
+    let controller_name = input.name.to_string();
+
+    CMetaStack::push("controller_kind", "websocket");
+    CMetaStack::push("controller_path", path);
+    CMetaStack::push("controller_name", &controller_name);
+    CMetaStack::push("websocket_controller_name", &controller_name);
+
+    let interceptors: Vec<Path> = input
+        .interceptors
+        .iter()
+        .filter_map(|interceptor| interceptor.sword_path().cloned())
+        .collect();
+
+    Ok(TokenStream::from(generate_websocket_controller_builder(
+        input,
+        &interceptors,
+    )?))
+}
@@ -0,0 +1,84 @@
+use crate::controllers::shared::CMetaStack;
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{ItemFn, LitStr};
+
+pub fn expand_websocket_on_handler(
+    attr: TokenStream,
+    item: TokenStream,
+) -> syn::Result<TokenStream> {
+    let event_lit = syn::parse::<LitStr>(attr)?;
+    let input_fn = syn::parse::<ItemFn>(item)?;
+
+    let controller_name = CMetaStack::get("websocket_controller_name").ok_or_else(|| {
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "#[on] must be used inside an impl block for a struct with #[controller(kind = Controller::WebSocket, ...)]",
+        )
+    })?;
+
+    let event_kind = match event_lit.value().as_str() {
+        "connection" => quote! { ::sword::internal::websocket::WebSocketEvent::Connection },
+        "text" => quote! { ::sword::internal::websocket::WebSocketEvent::Text },
+        "binary" => quote! { ::sword::internal::websocket::WebSocketEvent::Binary },
+        "ping" => quote! { ::sword::internal::websocket::WebSocketEvent::Ping },
+        "close" => quote! { ::sword::internal::websocket::WebSocketEvent::Close },
+        _ => {
+            return Err(syn::Error::new(
+                event_lit.span(),
+                "Unknown WebSocket event. Expected `connection`, `text`, `binary`, `ping` or `close`",
+            ));
+        }
+    };
+
+    let fn_name = &input_fn.sig.ident;
+    let controller_ident: syn::Ident = syn::parse_str(&controller_name)?;
+
+    let registration_name = format_ident!(
+        "__SWORD_WEBSOCKET_HANDLER_{}_{}",
+        controller_name.replace("::", "_"),
+        fn_name
+    );
+
+    let expanded = quote! {
+        #input_fn
+
+        #[allow(non_upper_case_globals)]
+        #[doc(hidden)]
+        const #registration_name: () = {
+            ::sword::internal::inventory::submit! {
+                ::sword::internal::websocket::WebSocketHandlerRegistrar {
+                    controller_type_id: ::std::any::TypeId::of::<#controller_ident>(),
+                    event: #event_kind,
+                    method_name: stringify!(#fn_name),
+                    call_fn: |
+                        controller_any: ::std::sync::Arc<dyn ::std::any::Any + Send + Sync>,
+                        ctx: ::sword::websocket::WebSocketContext,
+                    | -> ::std::pin::Pin<::std::boxed::Box<dyn ::std::future::Future<Output = ()> + Send>> {
+                        let controller = controller_any
+                            .downcast::<#controller_ident>()
+                            .unwrap_or_else(|_| {
+                                ::sword::internal::core::sword_error!(
+                                    title: "Failed to downcast WebSocket controller type",
+                                    reason: format!(
+                                        "Type mismatch while executing handler for {}",
+                                        stringify!(#controller_ident)
+                                    ),
+                                    context: {
+                                        "controller" => stringify!(#controller_ident),
+                                    },
+                                    hints: ["This indicates an internal macro invariant violation"],
+                                )
+                            });
+
+                        ::std::boxed::Box::pin(async move {
+                            controller.#fn_name(ctx).await;
+                        })
+                    },
+                }
+            }
+        };
+    };
+
+    Ok(TokenStream::from(expanded))
+}
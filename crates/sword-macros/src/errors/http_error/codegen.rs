@@ -13,6 +13,7 @@ impl HttpErrorCodegen {
         variant_name: &Ident,
         fields: &Fields,
         config: &HttpErrorConfig,
+        format: Option<&TokenStream>,
     ) -> TokenStream {
         if config.transparent {
             return quote! {
@@ -22,12 +23,12 @@ impl HttpErrorCodegen {
 
         let pattern = Self::generate_pattern(enum_name, variant_name, fields);
         let tracing_stmt = Self::generate_tracing_stmt(variant_name, config, fields);
-        let builder = Self::generate_json_builder(config);
+        let builder = Self::generate_error_builder(config);
 
         quote! {
             #pattern => {
                 #tracing_stmt
-                #builder
+                ::sword::web::JsonResponse::from(#builder #format)
             },
         }
     }
@@ -55,7 +56,7 @@ impl HttpErrorCodegen {
         }
     }
 
-    pub fn generate_json_builder(config: &HttpErrorConfig) -> TokenStream {
+    pub fn generate_error_builder(config: &HttpErrorConfig) -> TokenStream {
         let status_code = config.code.as_ref().unwrap().as_u16();
 
         let message_expr = match &config.message {
@@ -71,7 +72,7 @@ impl HttpErrorCodegen {
         };
 
         let mut builder = quote! {
-            ::sword::web::JsonResponse::status(#status_code).message(#message_expr)
+            ::sword::internal::web::ResponseError::new(#status_code, #message_expr)
         };
 
         if let Some(field_name) = &config.error_field {
@@ -84,6 +85,10 @@ impl HttpErrorCodegen {
             builder = quote! { #builder.errors(&#field_ident) };
         }
 
+        if let Some(problem_type) = &config.problem_type {
+            builder = quote! { #builder.problem_type(#problem_type) };
+        }
+
         builder
     }
 
@@ -5,7 +5,9 @@ mod validate;
 pub use parse::HttpErrorConfig;
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{Data, DeriveInput, Error};
+use syn::{Data, DeriveInput, Error, Fields};
+
+use crate::errors::MessageValue;
 
 pub fn derive_http_error(input: DeriveInput) -> syn::Result<TokenStream> {
     let enum_name = &input.ident;
@@ -18,8 +20,20 @@ pub fn derive_http_error(input: DeriveInput) -> syn::Result<TokenStream> {
     };
 
     let defaults = HttpErrorConfig::from_attrs(&input.attrs)?;
+
+    let format = defaults.format.as_deref().map(|format| {
+        let format = match format {
+            "problem" => quote! { Problem },
+            _ => quote! { Json },
+        };
+
+        quote! { .format(::sword::internal::web::ErrorFormat::#format) }
+    });
+
     let mut from_arms = Vec::new();
     let mut variant_fns = Vec::new();
+    let mut openapi_responses = Vec::new();
+    let mut openapi_transparent = Vec::new();
 
     for variant in &data.variants {
         let variant_config =
@@ -38,11 +52,27 @@ pub fn derive_http_error(input: DeriveInput) -> syn::Result<TokenStream> {
             &variant.ident,
             &variant.fields,
         ));
+        match (&variant.fields, merged.transparent) {
+            (Fields::Unnamed(fields), true) => {
+                openapi_transparent.extend(fields.unnamed.first().map(|field| &field.ty));
+            }
+            _ => {
+                let status = merged.code.as_ref().map_or(500, |code| code.as_u16());
+                let message = match &merged.message {
+                    Some(MessageValue::Static(message)) => message.clone(),
+                    _ => merged.default_message(),
+                };
+
+                openapi_responses.push(quote! { (#status, #message) });
+            }
+        }
+
         from_arms.push(codegen::HttpErrorCodegen::generate_from_arm(
             enum_name,
             &variant.ident,
             &variant.fields,
             &merged,
+            format.as_ref(),
         ));
     }
 
@@ -59,7 +89,13 @@ pub fn derive_http_error(input: DeriveInput) -> syn::Result<TokenStream> {
 
         impl ::sword::internal::web::IntoResponse for #enum_name {
             fn into_response(self) -> ::sword::internal::web::AxumResponse {
-                ::sword::web::JsonResponse::from(self).into_response()
+                let mut response = ::sword::web::JsonResponse::from(self).into_response();
+
+                response
+                    .extensions_mut()
+                    .insert(::sword::internal::web::Negotiation::Optional);
+
+                response
             }
         }
 
@@ -67,5 +103,11 @@ pub fn derive_http_error(input: DeriveInput) -> syn::Result<TokenStream> {
         impl #enum_name {
             #(#variant_fns)*
         }
+
+        ::sword::internal::web::openapi_errors! {
+            error: #enum_name,
+            responses: [#(#openapi_responses),*],
+            transparent: [#(#openapi_transparent),*],
+        }
     })
 }
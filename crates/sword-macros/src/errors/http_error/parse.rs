@@ -13,6 +13,8 @@ pub struct HttpErrorConfig {
     pub error_field: Option<String>,
     pub errors_field: Option<String>,
     pub tracing_level: Option<String>,
+    pub format: Option<String>,
+    pub problem_type: Option<String>,
 }
 
 impl HttpErrorConfig {
@@ -57,6 +59,8 @@ impl HttpErrorConfig {
             tracing_level: self
                 .tracing_level
                 .or_else(|| defaults.tracing_level.clone()),
+            format: defaults.format.clone(),
+            problem_type: self.problem_type.or_else(|| defaults.problem_type.clone()),
         }
     }
 
@@ -80,16 +84,24 @@ impl HttpErrorConfig {
     }
 
     fn validate_variant(&self, ident: &Ident) -> syn::Result<()> {
+        if self.format.is_some() {
+            return Err(Error::new_spanned(
+                ident,
+                "`format` is only valid inside #[http_error(...)] on the enum",
+            ));
+        }
+
         if self.transparent
             && (self.code.is_some()
                 || self.message.is_some()
                 || self.error_field.is_some()
                 || self.errors_field.is_some()
-                || self.tracing_level.is_some())
+                || self.tracing_level.is_some()
+                || self.problem_type.is_some())
         {
             return Err(Error::new_spanned(
                 ident,
-                "`transparent` cannot be combined with `code`, `message`, `error`, `errors`, or `tracing`",
+                "`transparent` cannot be combined with `code`, `message`, `error`, `errors`, `tracing`, or `type`",
             ));
         }
 
@@ -110,6 +122,8 @@ impl HttpErrorConfig {
                 "error" => self.set_error_field(ident, &meta),
                 "errors" => self.set_errors_field(ident, &meta),
                 "tracing" => self.set_tracing_level(ident, &meta),
+                "format" => self.set_format(ident, &meta),
+                "type" => self.set_problem_type(ident, &meta),
                 other => Err(Error::new(
                     ident.span(),
                     format!("unknown attribute `{other}` for this context"),
@@ -198,6 +212,40 @@ impl HttpErrorConfig {
         ))
     }
 
+    fn set_format(&mut self, ident: &Ident, meta: &ParseNestedMeta) -> syn::Result<()> {
+        if self.format.is_some() {
+            return Err(Error::new(ident.span(), "duplicate `format` attribute"));
+        }
+
+        let format = parse_field_ident(meta)?;
+
+        match format.to_string().as_str() {
+            "json" | "problem" => {
+                self.format = Some(format.to_string());
+                Ok(())
+            }
+            _ => Err(Error::new(
+                format.span(),
+                "invalid error format, expected one of: json, problem",
+            )),
+        }
+    }
+
+    fn set_problem_type(&mut self, ident: &Ident, meta: &ParseNestedMeta) -> syn::Result<()> {
+        if self.problem_type.is_some() {
+            return Err(Error::new(ident.span(), "duplicate `type` attribute"));
+        }
+
+        if !meta.input.peek(Token![=]) {
+            return Err(Error::new(ident.span(), "expected '=' after 'type'"));
+        }
+
+        meta.input.parse::<Token![=]>()?;
+        self.problem_type = Some(meta.input.parse::<LitStr>()?.value());
+
+        Ok(())
+    }
+
     fn set_tracing_level_value(&mut self, ident: &Ident, level: String) -> syn::Result<()> {
         if self.tracing_level.is_some() {
             return Err(Error::new(ident.span(), "duplicate `tracing` attribute"));
@@ -69,9 +69,25 @@ pub fn connect(attr: TokenStream, item: TokenStream) -> TokenStream {
     controllers::web::attributes::attribute("CONNECT", attr, item)
 }
 
+/// Declares a Server-Sent Events route. It is a `GET` route that is exempt from
+/// the request timeout, since its response stays open; the handler usually
+/// returns an `SseResponse`.
+///
+/// ```rust,ignore
+/// #[sse("/events")]
+/// async fn events(&self, last_event_id: LastEventId) -> SseResponse {
+///     SseResponse::new(self.feed.subscribe_from(last_event_id.0))
+/// }
+/// ```
+#[cfg(feature = "web-controllers")]
+#[proc_macro_attribute]
+pub fn sse(attr: TokenStream, item: TokenStream) -> TokenStream {
+    controllers::web::attributes::sse_attribute(attr, item)
+}
+
 /// Defines a Sword controller.
 /// Route handlers are declared directly inside the `impl` block using method attributes
-/// such as `#[get]`, `#[post]`, `#[put]`, `#[patch]`, `#[delete]`, `#[head]`, `#[options]`, `#[trace]`, and `#[connect]`.
+/// such as `#[get]`, `#[post]`, `#[put]`, `#[patch]`, `#[delete]`, `#[head]`, `#[options]`, `#[trace]`, `#[connect]` and `#[sse]`.
 ///
 /// ### Parameters
 /// - `kind`: Controller kind. Use `Controller::Web` or `Controller::SocketIo`.
@@ -134,6 +150,32 @@ pub fn interceptor(attr: TokenStream, item: TokenStream) -> TokenStream {
     item
 }
 
+/// Adds OpenAPI details to a web route handler. Must be placed below the route
+/// attribute, like `#[interceptor]`.
+///
+/// ### Parameters
+/// - `response = <Type>`: Schema of the `data` field of the success response.
+/// - `status = <u16>`: Status code of the success response. Defaults to 200.
+/// - `skip`: Leaves the route out of the generated document.
+///
+/// Has no effect unless the `openapi` feature is enabled.
+///
+/// ### Usage
+/// ```rust,ignore
+/// /// Creates a user.
+/// #[post("/")]
+/// #[openapi(response = UserDto, status = 201)]
+/// async fn create(&self, Json(user): Json<CreateUser>) -> WebResult {
+///     Ok(JsonResponse::Created().data(user))
+/// }
+/// ```
+#[cfg(feature = "web-controllers")]
+#[proc_macro_attribute]
+pub fn openapi(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let _ = attr;
+    item
+}
+
 /// Defines a configuration struct for the application.
 /// This macro generates the necessary code to deserialize the struct from
 /// the configuration toml file.
@@ -261,6 +303,12 @@ pub fn injectable(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// - `error = <field>`: Single error field to include (optional, named fields only)
 /// - `errors = <field>`: Multiple errors field to include (optional, named fields only)
 ///
+/// **Error format:**
+/// - `format = problem` inside `#[http_error(...)]`: Renders every variant as RFC 9457
+///   Problem Details, regardless of `web.errors.format` (`format = json` forces the envelope)
+/// - `type = "<uri>"`: Problem Details `type` of the variant, taking precedence over
+///   `web.errors.types` and `web.errors.type-base`
+///
 /// **For delegation:**
 /// - `transparent`: Delegate to inner type's `From<T> for Json` (for wrapping other `HttpError` types)
 ///
@@ -687,8 +735,8 @@ pub fn main(_args: TokenStream, item: TokenStream) -> TokenStream {
     output.into()
 }
 
-#[cfg(feature = "socketio-controllers")]
-/// Unified handler attribute for Socket.IO events.
+#[cfg(any(feature = "socketio-controllers", feature = "websocket-controllers"))]
+/// Unified handler attribute for Socket.IO and WebSocket events.
 ///
 /// ### Event Types
 /// - `#[on("connection")]` - Called when a client connects
@@ -721,6 +769,23 @@ pub fn main(_args: TokenStream, item: TokenStream) -> TokenStream {
 ///     }
 /// }
 /// ```
+///
+/// ### WebSocket Events
+/// Inside a `Controller::WebSocket` controller the event is one of `connection`,
+/// `text`, `binary`, `ping` or `close`, and handlers receive `ctx: WebSocketContext`.
+///
+/// ```rust,ignore
+/// #[controller(kind = Controller::WebSocket, path = "/ws")]
+/// pub struct DevicesController { ... }
+///
+/// impl DevicesController {
+///     #[on("text")]
+///     async fn on_text(&self, ctx: WebSocketContext) {
+///         let reading: Reading = ctx.try_data().unwrap();
+///         ctx.sender().send(&Ack { id: reading.id }).ok();
+///     }
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn on(attr: TokenStream, item: TokenStream) -> TokenStream {
     controllers::expand_on_handler(attr, item).unwrap_or_else(|err| err.to_compile_error().into())
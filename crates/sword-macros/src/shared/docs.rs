@@ -0,0 +1,39 @@
+use syn::{Attribute, Expr, ExprLit, Lit, Meta};
+
+/// Splits the `///` doc comments of an item into a summary (first paragraph)
+/// and a description (the remaining paragraphs).
+pub fn doc_comments(attrs: &[Attribute]) -> (String, String) {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) => match &meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(doc), ..
+                }) => Some(doc.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| {
+            line.strip_prefix(' ')
+                .unwrap_or(&line)
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+
+    let text = lines.join("\n");
+    let text = text.trim();
+
+    match text.split_once("\n\n") {
+        Some((summary, description)) => (
+            summary.lines().map(str::trim).collect::<Vec<_>>().join(" "),
+            description.trim().to_string(),
+        ),
+        None => (
+            text.lines().map(str::trim).collect::<Vec<_>>().join(" "),
+            String::new(),
+        ),
+    }
+}
@@ -1,5 +1,7 @@
+mod docs;
 mod generation;
 mod parsing;
 
+pub use docs::*;
 pub use generation::*;
 pub use parsing::*;
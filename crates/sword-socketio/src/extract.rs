@@ -59,9 +59,12 @@ where
     pub fn try_data<T: DeserializeOwned>(&self) -> Result<T, SocketError> {
         let mut data = self.data.write().take().ok_or(ParseError::InvalidData)?;
 
-        let result: T = match self.parser() {
-            SocketIoParser::Common(parser) => parser.decode_value(&mut data, true),
-            SocketIoParser::MsgPack(parser) => parser.decode_value(&mut data, true),
+        // Message payloads carry the event name, the connect auth payload doesn't.
+        let result: T = match (self.parser(), self.event.is_some()) {
+            (SocketIoParser::Common(parser), true) => parser.decode_value(&mut data, true),
+            (SocketIoParser::Common(parser), false) => parser.decode_default(Some(&data)),
+            (SocketIoParser::MsgPack(parser), true) => parser.decode_value(&mut data, true),
+            (SocketIoParser::MsgPack(parser), false) => parser.decode_default(Some(&data)),
         }
         .map_err(SocketError::from)?;
 
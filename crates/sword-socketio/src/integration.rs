@@ -21,17 +21,28 @@ fn socketio_setup(state: &State, config: &SocketIoServerConfig) -> SocketIoLayer
     layer
 }
 
+#[cfg_attr(not(feature = "opentelemetry"), allow(unused_variables))]
 fn apply_socketio_layer(
+    state: &State,
     mut router: Router<State>,
     layer: crate::SocketIoLayer,
     config: SocketIoServerConfig,
 ) -> Router<State> {
     router = router.layer(layer);
 
+    #[cfg(feature = "opentelemetry")]
+    let telemetry = state.get::<sword_layers::opentelemetry::Telemetry>().ok();
+
     router = router.layer(axum::middleware::from_fn(
-        move |mut req: Request, next: Next| async move {
+        move |mut req: Request, next: Next| {
             req.extensions_mut().insert::<SocketIoParser>(config.parser);
-            next.run(req).await
+
+            #[cfg(feature = "opentelemetry")]
+            if let Some(telemetry) = telemetry.clone() {
+                req.extensions_mut().insert(telemetry);
+            }
+
+            next.run(req)
         },
     ));
 
@@ -78,17 +89,46 @@ fn apply_socketio_extension(
     let socketio_config = config.get_or_default::<SocketIoServerConfig>();
     let socketio_layer = socketio_setup(state, &socketio_config);
 
-    router = apply_socketio_layer(router, socketio_layer, socketio_config);
+    router = apply_socketio_layer(state, router, socketio_layer, socketio_config);
 
     let controller_map = controller_registry.read();
 
     if let Some(handlers) = controller_map.get(&Controller::SocketIo) {
         apply_socketio_controllers(state, handlers);
+
+        #[cfg(feature = "metrics")]
+        register_socketio_metrics(state, handlers);
     }
 
     router
 }
 
+#[cfg(feature = "metrics")]
+fn register_socketio_metrics(state: &State, handlers: &ControllerIds) {
+    use sword_layers::metrics::MetricsConfig;
+
+    if !state
+        .get::<MetricsConfig>()
+        .is_ok_and(|config| config.enabled)
+    {
+        return;
+    }
+
+    let Ok(io) = state.get::<socketioxide::SocketIo>() else {
+        return;
+    };
+
+    let namespaces: HashSet<&'static str> = inventory::iter::<HandlerRegistrar>()
+        .filter(|handler| handlers.contains(&handler.controller_type_id))
+        .map(|handler| handler.namespace)
+        .collect();
+
+    crate::observability::register_connected_sockets_collector(
+        io,
+        namespaces.into_iter().collect(),
+    );
+}
+
 inventory::submit! {
     WebRouterExtension {
         apply: apply_socketio_extension,
@@ -0,0 +1,50 @@
+//! Socket.IO interceptor implementation of the built-in [`JwtAuth`] interceptor.
+
+use crate::extract::SocketContext;
+use crate::interceptor::OnConnect;
+
+use serde::Deserialize;
+use socketioxide::adapter::Adapter as SocketIoSocketAdapter;
+use sword_layers::jwt::{JwtAuth, JwtError, bearer_token};
+
+/// Handshake payload sent by clients, e.g. `io({ auth: { token } })`.
+#[derive(Deserialize)]
+struct HandshakeAuth {
+    token: Option<String>,
+}
+
+/// Verifies the `token` of the handshake auth payload, or the bearer token of
+/// the handshake request `Authorization` header. The claims are inserted into
+/// the socket extensions as `JwtClaims`.
+impl<A> OnConnect<A> for JwtAuth
+where
+    A: SocketIoSocketAdapter,
+{
+    type Error = JwtError;
+
+    async fn on_connect(&self, socket: SocketContext<A>) -> Result<(), Self::Error> {
+        let token = socket
+            .try_data::<HandshakeAuth>()
+            .ok()
+            .and_then(|auth| auth.token)
+            .or_else(|| {
+                socket
+                    .socket
+                    .req_parts()
+                    .headers
+                    .get("authorization")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(bearer_token)
+                    .map(str::to_string)
+            })
+            .ok_or(JwtError::MissingToken)?;
+
+        let claims = self.verify(&token).inspect_err(|err| {
+            tracing::debug!(target: "sword.socketio.jwt", error = ?err, "Rejected bearer token");
+        })?;
+
+        socket.extensions().insert(claims);
+
+        Ok(())
+    }
+}
@@ -4,6 +4,12 @@ pub mod error;
 pub mod extract;
 mod integration;
 pub mod interceptor;
+#[cfg(feature = "jwt")]
+mod jwt;
+mod observability;
+
+#[cfg(feature = "testing")]
+pub mod testing;
 
 pub(crate) use socketioxide::layer::SocketIoLayer;
 
@@ -18,6 +24,9 @@ pub mod prelude {
     pub use crate::error::SocketError;
     pub use crate::extract::SocketContext;
     pub use crate::interceptor::OnConnect;
+
+    #[cfg(feature = "jwt")]
+    pub use sword_layers::jwt::{JwtAuth, JwtClaims};
 }
 
 #[doc(hidden)]
@@ -25,6 +34,7 @@ pub mod internal {
     pub use crate::controller::{
         HandlerRegistrar, SocketEventKind, SocketIoController, SocketIoHandlerRegistrar,
     };
+    pub use crate::observability::{EventObservation, observe_event};
     pub use socketioxide::SocketError;
     pub use socketioxide::handler::ConnectHandler;
     pub use socketioxide::handler::connect::FromConnectParts;
@@ -0,0 +1,94 @@
+use crate::extract::SocketContext;
+
+#[cfg(feature = "opentelemetry")]
+use sword_layers::opentelemetry::{OtelContext, OtelKeyValue, Telemetry, TraceContextExt};
+
+/// Guard returned by [`observe_event`].
+///
+/// When the `opentelemetry` feature is enabled it holds the server span of
+/// the event, which ends when the guard is dropped.
+#[must_use]
+pub struct EventObservation {
+    #[cfg(feature = "opentelemetry")]
+    cx: Option<OtelContext>,
+}
+
+impl Drop for EventObservation {
+    fn drop(&mut self) {
+        #[cfg(feature = "opentelemetry")]
+        if let Some(cx) = &self.cx {
+            cx.span().end();
+        }
+    }
+}
+
+/// Records a received Socket.IO event and opens its server span.
+///
+/// Called by the handlers generated by `#[on(...)]`, so the event label is
+/// always one of the statically declared event names. The span uses the
+/// `traceparent` of the handshake request as its parent context.
+#[cfg_attr(not(feature = "opentelemetry"), allow(unused_variables))]
+pub fn observe_event(
+    namespace: &'static str,
+    event: &'static str,
+    ctx: &SocketContext,
+) -> EventObservation {
+    #[cfg(feature = "metrics")]
+    sword_layers::metrics::SwordMetrics::global().record_socketio_event(namespace, event);
+
+    #[cfg(feature = "opentelemetry")]
+    let cx = ctx
+        .socket
+        .req_parts()
+        .extensions
+        .get::<Telemetry>()
+        .map(|telemetry| {
+            let handshake = ctx.socket.req_parts();
+            let parent = telemetry.extract(&handshake.headers);
+
+            let mut attributes = vec![
+                OtelKeyValue::new("messaging.system", "socket.io"),
+                OtelKeyValue::new("sword.socketio.namespace", namespace),
+                OtelKeyValue::new("sword.socketio.event", event),
+                OtelKeyValue::new("sword.socketio.sid", ctx.socket.id.to_string()),
+            ];
+
+            if let Some(request_id) = handshake
+                .headers
+                .get("x-request-id")
+                .and_then(|value| value.to_str().ok())
+            {
+                attributes.push(OtelKeyValue::new(
+                    "http.request.header.x-request-id",
+                    request_id.to_owned(),
+                ));
+            }
+
+            telemetry.start_server_span(format!("{namespace} {event}"), &parent, attributes)
+        });
+
+    EventObservation {
+        #[cfg(feature = "opentelemetry")]
+        cx,
+    }
+}
+
+/// Keeps the connected sockets gauge up to date for the given namespaces.
+#[cfg(feature = "metrics")]
+pub(crate) fn register_connected_sockets_collector(
+    io: socketioxide::SocketIo,
+    namespaces: Vec<&'static str>,
+) {
+    sword_layers::metrics::SwordMetrics::global().register_collector(move |metrics| {
+        for namespace in &namespaces {
+            let connected = io
+                .of(namespace)
+                .map_or(0, |operators| operators.sockets().len());
+
+            metrics
+                .socketio_connected_sockets
+                .with_label_values(&[namespace])
+                .set(connected as i64);
+        }
+    });
+}
@@ -0,0 +1,301 @@
+//! Minimal Socket.IO v5 client driven in memory over the Engine.IO polling transport.
+//!
+//! Requests are sent straight to the application router, so no listener or network
+//! is involved. Only the common (JSON) parser is supported.
+
+use axum::{
+    Router,
+    body::{Body, to_bytes},
+    http::{Method, Request, StatusCode},
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use tokio::time::Instant;
+use tower::ServiceExt;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+const PACKET_SEPARATOR: char = '\x1e';
+
+/// An event emitted by the server to a [`SocketIoTestClient`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SocketIoTestEvent {
+    pub event: String,
+    pub args: Vec<Value>,
+}
+
+impl SocketIoTestEvent {
+    /// First argument of the event, or `Value::Null` when it carried none.
+    pub fn data(&self) -> &Value {
+        self.args.first().unwrap_or(&Value::Null)
+    }
+}
+
+pub struct SocketIoTestClient {
+    router: Router,
+    path: String,
+    namespace: String,
+    sid: String,
+    connected: bool,
+    next_ack_id: u64,
+    events: VecDeque<SocketIoTestEvent>,
+    acks: HashMap<u64, Vec<Value>>,
+}
+
+impl SocketIoTestClient {
+    /// Opens an Engine.IO session on `path` and joins `namespace`.
+    ///
+    /// Panics if the handshake fails or the namespace rejects the connection.
+    pub async fn connect(router: Router, path: &str, namespace: &str) -> Self {
+        Self::connect_with_auth(router, path, namespace, None).await
+    }
+
+    /// Like [`SocketIoTestClient::connect`], sending `auth` as the connect payload.
+    pub async fn connect_with_auth(
+        router: Router,
+        path: &str,
+        namespace: &str,
+        auth: Option<Value>,
+    ) -> Self {
+        let path = path.trim_end_matches('/').to_owned();
+        let uri = format!("{path}/?EIO=4&transport=polling");
+
+        let (status, body) = send(&router, Method::GET, uri, None).await;
+
+        assert_eq!(status, StatusCode::OK, "socket.io handshake failed: {body}");
+
+        let open = body
+            .strip_prefix('0')
+            .and_then(|open| serde_json::from_str::<Value>(open).ok())
+            .unwrap_or_else(|| panic!("unexpected engine.io open packet: {body}"));
+
+        let sid = open["sid"]
+            .as_str()
+            .unwrap_or_else(|| panic!("engine.io open packet has no sid: {body}"))
+            .to_owned();
+
+        let mut client = Self {
+            router,
+            path,
+            namespace: namespace.to_owned(),
+            sid,
+            connected: false,
+            next_ack_id: 0,
+            events: VecDeque::new(),
+            acks: HashMap::new(),
+        };
+
+        let auth = auth.map(|auth| auth.to_string()).unwrap_or_default();
+        let packet = format!("40{}{auth}", client.nsp_prefix());
+
+        client.post(packet).await;
+
+        let deadline = Instant::now() + DEFAULT_TIMEOUT;
+
+        while !client.connected {
+            assert!(
+                client.poll_until(deadline).await,
+                "timed out joining socket.io namespace {namespace}"
+            );
+        }
+
+        client
+    }
+
+    pub fn sid(&self) -> &str {
+        &self.sid
+    }
+
+    /// Emits `event` with `data` as its single argument. `()` emits no arguments.
+    pub async fn emit<T: Serialize>(&mut self, event: &str, data: T) {
+        let packet = format!("42{}{}", self.nsp_prefix(), encode_args(event, data));
+        self.post(packet).await;
+    }
+
+    /// Emits `event` and waits for the server acknowledgement, returning its first argument.
+    pub async fn emit_with_ack<T: Serialize>(&mut self, event: &str, data: T) -> Value {
+        let id = self.next_ack_id;
+        self.next_ack_id += 1;
+
+        let packet = format!("42{}{id}{}", self.nsp_prefix(), encode_args(event, data));
+        self.post(packet).await;
+
+        let deadline = Instant::now() + DEFAULT_TIMEOUT;
+
+        loop {
+            if let Some(args) = self.acks.remove(&id) {
+                return args.into_iter().next().unwrap_or(Value::Null);
+            }
+
+            assert!(
+                self.poll_until(deadline).await,
+                "timed out waiting for ack of `{event}`"
+            );
+        }
+    }
+
+    /// Waits for the next event emitted by the server. Panics after five seconds.
+    pub async fn recv(&mut self) -> SocketIoTestEvent {
+        self.recv_timeout(DEFAULT_TIMEOUT)
+            .await
+            .expect("timed out waiting for a socket.io event")
+    }
+
+    /// Waits up to `timeout` for the next event emitted by the server.
+    pub async fn recv_timeout(&mut self, timeout: Duration) -> Option<SocketIoTestEvent> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Some(event);
+            }
+
+            if !self.poll_until(deadline).await {
+                return None;
+            }
+        }
+    }
+
+    /// Leaves the namespace and closes the Engine.IO session.
+    pub async fn disconnect(mut self) {
+        let packet = format!("41{}", self.nsp_prefix());
+
+        self.post(packet).await;
+        self.post("1".to_owned()).await;
+    }
+
+    fn nsp_prefix(&self) -> String {
+        match self.namespace.as_str() {
+            "/" => String::new(),
+            namespace => format!("{namespace},"),
+        }
+    }
+
+    fn session_uri(&self) -> String {
+        format!("{}/?EIO=4&transport=polling&sid={}", self.path, self.sid)
+    }
+
+    async fn post(&mut self, payload: String) {
+        let (status, body) = send(
+            &self.router,
+            Method::POST,
+            self.session_uri(),
+            Some(payload),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK, "socket.io post failed: {body}");
+    }
+
+    /// Runs one long-polling request, returning `false` if `deadline` passed first.
+    async fn poll_until(&mut self, deadline: Instant) -> bool {
+        let request = send(&self.router, Method::GET, self.session_uri(), None);
+
+        let Ok((status, body)) = tokio::time::timeout_at(deadline, request).await else {
+            return false;
+        };
+
+        assert_eq!(status, StatusCode::OK, "socket.io poll failed: {body}");
+
+        for packet in body.split(PACKET_SEPARATOR) {
+            match packet.split_at_checked(1) {
+                Some(("2", _)) => self.post("3".to_owned()).await,
+                Some(("4", message)) => self.handle_message(message),
+                Some(("1", _)) => panic!("socket.io session was closed by the server"),
+                _ => {}
+            }
+        }
+
+        true
+    }
+
+    fn handle_message(&mut self, message: &str) {
+        let Some((kind, rest)) = message.split_at_checked(1) else {
+            return;
+        };
+
+        let (namespace, rest) = match rest.strip_prefix('/') {
+            Some(_) => rest.split_once(',').unwrap_or((rest, "")),
+            None => ("/", rest),
+        };
+
+        if namespace != self.namespace {
+            return;
+        }
+
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let (ack_id, data) = rest.split_at(digits);
+        let ack_id = ack_id.parse::<u64>().ok();
+
+        match kind {
+            "0" => self.connected = true,
+            "1" => self.connected = false,
+            "2" => {
+                let mut args = parse_args(data);
+
+                let event = match args.first() {
+                    Some(Value::String(_)) => args.remove(0),
+                    _ => panic!("malformed socket.io event packet: {message}"),
+                };
+
+                self.events.push_back(SocketIoTestEvent {
+                    event: event.as_str().unwrap_or_default().to_owned(),
+                    args,
+                });
+            }
+            "3" => {
+                if let Some(id) = ack_id {
+                    self.acks.insert(id, parse_args(data));
+                }
+            }
+            "4" => panic!("socket.io namespace {namespace} rejected the connection: {data}"),
+            _ => panic!("unsupported socket.io packet: {message}"),
+        }
+    }
+}
+
+fn encode_args<T: Serialize>(event: &str, data: T) -> String {
+    let data = serde_json::to_value(data).expect("socket.io event data must serialize to JSON");
+
+    let args = match data {
+        Value::Null => vec![Value::from(event)],
+        data => vec![Value::from(event), data],
+    };
+
+    Value::Array(args).to_string()
+}
+
+fn parse_args(data: &str) -> Vec<Value> {
+    match serde_json::from_str(data) {
+        Ok(Value::Array(args)) => args,
+        _ => panic!("malformed socket.io packet arguments: {data}"),
+    }
+}
+
+async fn send(
+    router: &Router,
+    method: Method,
+    uri: String,
+    body: Option<String>,
+) -> (StatusCode, String) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "text/plain; charset=UTF-8")
+        .body(body.map(Body::from).unwrap_or_else(Body::empty))
+        .expect("socket.io test request must be valid");
+
+    let response = router
+        .clone()
+        .oneshot(request)
+        .await
+        .unwrap_or_else(|err| match err {});
+
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("socket.io response body must be readable");
+
+    (status, String::from_utf8_lossy(&body).into_owned())
+}
@@ -1,9 +1,25 @@
 use crate::config::WebApplicationConfig;
+use crate::listener::{
+    ConnectionExtensions, ListenerBind, ListenerConfig, RouteFilter, WebListener,
+};
 use crate::router::WebRouter;
-use axum::Router;
-use std::net::SocketAddr;
+use axum::{Router, extract::Request, serve::IncomingStream};
+use listenfd::ListenFd;
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
 use sword_core::*;
-use tokio::net::TcpListener;
+use sword_layers::not_found::ForceNotFound;
+use tokio::{net::TcpListener, sync::watch, task::JoinSet};
+use tower::ServiceExt;
+
+#[cfg(unix)]
+use tokio::net::UnixListener;
+
+#[cfg(feature = "tls")]
+use sword_layers::{DisplayConfig, tls::TlsAcceptor};
+
+/// ALPN protocols advertised by TLS listeners, HTTP/2 first.
+#[cfg(feature = "tls")]
+const ALPN_PROTOCOLS: &[&[u8]] = &[b"h2", b"http/1.1"];
 
 pub struct WebApplication {
     pub state: State,
@@ -13,21 +29,74 @@ pub struct WebApplication {
 }
 
 impl WebApplication {
+    /// Binds every configured listener and serves the router on all of them
+    /// until they stop.
     pub async fn start(&self) {
-        let bind = format!("{}:{}", self.web_config.host, self.web_config.port);
+        let shutdown = self.state.get::<GracefulShutdown>().unwrap_or_default();
+
+        if self.graceful_shutdown {
+            let shutdown = shutdown.clone();
+
+            tokio::spawn(async move {
+                shutdown_signal().await;
+                shutdown.trigger();
+            });
+        }
+
+        let mut activated = ListenFd::from_env();
+        let mut servers = JoinSet::new();
+
+        #[cfg(feature = "tls")]
+        let tls = self.tls_acceptor();
+
+        for config in self.listener_configs() {
+            let listener = self
+                .bind(
+                    &config,
+                    &mut activated,
+                    #[cfg(feature = "tls")]
+                    tls.as_ref(),
+                )
+                .await;
+
+            tracing::info!(
+                target: "sword.startup.web",
+                bind = %config.bind,
+                router_prefix = self
+                    .web_config
+                    .router_prefix
+                    .as_deref()
+                    .unwrap_or("none"),
+                routes = ?config.routes,
+                exclude_routes = ?config.exclude_routes,
+                "Starting application listener"
+            );
+
+            let shutdown = self.graceful_shutdown.then(|| shutdown.clone());
+
+            servers.spawn(serve(listener, self.router(), config, shutdown));
+        }
+
+        while let Some(result) = servers.join_next().await {
+            if let Err(err) = result
+                && err.is_panic()
+            {
+                std::panic::resume_unwind(err.into_panic());
+            }
+        }
+    }
+
+    pub fn router(&self) -> axum::Router {
+        self.router.clone().with_state(self.state.clone())
+    }
 
-        tracing::info!(
-            target: "sword.startup.web",
-            bind,
-            router_prefix = self
-                .web_config
-                .router_prefix
-                .as_deref()
-                .unwrap_or("none"),
-            "Starting application listener"
-        );
+    /// The `listeners` list, or a single listener on `host:port` when it is empty.
+    fn listener_configs(&self) -> Vec<ListenerConfig> {
+        if !self.web_config.listeners.is_empty() {
+            return self.web_config.listeners.clone();
+        }
 
-        let app = self.router.clone().with_state(self.state.clone());
+        let bind = format!("{}:{}", self.web_config.host, self.web_config.port);
 
         let bind_addr: SocketAddr = bind.parse::<SocketAddr>().unwrap_or_else(|err| {
             sword_error! {
@@ -41,52 +110,224 @@ impl WebApplication {
             }
         });
 
-        let listener = TcpListener::bind(bind_addr).await.unwrap_or_else(|err| {
+        vec![ListenerConfig::tcp(bind_addr)]
+    }
+
+    async fn bind(
+        &self,
+        config: &ListenerConfig,
+        activated: &mut ListenFd,
+        #[cfg(feature = "tls")] tls: Option<&TlsAcceptor>,
+    ) -> WebListener {
+        let bind = config.bind.to_string();
+
+        let listener = match &config.bind {
+            ListenerBind::Tcp(addr) => TcpListener::bind(addr).await.map(WebListener::Tcp),
+
+            #[cfg(unix)]
+            ListenerBind::Unix(path) => bind_unix(path, config.mode),
+
+            #[cfg(not(unix))]
+            ListenerBind::Unix(_) => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "unix sockets are not supported on this platform",
+            )),
+
+            ListenerBind::Systemd(index) => take_activated(activated, *index),
+        };
+
+        let listener = listener.unwrap_or_else(|err| {
             sword_error! {
                 title: "Failed to bind HTTP listener",
                 reason: err,
                 context: {
-                    "host" => self.web_config.host.clone(),
-                    "port" => self.web_config.port.to_string(),
+                    "bind" => bind,
+                    "source" => "WebApplication::bind",
                 },
-                hints: ["Ensure the host/port is available and not already in use"],
+                hints: [
+                    "Ensure the host/port is available and not already in use",
+                    "Sockets passed by systemd are numbered from 0 in `systemd:<index>`",
+                ],
             }
         });
 
-        if self.graceful_shutdown {
-            axum::serve(listener, app)
-                .with_graceful_shutdown(shutdown_signal())
-                .await
-                .unwrap_or_else(|err| {
+        #[cfg(feature = "tls")]
+        let listener = match (listener, tls) {
+            (WebListener::Tcp(listener), Some(tls)) if config.tls.unwrap_or(true) => {
+                WebListener::Tls(tls.clone().bind(listener).unwrap_or_else(|err| {
                     sword_error! {
-                        title: "HTTP server stopped with an internal error",
+                        title: "Failed to initialize the TLS listener",
                         reason: err,
                         context: {
-                            "mode" => "graceful_shutdown",
-                            "host" => self.web_config.host.clone(),
-                            "port" => self.web_config.port.to_string(),
+                            "bind" => config.bind.to_string(),
+                            "source" => "WebApplication::bind",
                         },
                     }
-                });
+                }))
+            }
+            (listener, _) => listener,
+        };
 
-            return;
+        listener
+    }
+
+    /// Loads `[web.tls]` once, so every TLS listener shares one certificate watcher.
+    #[cfg(feature = "tls")]
+    fn tls_acceptor(&self) -> Option<TlsAcceptor> {
+        let config = &self.web_config.tls;
+
+        config.display();
+
+        if !config.enabled {
+            return None;
         }
 
-        axum::serve(listener, app).await.unwrap_or_else(|err| {
+        let acceptor = TlsAcceptor::new(config, ALPN_PROTOCOLS).unwrap_or_else(|err| {
             sword_error! {
-                title: "HTTP server stopped with an internal error",
+                title: "Failed to initialize the TLS listener",
                 reason: err,
                 context: {
-                    "mode" => "normal",
-                    "host" => self.web_config.host.clone(),
-                    "port" => self.web_config.port.to_string(),
+                    "cert" => config.cert.clone(),
+                    "key" => config.key.clone(),
+                    "source" => "WebApplication::tls_acceptor",
                 },
+                hints: ["Ensure `web.tls.cert` and `web.tls.key` point to readable PEM files"],
+            }
+        });
+
+        acceptor.watch();
+
+        Some(acceptor)
+    }
+}
+
+async fn serve(
+    listener: WebListener,
+    router: axum::Router,
+    config: ListenerConfig,
+    shutdown: Option<GracefulShutdown>,
+) {
+    let filter = RouteFilter::new(&config);
+
+    // Every connection gets its own router service, so the peer address
+    // and the client certificate travel with each of its requests.
+    let app = tower::service_fn(move |incoming: IncomingStream<'_, WebListener>| {
+        let connection = ConnectionExtensions::from_incoming(&incoming);
+        let router = router.clone();
+        let filter = filter.clone();
+
+        let service = tower::service_fn(move |mut req: Request| {
+            connection.insert_into(&mut req);
+
+            // Routes the listener doesn't serve are answered like unknown ones,
+            // so they go through the same error rendering.
+            if !filter.allows(req.uri().path()) {
+                req.extensions_mut().insert(ForceNotFound);
             }
+
+            router.clone().oneshot(req)
         });
+
+        std::future::ready(Ok::<_, Infallible>(service))
+    });
+
+    let server = axum::serve(listener, app);
+
+    let result = match &shutdown {
+        Some(shutdown) => {
+            let shutdown = shutdown.clone();
+
+            server
+                .with_graceful_shutdown(async move { shutdown.triggered().await })
+                .await
+        }
+        None => server.await,
+    };
+
+    if let ListenerBind::Unix(path) = &config.bind {
+        let _ = std::fs::remove_file(path);
     }
 
-    pub fn router(&self) -> axum::Router {
-        self.router.clone().with_state(self.state.clone())
+    result.unwrap_or_else(|err| {
+        sword_error! {
+            title: "HTTP server stopped with an internal error",
+            reason: err,
+            context: {
+                "mode" => if shutdown.is_some() { "graceful_shutdown" } else { "normal" },
+                "bind" => config.bind.to_string(),
+            },
+        }
+    });
+}
+
+/// Binds a Unix socket, replacing a stale socket file left by a previous run.
+#[cfg(unix)]
+fn bind_unix(path: &std::path::Path, mode: Option<u32>) -> std::io::Result<WebListener> {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    if std::fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+        std::fs::remove_file(path)?;
+    }
+
+    let listener = UnixListener::bind(path)?;
+
+    if let Some(mode) = mode {
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    }
+
+    Ok(WebListener::Unix(listener))
+}
+
+/// Takes a socket passed by the service manager (`LISTEN_FDS`), TCP or Unix.
+fn take_activated(activated: &mut ListenFd, index: usize) -> std::io::Result<WebListener> {
+    let missing = || {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("no socket was passed at index {index} (LISTEN_FDS)"),
+        )
+    };
+
+    if let Ok(listener) = activated.take_tcp_listener(index) {
+        let listener = listener.ok_or_else(missing)?;
+        listener.set_nonblocking(true)?;
+
+        return TcpListener::from_std(listener).map(WebListener::Tcp);
+    }
+
+    #[cfg(unix)]
+    {
+        let listener = activated.take_unix_listener(index)?.ok_or_else(missing)?;
+        listener.set_nonblocking(true)?;
+
+        UnixListener::from_std(listener).map(WebListener::Unix)
+    }
+
+    #[cfg(not(unix))]
+    Err(missing())
+}
+
+/// Notifies long-lived responses, such as `SseResponse` streams, that the
+/// server started a graceful shutdown so they can end and let it complete.
+///
+/// Stored in the application state when the router is built.
+#[derive(Clone, Default)]
+pub struct GracefulShutdown {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl GracefulShutdown {
+    pub fn trigger(&self) {
+        self.sender.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    /// Resolves once [`GracefulShutdown::trigger`] has been called.
+    pub async fn triggered(&self) {
+        let mut receiver = self.sender.subscribe();
+        let _ = receiver.wait_for(|triggered| *triggered).await;
     }
 }
 
@@ -1,6 +1,12 @@
+use crate::listener::ListenerConfig;
+use crate::response::{ETagConfig, ErrorsConfig};
+use crate::versioning::VersioningConfig;
+
 use serde::{Deserialize, Serialize};
 use sword_core::{ConfigItem, ConfigRegistrar, inventory_submit};
-use sword_layers::{body_limit::BodyLimitConfig, timeout::RequestTimeoutConfig};
+use sword_layers::{
+    body_limit::BodyLimitConfig, forwarded::TrustedProxiesConfig, timeout::RequestTimeoutConfig,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebApplicationConfig {
@@ -10,6 +16,11 @@ pub struct WebApplicationConfig {
     /// The port number to bind the server to. Defaults to 8000
     pub port: u16,
 
+    /// TCP addresses, Unix sockets and systemd sockets to serve on, each with an
+    /// optional subset of the routes. When set, `host` and `port` are not bound.
+    #[serde(default)]
+    pub listeners: Vec<ListenerConfig>,
+
     /// Optional global prefix for all web controller routes.
     #[serde(rename = "router-prefix")]
     pub router_prefix: Option<String>,
@@ -21,6 +32,49 @@ pub struct WebApplicationConfig {
     /// Request timeout policy applied to web controllers.
     #[serde(rename = "request-timeout")]
     pub request_timeout: RequestTimeoutConfig,
+
+    /// Proxies whose `Forwarded` / `X-Forwarded-*` headers are honoured
+    /// when resolving the client IP, scheme and host.
+    #[serde(default, rename = "trusted-proxies")]
+    pub trusted_proxies: TrustedProxiesConfig,
+
+    /// How versioned controllers are selected, and their deprecation policies.
+    #[serde(default)]
+    pub versioning: VersioningConfig,
+
+    /// Computed `ETag`s and `304 Not Modified` responses for `GET` and `HEAD`.
+    #[serde(default)]
+    pub etag: ETagConfig,
+
+    /// Format of error responses, e.g. RFC 9457 Problem Details.
+    #[serde(default)]
+    pub errors: ErrorsConfig,
+
+    /// TLS termination for the listener, with optional client certificates.
+    #[cfg(feature = "tls")]
+    #[serde(default)]
+    pub tls: sword_layers::tls::TlsConfig,
+
+    /// Idempotent retries of unsafe requests. Only enabled when the
+    /// `[web.idempotency]` section is present.
+    #[cfg(feature = "idempotency")]
+    #[serde(default)]
+    pub idempotency: Option<sword_layers::idempotency::IdempotencyConfig>,
+
+    /// Server-side sessions. Only enabled when the `[web.session]` section is present.
+    #[cfg(feature = "sessions")]
+    #[serde(default)]
+    pub session: Option<sword_layers::sessions::SessionConfig>,
+
+    /// CSRF protection. Only enabled when the `[web.csrf]` section is present.
+    #[cfg(feature = "csrf")]
+    #[serde(default)]
+    pub csrf: Option<sword_layers::csrf::CsrfConfig>,
+
+    /// Localised error messages. Only enabled when the `[web.i18n]` section is present.
+    #[cfg(feature = "i18n")]
+    #[serde(default)]
+    pub i18n: Option<crate::i18n::I18nConfig>,
 }
 
 impl Default for WebApplicationConfig {
@@ -28,9 +82,24 @@ impl Default for WebApplicationConfig {
         Self {
             host: "0.0.0.0".to_string(),
             port: 8000,
+            listeners: Vec::new(),
             router_prefix: None,
             body_limit: BodyLimitConfig::default(),
             request_timeout: RequestTimeoutConfig::default(),
+            trusted_proxies: TrustedProxiesConfig::default(),
+            versioning: VersioningConfig::default(),
+            etag: ETagConfig::default(),
+            errors: ErrorsConfig::default(),
+            #[cfg(feature = "tls")]
+            tls: Default::default(),
+            #[cfg(feature = "idempotency")]
+            idempotency: None,
+            #[cfg(feature = "sessions")]
+            session: None,
+            #[cfg(feature = "csrf")]
+            csrf: None,
+            #[cfg(feature = "i18n")]
+            i18n: None,
         }
     }
 }
@@ -46,3 +115,24 @@ inventory_submit! {[
         state.insert(config.get_or_default::<WebApplicationConfig>());
     })
 ]}
+
+#[cfg(feature = "metrics")]
+inventory_submit! {[
+    ConfigRegistrar::new(|state, config| {
+        state.insert(config.get_or_default::<sword_layers::metrics::MetricsConfig>());
+    })
+]}
+
+#[cfg(feature = "opentelemetry")]
+inventory_submit! {[
+    ConfigRegistrar::new(|state, config| {
+        state.insert(config.get_or_default::<sword_layers::opentelemetry::OpenTelemetryConfig>());
+    })
+]}
+
+#[cfg(feature = "openapi")]
+inventory_submit! {[
+    ConfigRegistrar::new(|state, config| {
+        state.insert(config.get_or_default::<crate::openapi::OpenApiConfig>());
+    })
+]}
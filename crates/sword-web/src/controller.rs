@@ -1,6 +1,8 @@
 use sword_core::ControllerSpec;
 
-pub use sword_macros::{connect, controller, delete, get, head, options, patch, post, put, trace};
+pub use sword_macros::{
+    connect, controller, delete, get, head, openapi, options, patch, post, put, sse, trace,
+};
 
 /// Trait for controllers with automatic dependency injection and interceptors support.
 ///
@@ -34,13 +36,27 @@ pub trait WebController: ControllerSpec {
 }
 
 use axum::routing::MethodRouter;
-use std::any::TypeId;
+use std::{any::TypeId, time::Duration};
 use sword_core::State;
 
 #[derive(Clone)]
 pub struct WebControllerRegistrar {
     pub controller_id: TypeId,
+    pub controller_name: &'static str,
     pub controller_path: &'static str,
+
+    /// Controller-level `body_limit` in bytes (e.g. "500MB"), overriding `web.body-limit`.
+    pub body_limit: Option<usize>,
+
+    /// Controller-level `timeout` (e.g. "5m"), overriding `web.request-timeout`.
+    pub timeout: Option<Duration>,
+
+    /// Controller-level `rate_limit` policy name, overriding the default policy.
+    pub rate_limit: Option<&'static str>,
+
+    /// API version of the controller, selected by `web.versioning`.
+    pub version: Option<&'static str>,
+
     pub build: fn(&State),
 }
 
@@ -52,6 +68,23 @@ pub struct RouteRegistrar {
     /// Path of this specific route (e.g., "/{id}")
     pub path: &'static str,
 
+    /// Whether the route streams a long-lived response (e.g. `#[sse]`).
+    /// Streaming routes are exempt from the request timeout.
+    pub streaming: bool,
+
+    /// Route-level `body_limit` in bytes, overriding the controller and global limit.
+    pub body_limit: Option<usize>,
+
+    /// Route-level `timeout`, overriding the controller and global timeout.
+    pub timeout: Option<Duration>,
+
+    /// Route-level `rate_limit` policy name, overriding the controller and
+    /// default policy.
+    pub rate_limit: Option<&'static str>,
+
+    /// Route-level `etag`, overriding `web.etag.enabled`.
+    pub etag: Option<bool>,
+
     /// Function that builds the MethodRouter for this route
     /// The closure constructs the controller from state and calls the specific __sword_route_* method
     pub handler: fn(State) -> MethodRouter<State>,
@@ -0,0 +1,76 @@
+//! CSRF protection configured under `[web.csrf]`, and the web interceptor
+//! implementations of [`CsrfProtection`].
+
+use crate::config::WebApplicationConfig;
+use crate::interceptor::{OnRequest, OnRequestStream, WebInterceptorResult};
+use crate::request::{Request, StreamRequest};
+
+use axum_responses::JsonResponse;
+use sword_core::{State, sword_error};
+use sword_layers::{
+    DisplayConfig,
+    csrf::{CsrfProtection, CsrfToken},
+};
+
+impl OnRequest for CsrfProtection {
+    async fn on_request(&self, req: Request) -> WebInterceptorResult {
+        if self.requires_token(req.method(), req.original_path()) {
+            self.verify(
+                req.extensions.get::<CsrfToken>(),
+                req.headers(),
+                Some(req.body_bytes()),
+            )
+            .map_err(JsonResponse::from)?;
+        }
+
+        req.next().await
+    }
+}
+
+/// Streaming requests only carry the token in the header.
+impl OnRequestStream for CsrfProtection {
+    async fn on_request(&self, req: StreamRequest) -> WebInterceptorResult {
+        if self.requires_token(req.method(), req.original_path()) {
+            self.verify(req.extensions.get::<CsrfToken>(), req.headers(), None)
+                .map_err(JsonResponse::from)?;
+        }
+
+        req.next().await
+    }
+}
+
+/// Builds `CsrfProtection` from `[web.csrf]` and stores it in the state, so
+/// routes can apply it and `#[interceptor(CsrfProtection)]` can resolve it.
+pub(crate) fn init_csrf(state: &State, web_config: &WebApplicationConfig) {
+    let Some(config) = &web_config.csrf else {
+        return;
+    };
+
+    config.display();
+
+    #[cfg(feature = "sessions")]
+    if config.mode == sword_layers::csrf::CsrfMode::Synchronizer && web_config.session.is_none() {
+        sword_error! {
+            title: "Failed to initialize CSRF protection",
+            reason: "The \"synchronizer\" mode stores tokens in the session",
+            context: {
+                "mode" => "synchronizer",
+                "source" => "WebRouter::init_csrf",
+            },
+            hints: ["Configure `[web.session]`, or use `mode = \"double-submit\"`"],
+        }
+    }
+
+    let protection = CsrfProtection::new(config).unwrap_or_else(|err| {
+        sword_error! {
+            title: "Failed to initialize CSRF protection",
+            reason: err,
+            context: {
+                "safe-methods" => config.safe_methods.join(", "),
+                "source" => "WebRouter::init_csrf",
+            },
+        }
+    });
+
+    state.insert(protection);
+}
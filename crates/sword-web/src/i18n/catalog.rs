@@ -0,0 +1,212 @@
+use super::I18nConfig;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, fs, io,
+    path::Path,
+    sync::Arc,
+};
+use thiserror::Error;
+use toml::{Table, Value};
+
+#[derive(Debug, Error)]
+pub enum I18nError {
+    #[error("Failed to read {path}: {source}")]
+    Read {
+        path: String,
+
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Failed to parse {path}: {source}")]
+    Parse {
+        path: String,
+
+        #[source]
+        source: toml::de::Error,
+    },
+
+    #[error("The message `{key}` of {path} is not a string")]
+    InvalidMessage { path: String, key: String },
+
+    #[error("There is no catalog for the default locale \"{0}\"")]
+    MissingDefaultLocale(String),
+}
+
+/// The locale of a request, negotiated from its `Accept-Language` header among
+/// the catalogs of `[web.i18n]` (e.g. "es" for `Accept-Language: es-CL, en;q=0.8`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Locale(Arc<str>);
+
+impl Locale {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The primary language subtag, e.g. "es" for "es-CL".
+    pub fn language(&self) -> &str {
+        primary_subtag(&self.0)
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+struct Catalog {
+    locale: Locale,
+    messages: HashMap<String, String>,
+}
+
+/// The message catalogs of every locale, keyed by the lowercase locale.
+#[derive(Clone)]
+pub(crate) struct Translations {
+    catalogs: Arc<BTreeMap<String, Catalog>>,
+    default_locale: Locale,
+}
+
+impl Translations {
+    /// Reads every `<locale>.toml` file of the configured directory.
+    pub(crate) fn load(config: &I18nConfig) -> Result<Self, I18nError> {
+        let read_error = |path: &Path| {
+            let path = path.display().to_string();
+            move |source| I18nError::Read { path, source }
+        };
+
+        let directory = Path::new(&config.directory);
+        let mut catalogs = BTreeMap::new();
+
+        for entry in fs::read_dir(directory).map_err(read_error(directory))? {
+            let path = entry.map_err(read_error(directory))?.path();
+
+            let Some(locale) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .filter(|_| path.extension().is_some_and(|ext| ext == "toml"))
+            else {
+                continue;
+            };
+
+            let content = fs::read_to_string(&path).map_err(read_error(&path))?;
+
+            let table = content
+                .parse::<Table>()
+                .map_err(|source| I18nError::Parse {
+                    path: path.display().to_string(),
+                    source,
+                })?;
+
+            let mut messages = HashMap::new();
+
+            flatten(table, "", &mut messages).map_err(|key| I18nError::InvalidMessage {
+                path: path.display().to_string(),
+                key,
+            })?;
+
+            let catalog = Catalog {
+                locale: Locale(locale.into()),
+                messages,
+            };
+
+            catalogs.insert(locale.to_ascii_lowercase(), catalog);
+        }
+
+        let default_locale = catalogs
+            .get(&config.default_locale.to_ascii_lowercase())
+            .map(|catalog| catalog.locale.clone())
+            .ok_or_else(|| I18nError::MissingDefaultLocale(config.default_locale.clone()))?;
+
+        Ok(Self {
+            catalogs: Arc::new(catalogs),
+            default_locale,
+        })
+    }
+
+    /// The best catalog for an `Accept-Language` header, by quality and then
+    /// order. Ranges match a catalog exactly, by their primary language
+    /// ("es-CL" uses "es"), or a regional catalog of their language ("es"
+    /// uses "es-CL"). Falls back to the default locale.
+    pub(crate) fn negotiate(&self, accept_language: Option<&str>) -> Locale {
+        let mut ranges = accept_language
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|range| {
+                let mut params = range.split(';');
+                let tag = params.next()?.trim();
+
+                let quality = match params.find_map(|param| param.trim().strip_prefix("q=")) {
+                    Some(quality) => quality.trim().parse::<f32>().ok()?,
+                    None => 1.0,
+                };
+
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect::<Vec<_>>();
+
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranges
+            .into_iter()
+            .take_while(|(tag, _)| *tag != "*")
+            .find_map(|(tag, _)| self.find(tag))
+            .unwrap_or_else(|| self.default_locale.clone())
+    }
+
+    fn find(&self, tag: &str) -> Option<Locale> {
+        let tag = tag.to_ascii_lowercase();
+        let language = primary_subtag(&tag);
+
+        self.catalogs
+            .get(&tag)
+            .or_else(|| self.catalogs.get(language))
+            .or_else(|| {
+                self.catalogs
+                    .iter()
+                    .find(|(locale, _)| primary_subtag(locale) == language)
+                    .map(|(_, catalog)| catalog)
+            })
+            .map(|catalog| catalog.locale.clone())
+    }
+
+    /// The message of `key` in the catalog of `locale`, or in the one of the
+    /// default locale.
+    pub(crate) fn translate(&self, locale: &Locale, key: &str) -> Option<&str> {
+        [locale, &self.default_locale]
+            .into_iter()
+            .filter_map(|locale| self.catalogs.get(&locale.as_str().to_ascii_lowercase()))
+            .find_map(|catalog| catalog.messages.get(key))
+            .map(String::as_str)
+    }
+}
+
+/// Collects the strings of a catalog under dotted keys, failing with the key
+/// of the first value that isn't a string or a table.
+fn flatten(
+    table: Table,
+    prefix: &str,
+    messages: &mut HashMap<String, String>,
+) -> Result<(), String> {
+    for (key, value) in table {
+        let key = match prefix {
+            "" => key,
+            prefix => format!("{prefix}.{key}"),
+        };
+
+        match value {
+            Value::String(message) => {
+                messages.insert(key, message);
+            }
+            Value::Table(table) => flatten(table, &key, messages)?,
+            _ => return Err(key),
+        }
+    }
+
+    Ok(())
+}
+
+fn primary_subtag(tag: &str) -> &str {
+    tag.split(['-', '_']).next().unwrap_or(tag)
+}
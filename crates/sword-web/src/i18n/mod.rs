@@ -0,0 +1,94 @@
+//! Localised error messages configured under `[web.i18n]`.
+
+mod catalog;
+mod translate;
+
+pub use catalog::{I18nError, Locale};
+
+pub(crate) use catalog::Translations;
+pub(crate) use translate::translate_error;
+
+use crate::config::WebApplicationConfig;
+
+use serde::{Deserialize, Serialize};
+use sword_core::{State, sword_error};
+use sword_layers::DisplayConfig;
+
+/// Configuration of the `[web.i18n]` key.
+///
+/// Catalogs are TOML files named after their locale (`locales/en.toml`,
+/// `locales/es.toml`). Nested tables become dotted keys:
+///
+/// ```toml
+/// [errors]
+/// not-found = "No encontramos el recurso solicitado"
+///
+/// [validation]
+/// email = "El campo {field} debe ser un correo válido"
+///
+/// [payments]
+/// insufficient-funds = "Fondos insuficientes"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct I18nConfig {
+    /// Directory of the message catalogs. Defaults to "locales".
+    pub directory: String,
+
+    /// Locale used when the `Accept-Language` header matches no catalog, and
+    /// for keys missing in the negotiated one. Defaults to "en".
+    #[serde(rename = "default-locale")]
+    pub default_locale: String,
+
+    /// Whether to display the configuration details. Defaults to false.
+    pub display: bool,
+}
+
+impl Default for I18nConfig {
+    fn default() -> Self {
+        Self {
+            directory: "locales".to_string(),
+            default_locale: "en".to_string(),
+            display: false,
+        }
+    }
+}
+
+impl DisplayConfig for I18nConfig {
+    fn display(&self) {
+        if !self.display {
+            return;
+        }
+
+        tracing::info!(
+            target: "sword.web.i18n",
+            directory = self.directory,
+            default_locale = self.default_locale,
+        );
+    }
+}
+
+/// Loads the catalogs of `[web.i18n]` and stores them in the state, where the
+/// error pipeline reads them from.
+pub(crate) fn init_i18n(state: &State, web_config: &WebApplicationConfig) {
+    let Some(config) = &web_config.i18n else {
+        return;
+    };
+
+    config.display();
+
+    let translations = Translations::load(config).unwrap_or_else(|err| {
+        sword_error! {
+            title: "Failed to load the i18n catalogs",
+            reason: err,
+            context: {
+                "directory" => config.directory.clone(),
+                "default-locale" => config.default_locale.clone(),
+                "source" => "WebRouter::init_i18n",
+            },
+            hints: ["Add one `<locale>.toml` file per locale to `web.i18n.directory`, including `web.i18n.default-locale`"],
+        }
+    });
+
+    state.insert(translations);
+}
@@ -0,0 +1,85 @@
+use super::{Locale, Translations};
+use crate::response::ResponseError;
+
+use serde_json::Value;
+
+/// Translates an error response to `locale`.
+///
+/// Messages are looked up in the catalog of the locale, then in the one of the
+/// default locale:
+///
+/// - Framework errors use `errors.<kind>`, e.g. `errors.not-found`.
+/// - Messages of `HttpError` variants that are catalog keys are replaced by
+///   their translation.
+/// - Validation errors use `validation.<field>.<code>`, then
+///   `validation.<code>`, with `{field}` replaced by the field name.
+///
+/// Returns whether anything was translated.
+pub(crate) fn translate_error(
+    translations: &Translations,
+    locale: &Locale,
+    error: &mut ResponseError,
+) -> bool {
+    let message = translate_message(translations, locale, error);
+    let translated_errors = translate_validation_errors(translations, locale, &mut error.errors);
+
+    let translated_message = message.is_some();
+
+    if let Some(message) = message {
+        error.message = message;
+    }
+
+    translated_message || translated_errors
+}
+
+fn translate_message(
+    translations: &Translations,
+    locale: &Locale,
+    error: &ResponseError,
+) -> Option<String> {
+    let by_kind = error
+        .kind
+        .and_then(|kind| translations.translate(locale, &format!("errors.{}", kind.as_str())));
+
+    let by_key = || translations.translate(locale, &error.message);
+
+    by_kind.or_else(by_key).map(ToString::to_string)
+}
+
+/// Translates the `{ "code", "message" }` entries of the `errors` member.
+fn translate_validation_errors(
+    translations: &Translations,
+    locale: &Locale,
+    errors: &mut Option<Value>,
+) -> bool {
+    let Some(Value::Object(fields)) = errors else {
+        return false;
+    };
+
+    let mut translated = false;
+
+    for (field, entries) in fields.iter_mut() {
+        let Value::Array(entries) = entries else {
+            continue;
+        };
+
+        for entry in entries.iter_mut().filter_map(Value::as_object_mut) {
+            let Some(code) = entry.get("code").and_then(Value::as_str) else {
+                continue;
+            };
+
+            let message = translations
+                .translate(locale, &format!("validation.{field}.{code}"))
+                .or_else(|| translations.translate(locale, &format!("validation.{code}")));
+
+            if let Some(message) = message {
+                let message = message.replace("{field}", field);
+
+                entry.insert("message".into(), Value::String(message));
+                translated = true;
+            }
+        }
+    }
+
+    translated
+}
@@ -0,0 +1,79 @@
+//! Idempotent retries configured under `[web.idempotency]`.
+
+use axum::Router;
+use std::sync::Arc;
+use sword_core::{Provider, State, sword_error};
+use sword_layers::{
+    DisplayConfig,
+    idempotency::{IdempotencyConfig, IdempotencyLayer, IdempotencyStore, MemoryIdempotencyStore},
+};
+
+/// Replaces the in-memory idempotency store, e.g. with one shared between instances.
+///
+/// ### Example
+/// ```rust,ignore
+/// Application::builder()
+///     .with_provider(IdempotencyStoreProvider::new(RedisIdempotencyStore::new(client)))
+///     .with_module::<PaymentsModule>()
+///     .build();
+/// ```
+#[derive(Clone)]
+pub struct IdempotencyStoreProvider(Arc<dyn IdempotencyStore>);
+
+impl IdempotencyStoreProvider {
+    pub fn new(store: impl IdempotencyStore) -> Self {
+        Self(Arc::new(store))
+    }
+}
+
+impl Provider for IdempotencyStoreProvider {}
+
+/// Applies the `IdempotencyLayer` when `[web.idempotency]` is configured.
+pub(crate) fn with_idempotency_layer(
+    router: Router<State>,
+    state: &State,
+    config: Option<&IdempotencyConfig>,
+) -> Router<State> {
+    let Some(config) = config else {
+        return router;
+    };
+
+    config.display();
+
+    let store = match state.get::<IdempotencyStoreProvider>() {
+        Ok(provider) => provider.0,
+        Err(_) => Arc::new(MemoryIdempotencyStore::new()),
+    };
+
+    let layer = IdempotencyLayer::new(config, store).unwrap_or_else(|err| {
+        sword_error! {
+            title: "Failed to initialize idempotency keys",
+            reason: err,
+            context: {
+                "header" => config.header.clone(),
+                "source" => "WebRouter::apply_web_layers",
+            },
+            hints: ["Set `web.idempotency.header` to a valid header name, e.g. \"Idempotency-Key\""],
+        }
+    });
+
+    #[cfg(feature = "jwt")]
+    let layer = with_jwt(state, layer);
+
+    router.layer(layer)
+}
+
+/// Lets keys be scoped by the subject of bearer tokens verified with `[auth.jwt]`.
+#[cfg(feature = "jwt")]
+fn with_jwt(state: &State, layer: IdempotencyLayer) -> IdempotencyLayer {
+    use sword_core::Interceptor;
+    use sword_layers::jwt::JwtAuth;
+
+    // Interceptors register in no particular order.
+    <JwtAuth as Interceptor>::register(state);
+
+    match state.get::<JwtAuth>() {
+        Ok(auth) => layer.with_jwt(auth),
+        Err(_) => layer,
+    }
+}
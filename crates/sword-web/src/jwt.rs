@@ -0,0 +1,37 @@
+//! Web interceptor implementations of the built-in [`JwtAuth`] interceptor.
+
+use crate::interceptor::{OnRequest, OnRequestStream, WebInterceptorResult};
+use crate::request::{Request, StreamRequest};
+
+use axum_responses::JsonResponse;
+use sword_layers::jwt::{JwtAuth, JwtError};
+
+impl OnRequest for JwtAuth {
+    async fn on_request(&self, mut req: Request) -> WebInterceptorResult {
+        let claims = self
+            .verify_authorization(req.authorization())
+            .map_err(unauthorized)?;
+
+        req.extensions.insert(claims);
+        req.next().await
+    }
+}
+
+impl OnRequestStream for JwtAuth {
+    async fn on_request(&self, mut req: StreamRequest) -> WebInterceptorResult {
+        let claims = self
+            .verify_authorization(req.authorization())
+            .map_err(unauthorized)?;
+
+        req.extensions.insert(claims);
+        req.next().await
+    }
+}
+
+fn unauthorized(err: JwtError) -> JsonResponse {
+    tracing::debug!(target: "sword.web.jwt", error = ?err, "Rejected bearer token");
+
+    JsonResponse::Unauthorized()
+        .message(err.to_string())
+        .header("WWW-Authenticate", "Bearer")
+}
@@ -2,26 +2,103 @@ pub mod application;
 pub mod config;
 pub mod controller;
 pub mod interceptor;
+pub mod listener;
 pub mod request;
 pub mod response;
 pub mod router;
+pub mod versioning;
+
+#[cfg(feature = "csrf")]
+mod csrf;
+
+#[cfg(feature = "jwt")]
+mod jwt;
+
+#[cfg(feature = "openapi")]
+pub mod openapi;
+
+#[cfg(feature = "idempotency")]
+pub mod idempotency;
+
+#[cfg(feature = "i18n")]
+pub mod i18n;
+
+#[cfg(feature = "sessions")]
+pub mod sessions;
 
 pub mod prelude {
+    pub use crate::application::GracefulShutdown;
     pub use crate::controller::{
-        WebController, connect, delete, get, head, options, patch, post, put, trace,
+        WebController, connect, delete, get, head, openapi, options, patch, post, put, sse, trace,
     };
     pub use crate::interceptor::{
         OnRequest, OnRequestStream, OnRequestStreamWithConfig, OnRequestWithConfig,
         WebInterceptorResult,
     };
-    pub use crate::request::{Request, RequestError, StreamRequest};
+    pub use crate::listener::{ListenerBind, ListenerConfig};
+    pub use crate::request::{
+        BodyDecoder, BodyDecoderRegistrar, BodyTarget, FromRequest, FromRequestParts, Header, Json,
+        JsonItemError, JsonItemStream, Path, Query, Request, RequestError, StreamRequest,
+        TypedHeader,
+    };
     pub use crate::response::{
-        ContentDisposition, File, HttpError, JsonResponse, JsonResponseBody, Redirect, WebResult,
+        BodyEncoder, BodyEncoderRegistrar, ConditionalResponse, ContentDisposition, ETag,
+        ErrorRenderer, ErrorRendererProvider, File, FrameworkError, FrameworkErrorKind, HttpError,
+        JsonResponse, JsonResponseBody, JsonStreamFormat, JsonStreamResponse, LastEventId,
+        Negotiated, Redirect, SseEvent, SseResponse, WebResult,
     };
+    pub use crate::versioning::ApiVersion;
+    pub use crate::{register_body_decoder, register_body_encoder};
+    pub use axum::http::{HeaderValue, request::Parts};
     pub use axum::middleware::Next;
+    pub use sword_core::State;
+    pub use sword_layers::forwarded::ClientInfo;
+    pub use sword_layers::request_id::RequestId;
+
+    #[cfg(feature = "tls")]
+    pub use sword_layers::tls::PeerIdentity;
+
+    #[cfg(feature = "jwt")]
+    pub use sword_layers::jwt::{JwtAuth, JwtClaims};
+
+    #[cfg(feature = "csrf")]
+    pub use sword_layers::csrf::{CsrfProtection, CsrfToken};
+
+    #[cfg(feature = "i18n")]
+    pub use crate::i18n::Locale;
+
+    #[cfg(feature = "idempotency")]
+    pub use crate::idempotency::IdempotencyStoreProvider;
+
+    #[cfg(feature = "idempotency")]
+    pub use sword_layers::idempotency::{
+        IdempotencyError, IdempotencyFuture, IdempotencyRecord, IdempotencyStore,
+        MemoryIdempotencyStore, StoredResponse,
+    };
+
+    #[cfg(feature = "rate-limit")]
+    pub use sword_core::{RateLimitStoreProvider, register_rate_limit_key};
+
+    #[cfg(feature = "rate-limit")]
+    pub use sword_layers::rate_limit::{
+        MemoryRateLimitStore, RateLimitDecision, RateLimitError, RateLimitFuture,
+        RateLimitKeyExtractor, RateLimitPolicy, RateLimitRequest, RateLimitState, RateLimitStore,
+        RateLimiter,
+    };
+
+    #[cfg(feature = "sessions")]
+    pub use crate::sessions::SessionStoreProvider;
+
+    #[cfg(feature = "sessions")]
+    pub use sword_layers::sessions::{
+        FileStore, MemoryStore, Session, SessionError, SessionFuture, SessionRecord, SessionStore,
+    };
+
+    #[cfg(feature = "sessions-sql")]
+    pub use sword_layers::sessions::SqlStore;
 
     #[cfg(feature = "validation-validator")]
-    pub use crate::request::ValidatorRequestValidation;
+    pub use crate::request::{ValidateExtractor, Validated, ValidatorRequestValidation};
 
     pub use sword_layers::cookies::{
         Cookies, Key as CookiesKey, PrivateCookies, SignedCookies,
@@ -55,4 +132,158 @@ pub mod internal {
     };
 
     pub use crate::controller::{RouteRegistrar, WebController, WebControllerRegistrar};
+    pub use crate::request::{BodyDecoderRegistrar, Extract};
+    pub use crate::response::{BodyEncoderRegistrar, ErrorFormat, Negotiation, ResponseError};
+
+    pub use crate::{
+        __sword_openapi_errors as openapi_errors, __sword_openapi_operation as openapi_operation,
+        __sword_openapi_tag as openapi_tag,
+    };
+
+    #[cfg(feature = "openapi")]
+    pub use crate::openapi;
+}
+
+/// Registers a custom [`BodyDecoder`](crate::request::BodyDecoder) used by
+/// `Request::body::<T>()`. Custom decoders take precedence over the built-in ones.
+///
+/// ```rust,ignore
+/// register_body_decoder!(YamlDecoder);
+/// ```
+#[macro_export]
+macro_rules! register_body_decoder {
+    ($decoder:expr) => {
+        const _: () = {
+            ::sword::internal::inventory::submit! {
+                ::sword::internal::web::BodyDecoderRegistrar::new(&$decoder)
+            }
+        };
+    };
+}
+
+/// Registers a custom [`BodyEncoder`](crate::response::BodyEncoder) used by
+/// [`Negotiated`](crate::response::Negotiated) and `HttpError` responses.
+/// Custom encoders take precedence over the built-in ones for exact media types.
+///
+/// ```rust,ignore
+/// register_body_encoder!(YamlEncoder);
+/// ```
+#[macro_export]
+macro_rules! register_body_encoder {
+    ($encoder:expr) => {
+        const _: () = {
+            ::sword::internal::inventory::submit! {
+                ::sword::internal::web::BodyEncoderRegistrar::new(&$encoder)
+            }
+        };
+    };
+}
+
+/// Registers the OpenAPI metadata of a route. Expands to nothing without the
+/// `openapi` feature, so handler types don't need to implement any schema trait.
+#[cfg(feature = "openapi")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sword_openapi_operation {
+    (
+        controller: $controller:ty,
+        method: $method:literal,
+        path: $path:literal,
+        handler: $handler:literal,
+        summary: $summary:literal,
+        description: $description:literal,
+        status: $status:literal,
+        response: [$($response:ty)?],
+        inputs: [$($input:ty),* $(,)?],
+        errors: [$($error:ty)?],
+        security: [$($security:ty),* $(,)?] $(,)?
+    ) => {
+        ::sword::internal::inventory::submit! {
+            ::sword::internal::web::openapi::OperationRegistrar {
+                controller_id: ::std::any::TypeId::of::<$controller>(),
+                method: $method,
+                path: $path,
+                handler: $handler,
+                summary: $summary,
+                description: $description,
+                describe: |operation: &mut ::sword::internal::web::openapi::Operation<'_>| {
+                    #[allow(unused_imports)]
+                    use ::sword::internal::web::openapi::__private::{
+                        Describe, DescribeErrors, DescribeErrorsFallback, DescribeInput,
+                        DescribeInputFallback, DescribeSecurity, DescribeSecurityFallback,
+                    };
+
+                    let data = ::std::option::Option::None
+                        $(.or(::std::option::Option::Some(operation.schema_for::<$response>())))?;
+
+                    operation.add_success_response($status, data);
+
+                    $((&Describe::<$input>::new()).describe_input(operation);)*
+                    $((&Describe::<$error>::new()).describe_errors(operation);)?
+                    $((&Describe::<$security>::new()).describe_security(operation);)*
+                },
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "openapi"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sword_openapi_operation {
+    ($($tt:tt)*) => {};
+}
+
+/// Registers the OpenAPI tag of a web controller.
+#[cfg(feature = "openapi")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sword_openapi_tag {
+    (controller: $controller:ty, name: $name:literal, description: $description:literal $(,)?) => {
+        ::sword::internal::inventory::submit! {
+            ::sword::internal::web::openapi::TagRegistrar {
+                controller_id: ::std::any::TypeId::of::<$controller>(),
+                name: $name,
+                description: $description,
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "openapi"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sword_openapi_tag {
+    ($($tt:tt)*) => {};
+}
+
+/// Implements `OpenApiErrors` for a `#[derive(HttpError)]` enum.
+#[cfg(feature = "openapi")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sword_openapi_errors {
+    (
+        error: $error:ty,
+        responses: [$(($status:literal, $message:literal)),* $(,)?],
+        transparent: [$($inner:ty),* $(,)?] $(,)?
+    ) => {
+        impl ::sword::internal::web::openapi::OpenApiErrors for $error {
+            fn describe(operation: &mut ::sword::internal::web::openapi::Operation<'_>) {
+                #[allow(unused_imports)]
+                use ::sword::internal::web::openapi::__private::{
+                    Describe, DescribeErrors, DescribeErrorsFallback,
+                };
+
+                $(operation.add_error_response($status, $message);)*
+                $((&Describe::<$inner>::new()).describe_errors(operation);)*
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "openapi"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sword_openapi_errors {
+    ($($tt:tt)*) => {};
 }
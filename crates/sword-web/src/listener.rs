@@ -0,0 +1,360 @@
+use axum::{
+    extract::{ConnectInfo, Request},
+    serve::{IncomingStream, Listener},
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    fmt, io,
+    net::SocketAddr,
+    path::PathBuf,
+    pin::Pin,
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::{TcpListener, TcpStream},
+};
+
+#[cfg(unix)]
+use tokio::net::{UnixListener, UnixStream};
+
+#[cfg(feature = "tls")]
+use sword_core::sword_error;
+
+#[cfg(feature = "tls")]
+use sword_layers::tls::{PeerIdentity, TlsListener};
+
+#[cfg(feature = "tls")]
+use tokio_rustls::server::TlsStream;
+
+/// One entry of the `[[web.listeners]]` list.
+///
+/// ```toml
+/// [[web.listeners]]
+/// bind = "0.0.0.0:8080"
+/// exclude-routes = ["/metrics", "/admin"]
+///
+/// [[web.listeners]]
+/// bind = "127.0.0.1:9000"
+/// routes = ["/metrics", "/admin", "/health"]
+///
+/// [[web.listeners]]
+/// bind = "unix:/run/app.sock"
+/// mode = "660"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListenerConfig {
+    /// Where to listen: `host:port`, `unix:/path/to.sock`, or `systemd:<index>`
+    /// for the socket at that position of the sockets passed by systemd
+    /// (`LISTEN_FDS`).
+    pub bind: ListenerBind,
+
+    /// Permissions of the Unix socket file, in octal (e.g. "660").
+    #[serde(default, deserialize_with = "deserialize_mode")]
+    pub mode: Option<u32>,
+
+    /// Path prefixes served by this listener. Defaults to every route.
+    pub routes: Option<Vec<String>>,
+
+    /// Path prefixes this listener doesn't serve.
+    #[serde(default, rename = "exclude-routes")]
+    pub exclude_routes: Vec<String>,
+
+    /// Whether `[web.tls]` applies to this listener. Defaults to `[web.tls].enabled`
+    /// for TCP listeners; Unix sockets are always served in plain text.
+    #[cfg(feature = "tls")]
+    pub tls: Option<bool>,
+}
+
+impl ListenerConfig {
+    pub(crate) fn tcp(addr: SocketAddr) -> Self {
+        Self {
+            bind: ListenerBind::Tcp(addr),
+            mode: None,
+            routes: None,
+            exclude_routes: Vec::new(),
+            #[cfg(feature = "tls")]
+            tls: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListenerBind {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+    Systemd(usize),
+}
+
+impl FromStr for ListenerBind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = value.strip_prefix("unix:") {
+            if path.is_empty() {
+                return Err("a unix listener needs a socket path".to_string());
+            }
+
+            return Ok(Self::Unix(PathBuf::from(path)));
+        }
+
+        if let Some(index) = value.strip_prefix("systemd:") {
+            return index
+                .parse()
+                .map(Self::Systemd)
+                .map_err(|_| format!("invalid systemd socket index '{index}'"));
+        }
+
+        value
+            .parse()
+            .map(Self::Tcp)
+            .map_err(|_| format!("invalid listener address '{value}'"))
+    }
+}
+
+impl fmt::Display for ListenerBind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "{addr}"),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+            Self::Systemd(index) => write!(f, "systemd:{index}"),
+        }
+    }
+}
+
+impl Serialize for ListenerBind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ListenerBind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+fn deserialize_mode<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|mode| {
+            u32::from_str_radix(&mode, 8)
+                .ok()
+                .filter(|mode| *mode <= 0o7777)
+                .ok_or_else(|| serde::de::Error::custom(format!("invalid socket mode '{mode}'")))
+        })
+        .transpose()
+}
+
+/// Path prefixes a listener serves, from its `routes` and `exclude-routes`.
+#[derive(Clone, Default)]
+pub(crate) struct RouteFilter {
+    routes: Option<Arc<[String]>>,
+    exclude: Arc<[String]>,
+}
+
+impl RouteFilter {
+    pub(crate) fn new(config: &ListenerConfig) -> Self {
+        Self {
+            routes: config.routes.as_deref().map(Arc::from),
+            exclude: Arc::from(config.exclude_routes.as_slice()),
+        }
+    }
+
+    /// Whether `path` is under a served prefix. Prefixes match whole segments,
+    /// so `/admin` covers `/admin/users` but not `/administrator`.
+    pub(crate) fn allows(&self, path: &str) -> bool {
+        let matches = |prefix: &String| {
+            let prefix = prefix.trim_end_matches('/');
+
+            prefix.is_empty()
+                || path
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        };
+
+        let included = match &self.routes {
+            Some(routes) => routes.iter().any(matches),
+            None => true,
+        };
+
+        included && !self.exclude.iter().any(matches)
+    }
+}
+
+/// A bound listener served by `WebApplication::start`.
+pub(crate) enum WebListener {
+    Tcp(TcpListener),
+
+    #[cfg(unix)]
+    Unix(UnixListener),
+
+    #[cfg(feature = "tls")]
+    Tls(TlsListener),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum WebAddr {
+    Tcp(SocketAddr),
+
+    /// Unix socket peers are unnamed, so no address is kept.
+    #[cfg(unix)]
+    Unix,
+}
+
+impl Listener for WebListener {
+    type Io = WebStream;
+    type Addr = WebAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self {
+            Self::Tcp(listener) => {
+                let (stream, addr) = Listener::accept(listener).await;
+                (WebStream::Tcp(stream), WebAddr::Tcp(addr))
+            }
+
+            #[cfg(unix)]
+            Self::Unix(listener) => {
+                let (stream, _) = Listener::accept(listener).await;
+                (WebStream::Unix(stream), WebAddr::Unix)
+            }
+
+            #[cfg(feature = "tls")]
+            Self::Tls(listener) => match listener.accept().await {
+                Some((stream, addr)) => (WebStream::Tls(Box::new(stream)), WebAddr::Tcp(addr)),
+                // The accept loop only stops when its task dies, so the
+                // listener can't serve anything else.
+                None => sword_error! {
+                    title: "TLS listener stopped accepting connections",
+                    reason: "the TLS accept task ended",
+                    context: {
+                        "address" => listener.local_addr().to_string(),
+                        "source" => "WebListener::accept",
+                    },
+                },
+            },
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        match self {
+            Self::Tcp(listener) => listener.local_addr().map(WebAddr::Tcp),
+
+            #[cfg(unix)]
+            Self::Unix(_) => Ok(WebAddr::Unix),
+
+            #[cfg(feature = "tls")]
+            Self::Tls(listener) => Ok(WebAddr::Tcp(listener.local_addr())),
+        }
+    }
+}
+
+pub(crate) enum WebStream {
+    Tcp(TcpStream),
+
+    #[cfg(unix)]
+    Unix(UnixStream),
+
+    #[cfg(feature = "tls")]
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+macro_rules! delegate_stream {
+    ($self:expr, $stream:ident => $call:expr) => {
+        match $self {
+            WebStream::Tcp($stream) => $call,
+
+            #[cfg(unix)]
+            WebStream::Unix($stream) => $call,
+
+            #[cfg(feature = "tls")]
+            WebStream::Tls($stream) => $call,
+        }
+    };
+}
+
+impl AsyncRead for WebStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        delegate_stream!(self.get_mut(), stream => Pin::new(stream).poll_read(cx, buf))
+    }
+}
+
+impl AsyncWrite for WebStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        delegate_stream!(self.get_mut(), stream => Pin::new(stream).poll_write(cx, buf))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        delegate_stream!(self.get_mut(), stream => Pin::new(stream).poll_write_vectored(cx, bufs))
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        delegate_stream!(self, stream => stream.is_write_vectored())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        delegate_stream!(self.get_mut(), stream => Pin::new(stream).poll_flush(cx))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        delegate_stream!(self.get_mut(), stream => Pin::new(stream).poll_shutdown(cx))
+    }
+}
+
+/// Connection details inserted into the extensions of every request.
+#[derive(Clone)]
+pub(crate) struct ConnectionExtensions {
+    /// `None` on Unix sockets, which have no peer IP address.
+    remote_addr: Option<SocketAddr>,
+
+    #[cfg(feature = "tls")]
+    peer_identity: Option<PeerIdentity>,
+}
+
+impl ConnectionExtensions {
+    pub(crate) fn from_incoming(incoming: &IncomingStream<'_, WebListener>) -> Self {
+        Self {
+            remote_addr: match incoming.remote_addr() {
+                WebAddr::Tcp(addr) => Some(*addr),
+
+                #[cfg(unix)]
+                WebAddr::Unix => None,
+            },
+
+            #[cfg(feature = "tls")]
+            peer_identity: match incoming.io() {
+                WebStream::Tls(stream) => PeerIdentity::from_stream(stream),
+                _ => None,
+            },
+        }
+    }
+
+    pub(crate) fn insert_into(&self, req: &mut Request) {
+        if let Some(addr) = self.remote_addr {
+            req.extensions_mut().insert(ConnectInfo(addr));
+        }
+
+        #[cfg(feature = "tls")]
+        if let Some(identity) = &self.peer_identity {
+            req.extensions_mut().insert(identity.clone());
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use sword_core::ConfigItem;
+use sword_layers::DisplayConfig;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpenApiConfig {
+    /// Whether the OpenAPI document is generated and served. Defaults to false.
+    pub enabled: bool,
+
+    /// Path where the JSON document is served. Defaults to "/openapi.json".
+    pub path: String,
+
+    /// `info.title` of the document. Defaults to "Sword API".
+    pub title: String,
+
+    /// `info.version` of the document. Defaults to "0.1.0".
+    pub version: String,
+
+    /// Optional `info.description` of the document.
+    pub description: Option<String>,
+
+    /// Swagger UI page configuration.
+    #[serde(rename = "swagger-ui")]
+    pub swagger_ui: SwaggerUiConfig,
+
+    /// Whether to display the configuration details. Defaults to false.
+    pub display: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SwaggerUiConfig {
+    /// Whether the Swagger UI page is served. Defaults to false.
+    pub enabled: bool,
+
+    /// Path where the Swagger UI page is served. Defaults to "/docs".
+    pub path: String,
+
+    /// Base URL to load the `swagger-ui-dist` assets from instead of the
+    /// bundled ones, e.g. "https://unpkg.com/swagger-ui-dist@5". By default
+    /// the bundled assets are served under `path`.
+    #[serde(rename = "assets-url")]
+    pub assets_url: Option<String>,
+}
+
+impl Default for OpenApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "/openapi.json".to_string(),
+            title: "Sword API".to_string(),
+            version: "0.1.0".to_string(),
+            description: None,
+            swagger_ui: SwaggerUiConfig::default(),
+            display: false,
+        }
+    }
+}
+
+impl Default for SwaggerUiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "/docs".to_string(),
+            assets_url: None,
+        }
+    }
+}
+
+impl ConfigItem for OpenApiConfig {
+    fn key() -> &'static str {
+        "openapi"
+    }
+}
+
+impl DisplayConfig for OpenApiConfig {
+    fn display(&self) {
+        if !self.display {
+            return;
+        }
+
+        tracing::info!(
+            target: "sword.web.openapi",
+            enabled = self.enabled,
+            path = self.path,
+            swagger_ui = self.swagger_ui.enabled,
+            swagger_ui_path = self.swagger_ui.path,
+        );
+    }
+}
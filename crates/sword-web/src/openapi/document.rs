@@ -0,0 +1,277 @@
+use super::{
+    OpenApiConfig,
+    operation::{Operation, RESPONSE_BODY_SCHEMA, response_body_schema},
+};
+use crate::controller::WebControllerRegistrar;
+use crate::router::route_template;
+use crate::versioning::VersioningConfig;
+
+use axum::{
+    Router,
+    http::header::{CACHE_CONTROL, CONTENT_TYPE},
+    response::Html,
+    routing::get,
+};
+use schemars::generate::SchemaSettings;
+use serde_json::{Map, Value, json};
+use std::{any::TypeId, collections::BTreeMap};
+use sword_core::{Controller, ControllerMap, State};
+
+/// Operation metadata registered by the route macros.
+#[doc(hidden)]
+pub struct OperationRegistrar {
+    pub controller_id: TypeId,
+
+    /// Lowercase HTTP method, as used in OpenAPI path items.
+    pub method: &'static str,
+
+    /// Route path relative to the controller path.
+    pub path: &'static str,
+
+    pub handler: &'static str,
+    pub summary: &'static str,
+    pub description: &'static str,
+    pub describe: fn(&mut Operation<'_>),
+}
+
+/// Controller tag metadata registered by the `#[controller]` macro.
+#[doc(hidden)]
+pub struct TagRegistrar {
+    pub controller_id: TypeId,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+inventory::collect!(OperationRegistrar);
+inventory::collect!(TagRegistrar);
+
+/// The generated OpenAPI document of an application.
+///
+/// Stored in the application state when the `openapi` feature is enabled, so it can
+/// also be exported without going through HTTP, e.g. `state.get::<OpenApiDocument>()`.
+#[derive(Debug, Clone)]
+pub struct OpenApiDocument {
+    document: Value,
+}
+
+impl OpenApiDocument {
+    /// Assembles the document from every web controller registered in the application.
+    pub(crate) fn build(
+        config: &OpenApiConfig,
+        router_prefix: Option<&str>,
+        versioning: &VersioningConfig,
+        controllers: &ControllerMap,
+    ) -> Self {
+        let registered = controllers
+            .get(&Controller::Web)
+            .cloned()
+            .unwrap_or_default();
+
+        let controller_paths: BTreeMap<TypeId, String> =
+            inventory::iter::<WebControllerRegistrar>()
+                .filter(|controller| registered.contains(&controller.controller_id))
+                .map(|controller| {
+                    let path =
+                        versioning.controller_path(controller.controller_path, controller.version);
+
+                    (controller.controller_id, path)
+                })
+                .collect();
+
+        let tags: BTreeMap<TypeId, &TagRegistrar> = inventory::iter::<TagRegistrar>()
+            .filter(|tag| registered.contains(&tag.controller_id))
+            .map(|tag| (tag.controller_id, tag))
+            .collect();
+
+        let mut generator = SchemaSettings::draft2020_12()
+            .with(|settings| {
+                settings.definitions_path = "/components/schemas".into();
+                settings.meta_schema = None;
+            })
+            .into_generator();
+
+        let mut security_schemes = BTreeMap::new();
+        let mut paths: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+
+        for registrar in inventory::iter::<OperationRegistrar>() {
+            let Some(controller_path) = controller_paths.get(&registrar.controller_id) else {
+                continue;
+            };
+
+            let path = route_template(router_prefix, controller_path, registrar.path);
+            let path = path.replace("{*", "{");
+            let mut operation = Operation::new(&path, &mut generator, &mut security_schemes);
+
+            (registrar.describe)(&mut operation);
+
+            let tag = tags.get(&registrar.controller_id).map(|tag| tag.name);
+            let mut metadata = Map::new();
+
+            if let Some(tag) = tag {
+                metadata.insert("tags".to_string(), json!([tag]));
+                metadata.insert(
+                    "operationId".to_string(),
+                    json!(format!("{tag}_{}", registrar.handler)),
+                );
+            } else {
+                metadata.insert("operationId".to_string(), json!(registrar.handler));
+            }
+
+            if !registrar.summary.is_empty() {
+                metadata.insert("summary".to_string(), json!(registrar.summary));
+            }
+
+            if !registrar.description.is_empty() {
+                metadata.insert("description".to_string(), json!(registrar.description));
+            }
+
+            paths
+                .entry(path)
+                .or_default()
+                .insert(registrar.method.to_string(), operation.into_value(metadata));
+        }
+
+        let mut schemas = generator.take_definitions(true);
+        schemas.insert(RESPONSE_BODY_SCHEMA.to_string(), response_body_schema());
+
+        let mut tags = tags
+            .values()
+            .map(|tag| {
+                let mut value = json!({ "name": tag.name });
+
+                if !tag.description.is_empty() {
+                    value["description"] = json!(tag.description);
+                }
+
+                value
+            })
+            .collect::<Vec<_>>();
+
+        tags.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+
+        let mut info = json!({ "title": config.title, "version": config.version });
+
+        if let Some(description) = &config.description {
+            info["description"] = json!(description);
+        }
+
+        let mut components = json!({ "schemas": schemas });
+
+        if !security_schemes.is_empty() {
+            components["securitySchemes"] = json!(security_schemes);
+        }
+
+        Self {
+            document: json!({
+                "openapi": "3.1.0",
+                "info": info,
+                "tags": tags,
+                "paths": paths,
+                "components": components,
+            }),
+        }
+    }
+
+    pub fn as_value(&self) -> &Value {
+        &self.document
+    }
+
+    pub fn into_value(self) -> Value {
+        self.document
+    }
+
+    pub fn to_json_pretty(&self) -> String {
+        serde_json::to_string_pretty(&self.document).unwrap_or_default()
+    }
+}
+
+/// Bundled `swagger-ui-dist` assets, served next to the Swagger UI page.
+const SWAGGER_UI_ASSETS: [(&str, &str, &[u8]); 2] = [
+    (
+        "swagger-ui.css",
+        "text/css; charset=utf-8",
+        include_bytes!("../../assets/swagger-ui/swagger-ui.css"),
+    ),
+    (
+        "swagger-ui-bundle.js",
+        "text/javascript; charset=utf-8",
+        include_bytes!("../../assets/swagger-ui/swagger-ui-bundle.js"),
+    ),
+];
+
+/// Serves the document, and the Swagger UI page when enabled.
+pub(crate) fn with_openapi_routes(
+    router: Router<State>,
+    document: &OpenApiDocument,
+    config: &OpenApiConfig,
+) -> Router<State> {
+    let body = document.document.to_string();
+
+    let mut router = router.route(
+        &config.path,
+        get(move || async move { ([(CONTENT_TYPE, "application/json")], body) }),
+    );
+
+    if config.swagger_ui.enabled {
+        let page = swagger_ui_page(config);
+        router = router.route(
+            &config.swagger_ui.path,
+            get(move || async move { Html(page) }),
+        );
+
+        if config.swagger_ui.assets_url.is_none() {
+            let base = config.swagger_ui.path.trim_end_matches('/');
+
+            for (name, content_type, asset) in SWAGGER_UI_ASSETS {
+                router = router.route(
+                    &format!("{base}/{name}"),
+                    get(move || async move {
+                        (
+                            [
+                                (CONTENT_TYPE, content_type),
+                                (CACHE_CONTROL, "public, max-age=86400"),
+                            ],
+                            asset,
+                        )
+                    }),
+                );
+            }
+        }
+    }
+
+    router
+}
+
+fn swagger_ui_page(config: &OpenApiConfig) -> String {
+    let assets = config
+        .swagger_ui
+        .assets_url
+        .as_deref()
+        .unwrap_or(&config.swagger_ui.path)
+        .trim_end_matches('/');
+    let title = config
+        .title
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    let url = Value::from(config.path.as_str());
+
+    format!(
+        r##"<!doctype html>
+<html lang="en">
+  <head>
+    <meta charset="utf-8" />
+    <title>{title}</title>
+    <link rel="stylesheet" href="{assets}/swagger-ui.css" />
+  </head>
+  <body>
+    <div id="swagger-ui"></div>
+    <script src="{assets}/swagger-ui-bundle.js"></script>
+    <script>
+      window.ui = SwaggerUIBundle({{ url: {url}, dom_id: "#swagger-ui" }});
+    </script>
+  </body>
+</html>
+"##
+    )
+}
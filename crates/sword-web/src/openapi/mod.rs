@@ -0,0 +1,195 @@
+//! OpenAPI 3.1 document generation for web controllers.
+//!
+//! The `#[controller]` and route macros register every operation (method, path,
+//! controller tag and doc comments) at compile time. The rest of the operation is
+//! derived from the handler signature:
+//!
+//! - parameters implementing [`OpenApiInput`] describe path, query, header and body
+//!   inputs (`Path`, `Query`, `Header`, `Json` and `Validated` use their `JsonSchema`),
+//! - a `Result<_, E>` return type whose error derives `HttpError` documents its variants,
+//! - interceptors implementing [`OpenApiSecurity`] add a security requirement,
+//! - `#[openapi(response = T, status = 201)]` documents the `data` of the success response.
+//!
+//! Types that don't implement these traits are left out of the document. Routes can be
+//! hidden with `#[openapi(skip)]`.
+
+mod config;
+mod document;
+mod operation;
+mod security;
+
+pub use config::*;
+pub use document::*;
+pub use operation::{Operation, ParameterLocation};
+pub use security::*;
+
+pub use schemars::{self, JsonSchema};
+
+use crate::request::{Header, Json, Path, Query, TypedHeader};
+use serde_json::Value;
+
+/// Describes how a handler parameter contributes to its operation.
+pub trait OpenApiInput {
+    fn describe(operation: &mut Operation<'_>);
+}
+
+/// Documents the error responses of a handler error type.
+///
+/// Implemented by `#[derive(HttpError)]` for every variant.
+pub trait OpenApiErrors {
+    fn describe(operation: &mut Operation<'_>);
+}
+
+impl<T: JsonSchema> OpenApiInput for Path<T> {
+    fn describe(operation: &mut Operation<'_>) {
+        let schema = operation.inline_schema_for::<T>();
+        let names = operation.path_params().to_vec();
+
+        let schemas: Vec<Value> = match (schema.get("properties"), schema.get("prefixItems")) {
+            (Some(properties), _) => names
+                .iter()
+                .map(|name| properties.get(name).cloned().unwrap_or_default())
+                .collect(),
+            (_, Some(Value::Array(items))) => items.clone(),
+            _ => vec![schema; names.len()],
+        };
+
+        for (name, schema) in names.into_iter().zip(schemas) {
+            operation.add_parameter(ParameterLocation::Path, name, true, schema);
+        }
+
+        operation.add_error_response(400, "Invalid path parameters");
+    }
+}
+
+impl<T: JsonSchema> OpenApiInput for Query<T> {
+    fn describe(operation: &mut Operation<'_>) {
+        let schema = operation.inline_schema_for::<T>();
+
+        let required = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+            return;
+        };
+
+        for (name, property) in properties {
+            let is_required = required.iter().any(|field| field == name);
+            operation.add_parameter(
+                ParameterLocation::Query,
+                name,
+                is_required,
+                property.clone(),
+            );
+        }
+
+        operation.add_error_response(400, "Invalid query parameters");
+    }
+}
+
+impl<T: JsonSchema> OpenApiInput for Json<T> {
+    fn describe(operation: &mut Operation<'_>) {
+        let schema = operation.schema_for::<T>();
+
+        operation.set_request_body("application/json", schema);
+        operation.add_error_response(400, "Invalid request body");
+        operation.add_error_response(415, "Unsupported media type");
+    }
+}
+
+impl<T: TypedHeader> OpenApiInput for Header<T> {
+    fn describe(operation: &mut Operation<'_>) {
+        operation.add_parameter(
+            ParameterLocation::Header,
+            T::NAME,
+            true,
+            serde_json::json!({ "type": "string" }),
+        );
+        operation.add_error_response(400, "Missing required header");
+    }
+}
+
+#[cfg(feature = "validation-validator")]
+impl<E: OpenApiInput> OpenApiInput for crate::request::Validated<E> {
+    fn describe(operation: &mut Operation<'_>) {
+        E::describe(operation);
+    }
+}
+
+/// Compile-time dispatch used by the generated route metadata.
+///
+/// The macros call `(&Describe::<T>::new()).describe_*(op)` for every handler
+/// parameter, error type and interceptor. Method resolution picks the `Describe<T>`
+/// impl when `T` implements the matching trait and falls back to the no-op
+/// `&Describe<T>` impl otherwise, so unsupported types are skipped instead of
+/// failing to compile.
+#[doc(hidden)]
+pub mod __private {
+    use super::*;
+    use std::marker::PhantomData;
+
+    pub struct Describe<T: ?Sized>(PhantomData<T>);
+
+    impl<T: ?Sized> Describe<T> {
+        pub const fn new() -> Self {
+            Self(PhantomData)
+        }
+    }
+
+    impl<T: ?Sized> Default for Describe<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    pub trait DescribeInput {
+        fn describe_input(&self, operation: &mut Operation<'_>);
+    }
+
+    pub trait DescribeInputFallback {
+        fn describe_input(&self, _: &mut Operation<'_>) {}
+    }
+
+    impl<T: OpenApiInput> DescribeInput for Describe<T> {
+        fn describe_input(&self, operation: &mut Operation<'_>) {
+            T::describe(operation);
+        }
+    }
+
+    impl<T: ?Sized> DescribeInputFallback for &Describe<T> {}
+
+    pub trait DescribeErrors {
+        fn describe_errors(&self, operation: &mut Operation<'_>);
+    }
+
+    pub trait DescribeErrorsFallback {
+        fn describe_errors(&self, _: &mut Operation<'_>) {}
+    }
+
+    impl<T: OpenApiErrors> DescribeErrors for Describe<T> {
+        fn describe_errors(&self, operation: &mut Operation<'_>) {
+            T::describe(operation);
+        }
+    }
+
+    impl<T: ?Sized> DescribeErrorsFallback for &Describe<T> {}
+
+    pub trait DescribeSecurity {
+        fn describe_security(&self, operation: &mut Operation<'_>);
+    }
+
+    pub trait DescribeSecurityFallback {
+        fn describe_security(&self, _: &mut Operation<'_>) {}
+    }
+
+    impl<T: OpenApiSecurity> DescribeSecurity for Describe<T> {
+        fn describe_security(&self, operation: &mut Operation<'_>) {
+            operation.add_security::<T>();
+        }
+    }
+
+    impl<T: ?Sized> DescribeSecurityFallback for &Describe<T> {}
+}
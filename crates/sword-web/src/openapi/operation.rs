@@ -0,0 +1,257 @@
+use super::{OpenApiSecurity, SecurityScheme};
+
+use axum::http::StatusCode;
+use schemars::{JsonSchema, SchemaGenerator, generate::SchemaSettings};
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+
+/// Name of the `components.schemas` entry describing the `JsonResponse` envelope.
+pub(crate) const RESPONSE_BODY_SCHEMA: &str = "JsonResponseBody";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParameterLocation {
+    Path,
+    Query,
+    Header,
+    Cookie,
+}
+
+#[derive(Default)]
+struct ResponseDoc {
+    descriptions: Vec<String>,
+    data: Option<Value>,
+}
+
+/// A single operation being assembled from its handler signature.
+///
+/// Passed to [`OpenApiInput`](super::OpenApiInput), [`OpenApiErrors`](super::OpenApiErrors)
+/// and the generated route metadata. Schemas created through [`Operation::schema_for`]
+/// are collected into `components.schemas`.
+pub struct Operation<'a> {
+    path_params: Vec<String>,
+    parameters: Vec<(ParameterLocation, String, bool, Value)>,
+    request_body: Option<(String, Value)>,
+    responses: BTreeMap<u16, ResponseDoc>,
+    security: Vec<(&'static str, Vec<String>)>,
+    generator: &'a mut SchemaGenerator,
+    security_schemes: &'a mut BTreeMap<String, SecurityScheme>,
+}
+
+impl<'a> Operation<'a> {
+    pub(crate) fn new(
+        path: &str,
+        generator: &'a mut SchemaGenerator,
+        security_schemes: &'a mut BTreeMap<String, SecurityScheme>,
+    ) -> Self {
+        let path_params = path_params(path);
+
+        let parameters = path_params
+            .iter()
+            .map(|name| {
+                (
+                    ParameterLocation::Path,
+                    name.clone(),
+                    true,
+                    json!({ "type": "string" }),
+                )
+            })
+            .collect();
+
+        Self {
+            path_params,
+            parameters,
+            request_body: None,
+            responses: BTreeMap::new(),
+            security: Vec::new(),
+            generator,
+            security_schemes,
+        }
+    }
+
+    /// Names of the `{param}` segments of the route path, in order.
+    pub fn path_params(&self) -> &[String] {
+        &self.path_params
+    }
+
+    /// Schema of `T`, registered under `components.schemas` when it is a named type.
+    pub fn schema_for<T: JsonSchema>(&mut self) -> Value {
+        self.generator.subschema_for::<T>().to_value()
+    }
+
+    /// Schema of `T` with every subschema inlined. Used to split a struct into
+    /// individual path or query parameters.
+    pub fn inline_schema_for<T: JsonSchema>(&self) -> Value {
+        let mut schema = SchemaSettings::draft2020_12()
+            .with(|settings| settings.inline_subschemas = true)
+            .into_generator()
+            .into_root_schema_for::<T>()
+            .to_value();
+
+        if let Some(object) = schema.as_object_mut() {
+            object.remove("$schema");
+        }
+
+        schema
+    }
+
+    /// Adds a parameter, replacing any previous one with the same name and location.
+    pub fn add_parameter(
+        &mut self,
+        location: ParameterLocation,
+        name: impl Into<String>,
+        required: bool,
+        schema: Value,
+    ) {
+        let name = name.into();
+
+        self.parameters
+            .retain(|(loc, existing, ..)| *loc != location || *existing != name);
+        self.parameters.push((location, name, required, schema));
+    }
+
+    pub fn set_request_body(&mut self, content_type: impl Into<String>, schema: Value) {
+        self.request_body = Some((content_type.into(), schema));
+    }
+
+    /// Documents a response wrapped in the `JsonResponse` envelope, with `data`
+    /// as the schema of its `data` field.
+    pub fn add_response(
+        &mut self,
+        status: u16,
+        description: impl Into<String>,
+        data: Option<Value>,
+    ) {
+        let response = self.responses.entry(status).or_default();
+        let description = description.into();
+
+        if !response.descriptions.contains(&description) {
+            response.descriptions.push(description);
+        }
+
+        if data.is_some() {
+            response.data = data;
+        }
+    }
+
+    /// Documents an error response. Several errors sharing a status code are
+    /// listed together in its description.
+    pub fn add_error_response(&mut self, status: u16, description: impl Into<String>) {
+        self.add_response(status, description, None);
+    }
+
+    /// Documents the default success response of the route.
+    pub fn add_success_response(&mut self, status: u16, data: Option<Value>) {
+        let description = StatusCode::from_u16(status)
+            .ok()
+            .and_then(|code| code.canonical_reason())
+            .unwrap_or("Successful response");
+
+        self.add_response(status, description, data);
+    }
+
+    pub fn add_security<S: OpenApiSecurity>(&mut self) {
+        self.security_schemes
+            .insert(S::SCHEME_NAME.to_string(), S::security_scheme());
+
+        if !self
+            .security
+            .iter()
+            .any(|(name, _)| *name == S::SCHEME_NAME)
+        {
+            self.security.push((S::SCHEME_NAME, S::scopes()));
+        }
+    }
+
+    pub(crate) fn into_value(self, mut operation: Map<String, Value>) -> Value {
+        if !self.parameters.is_empty() {
+            let parameters = self
+                .parameters
+                .into_iter()
+                .map(|(location, name, required, schema)| {
+                    json!({
+                        "name": name,
+                        "in": location,
+                        "required": required,
+                        "schema": schema,
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            operation.insert("parameters".to_string(), Value::Array(parameters));
+        }
+
+        if let Some((content_type, schema)) = self.request_body {
+            operation.insert(
+                "requestBody".to_string(),
+                json!({
+                    "required": true,
+                    "content": { content_type: { "schema": schema } },
+                }),
+            );
+        }
+
+        let responses = self
+            .responses
+            .into_iter()
+            .map(|(status, response)| (status.to_string(), response_value(response)))
+            .collect::<Map<_, _>>();
+
+        operation.insert("responses".to_string(), Value::Object(responses));
+
+        if !self.security.is_empty() {
+            let security = self
+                .security
+                .into_iter()
+                .map(|(name, scopes)| json!({ name: scopes }))
+                .collect::<Vec<_>>();
+
+            operation.insert("security".to_string(), Value::Array(security));
+        }
+
+        Value::Object(operation)
+    }
+}
+
+fn response_value(response: ResponseDoc) -> Value {
+    let envelope = json!({ "$ref": format!("#/components/schemas/{RESPONSE_BODY_SCHEMA}") });
+
+    let schema = match response.data {
+        Some(data) => json!({
+            "allOf": [envelope, { "properties": { "data": data } }],
+        }),
+        None => envelope,
+    };
+
+    json!({
+        "description": response.descriptions.join("; "),
+        "content": { "application/json": { "schema": schema } },
+    })
+}
+
+/// Schema of the envelope produced by `JsonResponse`.
+pub(crate) fn response_body_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "code": { "type": "integer" },
+            "success": { "type": "boolean" },
+            "message": { "type": "string" },
+            "timestamp": { "type": "string" },
+            "request_id": { "type": ["string", "null"] },
+            "data": {},
+            "error": {},
+            "errors": {},
+        },
+        "required": ["code", "success", "message", "timestamp"],
+    })
+}
+
+/// Extracts parameter names from an axum route path (`/files/{id}/{*rest}` => `id`, `rest`).
+fn path_params(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .map(|name| name.trim_start_matches('*').to_string())
+        .collect()
+}
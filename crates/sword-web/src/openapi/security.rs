@@ -0,0 +1,92 @@
+use serde::Serialize;
+
+/// An entry of `components.securitySchemes`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum SecurityScheme {
+    #[serde(rename = "http")]
+    Http {
+        scheme: String,
+
+        #[serde(rename = "bearerFormat", skip_serializing_if = "Option::is_none")]
+        bearer_format: Option<String>,
+    },
+
+    #[serde(rename = "apiKey")]
+    ApiKey {
+        name: String,
+
+        #[serde(rename = "in")]
+        location: ApiKeyLocation,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyLocation {
+    Header,
+    Query,
+    Cookie,
+}
+
+impl SecurityScheme {
+    /// `Authorization: Bearer <token>`.
+    pub fn bearer() -> Self {
+        Self::Http {
+            scheme: "bearer".to_string(),
+            bearer_format: None,
+        }
+    }
+
+    /// `Authorization: Bearer <token>` with a format hint such as `"JWT"`.
+    pub fn bearer_with_format(format: impl Into<String>) -> Self {
+        Self::Http {
+            scheme: "bearer".to_string(),
+            bearer_format: Some(format.into()),
+        }
+    }
+
+    /// `Authorization: Basic <credentials>`.
+    pub fn basic() -> Self {
+        Self::Http {
+            scheme: "basic".to_string(),
+            bearer_format: None,
+        }
+    }
+
+    pub fn api_key(location: ApiKeyLocation, name: impl Into<String>) -> Self {
+        Self::ApiKey {
+            name: name.into(),
+            location,
+        }
+    }
+}
+
+/// Declares the security scheme enforced by an interceptor.
+///
+/// Routes using the interceptor, directly or through their controller, get a
+/// security requirement on this scheme.
+///
+/// ```rust,ignore
+/// #[derive(Interceptor)]
+/// struct AuthInterceptor;
+///
+/// impl OpenApiSecurity for AuthInterceptor {
+///     const SCHEME_NAME: &'static str = "bearerAuth";
+///
+///     fn security_scheme() -> SecurityScheme {
+///         SecurityScheme::bearer_with_format("JWT")
+///     }
+/// }
+/// ```
+pub trait OpenApiSecurity {
+    /// Key of the scheme under `components.securitySchemes`.
+    const SCHEME_NAME: &'static str;
+
+    fn security_scheme() -> SecurityScheme;
+
+    /// Scopes required by the interceptor. Empty by default.
+    fn scopes() -> Vec<String> {
+        Vec::new()
+    }
+}
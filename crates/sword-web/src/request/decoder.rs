@@ -0,0 +1,235 @@
+use cbor4ii::core::dec::{Read as _, Reference};
+use serde::de::DeserializeOwned;
+use std::error::Error as StdError;
+
+type BoxError = Box<dyn StdError + Send + Sync>;
+
+type ErasedVisit<'a, 'de> =
+    dyn FnMut(&mut dyn erased_serde::Deserializer<'de>) -> Result<(), erased_serde::Error> + 'a;
+
+/// Decodes request bodies of one or more media types for `Request::body::<T>()`.
+///
+/// The decoder is picked from the request `Content-Type`. Custom decoders are
+/// registered with [`BodyDecoderRegistrar`] and take precedence over the built-in
+/// ones, so they can also replace them.
+///
+/// ```rust,ignore
+/// struct YamlDecoder;
+///
+/// impl BodyDecoder for YamlDecoder {
+///     fn accepts(&self, media_type: &str) -> bool {
+///         media_type == "application/yaml"
+///     }
+///
+///     fn decode<'de>(
+///         &self,
+///         body: &'de [u8],
+///         target: BodyTarget<'_, 'de>,
+///     ) -> Result<(), Box<dyn Error + Send + Sync>> {
+///         target.deserialize(serde_yaml::Deserializer::from_slice(body))
+///     }
+/// }
+///
+/// sword::inventory_submit! {[BodyDecoderRegistrar::new(&YamlDecoder)]}
+/// ```
+pub trait BodyDecoder: Send + Sync + 'static {
+    /// Whether the decoder handles `media_type`, given as a lowercase essence
+    /// without parameters (e.g. `application/json`).
+    fn accepts(&self, media_type: &str) -> bool;
+
+    /// Feeds `body` to `target` through a `serde` deserializer.
+    fn decode<'de>(
+        &self,
+        body: &'de [u8],
+        target: BodyTarget<'_, 'de>,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>>;
+}
+
+/// The value being decoded, independent of its concrete type.
+pub struct BodyTarget<'a, 'de> {
+    visit: &'a mut ErasedVisit<'a, 'de>,
+}
+
+impl<'de> BodyTarget<'_, 'de> {
+    /// Deserializes the target value from `deserializer`.
+    pub fn deserialize<D>(self, deserializer: D) -> Result<(), Box<dyn StdError + Send + Sync>>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut deserializer = <dyn erased_serde::Deserializer>::erase(deserializer);
+
+        (self.visit)(&mut deserializer).map_err(Into::into)
+    }
+}
+
+/// Registers a custom [`BodyDecoder`] through `inventory_submit!`.
+pub struct BodyDecoderRegistrar {
+    pub decoder: &'static dyn BodyDecoder,
+}
+
+impl BodyDecoderRegistrar {
+    pub const fn new(decoder: &'static dyn BodyDecoder) -> Self {
+        Self { decoder }
+    }
+}
+
+inventory::collect!(BodyDecoderRegistrar);
+
+/// `application/json` and `application/*+json`.
+pub struct JsonDecoder;
+
+impl BodyDecoder for JsonDecoder {
+    fn accepts(&self, media_type: &str) -> bool {
+        media_type == "application/json"
+            || media_type.starts_with("application/") && media_type.ends_with("+json")
+    }
+
+    fn decode<'de>(
+        &self,
+        body: &'de [u8],
+        target: BodyTarget<'_, 'de>,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let mut deserializer = serde_json::Deserializer::from_slice(body);
+
+        target.deserialize(&mut deserializer)?;
+        deserializer.end()?;
+
+        Ok(())
+    }
+}
+
+/// `application/x-www-form-urlencoded`.
+pub struct FormDecoder;
+
+impl BodyDecoder for FormDecoder {
+    fn accepts(&self, media_type: &str) -> bool {
+        media_type == "application/x-www-form-urlencoded"
+    }
+
+    fn decode<'de>(
+        &self,
+        body: &'de [u8],
+        target: BodyTarget<'_, 'de>,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        target.deserialize(serde_urlencoded::Deserializer::new(form_urlencoded::parse(
+            body,
+        )))
+    }
+}
+
+/// `application/msgpack`, `application/x-msgpack` and `application/vnd.msgpack`.
+pub struct MsgPackDecoder;
+
+impl BodyDecoder for MsgPackDecoder {
+    fn accepts(&self, media_type: &str) -> bool {
+        matches!(
+            media_type,
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack"
+        )
+    }
+
+    fn decode<'de>(
+        &self,
+        body: &'de [u8],
+        target: BodyTarget<'_, 'de>,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let mut rest = body;
+
+        target.deserialize(&mut rmp_serde::Deserializer::new(&mut rest))?;
+
+        if !rest.is_empty() {
+            return Err("trailing bytes after the MessagePack value".into());
+        }
+
+        Ok(())
+    }
+}
+
+/// `application/cbor`.
+pub struct CborDecoder;
+
+impl BodyDecoder for CborDecoder {
+    fn accepts(&self, media_type: &str) -> bool {
+        media_type == "application/cbor"
+    }
+
+    fn decode<'de>(
+        &self,
+        body: &'de [u8],
+        target: BodyTarget<'_, 'de>,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let reader = cbor4ii::core::utils::SliceReader::new(body);
+        let mut deserializer = cbor4ii::serde::Deserializer::new(reader);
+
+        target.deserialize(&mut deserializer)?;
+
+        let mut reader = deserializer.into_inner();
+
+        if !matches!(reader.fill(1)?, Reference::Long([]) | Reference::Short([])) {
+            return Err("trailing bytes after the CBOR value".into());
+        }
+
+        Ok(())
+    }
+}
+
+/// `application/xml`, `text/xml` and `application/*+xml`.
+#[cfg(feature = "xml")]
+pub struct XmlDecoder;
+
+#[cfg(feature = "xml")]
+impl BodyDecoder for XmlDecoder {
+    fn accepts(&self, media_type: &str) -> bool {
+        matches!(media_type, "application/xml" | "text/xml")
+            || media_type.starts_with("application/") && media_type.ends_with("+xml")
+    }
+
+    fn decode<'de>(
+        &self,
+        body: &'de [u8],
+        target: BodyTarget<'_, 'de>,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let body = std::str::from_utf8(body)?;
+
+        target.deserialize(&mut quick_xml::de::Deserializer::from_str(body))
+    }
+}
+
+fn built_in_decoders() -> impl Iterator<Item = &'static dyn BodyDecoder> {
+    let decoders: [&'static dyn BodyDecoder; 4] =
+        [&JsonDecoder, &FormDecoder, &MsgPackDecoder, &CborDecoder];
+
+    #[cfg(feature = "xml")]
+    let decoders = decoders
+        .into_iter()
+        .chain(std::iter::once(&XmlDecoder as &'static dyn BodyDecoder));
+
+    decoders.into_iter()
+}
+
+/// Finds the decoder of a `Content-Type` header value. Custom decoders are tried first.
+pub(crate) fn find_decoder(content_type: &str) -> Option<&'static dyn BodyDecoder> {
+    let mime = content_type.parse::<mime::Mime>().ok()?;
+    let media_type = mime.essence_str().to_ascii_lowercase();
+
+    inventory::iter::<BodyDecoderRegistrar>()
+        .map(|registrar| registrar.decoder)
+        .chain(built_in_decoders())
+        .find(|decoder| decoder.accepts(&media_type))
+}
+
+pub(crate) fn decode_body<T: DeserializeOwned>(
+    decoder: &dyn BodyDecoder,
+    body: &[u8],
+) -> Result<T, BoxError> {
+    let mut value = None;
+
+    let mut visit = |deserializer: &mut dyn erased_serde::Deserializer<'_>| {
+        value = Some(erased_serde::deserialize::<T>(deserializer)?);
+        Ok(())
+    };
+
+    decoder.decode(body, BodyTarget { visit: &mut visit })?;
+
+    value.ok_or_else(|| "body decoder did not produce a value".into())
+}
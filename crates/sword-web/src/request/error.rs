@@ -38,6 +38,9 @@ pub enum RequestError {
     #[error("Invalid header value for '{0}'")]
     InvalidHeaderValue(String),
 
+    #[error("Missing required header '{0}'")]
+    MissingHeader(String),
+
     #[cfg(feature = "multipart")]
     #[error("Multipart error: {0}")]
     MultipartError(#[from] axum::extract::multipart::MultipartError),
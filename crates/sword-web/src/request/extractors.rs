@@ -0,0 +1,296 @@
+use super::{FromRequest, FromRequestParts, Request, RequestError, parse_query};
+use sword_core::State;
+
+use axum::{
+    extract::{
+        FromRequest as AxumFromRequest, FromRequestParts as AxumFromRequestParts, Path as AxumPath,
+        Request as AxumReq, rejection::PathRejection,
+    },
+    http::{HeaderValue, request::Parts},
+    response::{IntoResponse, Response},
+};
+use axum_responses::JsonResponse;
+use serde::de::DeserializeOwned;
+use sword_layers::{forwarded::ClientInfo, request_id::RequestId};
+
+/// Extracts and deserializes the route path parameters.
+///
+/// A single parameter can be extracted directly (`Path<u32>`), several as a tuple
+/// (`Path<(u32, String)>`) or as a struct with named fields.
+///
+/// ```rust,ignore
+/// #[get("/users/{id}")]
+/// async fn get_user(&self, Path(id): Path<u32>) -> WebResult {
+///     Ok(JsonResponse::Ok().data(id))
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Path<T>(pub T);
+
+/// Deserializes the URL query string. A missing query string is treated as empty,
+/// so `T` should use `Option` or `#[serde(default)]` for optional parameters.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Query<T>(pub T);
+
+/// Deserializes a JSON request body.
+///
+/// Applies the same body limit, `Content-Type` and emptiness checks as `Request::body`.
+/// Since it consumes the body, it must be the last handler parameter.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+/// Extracts a single request header decoded through [`TypedHeader`].
+#[derive(Debug, Clone)]
+pub struct Header<T>(pub T);
+
+/// A header that can be extracted with [`Header`].
+///
+/// ```rust,ignore
+/// struct ApiKey(String);
+///
+/// impl TypedHeader for ApiKey {
+///     const NAME: &'static str = "x-api-key";
+///
+///     fn decode(value: &HeaderValue) -> Result<Self, RequestError> {
+///         let value = value.to_str().map_err(|_| RequestError::invalid_header_value(Self::NAME))?;
+///         Ok(Self(value.to_owned()))
+///     }
+/// }
+///
+/// #[get("/private")]
+/// async fn private(&self, Header(key): Header<ApiKey>) -> WebResult { ... }
+/// ```
+pub trait TypedHeader: Sized {
+    const NAME: &'static str;
+
+    fn decode(value: &HeaderValue) -> Result<Self, RequestError>;
+}
+
+impl<T> FromRequestParts for Path<T>
+where
+    T: DeserializeOwned + Send,
+{
+    type Rejection = JsonResponse;
+
+    async fn from_request_parts(parts: &mut Parts, _: &State) -> Result<Self, Self::Rejection> {
+        let AxumPath(value) = AxumPath::<T>::from_request_parts(parts, &())
+            .await
+            .map_err(|rejection| match rejection {
+                PathRejection::FailedToDeserializePathParams(err) => {
+                    RequestError::deserialization_error(
+                        "Invalid path parameters",
+                        err.body_text(),
+                        err.into(),
+                    )
+                }
+                rejection => {
+                    RequestError::parse_error("Invalid path parameters", rejection.body_text())
+                }
+            })?;
+
+        Ok(Self(value))
+    }
+}
+
+impl<T> FromRequestParts for Query<T>
+where
+    T: DeserializeOwned,
+{
+    type Rejection = JsonResponse;
+
+    async fn from_request_parts(parts: &mut Parts, _: &State) -> Result<Self, Self::Rejection> {
+        let query = parts.uri.query().unwrap_or_default();
+
+        Ok(Self(parse_query(query)?))
+    }
+}
+
+impl<T> FromRequest for Json<T>
+where
+    T: DeserializeOwned,
+{
+    type Rejection = JsonResponse;
+
+    async fn from_request(req: AxumReq, state: &State) -> Result<Self, Self::Rejection> {
+        let request = <Request as FromRequest>::from_request(req, state).await?;
+
+        if !request.body_bytes.is_empty() && !request.is_content_type_json() {
+            return Err(RequestError::unsupported_media_type(
+                "Expected Content-Type to be application/json",
+            ))?;
+        }
+
+        Ok(Self(request.body::<T>()?))
+    }
+}
+
+impl<T> FromRequestParts for Header<T>
+where
+    T: TypedHeader,
+{
+    type Rejection = JsonResponse;
+
+    async fn from_request_parts(parts: &mut Parts, _: &State) -> Result<Self, Self::Rejection> {
+        let value = parts
+            .headers
+            .get(T::NAME)
+            .ok_or_else(|| RequestError::MissingHeader(T::NAME.to_owned()))?;
+
+        Ok(Self(T::decode(value)?))
+    }
+}
+
+impl FromRequestParts for RequestId {
+    type Rejection = JsonResponse;
+
+    async fn from_request_parts(parts: &mut Parts, _: &State) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<RequestId>().cloned().ok_or_else(|| {
+            JsonResponse::InternalServerError()
+                .message("Can't extract request id. Is `RequestIdLayer` enabled?")
+        })
+    }
+}
+
+impl FromRequestParts for ClientInfo {
+    type Rejection = JsonResponse;
+
+    async fn from_request_parts(parts: &mut Parts, _: &State) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<ClientInfo>()
+            .cloned()
+            .ok_or_else(|| {
+                JsonResponse::InternalServerError()
+                    .message("Can't extract client info. Is `ClientInfoLayer` enabled?")
+            })
+    }
+}
+
+#[cfg(feature = "validation-validator")]
+pub use validated::*;
+
+#[cfg(feature = "validation-validator")]
+mod validated {
+    use super::*;
+    use crate::response::format_validator_errors;
+    use validator::Validate;
+
+    /// Runs `validator` rules on the value produced by another extractor.
+    ///
+    /// ```rust,ignore
+    /// #[post("/users")]
+    /// async fn create(&self, Validated(Json(user)): Validated<Json<CreateUser>>) -> WebResult {
+    ///     Ok(JsonResponse::Created().data(user))
+    /// }
+    /// ```
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct Validated<E>(pub E);
+
+    /// Extractors whose output can be checked by [`Validated`].
+    pub trait ValidateExtractor {
+        type Target: Validate;
+
+        /// Message attached to the validation error response.
+        const MESSAGE: &'static str;
+
+        fn target(&self) -> &Self::Target;
+    }
+
+    impl<T: Validate> ValidateExtractor for Json<T> {
+        type Target = T;
+        const MESSAGE: &'static str = "Invalid request body";
+
+        fn target(&self) -> &T {
+            &self.0
+        }
+    }
+
+    impl<T: Validate> ValidateExtractor for Query<T> {
+        type Target = T;
+        const MESSAGE: &'static str = "Invalid request query";
+
+        fn target(&self) -> &T {
+            &self.0
+        }
+    }
+
+    impl<T: Validate> ValidateExtractor for Path<T> {
+        type Target = T;
+        const MESSAGE: &'static str = "Invalid request params";
+
+        fn target(&self) -> &T {
+            &self.0
+        }
+    }
+
+    fn validate<E: ValidateExtractor>(extracted: E) -> Result<Validated<E>, JsonResponse> {
+        extracted.target().validate().map_err(|error| {
+            RequestError::validator_error(E::MESSAGE, format_validator_errors(error))
+        })?;
+
+        Ok(Validated(extracted))
+    }
+
+    impl<E> FromRequest for Validated<E>
+    where
+        E: FromRequest<Rejection = JsonResponse> + ValidateExtractor,
+    {
+        type Rejection = JsonResponse;
+
+        async fn from_request(req: AxumReq, state: &State) -> Result<Self, Self::Rejection> {
+            validate(E::from_request(req, state).await?)
+        }
+    }
+
+    impl<E> FromRequestParts for Validated<E>
+    where
+        E: FromRequestParts<Rejection = JsonResponse> + ValidateExtractor,
+    {
+        type Rejection = JsonResponse;
+
+        async fn from_request_parts(
+            parts: &mut Parts,
+            state: &State,
+        ) -> Result<Self, Self::Rejection> {
+            validate(E::from_request_parts(parts, state).await?)
+        }
+    }
+}
+
+/// Adapter used by the route macros to run sword extractors inside axum handlers.
+///
+/// Body extractors go through the `ViaRequest` marker while parts extractors use
+/// axum's own blanket implementation, so a type never matches both.
+#[doc(hidden)]
+pub struct Extract<T>(pub T);
+
+#[doc(hidden)]
+pub enum ViaRequest {}
+
+impl<T> AxumFromRequestParts<State> for Extract<T>
+where
+    T: FromRequestParts,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &State) -> Result<Self, Self::Rejection> {
+        T::from_request_parts(parts, state)
+            .await
+            .map(Self)
+            .map_err(IntoResponse::into_response)
+    }
+}
+
+impl<T> AxumFromRequest<State, ViaRequest> for Extract<T>
+where
+    T: FromRequest,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: AxumReq, state: &State) -> Result<Self, Self::Rejection> {
+        T::from_request(req, state)
+            .await
+            .map(Self)
+            .map_err(IntoResponse::into_response)
+    }
+}
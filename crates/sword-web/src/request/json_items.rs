@@ -0,0 +1,448 @@
+use crate::request::{RequestError, StreamRequest};
+
+use axum::body::BodyDataStream;
+use axum_responses::JsonResponse;
+use futures_lite::{Stream, StreamExt, stream};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use std::pin::Pin;
+use thiserror::Error;
+
+#[cfg(feature = "validation-validator")]
+use crate::response::format_validator_errors;
+
+#[cfg(feature = "validation-validator")]
+use validator::Validate;
+
+/// Items decoded from a streamed JSON body by [`StreamRequest::json_lines`] and
+/// [`StreamRequest::json_array`].
+pub type JsonItemStream<T> = Pin<Box<dyn Stream<Item = Result<T, JsonItemError>> + Send>>;
+
+/// An error for one item of a streamed JSON body.
+///
+/// Items that can't be decoded into the target type are reported and the stream
+/// continues with the next one. Malformed JSON, a body over the limit or a read
+/// failure end the stream.
+#[derive(Debug, Error)]
+#[error("Item {index} at byte {offset}: {error}")]
+pub struct JsonItemError {
+    /// Zero-based position of the item in the body.
+    pub index: usize,
+
+    /// Byte offset of the item (or of the error) in the body.
+    pub offset: usize,
+
+    pub error: RequestError,
+}
+
+impl From<JsonItemError> for JsonResponse {
+    fn from(item: JsonItemError) -> JsonResponse {
+        let position = json!({ "index": item.index, "offset": item.offset });
+
+        match item.error {
+            RequestError::BodyTooLarge => JsonResponse::PayloadTooLarge()
+                .message("The request body exceeds the maximum allowed size by the server")
+                .error(position),
+
+            #[cfg(feature = "validation-validator")]
+            RequestError::ValidatorError { message, details } => JsonResponse::BadRequest()
+                .message(message)
+                .error(position)
+                .errors(details),
+
+            error => {
+                tracing::error!(index = item.index, offset = item.offset, error = %error, "Invalid request body item");
+
+                JsonResponse::BadRequest()
+                    .message("Invalid request body item")
+                    .error(json!({
+                        "index": item.index,
+                        "offset": item.offset,
+                        "reason": error.to_string(),
+                    }))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum JsonItemFormat {
+    Lines,
+    Array,
+}
+
+#[derive(Clone, Copy)]
+enum ArrayState {
+    BeforeOpen,
+    BeforeItem {
+        first: bool,
+    },
+    InItem {
+        start: usize,
+        depth: usize,
+        in_string: bool,
+        escaped: bool,
+    },
+    Closed,
+}
+
+/// Splits a body into raw JSON items, reading it chunk by chunk.
+struct JsonItemReader {
+    body: BodyDataStream,
+    format: JsonItemFormat,
+    buffer: Vec<u8>,
+    /// Body offset of `buffer[0]`.
+    buffer_offset: usize,
+    /// Position in `buffer` up to which bytes have been scanned.
+    cursor: usize,
+    array: ArrayState,
+    body_limit: usize,
+    index: usize,
+    eof: bool,
+    finished: bool,
+}
+
+type RawItem = Result<(usize, Vec<u8>), (usize, RequestError)>;
+
+type ItemCheck<T> = fn(&T) -> Result<(), RequestError>;
+
+impl JsonItemReader {
+    fn new(request: StreamRequest, format: JsonItemFormat) -> Self {
+        Self {
+            body_limit: request.body_limit(),
+            body: request.into_body().into_data_stream(),
+            format,
+            buffer: Vec::new(),
+            buffer_offset: 0,
+            cursor: 0,
+            array: ArrayState::BeforeOpen,
+            index: 0,
+            eof: false,
+            finished: false,
+        }
+    }
+
+    /// Reads the next chunk, dropping the bytes that were already consumed.
+    async fn fill(&mut self) -> Result<(), RequestError> {
+        let keep_from = match self.array {
+            ArrayState::InItem { start, .. } => start,
+            _ => self.cursor,
+        };
+
+        let keep_from = match self.format {
+            JsonItemFormat::Lines => 0,
+            JsonItemFormat::Array => keep_from,
+        };
+
+        self.buffer.drain(..keep_from);
+        self.buffer_offset += keep_from;
+        self.cursor -= keep_from;
+
+        if let ArrayState::InItem { start, .. } = &mut self.array {
+            *start -= keep_from;
+        }
+
+        match self.body.next().await {
+            Some(Ok(chunk)) => {
+                if self.buffer_offset + self.buffer.len() + chunk.len() > self.body_limit {
+                    return Err(RequestError::BodyTooLarge);
+                }
+
+                self.buffer.extend_from_slice(&chunk);
+            }
+            Some(Err(error)) => {
+                return Err(RequestError::from_body_read_error(
+                    error.into_inner().as_ref(),
+                ));
+            }
+            None => self.eof = true,
+        }
+
+        Ok(())
+    }
+
+    async fn next_raw(&mut self) -> Option<RawItem> {
+        loop {
+            let found = match self.format {
+                JsonItemFormat::Lines => self.next_line(),
+                JsonItemFormat::Array => self.next_element(),
+            };
+
+            if let Some(found) = found {
+                return found;
+            }
+
+            if self.eof {
+                return None;
+            }
+
+            let offset = self.buffer_offset + self.buffer.len();
+
+            if let Err(error) = self.fill().await {
+                return Some(Err((offset, error)));
+            }
+        }
+    }
+
+    /// `Some(None)` ends the stream; `None` needs more data.
+    fn next_line(&mut self) -> Option<Option<RawItem>> {
+        loop {
+            let newline = self.buffer[self.cursor..]
+                .iter()
+                .position(|byte| *byte == b'\n')
+                .map(|position| self.cursor + position);
+
+            let end = match newline {
+                Some(end) => end,
+                None if self.eof => self.buffer.len(),
+                None => {
+                    self.cursor = self.buffer.len();
+                    return None;
+                }
+            };
+
+            let line = self.buffer[..end].to_vec();
+            let offset = self.buffer_offset;
+            let consumed = (end + 1).min(self.buffer.len());
+
+            self.buffer.drain(..consumed);
+            self.buffer_offset += consumed;
+            self.cursor = 0;
+
+            if !line.trim_ascii().is_empty() {
+                return Some(Some(Ok((offset, line))));
+            }
+
+            if newline.is_none() {
+                return Some(None);
+            }
+        }
+    }
+
+    fn next_element(&mut self) -> Option<Option<RawItem>> {
+        while self.cursor < self.buffer.len() {
+            let position = self.cursor;
+            let byte = self.buffer[position];
+            let offset = self.buffer_offset + position;
+
+            match &mut self.array {
+                ArrayState::BeforeOpen | ArrayState::BeforeItem { .. } | ArrayState::Closed
+                    if byte.is_ascii_whitespace() =>
+                {
+                    self.cursor += 1;
+                }
+                ArrayState::BeforeOpen if byte == b'[' => {
+                    self.array = ArrayState::BeforeItem { first: true };
+                    self.cursor += 1;
+                }
+                ArrayState::BeforeOpen => {
+                    return Some(Some(Err(syntax_error(offset, "expected a JSON array"))));
+                }
+                ArrayState::BeforeItem { first: true } if byte == b']' => {
+                    self.array = ArrayState::Closed;
+                    self.cursor += 1;
+                }
+                ArrayState::BeforeItem { .. } if matches!(byte, b',' | b']') => {
+                    return Some(Some(Err(syntax_error(offset, "expected an array element"))));
+                }
+                ArrayState::BeforeItem { .. } => {
+                    self.array = ArrayState::InItem {
+                        start: position,
+                        depth: 0,
+                        in_string: false,
+                        escaped: false,
+                    };
+                }
+                ArrayState::InItem {
+                    in_string: in_string @ true,
+                    escaped,
+                    ..
+                } => {
+                    if *escaped {
+                        *escaped = false;
+                    } else if byte == b'\\' {
+                        *escaped = true;
+                    } else if byte == b'"' {
+                        *in_string = false;
+                    }
+
+                    self.cursor += 1;
+                }
+                ArrayState::InItem {
+                    start,
+                    depth,
+                    in_string,
+                    ..
+                } => {
+                    match byte {
+                        b'"' => *in_string = true,
+                        b'{' | b'[' => *depth += 1,
+                        b'}' | b']' if *depth > 0 => *depth -= 1,
+                        b',' | b']' if *depth == 0 => {
+                            let start = *start;
+                            let element = self.buffer[start..position].to_vec();
+
+                            self.array = match byte {
+                                b',' => ArrayState::BeforeItem { first: false },
+                                _ => ArrayState::Closed,
+                            };
+
+                            self.cursor += 1;
+
+                            return Some(Some(Ok((self.buffer_offset + start, element))));
+                        }
+                        b'}' => {
+                            return Some(Some(Err(syntax_error(offset, "unbalanced '}'"))));
+                        }
+                        _ => {}
+                    }
+
+                    self.cursor += 1;
+                }
+                ArrayState::Closed => {
+                    return Some(Some(Err(syntax_error(
+                        offset,
+                        "trailing characters after the JSON array",
+                    ))));
+                }
+            }
+        }
+
+        if !self.eof {
+            return None;
+        }
+
+        match self.array {
+            ArrayState::Closed => Some(None),
+            _ => {
+                let offset = self.buffer_offset + self.buffer.len();
+                Some(Some(Err(syntax_error(
+                    offset,
+                    "unexpected end of the JSON array",
+                ))))
+            }
+        }
+    }
+
+    async fn next_item<T: DeserializeOwned>(
+        &mut self,
+        check: ItemCheck<T>,
+    ) -> Option<Result<T, JsonItemError>> {
+        if self.finished {
+            return None;
+        }
+
+        let index = self.index;
+
+        let (offset, raw) = match self.next_raw().await? {
+            Ok(raw) => raw,
+            Err((offset, error)) => {
+                self.finished = true;
+                return Some(Err(JsonItemError {
+                    index,
+                    offset,
+                    error,
+                }));
+            }
+        };
+
+        self.index += 1;
+
+        let item = serde_json::from_slice::<T>(&raw)
+            .map_err(|error| {
+                RequestError::deserialization_error(
+                    "Invalid request body item",
+                    error.to_string(),
+                    error.into(),
+                )
+            })
+            .and_then(|item| check(&item).map(|()| item))
+            .map_err(|error| JsonItemError {
+                index,
+                offset,
+                error,
+            });
+
+        Some(item)
+    }
+}
+
+fn syntax_error(offset: usize, details: &str) -> (usize, RequestError) {
+    (
+        offset,
+        RequestError::parse_error("Invalid JSON array", details),
+    )
+}
+
+fn decode<T>(
+    request: StreamRequest,
+    format: JsonItemFormat,
+    check: ItemCheck<T>,
+) -> JsonItemStream<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    let reader = JsonItemReader::new(request, format);
+
+    Box::pin(stream::unfold(reader, move |mut reader| async move {
+        let item = reader.next_item(check).await?;
+        Some((item, reader))
+    }))
+}
+
+#[cfg(feature = "validation-validator")]
+fn validate<T: Validate>(item: &T) -> Result<(), RequestError> {
+    item.validate().map_err(|error| {
+        RequestError::validator_error("Invalid request body item", format_validator_errors(error))
+    })
+}
+
+impl StreamRequest {
+    /// Decodes the body as newline-delimited JSON, one `T` per non-empty line,
+    /// without buffering it. The body limit applies to the whole body.
+    ///
+    /// ```rust,ignore
+    /// #[post("/import")]
+    /// async fn import(&self, req: StreamRequest) -> WebResult {
+    ///     let mut rows = req.json_lines::<Row>();
+    ///
+    ///     while let Some(row) = rows.next().await {
+    ///         self.rows.insert(row?).await;
+    ///     }
+    ///
+    ///     Ok(JsonResponse::Ok())
+    /// }
+    /// ```
+    pub fn json_lines<T>(self) -> JsonItemStream<T>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        decode(self, JsonItemFormat::Lines, |_| Ok(()))
+    }
+
+    /// Decodes the body as a JSON array, yielding each element as it arrives.
+    pub fn json_array<T>(self) -> JsonItemStream<T>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        decode(self, JsonItemFormat::Array, |_| Ok(()))
+    }
+
+    /// Like [`StreamRequest::json_lines`], validating every item.
+    #[cfg(feature = "validation-validator")]
+    pub fn json_lines_validator<T>(self) -> JsonItemStream<T>
+    where
+        T: DeserializeOwned + Validate + Send + 'static,
+    {
+        decode(self, JsonItemFormat::Lines, validate::<T>)
+    }
+
+    /// Like [`StreamRequest::json_array`], validating every item.
+    #[cfg(feature = "validation-validator")]
+    pub fn json_array_validator<T>(self) -> JsonItemStream<T>
+    where
+        T: DeserializeOwned + Validate + Send + 'static,
+    {
+        decode(self, JsonItemFormat::Array, validate::<T>)
+    }
+}
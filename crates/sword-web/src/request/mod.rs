@@ -1,11 +1,15 @@
+mod decoder;
 mod error;
 mod extract;
+mod extractors;
+mod json_items;
 mod parts;
 
 #[cfg(feature = "validation-validator")]
 mod validator;
 
 use crate::interceptor::WebInterceptorResult;
+use crate::response::{ETag, check_preconditions};
 use axum::{
     body::Body as AxumBody,
     body::Bytes as BodyBytes,
@@ -14,14 +18,45 @@ use axum::{
 };
 use axum_responses::JsonResponse;
 use serde::de::DeserializeOwned;
-use std::{collections::HashMap, fmt::Display, str::FromStr};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    time::SystemTime,
+};
 use sword_layers::cookies::Cookies;
+use sword_layers::forwarded::ClientInfo;
 use sword_layers::request_id::RequestId;
 
+#[cfg(feature = "tls")]
+use sword_layers::tls::PeerIdentity;
+
+#[cfg(feature = "jwt")]
+use sword_layers::jwt::JwtClaims;
+
+#[cfg(feature = "i18n")]
+use crate::i18n::Locale;
+
+#[cfg(feature = "sessions")]
+use sword_layers::sessions::Session;
+
+#[cfg(feature = "csrf")]
+use {axum::extract::OriginalUri, sword_layers::csrf::CsrfToken};
+
+pub use decoder::{
+    BodyDecoder, BodyDecoderRegistrar, BodyTarget, CborDecoder, FormDecoder, JsonDecoder,
+    MsgPackDecoder,
+};
 pub use error::*;
 
+#[cfg(feature = "xml")]
+pub use decoder::XmlDecoder;
+
 #[allow(unused_imports)]
 pub use extract::*;
+pub use extractors::*;
+pub use json_items::{JsonItemError, JsonItemStream};
 
 #[cfg(feature = "validation-validator")]
 pub use validator::ValidatorRequestValidation;
@@ -178,27 +213,28 @@ impl Request {
     //     )
     // }
 
-    /// Deserializes the request body from JSON to a specific type.
+    /// Deserializes the request body to a specific type.
     ///
-    /// This method reads the request body and attempts to parse it as JSON,
-    /// deserializing it to the specified type. The body is consumed during
-    /// this operation.
+    /// The body is decoded according to its `Content-Type`: JSON, form
+    /// (`application/x-www-form-urlencoded`), MessagePack, CBOR and, with the `xml`
+    /// feature, XML are supported out of the box. Additional formats can be added
+    /// by registering a [`BodyDecoder`].
     ///
     /// ### Type Parameters
     ///
-    /// * `T` - The type to deserialize the JSON body to (must implement `DeserializeOwned`)
+    /// * `T` - The type to deserialize the body to (must implement `DeserializeOwned`)
     ///
     /// ### Returns
     ///
-    /// Returns `Ok(T)` with the deserialized instance if the JSON is valid,
-    /// or `Err(RequestError)` if the body is empty or invalid JSON.
+    /// Returns `Ok(T)` with the deserialized instance if the body is valid,
+    /// or `Err(RequestError)` if the body is empty or can't be decoded.
     ///
     /// # Errors
     ///
     /// This function will return an error if:
     /// - The request body is empty
-    /// - The body contains invalid JSON
-    /// - The JSON structure doesn't match the target type `T`
+    /// - No decoder handles the request `Content-Type`
+    /// - The body is malformed or doesn't match the target type `T`
     ///
     /// ### Example
     ///
@@ -229,17 +265,18 @@ impl Request {
             return Err(RequestError::BodyIsEmpty);
         }
 
-        if !self.is_content_type_json() {
-            return Err(RequestError::unsupported_media_type(
-                "Expected Content-Type to be application/json",
-            ));
-        }
+        let decoder = self
+            .content_type()
+            .and_then(decoder::find_decoder)
+            .ok_or_else(|| {
+                RequestError::unsupported_media_type("Unsupported Content-Type for request body")
+            })?;
 
-        serde_json::from_slice(&self.body_bytes).map_err(|e| {
+        decoder::decode_body(decoder, &self.body_bytes).map_err(|e| {
             RequestError::deserialization_error(
                 "Invalid request body",
                 "Failed to deserialize request body to the required type.".into(),
-                e.into(),
+                e,
             )
         })
     }
@@ -304,18 +341,7 @@ impl Request {
             return Ok(None);
         }
 
-        let deserializer =
-            serde_urlencoded::Deserializer::new(form_urlencoded::parse(query_string.as_bytes()));
-
-        let deserialized = T::deserialize(deserializer).map_err(|e| {
-            RequestError::deserialization_error(
-                "Invalid query parameters",
-                "Failed to deserialize query params to the required type.".into(),
-                e.into(),
-            )
-        })?;
-
-        Ok(Some(deserialized))
+        parse_query(query_string).map(Some)
     }
 
     /// Access the cookies from the request.
@@ -365,17 +391,164 @@ impl Request {
         self.header("User-Agent")
     }
 
-    pub fn ip(&self) -> Option<&str> {
-        self.header("X-Forwarded-For")
+    /// The resolved origin of the request. See [`ClientInfo`].
+    pub fn client_info(&self) -> Option<&ClientInfo> {
+        self.extensions.get::<ClientInfo>()
+    }
+
+    /// Address of the socket peer, which may be a proxy.
+    pub fn peer_addr(&self) -> Option<SocketAddr> {
+        self.client_info().and_then(|info| info.peer)
+    }
+
+    /// Address of the client. `Forwarded` or `X-Forwarded-For`, as selected by
+    /// `[web.trusted-proxies]`, is only honoured for hops added by a listed proxy.
+    pub fn ip(&self) -> Option<IpAddr> {
+        self.client_info().and_then(|info| info.ip)
     }
 
-    pub fn ips(&self) -> Option<Vec<&str>> {
-        self.header("X-Forwarded-For")
-            .map(|ips| ips.split(',').map(|s| s.trim()).collect())
+    /// Addresses from the client to the socket peer, client first.
+    pub fn ips(&self) -> Vec<IpAddr> {
+        self.client_info()
+            .map(|info| info.ips.clone())
+            .unwrap_or_default()
     }
 
+    /// Scheme used by the client, as reported by trusted proxies. Defaults to `http`.
     pub fn protocol(&self) -> &str {
-        self.header("X-Forwarded-Proto").unwrap_or("http")
+        self.client_info()
+            .map(|info| info.protocol.as_str())
+            .unwrap_or("http")
+    }
+
+    /// Host requested by the client, as reported by trusted proxies or the `Host` header.
+    pub fn host(&self) -> Option<&str> {
+        match self.client_info() {
+            Some(info) => info.host.as_deref(),
+            None => self.header("Host"),
+        }
+    }
+
+    /// Certificate identity of the client on a TLS listener with `client-ca` set.
+    #[cfg(feature = "tls")]
+    pub fn peer_identity(&self) -> Option<&PeerIdentity> {
+        self.extensions.get::<PeerIdentity>()
+    }
+
+    /// Deserializes the claims of the bearer token verified by `JwtAuth`.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[get("/me")]
+    /// #[interceptor(JwtAuth)]
+    /// async fn me(&self, req: Request) -> WebResult {
+    ///     let claims = req.claims::<UserClaims>()?;
+    ///     Ok(JsonResponse::Ok().data(claims.sub))
+    /// }
+    /// ```
+    #[cfg(feature = "jwt")]
+    pub fn claims<T: DeserializeOwned>(&self) -> Result<T, JsonResponse> {
+        jwt_claims(&self.extensions)
+    }
+
+    /// The server-side session of the request, configured under `[web.session]`.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[post("/login")]
+    /// async fn login(&self, req: Request) -> WebResult {
+    ///     let session = req.session()?;
+    ///
+    ///     session.rotate_id();
+    ///     session.insert("user_id", 42)?;
+    ///
+    ///     Ok(JsonResponse::Ok())
+    /// }
+    /// ```
+    #[cfg(feature = "sessions")]
+    pub fn session(&self) -> Result<&Session, JsonResponse> {
+        self.extensions.get::<Session>().ok_or_else(|| {
+            JsonResponse::InternalServerError()
+                .message("Can't access the session. Is `[web.session]` configured?")
+        })
+    }
+
+    /// The CSRF token of the request, to embed in forms or hand to scripts.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[get("/form")]
+    /// async fn form(&self, req: Request) -> WebResult {
+    ///     Ok(JsonResponse::Ok().data(json!({ "csrf": req.csrf_token()? })))
+    /// }
+    /// ```
+    #[cfg(feature = "csrf")]
+    pub fn csrf_token(&self) -> Result<&str, JsonResponse> {
+        self.extensions
+            .get::<CsrfToken>()
+            .map(CsrfToken::as_str)
+            .ok_or_else(|| {
+                JsonResponse::InternalServerError()
+                    .message("Can't read the CSRF token. Is `[web.csrf]` configured?")
+            })
+    }
+
+    /// The locale negotiated from the `Accept-Language` header among the
+    /// catalogs of `[web.i18n]`.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[get("/greeting")]
+    /// async fn greeting(&self, req: Request) -> WebResult {
+    ///     let message = match req.locale()?.language() {
+    ///         "es" => "Hola",
+    ///         _ => "Hello",
+    ///     };
+    ///
+    ///     Ok(JsonResponse::Ok().message(message))
+    /// }
+    /// ```
+    #[cfg(feature = "i18n")]
+    pub fn locale(&self) -> Result<&Locale, JsonResponse> {
+        self.extensions.get::<Locale>().ok_or_else(|| {
+            JsonResponse::InternalServerError()
+                .message("Can't read the locale. Is `[web.i18n]` configured?")
+        })
+    }
+
+    /// Checks `If-Match`, `If-Unmodified-Since` and `If-None-Match` against the
+    /// current validators of the entity, failing with `412 Precondition Failed`.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[put("/{id}")]
+    /// async fn update(&self, req: Request) -> WebResult {
+    ///     let order = self.orders.find(req.param("id")?).await?;
+    ///
+    ///     req.check_preconditions(Some(&ETag::strong(order.version)), Some(order.updated_at))?;
+    ///
+    ///     Ok(JsonResponse::Ok().data(self.orders.update(order, req.body()?).await?))
+    /// }
+    /// ```
+    pub fn check_preconditions(
+        &self,
+        etag: Option<&ETag>,
+        last_modified: Option<SystemTime>,
+    ) -> Result<(), JsonResponse> {
+        check_preconditions(&self.method, &self.headers, etag, last_modified)
+    }
+
+    /// The path the client requested, before any router prefix was stripped.
+    #[cfg(feature = "csrf")]
+    pub(crate) fn original_path(&self) -> &str {
+        self.extensions
+            .get::<OriginalUri>()
+            .map_or_else(|| self.uri.path(), |uri| uri.path())
+    }
+
+    #[cfg(feature = "csrf")]
+    pub(crate) fn body_bytes(&self) -> &[u8] {
+        &self.body_bytes
     }
 
     pub fn content_length(&self) -> Option<u64> {
@@ -530,17 +703,159 @@ impl StreamRequest {
         self.header("User-Agent")
     }
 
-    pub fn ip(&self) -> Option<&str> {
-        self.header("X-Forwarded-For")
+    /// The resolved origin of the request. See [`ClientInfo`].
+    pub fn client_info(&self) -> Option<&ClientInfo> {
+        self.extensions.get::<ClientInfo>()
+    }
+
+    /// Address of the socket peer, which may be a proxy.
+    pub fn peer_addr(&self) -> Option<SocketAddr> {
+        self.client_info().and_then(|info| info.peer)
+    }
+
+    /// Address of the client. `Forwarded` or `X-Forwarded-For`, as selected by
+    /// `[web.trusted-proxies]`, is only honoured for hops added by a listed proxy.
+    pub fn ip(&self) -> Option<IpAddr> {
+        self.client_info().and_then(|info| info.ip)
     }
 
-    pub fn ips(&self) -> Option<Vec<&str>> {
-        self.header("X-Forwarded-For")
-            .map(|ips| ips.split(',').map(|s| s.trim()).collect())
+    /// Addresses from the client to the socket peer, client first.
+    pub fn ips(&self) -> Vec<IpAddr> {
+        self.client_info()
+            .map(|info| info.ips.clone())
+            .unwrap_or_default()
     }
 
+    /// Scheme used by the client, as reported by trusted proxies. Defaults to `http`.
     pub fn protocol(&self) -> &str {
-        self.header("X-Forwarded-Proto").unwrap_or("http")
+        self.client_info()
+            .map(|info| info.protocol.as_str())
+            .unwrap_or("http")
+    }
+
+    /// Host requested by the client, as reported by trusted proxies or the `Host` header.
+    pub fn host(&self) -> Option<&str> {
+        match self.client_info() {
+            Some(info) => info.host.as_deref(),
+            None => self.header("Host"),
+        }
+    }
+
+    /// Certificate identity of the client on a TLS listener with `client-ca` set.
+    #[cfg(feature = "tls")]
+    pub fn peer_identity(&self) -> Option<&PeerIdentity> {
+        self.extensions.get::<PeerIdentity>()
+    }
+
+    /// Deserializes the claims of the bearer token verified by `JwtAuth`.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[get("/me")]
+    /// #[interceptor(JwtAuth)]
+    /// async fn me(&self, req: Request) -> WebResult {
+    ///     let claims = req.claims::<UserClaims>()?;
+    ///     Ok(JsonResponse::Ok().data(claims.sub))
+    /// }
+    /// ```
+    #[cfg(feature = "jwt")]
+    pub fn claims<T: DeserializeOwned>(&self) -> Result<T, JsonResponse> {
+        jwt_claims(&self.extensions)
+    }
+
+    /// The server-side session of the request, configured under `[web.session]`.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[post("/login")]
+    /// async fn login(&self, req: Request) -> WebResult {
+    ///     let session = req.session()?;
+    ///
+    ///     session.rotate_id();
+    ///     session.insert("user_id", 42)?;
+    ///
+    ///     Ok(JsonResponse::Ok())
+    /// }
+    /// ```
+    #[cfg(feature = "sessions")]
+    pub fn session(&self) -> Result<&Session, JsonResponse> {
+        self.extensions.get::<Session>().ok_or_else(|| {
+            JsonResponse::InternalServerError()
+                .message("Can't access the session. Is `[web.session]` configured?")
+        })
+    }
+
+    /// The CSRF token of the request, to embed in forms or hand to scripts.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[get("/form")]
+    /// async fn form(&self, req: Request) -> WebResult {
+    ///     Ok(JsonResponse::Ok().data(json!({ "csrf": req.csrf_token()? })))
+    /// }
+    /// ```
+    #[cfg(feature = "csrf")]
+    pub fn csrf_token(&self) -> Result<&str, JsonResponse> {
+        self.extensions
+            .get::<CsrfToken>()
+            .map(CsrfToken::as_str)
+            .ok_or_else(|| {
+                JsonResponse::InternalServerError()
+                    .message("Can't read the CSRF token. Is `[web.csrf]` configured?")
+            })
+    }
+
+    /// The locale negotiated from the `Accept-Language` header among the
+    /// catalogs of `[web.i18n]`.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[get("/greeting")]
+    /// async fn greeting(&self, req: Request) -> WebResult {
+    ///     let message = match req.locale()?.language() {
+    ///         "es" => "Hola",
+    ///         _ => "Hello",
+    ///     };
+    ///
+    ///     Ok(JsonResponse::Ok().message(message))
+    /// }
+    /// ```
+    #[cfg(feature = "i18n")]
+    pub fn locale(&self) -> Result<&Locale, JsonResponse> {
+        self.extensions.get::<Locale>().ok_or_else(|| {
+            JsonResponse::InternalServerError()
+                .message("Can't read the locale. Is `[web.i18n]` configured?")
+        })
+    }
+
+    /// Checks `If-Match`, `If-Unmodified-Since` and `If-None-Match` against the
+    /// current validators of the entity, failing with `412 Precondition Failed`.
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// #[put("/{id}")]
+    /// async fn update(&self, req: Request) -> WebResult {
+    ///     let order = self.orders.find(req.param("id")?).await?;
+    ///
+    ///     req.check_preconditions(Some(&ETag::strong(order.version)), Some(order.updated_at))?;
+    ///
+    ///     Ok(JsonResponse::Ok().data(self.orders.update(order, req.body()?).await?))
+    /// }
+    /// ```
+    pub fn check_preconditions(
+        &self,
+        etag: Option<&ETag>,
+        last_modified: Option<SystemTime>,
+    ) -> Result<(), JsonResponse> {
+        check_preconditions(&self.method, &self.headers, etag, last_modified)
+    }
+
+    /// The path the client requested, before any router prefix was stripped.
+    #[cfg(feature = "csrf")]
+    pub(crate) fn original_path(&self) -> &str {
+        self.extensions
+            .get::<OriginalUri>()
+            .map_or_else(|| self.uri.path(), |uri| uri.path())
     }
 
     pub fn content_length(&self) -> Option<u64> {
@@ -588,3 +903,29 @@ impl StreamRequest {
         Ok(next.run(self.try_into()?).await)
     }
 }
+
+pub(crate) fn parse_query<T: DeserializeOwned>(query: &str) -> Result<T, RequestError> {
+    let deserializer =
+        serde_urlencoded::Deserializer::new(form_urlencoded::parse(query.as_bytes()));
+
+    T::deserialize(deserializer).map_err(|e| {
+        RequestError::deserialization_error(
+            "Invalid query parameters",
+            "Failed to deserialize query params to the required type.".into(),
+            e.into(),
+        )
+    })
+}
+
+#[cfg(feature = "jwt")]
+fn jwt_claims<T: DeserializeOwned>(extensions: &Extensions) -> Result<T, JsonResponse> {
+    let claims = extensions.get::<JwtClaims>().ok_or_else(|| {
+        JsonResponse::InternalServerError()
+            .message("Can't extract JWT claims. Is `JwtAuth` applied to this route?")
+    })?;
+
+    claims.deserialize().map_err(|err| {
+        tracing::debug!(target: "sword.web.jwt", error = %err, "Unexpected token claims");
+        JsonResponse::Unauthorized().message("Invalid token claims")
+    })
+}
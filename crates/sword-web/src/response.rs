@@ -1,5 +1,33 @@
+mod encoder;
+mod error;
+mod etag;
+mod json_stream;
+mod negotiation;
+mod pipeline;
+mod problem;
+mod renderer;
+mod sse;
+
 use crate::request::RequestError;
 
+pub use encoder::{BodyEncoder, BodyEncoderRegistrar, CborEncoder, JsonEncoder, MsgPackEncoder};
+pub use error::ResponseError;
+pub use etag::{ConditionalResponse, ETag, ETagConfig};
+pub use json_stream::{JsonStreamFormat, JsonStreamResponse};
+pub use negotiation::{Negotiated, Negotiation};
+pub use problem::{ErrorFormat, ErrorsConfig};
+pub use renderer::{ErrorRenderer, ErrorRendererProvider, FrameworkError, FrameworkErrorKind};
+pub use sse::{LastEventId, SseEvent, SseResponse};
+
+#[cfg(feature = "xml")]
+pub use encoder::XmlEncoder;
+
+pub(crate) use etag::{AutoETag, check_preconditions, etag_middleware};
+pub(crate) use json_stream::json_stream_timeout_middleware;
+pub(crate) use negotiation::negotiation_middleware;
+pub(crate) use pipeline::{ErrorPipeline, error_pipeline_middleware};
+pub(crate) use sse::sse_shutdown_middleware;
+
 pub use axum_responses::{ContentDisposition, File, JsonResponse, JsonResponseBody, Redirect};
 
 pub use sword_macros::HttpError;
@@ -8,56 +36,7 @@ pub type WebResult<T = JsonResponse, E = JsonResponse> = std::result::Result<T,
 
 impl From<RequestError> for JsonResponse {
     fn from(error: RequestError) -> JsonResponse {
-        match error {
-            RequestError::ParseError { message, details } => {
-                tracing::error!(details = ?details,  "Request parse error: {message}");
-                JsonResponse::BadRequest().message(message).error(details)
-            }
-            #[cfg(feature = "validation-validator")]
-            RequestError::ValidatorError { message, details } => {
-                tracing::error!(details = ?details,  "Request validation error: {message}");
-                JsonResponse::BadRequest().message(message).errors(details)
-            }
-
-            RequestError::BodyIsEmpty => {
-                JsonResponse::BadRequest().message("Request body is empty")
-            }
-            RequestError::BodyTooLarge => JsonResponse::PayloadTooLarge()
-                .message("The request body exceeds the maximum allowed size by the server"),
-            RequestError::UnsupportedMediaType { message } => {
-                JsonResponse::UnsupportedMediaType().message(message)
-            }
-            RequestError::DeserializationError {
-                message,
-                error,
-                source,
-            } => {
-                tracing::error!(source = %source, "Request deserialization error: {message}");
-                JsonResponse::BadRequest().message(message).error(error)
-            }
-            RequestError::InvalidHeaderName(name) => {
-                tracing::error!(header = %name, "Invalid header name");
-                JsonResponse::BadRequest()
-                    .message("Invalid header name")
-                    .error(format!("Header '{name}' contains invalid characters"))
-            }
-            RequestError::InvalidHeaderValue(name) => {
-                tracing::error!(header = %name, "Invalid header value");
-                JsonResponse::BadRequest()
-                    .message("Invalid header value")
-                    .error(format!("Header '{name}' contains an invalid value",))
-            }
-            #[cfg(feature = "multipart")]
-            RequestError::MultipartError(err) => {
-                tracing::error!(error = %err, "Multipart error");
-                JsonResponse::status(err.status()).message("Multipart error")
-            }
-            #[cfg(feature = "multipart")]
-            RequestError::MultipartRejection(err) => {
-                tracing::error!(error = %err, "Multipart rejection");
-                JsonResponse::status(err.status()).message("Multipart rejection")
-            }
-        }
+        JsonResponse::from(ResponseError::from(FrameworkError::from(error)))
     }
 }
 
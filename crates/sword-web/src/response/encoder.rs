@@ -0,0 +1,183 @@
+use serde_json::Value;
+use std::error::Error as StdError;
+
+/// Serializes negotiated responses into one media type.
+///
+/// The encoder is picked from the request `Accept` header for responses wrapped
+/// in [`Negotiated`](super::Negotiated) and for `HttpError` responses. It receives
+/// the same envelope `JsonResponse` produces. Custom encoders are registered with
+/// [`BodyEncoderRegistrar`], like body decoders.
+///
+/// ```rust,ignore
+/// struct YamlEncoder;
+///
+/// impl BodyEncoder for YamlEncoder {
+///     fn media_type(&self) -> &'static str {
+///         "application/yaml"
+///     }
+///
+///     fn encode(&self, value: &Value) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+///         Ok(serde_yaml::to_string(value)?.into_bytes())
+///     }
+/// }
+///
+/// register_body_encoder!(YamlEncoder);
+/// ```
+pub trait BodyEncoder: Send + Sync + 'static {
+    /// `Content-Type` of the encoded body.
+    fn media_type(&self) -> &'static str;
+
+    /// Whether the encoder produces `media_type`, given as a lowercase essence.
+    /// Defaults to comparing it with [`BodyEncoder::media_type`].
+    fn accepts(&self, media_type: &str) -> bool {
+        media_type == self.media_type()
+    }
+
+    fn encode(&self, value: &Value) -> Result<Vec<u8>, Box<dyn StdError + Send + Sync>>;
+}
+
+/// Registers a custom [`BodyEncoder`] through `inventory_submit!`.
+pub struct BodyEncoderRegistrar {
+    pub encoder: &'static dyn BodyEncoder,
+}
+
+impl BodyEncoderRegistrar {
+    pub const fn new(encoder: &'static dyn BodyEncoder) -> Self {
+        Self { encoder }
+    }
+}
+
+inventory::collect!(BodyEncoderRegistrar);
+
+/// `application/json`.
+pub struct JsonEncoder;
+
+impl BodyEncoder for JsonEncoder {
+    fn media_type(&self) -> &'static str {
+        "application/json"
+    }
+
+    fn encode(&self, value: &Value) -> Result<Vec<u8>, Box<dyn StdError + Send + Sync>> {
+        Ok(serde_json::to_vec(value)?)
+    }
+}
+
+/// `application/msgpack`, also accepted as `application/x-msgpack` and `application/vnd.msgpack`.
+pub struct MsgPackEncoder;
+
+impl BodyEncoder for MsgPackEncoder {
+    fn media_type(&self) -> &'static str {
+        "application/msgpack"
+    }
+
+    fn accepts(&self, media_type: &str) -> bool {
+        matches!(
+            media_type,
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack"
+        )
+    }
+
+    fn encode(&self, value: &Value) -> Result<Vec<u8>, Box<dyn StdError + Send + Sync>> {
+        Ok(rmp_serde::to_vec_named(value)?)
+    }
+}
+
+/// `application/cbor`.
+pub struct CborEncoder;
+
+impl BodyEncoder for CborEncoder {
+    fn media_type(&self) -> &'static str {
+        "application/cbor"
+    }
+
+    fn encode(&self, value: &Value) -> Result<Vec<u8>, Box<dyn StdError + Send + Sync>> {
+        Ok(cbor4ii::serde::to_vec(Vec::new(), value)?)
+    }
+}
+
+/// `application/xml`, also accepted as `text/xml`. The envelope is wrapped in
+/// a `<response>` root element.
+#[cfg(feature = "xml")]
+pub struct XmlEncoder;
+
+#[cfg(feature = "xml")]
+impl BodyEncoder for XmlEncoder {
+    fn media_type(&self) -> &'static str {
+        "application/xml"
+    }
+
+    fn accepts(&self, media_type: &str) -> bool {
+        matches!(media_type, "application/xml" | "text/xml")
+    }
+
+    fn encode(&self, value: &Value) -> Result<Vec<u8>, Box<dyn StdError + Send + Sync>> {
+        Ok(quick_xml::se::to_string_with_root("response", value)?.into_bytes())
+    }
+}
+
+/// Built-in encoders, JSON first so it is preferred for wildcard ranges.
+fn built_in_encoders() -> impl Iterator<Item = &'static dyn BodyEncoder> {
+    let encoders: [&'static dyn BodyEncoder; 3] = [&JsonEncoder, &MsgPackEncoder, &CborEncoder];
+
+    #[cfg(feature = "xml")]
+    let encoders = encoders
+        .into_iter()
+        .chain(std::iter::once(&XmlEncoder as &'static dyn BodyEncoder));
+
+    encoders.into_iter()
+}
+
+fn custom_encoders() -> impl Iterator<Item = &'static dyn BodyEncoder> {
+    inventory::iter::<BodyEncoderRegistrar>().map(|registrar| registrar.encoder)
+}
+
+/// Media types that can be produced, in preference order.
+pub(crate) fn supported_media_types() -> Vec<&'static str> {
+    let mut media_types = built_in_encoders()
+        .chain(custom_encoders())
+        .map(|encoder| encoder.media_type())
+        .collect::<Vec<_>>();
+
+    media_types.dedup();
+    media_types
+}
+
+/// Picks the encoder of an `Accept` header value.
+///
+/// Ranges are tried by decreasing quality. Exact ranges prefer custom encoders, so
+/// they can replace built-in ones; wildcard ranges prefer JSON. A missing header
+/// selects JSON. Returns `None` when no range can be produced.
+pub(crate) fn find_encoder(accept: Option<&str>) -> Option<&'static dyn BodyEncoder> {
+    let Some(accept) = accept.filter(|accept| !accept.trim().is_empty()) else {
+        return Some(&JsonEncoder);
+    };
+
+    let mut ranges = accept
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let media_range = parts.next()?.trim().to_ascii_lowercase();
+
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            (quality > 0.0 && !media_range.is_empty()).then_some((media_range, quality))
+        })
+        .collect::<Vec<_>>();
+
+    ranges.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    ranges
+        .into_iter()
+        .find_map(|(range, _)| match range.split_once('/') {
+            Some(("*", "*")) => Some(&JsonEncoder as &'static dyn BodyEncoder),
+            Some((kind, "*")) => built_in_encoders()
+                .chain(custom_encoders())
+                .find(|encoder| encoder.media_type().split('/').next() == Some(kind)),
+            _ => custom_encoders()
+                .chain(built_in_encoders())
+                .find(|encoder| encoder.accepts(&range)),
+        })
+}
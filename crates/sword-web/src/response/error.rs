@@ -0,0 +1,158 @@
+use super::{
+    problem::ErrorFormat,
+    renderer::{FrameworkError, FrameworkErrorKind},
+};
+
+use axum::http::StatusCode;
+use axum_responses::JsonResponse;
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+};
+use sword_layers::LayerError;
+
+tokio::task_local! {
+    static RAISED_ERROR: Arc<Mutex<Option<ResponseError>>>;
+}
+
+/// An error response described by value, so the error pipeline can render it
+/// with the configured format without reading the `JsonResponse` body back.
+///
+/// Built by `HttpError` derives and request errors. Not meant to be used
+/// directly.
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct ResponseError {
+    pub(crate) status: StatusCode,
+    pub(crate) message: String,
+    pub(crate) error: Option<Value>,
+    pub(crate) errors: Option<Value>,
+    pub(crate) kind: Option<FrameworkErrorKind>,
+    pub(crate) format: Option<ErrorFormat>,
+    pub(crate) problem_type: Option<String>,
+}
+
+impl ResponseError {
+    pub fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            message: message.into(),
+            error: None,
+            errors: None,
+            kind: None,
+            format: None,
+            problem_type: None,
+        }
+    }
+
+    pub fn error<T: Serialize>(mut self, error: T) -> Self {
+        self.error = Some(to_value(error));
+        self
+    }
+
+    pub fn errors<T: Serialize>(mut self, errors: T) -> Self {
+        self.errors = Some(to_value(errors));
+        self
+    }
+
+    pub fn format(mut self, format: ErrorFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    pub fn problem_type(mut self, problem_type: impl Into<String>) -> Self {
+        self.problem_type = Some(problem_type.into());
+        self
+    }
+
+    /// The `JsonResponse` envelope of the error.
+    pub(crate) fn envelope(&self) -> JsonResponse {
+        let response = JsonResponse::status(self.status).message(&self.message);
+
+        let response = match &self.error {
+            Some(error) => response.error(error),
+            None => response,
+        };
+
+        match &self.errors {
+            Some(errors) => response.errors(errors),
+            None => response,
+        }
+    }
+
+    /// The error as given to an `ErrorRenderer`.
+    pub(crate) fn framework_error(
+        self,
+        kind: FrameworkErrorKind,
+        request_id: Option<String>,
+    ) -> FrameworkError {
+        FrameworkError {
+            kind,
+            status: self.status,
+            message: self.message,
+            details: self.errors.or(self.error),
+            request_id,
+        }
+    }
+}
+
+/// Records the error for the error pipeline of the current request and returns
+/// its envelope.
+impl From<ResponseError> for JsonResponse {
+    fn from(error: ResponseError) -> JsonResponse {
+        let response = error.envelope();
+
+        let _ = RAISED_ERROR.try_with(|raised| {
+            if let Ok(mut raised) = raised.lock() {
+                *raised = Some(error);
+            }
+        });
+
+        response
+    }
+}
+
+impl From<FrameworkError> for ResponseError {
+    fn from(error: FrameworkError) -> Self {
+        let mut response = Self::new(error.status.as_u16(), error.message);
+        response.kind = Some(error.kind);
+
+        match (error.kind, error.details) {
+            (FrameworkErrorKind::Validation, Some(details)) => response.errors = Some(details),
+            (_, details) => response.error = details,
+        }
+
+        response
+    }
+}
+
+impl From<LayerError> for ResponseError {
+    fn from(error: LayerError) -> Self {
+        let mut response = Self::new(error.status().as_u16(), error.message());
+        response.kind = Some(error.into());
+
+        response
+    }
+}
+
+/// Runs a request, returning the last error converted into a `JsonResponse`
+/// while it ran, e.g. by `?` in a handler returning `WebResult`.
+pub(crate) async fn capture_raised_error<F: Future>(
+    future: F,
+) -> (F::Output, Option<ResponseError>) {
+    let raised = Arc::new(Mutex::new(None));
+    let output = RAISED_ERROR.scope(raised.clone(), future).await;
+
+    let error = raised.lock().ok().and_then(|mut raised| raised.take());
+
+    (output, error)
+}
+
+fn to_value<T: Serialize>(value: T) -> Value {
+    serde_json::to_value(value).unwrap_or_else(|err| {
+        tracing::warn!(error = %err, "Failed to serialize error response member");
+        Value::String("Serialization failed".into())
+    })
+}
@@ -0,0 +1,370 @@
+use super::negotiation::Negotiation;
+
+use axum::{
+    body::{Body, HttpBody, to_bytes},
+    extract::Request,
+    http::{HeaderMap, HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_responses::JsonResponse;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use sword_core::ByteConfig;
+use sword_layers::DisplayConfig;
+
+/// Configuration of the `[web.etag]` key.
+///
+/// ```toml
+/// [web.etag]
+/// enabled = true
+/// max-size = "512KB"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ETagConfig {
+    /// Whether `GET` and `HEAD` responses get a weak `ETag` computed from
+    /// their body. Routes override it with `etag = true` or `etag = false`.
+    /// Defaults to false.
+    pub enabled: bool,
+
+    /// Largest body an `ETag` is computed for. Defaults to "1MB".
+    #[serde(rename = "max-size")]
+    pub max_size: ByteConfig,
+
+    /// Whether to display the configuration details. Defaults to false.
+    pub display: bool,
+}
+
+impl Default for ETagConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_size: ByteConfig {
+                parsed: 1_000_000,
+                raw: "1MB".to_string(),
+            },
+            display: false,
+        }
+    }
+}
+
+impl DisplayConfig for ETagConfig {
+    fn display(&self) {
+        if !self.display {
+            return;
+        }
+
+        tracing::info!(
+            target: "sword.web.etag",
+            enabled = self.enabled,
+            max_size = self.max_size.raw,
+        );
+    }
+}
+
+/// An entity tag, as sent in `ETag` and compared with `If-Match` and
+/// `If-None-Match`.
+///
+/// Handlers send strong tags derived from entity versions, which take
+/// precedence over the computed ones:
+///
+/// ```rust,ignore
+/// #[get("/{id}")]
+/// async fn show(&self, req: Request) -> WebResult {
+///     let order = self.orders.find(req.param("id")?).await?;
+///
+///     Ok(JsonResponse::Ok()
+///         .data(&order)
+///         .etag(&ETag::strong(order.version))
+///         .last_modified(order.updated_at))
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ETag {
+    weak: bool,
+    tag: String,
+}
+
+impl ETag {
+    /// A tag that changes whenever the representation changes.
+    pub fn strong(tag: impl fmt::Display) -> Self {
+        Self {
+            weak: false,
+            tag: tag.to_string(),
+        }
+    }
+
+    /// A tag that changes whenever the representation changes meaningfully.
+    pub fn weak(tag: impl fmt::Display) -> Self {
+        Self {
+            weak: true,
+            tag: tag.to_string(),
+        }
+    }
+
+    /// Parses a single `"tag"` or `W/"tag"` value.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let (weak, quoted) = match value.strip_prefix("W/") {
+            Some(quoted) => (true, quoted),
+            None => (false, value),
+        };
+
+        let tag = quoted.strip_prefix('"')?.strip_suffix('"')?;
+
+        (!tag.contains('"')).then(|| Self {
+            weak,
+            tag: tag.to_string(),
+        })
+    }
+
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Strong comparison: both tags are strong and equal. Used by `If-Match`.
+    pub fn strong_eq(&self, other: &ETag) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+
+    /// Weak comparison: the tags are equal, weak or not. Used by `If-None-Match`.
+    pub fn weak_eq(&self, other: &ETag) -> bool {
+        self.tag == other.tag
+    }
+
+    /// The weak tag of a response body. The `timestamp` of JSON envelopes is
+    /// left out, so the same payload keeps its tag.
+    fn of_body(body: &[u8], json: bool) -> Self {
+        let value = json
+            .then(|| serde_json::from_slice::<Value>(body).ok())
+            .flatten()
+            .map(|mut value| {
+                if let Some(object) = value.as_object_mut() {
+                    object.remove("timestamp");
+                }
+
+                serde_json::to_vec(&value).unwrap_or_default()
+            });
+
+        let bytes = value.as_deref().unwrap_or(body);
+
+        Self::weak(format!("{:x}-{:016x}", bytes.len(), fnv1a(bytes)))
+    }
+}
+
+impl fmt::Display for ETag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.weak {
+            true => write!(f, "W/\"{}\"", self.tag),
+            false => write!(f, "\"{}\"", self.tag),
+        }
+    }
+}
+
+/// 64-bit FNV-1a, stable across builds and instances.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Sets the validators of a response, used to answer conditional requests.
+pub trait ConditionalResponse {
+    /// Sets the `ETag` header.
+    fn etag(self, etag: &ETag) -> Self;
+
+    /// Sets the `Last-Modified` header, truncated to seconds.
+    fn last_modified(self, time: SystemTime) -> Self;
+}
+
+impl ConditionalResponse for JsonResponse {
+    fn etag(self, etag: &ETag) -> Self {
+        self.header(header::ETAG.as_str(), &etag.to_string())
+    }
+
+    fn last_modified(self, time: SystemTime) -> Self {
+        self.header(
+            header::LAST_MODIFIED.as_str(),
+            &httpdate::fmt_http_date(time),
+        )
+    }
+}
+
+/// Route-level `etag` option, overriding `web.etag.enabled`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AutoETag(pub bool);
+
+/// Evaluates `If-Match`, `If-Unmodified-Since` and, for unsafe methods,
+/// `If-None-Match` against the current validators of an entity.
+pub(crate) fn check_preconditions(
+    method: &Method,
+    headers: &HeaderMap,
+    etag: Option<&ETag>,
+    last_modified: Option<SystemTime>,
+) -> Result<(), JsonResponse> {
+    let failed = || JsonResponse::PreconditionFailed().message("Precondition failed");
+
+    if let Some(if_match) = header_str(headers, header::IF_MATCH) {
+        if !matches_any(if_match, etag, ETag::strong_eq) {
+            return Err(failed());
+        }
+    } else if let (Some(since), Some(modified)) = (
+        header_date(headers, header::IF_UNMODIFIED_SINCE),
+        last_modified,
+    ) && seconds(modified) > seconds(since)
+    {
+        return Err(failed());
+    }
+
+    let safe = matches!(*method, Method::GET | Method::HEAD);
+
+    if !safe
+        && let Some(if_none_match) = header_str(headers, header::IF_NONE_MATCH)
+        && matches_any(if_none_match, etag, ETag::weak_eq)
+    {
+        return Err(failed());
+    }
+
+    Ok(())
+}
+
+/// Adds weak `ETag`s to `GET` and `HEAD` responses and answers
+/// `If-None-Match` and `If-Modified-Since` with `304 Not Modified`.
+///
+/// Applied inside `Accept` negotiation, so the tag is computed from the JSON
+/// envelope and shared by every negotiated format. Bodies without a known
+/// size, like SSE and JSON streams, never get a computed tag.
+pub(crate) async fn etag_middleware(
+    enabled: bool,
+    max_size: usize,
+    req: Request,
+    next: Next,
+) -> Response {
+    if !matches!(*req.method(), Method::GET | Method::HEAD) {
+        return next.run(req).await;
+    }
+
+    let if_none_match = header_str(req.headers(), header::IF_NONE_MATCH).map(str::to_string);
+    let if_modified_since = header_date(req.headers(), header::IF_MODIFIED_SINCE);
+
+    let response = next.run(req).await;
+
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let auto = response
+        .extensions()
+        .get::<AutoETag>()
+        .map_or(enabled, |auto| auto.0);
+
+    let response = match response.headers().contains_key(header::ETAG) || !auto {
+        true => response,
+        false => with_computed_etag(response, max_size).await,
+    };
+
+    let etag = header_str(response.headers(), header::ETAG).and_then(ETag::parse);
+
+    let not_modified = match (if_none_match, if_modified_since) {
+        (Some(if_none_match), _) => matches_any(&if_none_match, etag.as_ref(), ETag::weak_eq),
+        (None, Some(since)) => header_date(response.headers(), header::LAST_MODIFIED)
+            .is_some_and(|modified| seconds(modified) <= seconds(since)),
+        (None, None) => false,
+    };
+
+    match not_modified {
+        true => into_not_modified(response),
+        false => response,
+    }
+}
+
+async fn with_computed_etag(response: Response, max_size: usize) -> Response {
+    let fits = response
+        .body()
+        .size_hint()
+        .exact()
+        .is_some_and(|size| size <= max_size as u64);
+
+    if !fits {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+
+    let Ok(bytes) = to_bytes(body, max_size).await else {
+        return JsonResponse::InternalServerError()
+            .message("Failed to read the response body")
+            .into_response();
+    };
+
+    let json = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+
+    if let Ok(value) = HeaderValue::try_from(ETag::of_body(&bytes, json).to_string()) {
+        parts.headers.insert(header::ETAG, value);
+    }
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// Keeps the headers of the full response, except those describing its body.
+fn into_not_modified(response: Response) -> Response {
+    let negotiated = response.extensions().get::<Negotiation>().is_some();
+    let (parts, _) = response.into_parts();
+
+    let mut not_modified = Response::new(Body::empty());
+
+    *not_modified.status_mut() = StatusCode::NOT_MODIFIED;
+    *not_modified.headers_mut() = parts.headers;
+
+    let headers = not_modified.headers_mut();
+
+    headers.remove(header::CONTENT_TYPE);
+    headers.remove(header::CONTENT_LENGTH);
+
+    if negotiated {
+        headers.append(header::VARY, HeaderValue::from_static("accept"));
+    }
+
+    not_modified
+}
+
+/// Whether a `*` or comma-separated list of tags matches `etag`.
+fn matches_any(list: &str, etag: Option<&ETag>, eq: fn(&ETag, &ETag) -> bool) -> bool {
+    let Some(etag) = etag else {
+        return false;
+    };
+
+    list.trim() == "*"
+        || list
+            .split(',')
+            .filter_map(ETag::parse)
+            .any(|candidate| eq(&candidate, etag))
+}
+
+fn header_str(headers: &HeaderMap, name: header::HeaderName) -> Option<&str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn header_date(headers: &HeaderMap, name: header::HeaderName) -> Option<SystemTime> {
+    header_str(headers, name).and_then(|value| httpdate::parse_http_date(value).ok())
+}
+
+/// HTTP dates have a one second resolution.
+fn seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
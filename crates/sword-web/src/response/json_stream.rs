@@ -0,0 +1,235 @@
+use axum::{
+    body::{Body, Bytes},
+    extract::Request,
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures_lite::{Stream, StreamExt, stream};
+use serde::Serialize;
+use serde_json::json;
+use std::{
+    fmt::Display,
+    pin::Pin,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+use tokio::time::{Instant, timeout_at};
+
+type ItemStream = Pin<Box<dyn Stream<Item = Result<Vec<u8>, StreamFailure>> + Send>>;
+
+/// Wire format of a [`JsonStreamResponse`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonStreamFormat {
+    /// One JSON value per line (`application/x-ndjson`).
+    Lines,
+
+    /// A single JSON array (`application/json`).
+    Array,
+}
+
+/// A JSON response written incrementally from a stream of items, with chunked
+/// transfer encoding, so large result sets never have to be collected in memory.
+///
+/// When the stream yields an error, or an item can't be serialized, the error is
+/// logged and the body ends with a terminal error object:
+/// `{"error":{"code":500,"message":"..."}}`. It is the last line of an NDJSON body
+/// and the last element of an array body, so the array stays well-formed. The
+/// request timeout bounds the whole body: when it elapses, the body ends with the
+/// same object using code `408`.
+///
+/// ```rust,ignore
+/// #[get("/export")]
+/// async fn export(&self) -> JsonStreamResponse {
+///     JsonStreamResponse::lines(self.orders.stream_all())
+/// }
+/// ```
+pub struct JsonStreamResponse {
+    items: ItemStream,
+    format: JsonStreamFormat,
+}
+
+impl JsonStreamResponse {
+    /// Streams items as NDJSON.
+    pub fn lines<S, T, E>(items: S) -> Self
+    where
+        S: Stream<Item = Result<T, E>> + Send + 'static,
+        T: Serialize,
+        E: Display,
+    {
+        Self::new(items, JsonStreamFormat::Lines)
+    }
+
+    /// Streams items as the elements of a JSON array.
+    pub fn array<S, T, E>(items: S) -> Self
+    where
+        S: Stream<Item = Result<T, E>> + Send + 'static,
+        T: Serialize,
+        E: Display,
+    {
+        Self::new(items, JsonStreamFormat::Array)
+    }
+
+    pub fn new<S, T, E>(items: S, format: JsonStreamFormat) -> Self
+    where
+        S: Stream<Item = Result<T, E>> + Send + 'static,
+        T: Serialize,
+        E: Display,
+    {
+        let items = items.map(|item| {
+            let item = item.map_err(|error| {
+                tracing::error!(error = %error, "JSON response stream failed");
+                StreamFailure::Source
+            })?;
+
+            serde_json::to_vec(&item).map_err(|error| {
+                tracing::error!(error = %error, "Failed to serialize JSON stream item");
+                StreamFailure::Serialization
+            })
+        });
+
+        Self {
+            items: Box::pin(items),
+            format,
+        }
+    }
+}
+
+impl IntoResponse for JsonStreamResponse {
+    fn into_response(self) -> Response {
+        let deadline = StreamDeadline::default();
+
+        let writer = StreamWriter {
+            items: self.items,
+            format: self.format,
+            deadline: deadline.clone(),
+            started: false,
+            finished: false,
+        };
+
+        let body = stream::unfold(writer, |mut writer| async move {
+            let chunk = writer.next_chunk().await?;
+            Some((
+                Ok::<_, std::convert::Infallible>(Bytes::from(chunk)),
+                writer,
+            ))
+        });
+
+        let content_type = match self.format {
+            JsonStreamFormat::Lines => "application/x-ndjson",
+            JsonStreamFormat::Array => "application/json",
+        };
+
+        let mut response = Response::new(Body::from_stream(body));
+
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+
+        response.extensions_mut().insert(deadline);
+
+        response
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum StreamFailure {
+    Source,
+    Serialization,
+    TimedOut,
+}
+
+impl StreamFailure {
+    fn to_json(self) -> Vec<u8> {
+        let (code, message) = match self {
+            Self::Source => (500, "The response stream failed"),
+            Self::Serialization => (500, "Failed to serialize a response item"),
+            Self::TimedOut => (408, "Request timed out"),
+        };
+
+        json!({ "error": { "code": code, "message": message } })
+            .to_string()
+            .into_bytes()
+    }
+}
+
+/// End of the body, set once the request timeout applies to the response.
+#[derive(Clone, Default)]
+struct StreamDeadline(Arc<OnceLock<Instant>>);
+
+struct StreamWriter {
+    items: ItemStream,
+    format: JsonStreamFormat,
+    deadline: StreamDeadline,
+    started: bool,
+    finished: bool,
+}
+
+impl StreamWriter {
+    async fn next_item(&mut self) -> Option<Result<Vec<u8>, StreamFailure>> {
+        match self.deadline.0.get() {
+            Some(deadline) => timeout_at(*deadline, self.items.next())
+                .await
+                .unwrap_or(Some(Err(StreamFailure::TimedOut))),
+            None => self.items.next().await,
+        }
+    }
+
+    async fn next_chunk(&mut self) -> Option<Vec<u8>> {
+        if self.finished {
+            return None;
+        }
+
+        let (item, last) = match self.next_item().await {
+            Some(Ok(item)) => (Some(item), false),
+            Some(Err(failure)) => (Some(failure.to_json()), true),
+            None => (None, true),
+        };
+
+        self.finished = last;
+
+        let mut chunk = Vec::new();
+
+        match self.format {
+            JsonStreamFormat::Lines => {
+                let item = item?;
+                chunk.extend(item);
+                chunk.push(b'\n');
+            }
+            JsonStreamFormat::Array => {
+                chunk.push(if self.started { b',' } else { b'[' });
+
+                match item {
+                    Some(item) => chunk.extend(item),
+                    None if self.started => chunk.clear(),
+                    None => {}
+                }
+
+                if last {
+                    chunk.push(b']');
+                }
+            }
+        }
+
+        self.started = true;
+
+        Some(chunk)
+    }
+}
+
+/// Applies the request timeout to the body of [`JsonStreamResponse`]s, counted
+/// from the moment the route is reached.
+pub(crate) async fn json_stream_timeout_middleware(
+    timeout: Duration,
+    req: Request,
+    next: Next,
+) -> Response {
+    let started_at = Instant::now();
+    let response = next.run(req).await;
+
+    if let Some(deadline) = response.extensions().get::<StreamDeadline>() {
+        let _ = deadline.0.set(started_at + timeout);
+    }
+
+    response
+}
@@ -0,0 +1,144 @@
+use super::encoder::{BodyEncoder, find_encoder, supported_media_types};
+use crate::request::RequestError;
+
+use axum::{
+    body::{Body, to_bytes},
+    extract::Request,
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_responses::JsonResponse;
+
+/// A `JsonResponse` serialized in the format requested by the `Accept` header.
+///
+/// The payload and envelope are the same as the JSON response; they are encoded
+/// with the matching [`BodyEncoder`] (JSON, msgpack, CBOR, XML with the `xml`
+/// feature, or a registered one). When no acceptable format can be produced,
+/// a `406 Not Acceptable` JSON error listing the supported types is returned.
+///
+/// ```rust,ignore
+/// #[get("/{id}")]
+/// async fn show(&self, req: Request) -> WebResult<Negotiated, Negotiated> {
+///     let user = self.users.find(req.param::<Uuid>("id")?).await?;
+///     Ok(JsonResponse::Ok().data(user).into())
+/// }
+/// ```
+pub struct Negotiated(pub JsonResponse);
+
+impl From<JsonResponse> for Negotiated {
+    fn from(response: JsonResponse) -> Self {
+        Self(response)
+    }
+}
+
+impl From<RequestError> for Negotiated {
+    fn from(error: RequestError) -> Self {
+        Self(error.into())
+    }
+}
+
+impl IntoResponse for Negotiated {
+    fn into_response(self) -> Response {
+        let mut response = self.0.into_response();
+        response.extensions_mut().insert(Negotiation::Required);
+
+        response
+    }
+}
+
+/// Marks a JSON response as eligible for `Accept` negotiation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Negotiation {
+    /// Respond with `406 Not Acceptable` when no format matches.
+    Required,
+
+    /// Keep the JSON body when no format matches. Used by `HttpError` responses,
+    /// so an error is never replaced by a `406`.
+    Optional,
+}
+
+/// Re-encodes marked JSON responses according to the request `Accept` header.
+///
+/// Error responses are negotiated by the error pipeline instead, once they
+/// have been translated and rendered.
+pub(crate) async fn negotiation_middleware(req: Request, next: Next) -> Response {
+    let accept = req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .map(ToString::to_string);
+
+    let response = next.run(req).await;
+    let status = response.status();
+
+    if status.is_client_error() || status.is_server_error() {
+        return response;
+    }
+
+    negotiate(accept.as_deref(), response).await
+}
+
+/// Re-encodes a marked JSON response in the best format for `accept`.
+pub(crate) async fn negotiate(accept: Option<&str>, mut response: Response) -> Response {
+    let Some(negotiation) = response.extensions_mut().remove::<Negotiation>() else {
+        return response;
+    };
+
+    response
+        .headers_mut()
+        .append(header::VARY, HeaderValue::from_static("accept"));
+
+    match find_encoder(accept) {
+        Some(encoder) if encoder.media_type() == "application/json" => response,
+        Some(encoder) => encode_response(response, encoder).await,
+        None if negotiation == Negotiation::Optional => response,
+        None => not_acceptable(),
+    }
+}
+
+async fn encode_response(response: Response, encoder: &dyn BodyEncoder) -> Response {
+    let (mut parts, body) = response.into_parts();
+
+    let encoded = to_bytes(body, usize::MAX)
+        .await
+        .map_err(|err| err.to_string())
+        .and_then(|bytes| serde_json::from_slice(&bytes).map_err(|err| err.to_string()))
+        .and_then(|value| encoder.encode(&value).map_err(|err| err.to_string()));
+
+    let body = match encoded {
+        Ok(body) => body,
+        Err(error) => {
+            tracing::error!(
+                media_type = encoder.media_type(),
+                error = %error,
+                "Failed to encode negotiated response"
+            );
+
+            return JsonResponse::InternalServerError()
+                .message("Failed to encode the response")
+                .into_response();
+        }
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(encoder.media_type()),
+    );
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn not_acceptable() -> Response {
+    let mut response = JsonResponse::NotAcceptable()
+        .message("None of the accepted media types can be produced")
+        .error(serde_json::json!({ "supported": supported_media_types() }))
+        .into_response();
+
+    response
+        .headers_mut()
+        .append(header::VARY, HeaderValue::from_static("accept"));
+
+    response
+}
@@ -0,0 +1,147 @@
+use super::{
+    error::{ResponseError, capture_raised_error},
+    negotiation::negotiate,
+    problem::{ErrorFormat, ErrorsConfig, problem_response},
+    renderer::{ErrorRenderer, ErrorRendererProvider, render_error},
+};
+
+#[cfg(feature = "i18n")]
+use crate::i18n::{Translations, translate_error};
+
+use axum::{
+    extract::Request,
+    http::{HeaderMap, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use sword_core::State;
+use sword_layers::LayerError;
+
+/// What the error pipeline needs to rewrite error responses.
+pub(crate) struct ErrorPipeline {
+    config: ErrorsConfig,
+    renderer: Option<Arc<dyn ErrorRenderer>>,
+
+    #[cfg(feature = "i18n")]
+    translations: Option<Translations>,
+}
+
+impl ErrorPipeline {
+    pub(crate) fn new(state: &State, config: &ErrorsConfig) -> Self {
+        Self {
+            config: config.clone(),
+            renderer: ErrorRendererProvider::resolve(state),
+
+            #[cfg(feature = "i18n")]
+            translations: state.get::<Translations>().ok(),
+        }
+    }
+}
+
+/// Renders error responses once, from the [`ResponseError`] they were built
+/// from:
+///
+/// 1. Messages are translated to the negotiated `Locale` (`i18n` feature).
+/// 2. Framework errors are rendered with the registered [`ErrorRenderer`].
+/// 3. Other errors are rendered as Problem Details when configured.
+/// 4. Errors of negotiated routes are encoded in the accepted format.
+///
+/// The error is the [`LayerError`] in the response extensions, or the last
+/// `HttpError` or request error converted into a `JsonResponse` while the
+/// request ran, when it has the status of the response. Other responses, like
+/// a `JsonResponse` built by a handler, only go through the last step.
+///
+/// Applied outside every other layer, so unknown routes go through every step.
+pub(crate) async fn error_pipeline_middleware(
+    pipeline: Arc<ErrorPipeline>,
+    #[cfg_attr(not(feature = "i18n"), allow(unused_mut))] mut req: Request,
+    next: Next,
+) -> Response {
+    let instance = req.uri().path().to_string();
+    let accept = header_value(req.headers(), header::ACCEPT.as_str());
+    let request_id = header_value(req.headers(), "x-request-id");
+
+    #[cfg(feature = "i18n")]
+    let locale = pipeline.translations.as_ref().map(|translations| {
+        let accept_language = req
+            .headers()
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok());
+
+        let locale = translations.negotiate(accept_language);
+        req.extensions_mut().insert(locale.clone());
+
+        locale
+    });
+
+    let (mut response, raised) = capture_raised_error(next.run(req)).await;
+    let status = response.status();
+
+    if !status.is_client_error() && !status.is_server_error() {
+        return negotiate(accept.as_deref(), response).await;
+    }
+
+    let error = response
+        .extensions_mut()
+        .remove::<LayerError>()
+        .map(ResponseError::from)
+        .or_else(|| raised.filter(|error| error.status == status));
+
+    #[cfg_attr(not(feature = "i18n"), allow(unused_mut))]
+    let Some(mut error) = error else {
+        return negotiate(accept.as_deref(), response).await;
+    };
+
+    let (mut parts, body) = response.into_parts();
+
+    #[cfg_attr(not(feature = "i18n"), allow(unused_mut))]
+    let mut translated = false;
+
+    #[cfg(feature = "i18n")]
+    if let (Some(translations), Some(locale)) = (&pipeline.translations, &locale)
+        && translate_error(translations, locale, &mut error)
+    {
+        translated = true;
+
+        if let Ok(value) = header::HeaderValue::from_str(locale.as_str()) {
+            parts.headers.insert(header::CONTENT_LANGUAGE, value);
+        }
+    }
+
+    // Set by `RequestIdLayer` on the response, unless the client sent one.
+    let request_id = header_value(&parts.headers, "x-request-id").or(request_id);
+
+    let format = error.format.unwrap_or(pipeline.config.format);
+
+    let response = match (&pipeline.renderer, error.kind) {
+        (Some(renderer), Some(kind)) => render_error(
+            renderer.as_ref(),
+            error.framework_error(kind, request_id),
+            parts,
+        ),
+        _ if format == ErrorFormat::Problem => problem_response(
+            &pipeline.config,
+            error,
+            parts,
+            &instance,
+            request_id.as_deref(),
+        ),
+        _ if translated => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            let body = error.envelope().into_response().into_body();
+
+            Response::from_parts(parts, body)
+        }
+        _ => Response::from_parts(parts, body),
+    };
+
+    negotiate(accept.as_deref(), response).await
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(ToString::to_string)
+}
@@ -0,0 +1,176 @@
+use super::error::ResponseError;
+
+use axum::{
+    body::Body,
+    http::{HeaderValue, header, response::Parts},
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use sword_layers::DisplayConfig;
+
+/// Configuration of the `[web.errors]` key.
+///
+/// ```toml
+/// [web.errors]
+/// format = "problem"
+/// type-base = "https://api.example.com/problems"
+///
+/// [web.errors.types]
+/// 404 = "https://api.example.com/problems/missing-resource"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ErrorsConfig {
+    /// How error responses are rendered: "json" (default) for the JSON
+    /// envelope, or "problem" for RFC 9457 Problem Details.
+    pub format: ErrorFormat,
+
+    /// Base URI of Problem Details `type` members, followed by the slug of the
+    /// status title (e.g. `{type-base}/not-found`). Without it, problems have
+    /// the "about:blank" type.
+    #[serde(rename = "type-base")]
+    pub type_base: Option<String>,
+
+    /// `type` URIs by status code, taking precedence over `type-base`.
+    pub types: HashMap<String, String>,
+
+    /// Whether problems include the request path as `instance`. Defaults to true.
+    pub instance: bool,
+
+    /// Whether problems include the `request_id` member. Defaults to true.
+    #[serde(rename = "request-id")]
+    pub request_id: bool,
+
+    /// Whether to display the configuration details. Defaults to false.
+    pub display: bool,
+}
+
+impl Default for ErrorsConfig {
+    fn default() -> Self {
+        Self {
+            format: ErrorFormat::Json,
+            type_base: None,
+            types: HashMap::new(),
+            instance: true,
+            request_id: true,
+            display: false,
+        }
+    }
+}
+
+impl DisplayConfig for ErrorsConfig {
+    fn display(&self) {
+        if !self.display {
+            return;
+        }
+
+        tracing::info!(
+            target: "sword.web.errors",
+            format = ?self.format,
+            type_base = self.type_base,
+            types = ?self.types,
+            instance = self.instance,
+            request_id = self.request_id,
+        );
+    }
+}
+
+/// Format of error responses. Set globally with `web.errors.format`, or for
+/// the variants of an `HttpError` with `#[http_error(format = problem)]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorFormat {
+    /// The JSON envelope of `JsonResponse`.
+    #[default]
+    Json,
+
+    /// `application/problem+json`, as defined by RFC 9457.
+    Problem,
+}
+
+/// Renders an error as Problem Details, keeping the headers and extensions
+/// of its original response.
+///
+/// The error message becomes `detail`, and its `error` and `errors` members
+/// (e.g. validation errors) are kept as extension members.
+pub(crate) fn problem_response(
+    config: &ErrorsConfig,
+    error: ResponseError,
+    mut parts: Parts,
+    instance: &str,
+    request_id: Option<&str>,
+) -> Response {
+    let problem = problem_details(
+        config,
+        error,
+        config.instance.then_some(instance),
+        request_id.filter(|_| config.request_id),
+    );
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/problem+json"),
+    );
+
+    let body = serde_json::to_vec(&problem).unwrap_or_default();
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn problem_details(
+    config: &ErrorsConfig,
+    error: ResponseError,
+    instance: Option<&str>,
+    request_id: Option<&str>,
+) -> Map<String, Value> {
+    let status = error.status;
+    let title = status.canonical_reason().unwrap_or("Unknown Error");
+
+    let problem_type = match error.problem_type {
+        Some(problem_type) => problem_type,
+        None => match (config.types.get(status.as_str()), &config.type_base) {
+            (Some(problem_type), _) => problem_type.clone(),
+            (None, Some(base)) => format!("{}/{}", base.trim_end_matches('/'), slug(title)),
+            (None, None) => "about:blank".to_string(),
+        },
+    };
+
+    let mut problem = Map::from_iter([
+        ("type".into(), Value::String(problem_type)),
+        ("title".into(), Value::String(title.into())),
+        ("status".into(), Value::Number(status.as_u16().into())),
+    ]);
+
+    if error.message != title {
+        problem.insert("detail".into(), Value::String(error.message));
+    }
+
+    if let Some(instance) = instance {
+        problem.insert("instance".into(), Value::String(instance.into()));
+    }
+
+    if let Some(request_id) = request_id {
+        problem.insert("request_id".into(), Value::String(request_id.into()));
+    }
+
+    for (member, value) in [("error", error.error), ("errors", error.errors)] {
+        if let Some(value) = value.filter(|value| !value.is_null()) {
+            problem.insert(member.into(), value);
+        }
+    }
+
+    problem
+}
+
+/// "Payload Too Large" becomes "payload-too-large".
+fn slug(title: &str) -> String {
+    title
+        .split(|char: char| !char.is_ascii_alphanumeric() && char != '\'')
+        .filter(|word| !word.is_empty())
+        .map(|word| word.replace('\'', "").to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
@@ -0,0 +1,297 @@
+use crate::request::RequestError;
+
+use axum::{
+    http::{HeaderName, StatusCode, header, response::Parts},
+    response::Response,
+};
+use axum_responses::JsonResponse;
+use serde_json::Value;
+use std::sync::Arc;
+use sword_core::{Provider, State};
+use sword_layers::LayerError;
+
+/// Renders the error responses produced by the framework itself: request
+/// errors (`RequestError`), unknown routes, request timeouts and oversized
+/// bodies. Errors returned by handlers are not affected.
+///
+/// Registered with [`ErrorRendererProvider`]. Without one, framework errors
+/// use the `JsonResponse` envelope, which is also available to renderers
+/// through `JsonResponse::from(error)`.
+///
+/// ### Example
+/// ```rust,ignore
+/// struct CompanyErrors;
+///
+/// impl ErrorRenderer for CompanyErrors {
+///     fn render(&self, error: FrameworkError) -> Response {
+///         let body = json!({
+///             "kind": error.kind.as_str(),
+///             "description": error.message,
+///             "correlation_id": error.request_id,
+///         });
+///
+///         (error.status, Json(body)).into_response()
+///     }
+/// }
+/// ```
+pub trait ErrorRenderer: Send + Sync + 'static {
+    fn render(&self, error: FrameworkError) -> Response;
+}
+
+/// Replaces the rendering of framework errors.
+///
+/// ### Example
+/// ```rust,ignore
+/// Application::builder()
+///     .with_provider(ErrorRendererProvider::new(CompanyErrors))
+///     .with_module::<UsersModule>()
+///     .build();
+/// ```
+#[derive(Clone)]
+pub struct ErrorRendererProvider(Arc<dyn ErrorRenderer>);
+
+impl ErrorRendererProvider {
+    pub fn new(renderer: impl ErrorRenderer) -> Self {
+        Self(Arc::new(renderer))
+    }
+
+    /// The registered renderer, if any.
+    pub(crate) fn resolve(state: &State) -> Option<Arc<dyn ErrorRenderer>> {
+        state.get::<Self>().ok().map(|provider| provider.0)
+    }
+}
+
+impl Provider for ErrorRendererProvider {}
+
+/// An error response produced by the framework, as given to an [`ErrorRenderer`].
+#[derive(Debug, Clone)]
+pub struct FrameworkError {
+    pub kind: FrameworkErrorKind,
+    pub status: StatusCode,
+
+    /// The default message of the error, e.g. "Request body is empty".
+    pub message: String,
+
+    /// Additional information, such as the field errors of a validation error
+    /// or the cause of a parse error.
+    pub details: Option<Value>,
+
+    /// The `x-request-id` of the request, when it has one.
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FrameworkErrorKind {
+    Parse,
+    Deserialization,
+    Validation,
+    EmptyBody,
+    BodyTooLarge,
+    UnsupportedMediaType,
+    InvalidHeaderName,
+    InvalidHeaderValue,
+    MissingHeader,
+    Multipart,
+    NotFound,
+    RequestTimeout,
+}
+
+impl FrameworkErrorKind {
+    /// The kebab-case name of the kind, e.g. "body-too-large".
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Parse => "parse",
+            Self::Deserialization => "deserialization",
+            Self::Validation => "validation",
+            Self::EmptyBody => "empty-body",
+            Self::BodyTooLarge => "body-too-large",
+            Self::UnsupportedMediaType => "unsupported-media-type",
+            Self::InvalidHeaderName => "invalid-header-name",
+            Self::InvalidHeaderValue => "invalid-header-value",
+            Self::MissingHeader => "missing-header",
+            Self::Multipart => "multipart",
+            Self::NotFound => "not-found",
+            Self::RequestTimeout => "request-timeout",
+        }
+    }
+}
+
+impl From<LayerError> for FrameworkErrorKind {
+    fn from(error: LayerError) -> Self {
+        match error {
+            LayerError::NotFound => Self::NotFound,
+            LayerError::RequestTimeout => Self::RequestTimeout,
+            LayerError::BodyTooLarge => Self::BodyTooLarge,
+        }
+    }
+}
+
+impl From<RequestError> for FrameworkError {
+    fn from(error: RequestError) -> Self {
+        let (kind, status, message, details) = match error {
+            RequestError::ParseError { message, details } => {
+                tracing::error!(details = ?details,  "Request parse error: {message}");
+                let details = Some(Value::String(details));
+
+                (
+                    FrameworkErrorKind::Parse,
+                    StatusCode::BAD_REQUEST,
+                    message,
+                    details,
+                )
+            }
+            #[cfg(feature = "validation-validator")]
+            RequestError::ValidatorError { message, details } => {
+                tracing::error!(details = ?details,  "Request validation error: {message}");
+
+                (
+                    FrameworkErrorKind::Validation,
+                    StatusCode::BAD_REQUEST,
+                    message.into(),
+                    Some(details),
+                )
+            }
+            RequestError::BodyIsEmpty => (
+                FrameworkErrorKind::EmptyBody,
+                StatusCode::BAD_REQUEST,
+                "Request body is empty".into(),
+                None,
+            ),
+            RequestError::BodyTooLarge => (
+                FrameworkErrorKind::BodyTooLarge,
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "The request body exceeds the maximum allowed size by the server".into(),
+                None,
+            ),
+            RequestError::UnsupportedMediaType { message } => (
+                FrameworkErrorKind::UnsupportedMediaType,
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                message.into(),
+                None,
+            ),
+            RequestError::DeserializationError {
+                message,
+                error,
+                source,
+            } => {
+                tracing::error!(source = %source, "Request deserialization error: {message}");
+
+                (
+                    FrameworkErrorKind::Deserialization,
+                    StatusCode::BAD_REQUEST,
+                    message.into(),
+                    Some(Value::String(error)),
+                )
+            }
+            RequestError::InvalidHeaderName(name) => {
+                tracing::error!(header = %name, "Invalid header name");
+                let details = format!("Header '{name}' contains invalid characters");
+
+                (
+                    FrameworkErrorKind::InvalidHeaderName,
+                    StatusCode::BAD_REQUEST,
+                    "Invalid header name".into(),
+                    Some(Value::String(details)),
+                )
+            }
+            RequestError::InvalidHeaderValue(name) => {
+                tracing::error!(header = %name, "Invalid header value");
+                let details = format!("Header '{name}' contains an invalid value");
+
+                (
+                    FrameworkErrorKind::InvalidHeaderValue,
+                    StatusCode::BAD_REQUEST,
+                    "Invalid header value".into(),
+                    Some(Value::String(details)),
+                )
+            }
+            RequestError::MissingHeader(name) => (
+                FrameworkErrorKind::MissingHeader,
+                StatusCode::BAD_REQUEST,
+                "Missing required header".into(),
+                Some(Value::String(format!("Header '{name}' is required"))),
+            ),
+            #[cfg(feature = "multipart")]
+            RequestError::MultipartError(err) => {
+                tracing::error!(error = %err, "Multipart error");
+
+                (
+                    FrameworkErrorKind::Multipart,
+                    err.status(),
+                    "Multipart error".into(),
+                    None,
+                )
+            }
+            #[cfg(feature = "multipart")]
+            RequestError::MultipartRejection(err) => {
+                tracing::error!(error = %err, "Multipart rejection");
+
+                (
+                    FrameworkErrorKind::Multipart,
+                    err.status(),
+                    "Multipart rejection".into(),
+                    None,
+                )
+            }
+        };
+
+        Self {
+            kind,
+            status,
+            message,
+            details,
+            request_id: None,
+        }
+    }
+}
+
+/// The default rendering: the `JsonResponse` envelope, with validation
+/// details as `errors` and any other details as `error`.
+impl From<FrameworkError> for JsonResponse {
+    fn from(error: FrameworkError) -> JsonResponse {
+        let response = JsonResponse::status(error.status).message(error.message);
+
+        let response = match (error.kind, error.details) {
+            (FrameworkErrorKind::Validation, Some(details)) => response.errors(details),
+            (_, Some(details)) => response.error(details),
+            (_, None) => response,
+        };
+
+        match error.request_id {
+            Some(request_id) => response.request_id(request_id),
+            None => response,
+        }
+    }
+}
+
+/// Renders a framework error with the registered [`ErrorRenderer`].
+///
+/// Headers and extensions of the original response (e.g. `x-request-id` or the
+/// negotiation marker) are kept unless the renderer sets them.
+pub(crate) fn render_error(
+    renderer: &dyn ErrorRenderer,
+    error: FrameworkError,
+    parts: Parts,
+) -> Response {
+    let mut rendered = renderer.render(error);
+    rendered.extensions_mut().extend(parts.extensions);
+
+    let headers = rendered.headers_mut();
+
+    for name in parts.headers.keys() {
+        if is_body_header(name) || headers.contains_key(name) {
+            continue;
+        }
+
+        for value in parts.headers.get_all(name) {
+            headers.append(name.clone(), value.clone());
+        }
+    }
+
+    rendered
+}
+
+fn is_body_header(name: &HeaderName) -> bool {
+    name == header::CONTENT_TYPE || name == header::CONTENT_LENGTH
+}
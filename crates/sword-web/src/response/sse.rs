@@ -0,0 +1,250 @@
+use crate::application::GracefulShutdown;
+use crate::request::{FromRequestParts, RequestError};
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{HeaderValue, request::Parts},
+    middleware::Next,
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use axum_responses::JsonResponse;
+use futures_lite::{Stream, StreamExt, stream};
+use serde::Serialize;
+use std::{
+    convert::Infallible,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use sword_core::State;
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>;
+
+/// A single Server-Sent Event whose data is serialized as JSON.
+///
+/// Event names and ids must not contain newlines.
+#[derive(Debug, Clone)]
+pub struct SseEvent<T> {
+    data: T,
+    event: Option<String>,
+    id: Option<String>,
+    retry: Option<Duration>,
+}
+
+impl<T: Serialize> SseEvent<T> {
+    pub fn new(data: T) -> Self {
+        Self {
+            data,
+            event: None,
+            id: None,
+            retry: None,
+        }
+    }
+
+    /// Sets the event name (`event:`), dispatched to `addEventListener(name)` listeners.
+    pub fn event(mut self, name: impl Into<String>) -> Self {
+        self.event = Some(name.into());
+        self
+    }
+
+    /// Sets the event id (`id:`), sent back by the client as `Last-Event-ID` when it reconnects.
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the reconnection delay the client should use from now on (`retry:`).
+    pub fn retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    fn into_event(self) -> Result<Event, axum::Error> {
+        let mut event = Event::default().json_data(self.data)?;
+
+        if let Some(name) = self.event {
+            event = event.event(name);
+        }
+
+        if let Some(id) = self.id {
+            event = event.id(id);
+        }
+
+        if let Some(retry) = self.retry {
+            event = event.retry(retry);
+        }
+
+        Ok(event)
+    }
+}
+
+/// A `text/event-stream` response built from a stream of [`SseEvent`]s.
+///
+/// A keep-alive comment is sent every 15 seconds by default. The stream is closed
+/// when the server starts a graceful shutdown, so open connections don't hold it.
+/// Routes declared with `#[sse]` are also exempt from the request timeout.
+///
+/// ```rust,ignore
+/// #[sse("/prices")]
+/// async fn prices(&self, last_event_id: LastEventId) -> SseResponse {
+///     let updates = self.prices.subscribe_after(last_event_id.0).map(|price| {
+///         SseEvent::new(price).event("price").id(price.sequence.to_string())
+///     });
+///
+///     SseResponse::new(updates).retry(Duration::from_secs(5))
+/// }
+/// ```
+pub struct SseResponse {
+    events: EventStream,
+    keep_alive: Option<KeepAlive>,
+    retry: Option<Duration>,
+}
+
+impl SseResponse {
+    /// Creates a response from a stream of events. Events whose data can't be
+    /// serialized are logged and skipped.
+    pub fn new<S, T>(events: S) -> Self
+    where
+        S: Stream<Item = SseEvent<T>> + Send + 'static,
+        T: Serialize,
+    {
+        let events = events.filter_map(|event| match event.into_event() {
+            Ok(event) => Some(Ok(event)),
+            Err(error) => {
+                tracing::error!(error = %error, "Failed to serialize SSE event data");
+                None
+            }
+        });
+
+        Self {
+            events: Box::pin(events),
+            keep_alive: Some(KeepAlive::default()),
+            retry: None,
+        }
+    }
+
+    /// Sends a keep-alive comment after `interval` without events.
+    pub fn keep_alive(mut self, interval: Duration) -> Self {
+        self.keep_alive = Some(KeepAlive::new().interval(interval));
+        self
+    }
+
+    /// Disables keep-alive comments.
+    pub fn without_keep_alive(mut self) -> Self {
+        self.keep_alive = None;
+        self
+    }
+
+    /// Sends a `retry:` hint before the first event, setting the client reconnection delay.
+    pub fn retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+}
+
+impl IntoResponse for SseResponse {
+    fn into_response(self) -> Response {
+        let events: EventStream = match self.retry {
+            Some(retry) => {
+                Box::pin(stream::once(Ok(Event::default().retry(retry))).chain(self.events))
+            }
+            None => self.events,
+        };
+
+        let sse = Sse::new(events);
+
+        let mut response = match self.keep_alive {
+            Some(keep_alive) => sse.keep_alive(keep_alive).into_response(),
+            None => sse.into_response(),
+        };
+
+        // Disables response buffering in reverse proxies such as nginx.
+        response
+            .headers_mut()
+            .insert("x-accel-buffering", HeaderValue::from_static("no"));
+
+        response.extensions_mut().insert(EventStreamResponse);
+
+        response
+    }
+}
+
+/// The `Last-Event-ID` header, sent by an `EventSource` reconnecting after the
+/// stream dropped. `None` on the first connection.
+///
+/// ```rust,ignore
+/// #[sse("/events")]
+/// async fn events(&self, LastEventId(last_id): LastEventId) -> SseResponse { ... }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LastEventId(pub Option<String>);
+
+impl FromRequestParts for LastEventId {
+    type Rejection = JsonResponse;
+
+    async fn from_request_parts(parts: &mut Parts, _: &State) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get("last-event-id") else {
+            return Ok(Self(None));
+        };
+
+        let value = value
+            .to_str()
+            .map_err(|_| RequestError::invalid_header_value("last-event-id"))?;
+
+        Ok(Self(Some(value.to_owned())))
+    }
+}
+
+/// Marks responses streamed by [`SseResponse`].
+#[derive(Clone, Copy)]
+struct EventStreamResponse;
+
+/// Ends event stream bodies when the graceful shutdown starts.
+pub(crate) async fn sse_shutdown_middleware(
+    shutdown: GracefulShutdown,
+    req: Request,
+    next: Next,
+) -> Response {
+    let response = next.run(req).await;
+
+    if response.extensions().get::<EventStreamResponse>().is_none() {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+
+    let body = UntilShutdown {
+        inner: body.into_data_stream(),
+        shutdown: Some(Box::pin(async move { shutdown.triggered().await })),
+    };
+
+    Response::from_parts(parts, Body::from_stream(body))
+}
+
+struct UntilShutdown<S> {
+    inner: S,
+    /// `None` once the shutdown started.
+    shutdown: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+}
+
+impl<S: Stream + Unpin> Stream for UntilShutdown<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let Some(shutdown) = self.shutdown.as_mut() else {
+            return Poll::Ready(None);
+        };
+
+        if shutdown.as_mut().poll(cx).is_ready() {
+            self.shutdown = None;
+            return Poll::Ready(None);
+        }
+
+        self.inner.poll_next(cx)
+    }
+}
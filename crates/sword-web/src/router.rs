@@ -1,21 +1,65 @@
+use crate::application::GracefulShutdown;
 use crate::config::WebApplicationConfig;
 use crate::controller::{RouteRegistrar, WebControllerRegistrar};
-use crate::response::JsonResponse;
-
-use axum::{Router, extract::Request, middleware::Next};
+use crate::response::{
+    AutoETag, ErrorPipeline, JsonResponse, error_pipeline_middleware, etag_middleware,
+    json_stream_timeout_middleware, negotiation_middleware, sse_shutdown_middleware,
+};
+use crate::versioning::VersionedRouters;
+
+use axum::{
+    Router,
+    extract::{DefaultBodyLimit, Request},
+    http::StatusCode,
+    middleware::Next,
+    routing::MethodRouter,
+};
 use std::any::TypeId;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
 use sword_core::*;
 use sword_layers::{
+    DisplayConfig,
     body_limit::{BodyLimitLayer, BodyLimitValue},
     cookies::CookieManagerLayer,
+    forwarded::ClientInfoLayer,
     layer_stack::LayerStack,
     not_found::NotFoundLayer,
     request_id::RequestIdLayer,
     timeout::{RequestTimeoutResponseLayer, TimeoutLayer},
 };
 
+#[cfg(feature = "metrics")]
+use sword_layers::metrics::{
+    HttpMetricsLayer, MetricsConfig, RouteMetricsLayer, with_metrics_route,
+};
+
+#[cfg(feature = "opentelemetry")]
+use sword_layers::opentelemetry::{HttpTraceLayer, OpenTelemetryConfig, Telemetry};
+
+#[cfg(feature = "openapi")]
+use crate::openapi::{OpenApiConfig, OpenApiDocument, with_openapi_routes};
+
+#[cfg(feature = "i18n")]
+use crate::i18n::init_i18n;
+
+#[cfg(feature = "idempotency")]
+use crate::idempotency::with_idempotency_layer;
+
+#[cfg(feature = "sessions")]
+use crate::sessions::with_session_layer;
+
+#[cfg(feature = "csrf")]
+use {
+    crate::csrf::init_csrf,
+    sword_layers::csrf::{CsrfLayer, CsrfProtection},
+};
+
+#[cfg(feature = "rate-limit")]
+use sword_layers::rate_limit::{DEFAULT_POLICY, RateLimitConfig, RateLimitLayer, RateLimiter};
+
 pub(crate) struct WebRouter<'a> {
     pub state: State,
     pub config: &'a Config,
@@ -35,8 +79,31 @@ impl<'a> WebRouter<'a> {
     pub(crate) fn build(self) -> Router<State> {
         let mut router = Router::new();
 
-        router = Self::apply_controllers(&self.state, router, &self.controller_registry.read());
-        router = Self::apply_web_layers(router, &self.web_config);
+        #[cfg(feature = "opentelemetry")]
+        Self::init_telemetry(&self.state);
+
+        let shutdown = GracefulShutdown::default();
+        self.state.insert(shutdown.clone());
+
+        #[cfg(feature = "csrf")]
+        init_csrf(&self.state, &self.web_config);
+
+        #[cfg(feature = "i18n")]
+        init_i18n(&self.state, &self.web_config);
+
+        router = Self::apply_controllers(
+            &self.state,
+            router,
+            &self.controller_registry.read(),
+            &self.web_config,
+        );
+
+        router = Self::apply_web_layers(router, &self.state, &self.web_config, shutdown);
+
+        #[cfg(feature = "metrics")]
+        if enabled_metrics_config(&self.state).is_some() {
+            router = router.layer(HttpMetricsLayer);
+        }
 
         for extension in inventory::iter::<WebRouterExtension>() {
             router = (extension.apply)(&self.state, self.config, router, self.controller_registry);
@@ -44,6 +111,11 @@ impl<'a> WebRouter<'a> {
 
         router = self.layer_stack.apply(router);
 
+        // Resolved outside every other layer so global layers and interceptors
+        // see the same client address as handlers.
+        self.web_config.trusted_proxies.display();
+        router = router.layer(ClientInfoLayer::new(&self.web_config.trusted_proxies));
+
         if let Some(prefix) = &self.web_config.router_prefix {
             router = Router::new().nest(prefix, router);
         }
@@ -53,8 +125,38 @@ impl<'a> WebRouter<'a> {
             axum::routing::get(|| async { JsonResponse::Ok().message("healthy") }),
         );
 
+        #[cfg(feature = "metrics")]
+        if let Some(metrics_config) = enabled_metrics_config(&self.state) {
+            metrics_config.display();
+            router = with_metrics_route(router, &metrics_config);
+        }
+
+        #[cfg(feature = "openapi")]
+        if let Some(openapi_config) = enabled_openapi_config(&self.state) {
+            openapi_config.display();
+
+            let document = OpenApiDocument::build(
+                &openapi_config,
+                self.web_config.router_prefix.as_deref(),
+                &self.web_config.versioning,
+                &self.controller_registry.read(),
+            );
+
+            router = with_openapi_routes(router, &document, &openapi_config);
+            self.state.insert(document);
+        }
+
         router = router.layer(NotFoundLayer);
 
+        // Outside `NotFoundLayer`, so unknown routes go through the pipeline too.
+        self.web_config.errors.display();
+
+        let pipeline = Arc::new(ErrorPipeline::new(&self.state, &self.web_config.errors));
+
+        router = router.layer(axum::middleware::from_fn(move |req, next| {
+            error_pipeline_middleware(pipeline.clone(), req, next)
+        }));
+
         router
     }
 
@@ -63,10 +165,11 @@ impl<'a> WebRouter<'a> {
         state: &State,
         mut router: Router<State>,
         controllers: &ControllerMap,
+        web_config: &WebApplicationConfig,
     ) -> Router<State> {
         for (kind, ids) in controllers.iter() {
             if kind == &Controller::Web {
-                router = Self::apply_web_controllers(state, router, ids);
+                router = Self::apply_web_controllers(state, router, ids, web_config);
             }
         }
 
@@ -77,6 +180,7 @@ impl<'a> WebRouter<'a> {
         state: &State,
         mut router: Router<State>,
         controllers: &ControllerIds,
+        web_config: &WebApplicationConfig,
     ) -> Router<State> {
         let controller_registrars: HashMap<TypeId, &WebControllerRegistrar> =
             inventory::iter::<WebControllerRegistrar>()
@@ -84,6 +188,7 @@ impl<'a> WebRouter<'a> {
                 .collect();
 
         let mut routes_by_controller: HashMap<TypeId, Vec<&RouteRegistrar>> = HashMap::new();
+        let mut versioned = VersionedRouters::default();
 
         for route in inventory::iter::<RouteRegistrar>() {
             routes_by_controller
@@ -129,53 +234,313 @@ impl<'a> WebRouter<'a> {
 
             let mut controller_router = Router::new();
 
-            for route in controller_routes {
-                controller_router =
-                    controller_router.route(route.path, (route.handler)(state.clone()));
+            for route in &controller_routes {
+                let method_router = Self::apply_route_layers(
+                    state,
+                    web_config,
+                    controller_registrar,
+                    route,
+                    (route.handler)(state.clone()),
+                );
+
+                controller_router = controller_router.route(route.path, method_router);
             }
 
-            if controller_registrar.controller_path == "/" {
+            if let Some(version) = controller_registrar.version {
+                versioned.add(
+                    version,
+                    controller_registrar.controller_path,
+                    controller_routes.iter().map(|route| route.path),
+                    controller_router,
+                );
+            } else if controller_registrar.controller_path == "/" {
                 router = router.merge(controller_router);
             } else {
                 router = router.nest(controller_registrar.controller_path, controller_router);
             }
         }
 
-        router
+        versioned.apply(router, state, &web_config.versioning)
     }
 
-    /// Apply mandatory web layers.
+    /// Apply the layers that need to know which controller route they wrap.
     ///
-    /// These are applied BEFORE the SocketIO layer, so SocketIO traffic bypasses
-    /// HTTP controller timeout semantics.
-    fn apply_web_layers(
-        mut router: Router<State>,
+    /// Route-level observability layers are labelled with the route template
+    /// built from the `RouteRegistrar` path, never with the raw request URI.
+    /// The request timeout and body limit are applied here too, so routes and
+    /// controllers can override them and streaming routes can skip the timeout;
+    /// the timeout also bounds `JsonStreamResponse` bodies. Rate limiting wraps
+    /// the others, so rejected requests skip them; only the observability
+    /// layers sit outside it.
+    #[cfg_attr(
+        not(any(
+            feature = "metrics",
+            feature = "opentelemetry",
+            feature = "csrf",
+            feature = "rate-limit"
+        )),
+        allow(unused_variables)
+    )]
+    fn apply_route_layers(
+        state: &State,
         web_config: &WebApplicationConfig,
-    ) -> Router<State> {
-        let body_limit_config = web_config.body_limit.clone();
-
-        router = router.layer(BodyLimitLayer::new(&body_limit_config));
+        controller: &WebControllerRegistrar,
+        route: &RouteRegistrar,
+        mut method_router: MethodRouter<State>,
+    ) -> MethodRouter<State> {
+        if let Some(etag) = route.etag {
+            method_router = method_router.layer(axum::middleware::map_response(
+                move |mut response: axum::response::Response| async move {
+                    response.extensions_mut().insert(AutoETag(etag));
+                    response
+                },
+            ));
+        }
 
-        if web_config.request_timeout.enabled {
-            let timeout_service: TimeoutLayer = web_config.request_timeout.clone().into();
-            let response_mapper = RequestTimeoutResponseLayer::new();
+        // Inside the body limit, so form fields are read from bounded bodies.
+        #[cfg(feature = "csrf")]
+        if let Ok(protection) = state.get::<CsrfProtection>() {
+            method_router = method_router.layer(CsrfLayer::new(protection));
+        }
 
-            router = router.layer(timeout_service);
-            router = router.layer(response_mapper);
+        let timeout = match route.streaming {
+            true => None,
+            false => route_timeout(web_config, controller, route),
+        };
+
+        if let Some(timeout) = timeout {
+            method_router = method_router.layer(axum::middleware::from_fn(move |req, next| {
+                json_stream_timeout_middleware(timeout, req, next)
+            }));
+            method_router = method_router.layer(TimeoutLayer::with_status_code(
+                StatusCode::REQUEST_TIMEOUT,
+                timeout,
+            ));
+            method_router = method_router.layer(RequestTimeoutResponseLayer::new());
         }
 
-        router = router.layer(axum::middleware::from_fn(
-            move |mut req: Request, next: Next| async move {
-                req.extensions_mut()
-                    .insert(BodyLimitValue(body_limit_config.max_size.parsed));
+        let body_limit = route_body_limit(web_config, controller, route);
 
+        method_router = method_router.layer(BodyLimitLayer::with_max_size(body_limit));
+        method_router = method_router.layer(DefaultBodyLimit::max(body_limit));
+        method_router = method_router.layer(axum::middleware::from_fn(
+            move |mut req: Request, next: Next| async move {
+                req.extensions_mut().insert(BodyLimitValue(body_limit));
                 next.run(req).await
             },
         ));
 
+        #[cfg(feature = "rate-limit")]
+        if let Ok(limiter) = state.get::<RateLimiter>()
+            && let Some(policy) = route_rate_limit(state, &limiter, controller, route)
+        {
+            method_router = method_router.layer(RateLimitLayer::new(limiter, policy));
+        }
+
+        // Outermost, so requests rejected by the layers above are still
+        // recorded with their route.
+        #[cfg(any(feature = "metrics", feature = "opentelemetry"))]
+        let template = route_template(
+            web_config.router_prefix.as_deref(),
+            &web_config
+                .versioning
+                .controller_path(controller.controller_path, controller.version),
+            route.path,
+        );
+
+        #[cfg(feature = "metrics")]
+        if enabled_metrics_config(state).is_some() {
+            method_router = method_router.layer(RouteMetricsLayer::new(
+                controller.controller_name,
+                template.clone(),
+            ));
+        }
+
+        #[cfg(feature = "opentelemetry")]
+        if let Ok(telemetry) = state.get::<Telemetry>() {
+            method_router = method_router.layer(HttpTraceLayer::new(
+                telemetry,
+                controller.controller_name,
+                template,
+            ));
+        }
+
+        method_router
+    }
+
+    /// Apply mandatory web layers.
+    ///
+    /// These are applied BEFORE the SocketIO layer, so SocketIO traffic bypasses
+    /// them. The request timeout and body limit are applied per route by
+    /// `apply_route_layers`.
+    #[cfg_attr(
+        not(any(feature = "idempotency", feature = "sessions")),
+        allow(unused_variables)
+    )]
+    fn apply_web_layers(
+        mut router: Router<State>,
+        state: &State,
+        web_config: &WebApplicationConfig,
+        shutdown: GracefulShutdown,
+    ) -> Router<State> {
+        let etag = &web_config.etag;
+        let (enabled, max_size) = (etag.enabled, etag.max_size.parsed);
+
+        etag.display();
+
+        // Inside negotiation, so tags are computed from the JSON envelope.
+        router = router.layer(axum::middleware::from_fn(move |req, next| {
+            etag_middleware(enabled, max_size, req, next)
+        }));
+        router = router.layer(axum::middleware::from_fn(negotiation_middleware));
+        router = router.layer(axum::middleware::from_fn(move |req, next| {
+            sse_shutdown_middleware(shutdown.clone(), req, next)
+        }));
+
+        // Inside the request id, so replayed responses carry the id of the repeat.
+        #[cfg(feature = "idempotency")]
+        {
+            router = with_idempotency_layer(router, state, web_config.idempotency.as_ref());
+        }
+
         router = router.layer(RequestIdLayer::new());
+
+        #[cfg(feature = "sessions")]
+        {
+            router = with_session_layer(router, state, web_config.session.as_ref());
+        }
+
         router = router.layer(CookieManagerLayer::new());
 
         router
     }
 }
+
+#[cfg(feature = "opentelemetry")]
+impl WebRouter<'_> {
+    /// Build the OTLP exporter and store the shared `Telemetry` handle in state.
+    fn init_telemetry(state: &State) {
+        let Ok(config) = state.get::<OpenTelemetryConfig>() else {
+            return;
+        };
+
+        config.display();
+
+        if !config.enabled {
+            return;
+        }
+
+        let telemetry = Telemetry::from_config(&config).unwrap_or_else(|err| {
+            sword_error! {
+                title: "Failed to initialize OpenTelemetry exporter",
+                reason: err,
+                context: {
+                    "endpoint" => config.endpoint.clone(),
+                    "source" => "WebRouter::init_telemetry",
+                },
+                hints: ["Ensure the `opentelemetry.endpoint` value is a valid URI"],
+            }
+        });
+
+        state.insert(telemetry);
+    }
+}
+
+/// The body limit of a route: its own `body_limit`, then the controller's,
+/// then `web.body-limit`.
+fn route_body_limit(
+    web_config: &WebApplicationConfig,
+    controller: &WebControllerRegistrar,
+    route: &RouteRegistrar,
+) -> usize {
+    route
+        .body_limit
+        .or(controller.body_limit)
+        .unwrap_or(web_config.body_limit.max_size.parsed)
+}
+
+/// The request timeout of a route: its own `timeout`, then the controller's,
+/// then `web.request-timeout` when enabled.
+fn route_timeout(
+    web_config: &WebApplicationConfig,
+    controller: &WebControllerRegistrar,
+    route: &RouteRegistrar,
+) -> Option<Duration> {
+    route.timeout.or(controller.timeout).or_else(|| {
+        web_config
+            .request_timeout
+            .enabled
+            .then_some(web_config.request_timeout.timeout.parsed)
+    })
+}
+
+/// The rate limit policy of a route: its own `rate_limit`, then the
+/// controller's, then the default policy when `rate-limit.global` is set.
+/// `rate_limit = "none"` exempts a route.
+#[cfg(feature = "rate-limit")]
+fn route_rate_limit(
+    state: &State,
+    limiter: &RateLimiter,
+    controller: &WebControllerRegistrar,
+    route: &RouteRegistrar,
+) -> Option<&'static str> {
+    match route.rate_limit.or(controller.rate_limit) {
+        Some("none") => None,
+        Some(policy) if limiter.policy(policy).is_some() => Some(policy),
+        Some(policy) => {
+            sword_error! {
+                title: "Invalid route option",
+                reason: format!("No rate limit policy is named \"{policy}\""),
+                context: {
+                    "option" => format!("rate_limit = \"{policy}\""),
+                    "controller" => controller.controller_name,
+                    "route" => route.path,
+                    "source" => "WebRouter::apply_route_layers",
+                },
+                hints: ["Define the policy under `[rate-limit.policies.<name>]`"],
+            }
+        }
+        None => state
+            .get::<RateLimitConfig>()
+            .is_ok_and(|config| config.global)
+            .then_some(DEFAULT_POLICY),
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn enabled_metrics_config(state: &State) -> Option<MetricsConfig> {
+    state
+        .get::<MetricsConfig>()
+        .ok()
+        .filter(|config| config.enabled)
+}
+
+#[cfg(feature = "openapi")]
+fn enabled_openapi_config(state: &State) -> Option<OpenApiConfig> {
+    state
+        .get::<OpenApiConfig>()
+        .ok()
+        .filter(|config| config.enabled)
+}
+
+/// Joins the router prefix, a controller base path and a route path into the
+/// route template used by observability layers and the OpenAPI document
+/// (e.g. `/api` + `/users` + `/{id}` => `/api/users/{id}`).
+#[cfg(any(feature = "metrics", feature = "opentelemetry", feature = "openapi"))]
+pub(crate) fn route_template(
+    router_prefix: Option<&str>,
+    controller_path: &str,
+    route_path: &str,
+) -> String {
+    let controller_path = controller_path.trim_end_matches('/');
+
+    let template = match route_path {
+        "" | "/" if !controller_path.is_empty() => controller_path.to_string(),
+        _ => format!("{controller_path}{route_path}"),
+    };
+
+    match router_prefix {
+        Some(prefix) => format!("{}{template}", prefix.trim_end_matches('/')),
+        None => template,
+    }
+}
@@ -0,0 +1,68 @@
+//! Server-side sessions configured under `[web.session]`.
+
+use axum::Router;
+use std::sync::Arc;
+use sword_core::{Provider, State, sword_error};
+use sword_layers::{
+    DisplayConfig,
+    sessions::{SessionConfig, SessionLayer, SessionStore},
+};
+
+/// Replaces the store selected by `web.session.store` with a custom one.
+///
+/// ### Example
+/// ```rust,ignore
+/// Application::builder()
+///     .with_provider(SessionStoreProvider::new(RedisStore::new(client)))
+///     .with_module::<AccountModule>()
+///     .build();
+/// ```
+#[derive(Clone)]
+pub struct SessionStoreProvider(Arc<dyn SessionStore>);
+
+impl SessionStoreProvider {
+    pub fn new(store: impl SessionStore) -> Self {
+        Self(Arc::new(store))
+    }
+}
+
+impl Provider for SessionStoreProvider {}
+
+/// Applies the `SessionLayer` when `[web.session]` is configured.
+///
+/// Must be applied before `CookieManagerLayer`, which provides the cookies.
+pub(crate) fn with_session_layer(
+    router: Router<State>,
+    state: &State,
+    config: Option<&SessionConfig>,
+) -> Router<State> {
+    let Some(config) = config else {
+        return router;
+    };
+
+    config.display();
+
+    let store = match state.get::<SessionStoreProvider>() {
+        Ok(provider) => Ok(provider.0),
+        Err(_) => config.open_store(),
+    };
+
+    let layer = store
+        .and_then(|store| SessionLayer::new(config, store))
+        .unwrap_or_else(|err| {
+            sword_error! {
+                title: "Failed to initialize sessions",
+                reason: err,
+                context: {
+                    "store" => format!("{:?}", config.store),
+                    "source" => "WebRouter::apply_web_layers",
+                },
+                hints: [
+                    "Set `web.session.secret` to a random value of at least 32 bytes",
+                    "The \"sql\" store requires `web.session.url` and the `sessions-sql` feature",
+                ],
+            }
+        });
+
+    router.layer(layer)
+}
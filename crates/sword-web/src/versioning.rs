@@ -0,0 +1,449 @@
+//! API versioning for web controllers declared with `version = "..."`.
+//!
+//! Controllers of the same version are grouped in their own router. Depending on
+//! the configured strategy, the version is read from a URL segment (`/v2/users`),
+//! a request header (`Accept-Version: 2`) or a media-type parameter
+//! (`Accept: application/json; version=2`).
+
+use crate::request::FromRequestParts;
+
+use axum::{
+    Router,
+    body::Body,
+    extract::Request,
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::any,
+};
+use axum_responses::JsonResponse;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::Infallible,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use sword_core::{State, sword_error};
+use sword_layers::DisplayConfig;
+use tower::ServiceExt;
+
+/// Where the requested API version is read from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VersioningStrategy {
+    /// A leading URL segment, e.g. `/v2/users`.
+    #[default]
+    Path,
+
+    /// A request header, `Accept-Version` by default.
+    Header,
+
+    /// A parameter of the `Accept` media type, e.g. `application/json; version=2`.
+    MediaType,
+}
+
+/// Configuration of the `[web.versioning]` key.
+///
+/// ```toml
+/// [web.versioning]
+/// strategy = "header"
+/// default-version = "2"
+///
+/// [web.versioning.versions."1"]
+/// deprecation = "Wed, 01 Jan 2025 00:00:00 GMT"
+/// sunset = "Thu, 01 Jan 2026 00:00:00 GMT"
+/// link = "https://example.com/docs/migrating-to-v2"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VersioningConfig {
+    /// How the version is selected: "path" (default), "header" or "media-type".
+    pub strategy: VersioningStrategy,
+
+    /// Header read by the "header" strategy. Defaults to "Accept-Version".
+    pub header: String,
+
+    /// `Accept` parameter read by the "media-type" strategy. Defaults to "version".
+    #[serde(rename = "media-type-parameter")]
+    pub media_type_parameter: String,
+
+    /// Segment prefix of the "path" strategy, so version "2" is served under `/v2`.
+    #[serde(rename = "path-prefix")]
+    pub path_prefix: String,
+
+    /// Version that serves requests without one. With the "path" strategy its
+    /// controllers are also served without the version segment.
+    #[serde(rename = "default-version")]
+    pub default_version: Option<String>,
+
+    /// Deprecation policies by version.
+    pub versions: BTreeMap<String, VersionPolicy>,
+
+    /// Whether to display the configuration details.
+    pub display: bool,
+}
+
+/// Deprecation headers added to every response of a version.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VersionPolicy {
+    /// HTTP date the version was deprecated on, sent as `Deprecation: @<unix time>`.
+    pub deprecation: Option<String>,
+
+    /// HTTP date after which the version may stop responding, sent as `Sunset`.
+    pub sunset: Option<String>,
+
+    /// Migration documentation, sent as `Link: <url>; rel="deprecation"`.
+    pub link: Option<String>,
+}
+
+impl Default for VersioningConfig {
+    fn default() -> Self {
+        Self {
+            strategy: VersioningStrategy::Path,
+            header: "Accept-Version".to_string(),
+            media_type_parameter: "version".to_string(),
+            path_prefix: "v".to_string(),
+            default_version: None,
+            versions: BTreeMap::new(),
+            display: false,
+        }
+    }
+}
+
+impl DisplayConfig for VersioningConfig {
+    fn display(&self) {
+        if !self.display {
+            return;
+        }
+
+        tracing::info!(
+            target: "sword.web.versioning",
+            strategy = ?self.strategy,
+            default_version = ?self.default_version,
+            deprecated = ?self.versions.keys().collect::<Vec<_>>(),
+        );
+    }
+}
+
+impl VersioningConfig {
+    /// Path a versioned controller is served under, e.g. `/v2/users`.
+    #[cfg(any(feature = "metrics", feature = "opentelemetry", feature = "openapi"))]
+    pub(crate) fn controller_path(&self, path: &str, version: Option<&str>) -> String {
+        match version {
+            Some(version) if self.strategy == VersioningStrategy::Path => {
+                format!(
+                    "/{}{version}{}",
+                    self.path_prefix,
+                    path.trim_end_matches('/')
+                )
+            }
+            _ => path.to_string(),
+        }
+    }
+
+    /// Reads the requested version. `Ok(None)` when the request doesn't carry one.
+    fn requested_version(&self, header: &HeaderName, req: &Request) -> Result<Option<String>, ()> {
+        match self.strategy {
+            VersioningStrategy::Path => Ok(None),
+
+            VersioningStrategy::Header => req
+                .headers()
+                .get(header)
+                .map(|value| value.to_str().map(|value| value.trim().to_string()))
+                .transpose()
+                .map_err(|_| ()),
+
+            VersioningStrategy::MediaType => Ok(req
+                .headers()
+                .get_all(header::ACCEPT)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .filter_map(|media_type| media_type.trim().parse::<mime::Mime>().ok())
+                .find_map(|media_type| {
+                    media_type
+                        .get_param(self.media_type_parameter.as_str())
+                        .map(|version| version.as_str().to_string())
+                })),
+        }
+    }
+}
+
+/// The API version a request was routed to.
+///
+/// Inserted as a request extension on routes of versioned controllers, and
+/// available as a handler parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiVersion(pub String);
+
+impl ApiVersion {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromRequestParts for ApiVersion {
+    type Rejection = JsonResponse;
+
+    async fn from_request_parts(parts: &mut Parts, _: &State) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<ApiVersion>()
+            .cloned()
+            .ok_or_else(|| {
+                JsonResponse::InternalServerError().message(
+                    "Can't extract API version. Is the controller declared with `version`?",
+                )
+            })
+    }
+}
+
+/// Routers of versioned controllers, one per version.
+#[derive(Default)]
+pub(crate) struct VersionedRouters {
+    routers: BTreeMap<&'static str, Router<State>>,
+
+    /// Route templates of every version, used to tell version errors apart
+    /// from unknown paths.
+    paths: BTreeSet<String>,
+}
+
+impl VersionedRouters {
+    pub(crate) fn add<'a>(
+        &mut self,
+        version: &'static str,
+        path: &str,
+        routes: impl IntoIterator<Item = &'a str>,
+        controller: Router<State>,
+    ) {
+        for route in routes {
+            self.paths.insert(match (path, route) {
+                ("/", route) => route.to_string(),
+                (path, "/") => path.to_string(),
+                (path, route) => format!("{}{route}", path.trim_end_matches('/')),
+            });
+        }
+
+        let router = self.routers.entry(version).or_default();
+
+        *router = match path {
+            "/" => std::mem::take(router).merge(controller),
+            path => std::mem::take(router).nest(path, controller),
+        };
+    }
+
+    /// Mounts every version on `router` according to the configured strategy.
+    pub(crate) fn apply(
+        self,
+        mut router: Router<State>,
+        state: &State,
+        config: &VersioningConfig,
+    ) -> Router<State> {
+        if self.routers.is_empty() {
+            return router;
+        }
+
+        config.display();
+
+        if let Some(default) = &config.default_version
+            && !self.routers.contains_key(default.as_str())
+        {
+            sword_error! {
+                title: "Invalid default API version",
+                reason: format!("No controller is declared with version \"{default}\""),
+                context: {
+                    "versions" => self.routers.keys().copied().collect::<Vec<_>>().join(", "),
+                    "source" => "VersionedRouters::apply",
+                },
+                hints: ["Set `web.versioning.default-version` to the version of a registered controller"],
+            }
+        }
+
+        let header = HeaderName::try_from(config.header.as_str()).unwrap_or_else(|err| {
+            sword_error! {
+                title: "Invalid API version header",
+                reason: err,
+                context: {
+                    "header" => config.header.clone(),
+                    "source" => "VersionedRouters::apply",
+                },
+            }
+        });
+
+        let vary = match config.strategy {
+            VersioningStrategy::Path => None,
+            VersioningStrategy::Header => Some(header.clone()),
+            VersioningStrategy::MediaType => Some(header::ACCEPT),
+        };
+
+        let routers = self.routers.into_iter().map(|(version, versioned)| {
+            let headers = policy_headers(version, config.versions.get(version));
+            let vary = vary.clone();
+
+            let versioned = versioned.layer(axum::middleware::from_fn(
+                move |req: Request, next: Next| {
+                    version_middleware(version, headers.clone(), vary.clone(), req, next)
+                },
+            ));
+
+            (version, versioned)
+        });
+
+        if config.strategy == VersioningStrategy::Path {
+            for (version, versioned) in routers {
+                if config.default_version.as_deref() == Some(version) {
+                    router = router.merge(versioned.clone());
+                }
+
+                router = router.nest(&format!("/{}{version}", config.path_prefix), versioned);
+            }
+
+            return router;
+        }
+
+        let routers = Arc::new(
+            routers
+                .map(|(version, versioned)| {
+                    (version.to_string(), versioned.with_state(state.clone()))
+                })
+                .collect::<BTreeMap<_, _>>(),
+        );
+
+        let config = Arc::new(config.clone());
+
+        // Matches the paths of versioned routes without running their handlers.
+        let probe = self.paths.iter().fold(Router::new(), |probe, path| {
+            probe.route(path, any(|| async { StatusCode::NO_CONTENT }))
+        });
+
+        // Requests no unversioned route matches are dispatched to the router of
+        // the requested version, or of the default version. Paths no version
+        // declares fall through to the not found response instead of a version
+        // error.
+        router.fallback_service(tower::service_fn(move |req: Request| {
+            let routers = Arc::clone(&routers);
+            let config = Arc::clone(&config);
+            let probe = probe.clone();
+            let requested = config.requested_version(&header, &req);
+
+            async move {
+                let uri = req.uri().clone();
+                let probe_req = Request::builder().uri(uri).body(Body::empty());
+
+                let is_versioned = match probe_req {
+                    Ok(probe_req) => {
+                        probe.oneshot(probe_req).await?.status() != StatusCode::NOT_FOUND
+                    }
+                    Err(_) => false,
+                };
+
+                if !is_versioned {
+                    return Ok(StatusCode::NOT_FOUND.into_response());
+                }
+
+                let version = match requested {
+                    Ok(Some(version)) => version,
+                    Ok(None) => match &config.default_version {
+                        Some(version) => version.clone(),
+                        None => {
+                            return Ok::<_, Infallible>(
+                                JsonResponse::BadRequest()
+                                    .message("An API version is required")
+                                    .into_response(),
+                            );
+                        }
+                    },
+                    Err(()) => {
+                        return Ok(JsonResponse::BadRequest()
+                            .message("Invalid API version")
+                            .into_response());
+                    }
+                };
+
+                match routers.get(&version) {
+                    Some(router) => router.clone().oneshot(req).await,
+                    None => Ok(JsonResponse::BadRequest()
+                        .message(format!("Unsupported API version \"{version}\""))
+                        .into_response()),
+                }
+            }
+        }))
+    }
+}
+
+async fn version_middleware(
+    version: &'static str,
+    headers: HeaderMap,
+    vary: Option<HeaderName>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    req.extensions_mut().insert(ApiVersion(version.to_string()));
+
+    let mut response = next.run(req).await;
+
+    response.headers_mut().extend(headers);
+
+    if let Some(vary) = vary {
+        response
+            .headers_mut()
+            .append(header::VARY, HeaderValue::from_name(vary));
+    }
+
+    response
+}
+
+/// Builds the `Deprecation`, `Sunset` and `Link` headers of a version.
+fn policy_headers(version: &str, policy: Option<&VersionPolicy>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+
+    let Some(policy) = policy else {
+        return headers;
+    };
+
+    let invalid = |key: &str, value: &str, hint: &str| -> ! {
+        sword_error! {
+            title: "Invalid API version policy",
+            reason: format!("`{key}` has an invalid value \"{value}\""),
+            context: {
+                "version" => version.to_string(),
+                "source" => "VersionedRouters::apply",
+            },
+            hints: [hint],
+        }
+    };
+
+    let http_date = "Use an HTTP date, e.g. \"Wed, 01 Jan 2025 00:00:00 GMT\"";
+
+    if let Some(deprecation) = &policy.deprecation {
+        let seconds = httpdate::parse_http_date(deprecation)
+            .ok()
+            .and_then(|date| date.duration_since(UNIX_EPOCH).ok())
+            .unwrap_or_else(|| invalid("deprecation", deprecation, http_date))
+            .as_secs();
+
+        if let Ok(value) = HeaderValue::try_from(format!("@{seconds}")) {
+            headers.insert(HeaderName::from_static("deprecation"), value);
+        }
+    }
+
+    if let Some(sunset) = &policy.sunset {
+        let date: SystemTime = httpdate::parse_http_date(sunset)
+            .unwrap_or_else(|_| invalid("sunset", sunset, http_date));
+
+        if let Ok(value) = HeaderValue::try_from(httpdate::fmt_http_date(date)) {
+            headers.insert(HeaderName::from_static("sunset"), value);
+        }
+    }
+
+    if let Some(link) = &policy.link {
+        let value = HeaderValue::try_from(format!("<{link}>; rel=\"deprecation\""))
+            .unwrap_or_else(|_| invalid("link", link, "Use an absolute URL"));
+
+        headers.insert(header::LINK, value);
+    }
+
+    headers
+}
@@ -0,0 +1,204 @@
+use crate::context::WebSocketContext;
+use crate::controller::{
+    UpgradeInterceptor, WebSocketCallFn, WebSocketControllerRegistrar, WebSocketEvent,
+    WebSocketSetup,
+};
+
+use axum::{
+    extract::{
+        FromRequestParts, Request,
+        ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade, close_code},
+    },
+    response::{IntoResponse, Response},
+};
+use axum_responses::JsonResponse;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+use sword_web::prelude::GracefulShutdown;
+use tokio::sync::mpsc::{self, Receiver};
+
+/// Frames queued for one connection before `WebSocketSender::send` waits.
+const OUTGOING_BUFFER_SIZE: usize = 128;
+
+/// A WebSocket controller route: the built controller, its interceptors and handlers.
+pub(crate) struct WebSocketEndpoint {
+    controller: Arc<dyn Any + Send + Sync>,
+    interceptors: Vec<UpgradeInterceptor>,
+    handlers: HashMap<WebSocketEvent, WebSocketCallFn>,
+    shutdown: GracefulShutdown,
+}
+
+impl WebSocketEndpoint {
+    pub(crate) fn new(
+        registrar: &WebSocketControllerRegistrar,
+        setup: WebSocketSetup,
+        handlers: HashMap<WebSocketEvent, WebSocketCallFn>,
+        shutdown: GracefulShutdown,
+    ) -> Self {
+        tracing::debug!(
+            controller = registrar.controller_name,
+            path = registrar.path,
+            handlers = handlers.len(),
+            "WebSocket controller registered"
+        );
+
+        Self {
+            controller: setup.controller,
+            interceptors: setup.interceptors,
+            handlers,
+            shutdown,
+        }
+    }
+
+    /// Runs the interceptors and upgrades the connection.
+    pub(crate) async fn upgrade(self: Arc<Self>, req: Request) -> Response {
+        let (mut parts, _) = req.into_parts();
+
+        let upgrade = match WebSocketUpgrade::from_request_parts(&mut parts, &()).await {
+            Ok(upgrade) => upgrade,
+            Err(rejection) => {
+                return JsonResponse::status(rejection.status())
+                    .message(rejection.body_text())
+                    .into_response();
+            }
+        };
+
+        let (outgoing_tx, outgoing_rx) = mpsc::channel(OUTGOING_BUFFER_SIZE);
+        let ctx = WebSocketContext::new(parts, outgoing_tx);
+
+        for interceptor in &self.interceptors {
+            if let Err(response) = interceptor(ctx.clone()).await {
+                return response;
+            }
+        }
+
+        upgrade.on_upgrade(move |socket| self.serve(socket, ctx, outgoing_rx))
+    }
+
+    async fn dispatch(&self, event: WebSocketEvent, ctx: WebSocketContext) {
+        if let Some(call) = self.handlers.get(&event) {
+            call(Arc::clone(&self.controller), ctx).await;
+        }
+    }
+
+    /// Dispatches an event while writing the frames queued meanwhile, so a
+    /// handler waiting for room in the outgoing buffer can make progress.
+    /// Returns false when writing to the socket fails.
+    async fn dispatch_writing(
+        &self,
+        event: WebSocketEvent,
+        ctx: WebSocketContext,
+        socket: &mut WebSocket,
+        outgoing: &mut Receiver<Message>,
+        closing: &mut bool,
+    ) -> bool {
+        let dispatch = self.dispatch(event, ctx);
+        tokio::pin!(dispatch);
+
+        loop {
+            tokio::select! {
+                () = &mut dispatch => return true,
+
+                Some(message) = outgoing.recv() => {
+                    if !write(socket, message, closing).await {
+                        return false;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Dispatches incoming frames and writes queued outgoing frames until the
+    /// connection closes. Handlers are awaited one at a time, in frame order.
+    async fn serve(
+        self: Arc<Self>,
+        mut socket: WebSocket,
+        ctx: WebSocketContext,
+        mut outgoing: Receiver<Message>,
+    ) {
+        let mut closing = false;
+
+        let connected = self
+            .dispatch_writing(
+                WebSocketEvent::Connection,
+                ctx.clone(),
+                &mut socket,
+                &mut outgoing,
+                &mut closing,
+            )
+            .await;
+
+        let close_frame = loop {
+            if !connected {
+                break None;
+            }
+
+            tokio::select! {
+                incoming = socket.recv() => {
+                    let message = match incoming {
+                        Some(Ok(Message::Close(frame))) => break frame,
+                        Some(Ok(message)) => message,
+                        Some(Err(error)) => {
+                            tracing::debug!(error = %error, connection = %ctx.id(), "WebSocket receive failed");
+                            break None;
+                        }
+                        None => break None,
+                    };
+
+                    let event = match message {
+                        Message::Text(_) => WebSocketEvent::Text,
+                        Message::Binary(_) => WebSocketEvent::Binary,
+                        Message::Ping(_) => WebSocketEvent::Ping,
+                        Message::Pong(_) | Message::Close(_) => continue,
+                    };
+
+                    let ctx = ctx.with_message(message);
+
+                    if !self.dispatch_writing(event, ctx, &mut socket, &mut outgoing, &mut closing).await {
+                        break None;
+                    }
+                }
+
+                Some(message) = outgoing.recv() => {
+                    if !write(&mut socket, message, &mut closing).await {
+                        break None;
+                    }
+                }
+
+                _ = self.shutdown.triggered(), if !closing => {
+                    closing = true;
+
+                    let frame = CloseFrame {
+                        code: close_code::AWAY,
+                        reason: "Server is shutting down".into(),
+                    };
+
+                    if socket.send(Message::Close(Some(frame))).await.is_err() {
+                        break None;
+                    }
+                }
+            }
+        };
+
+        outgoing.close();
+
+        self.dispatch(
+            WebSocketEvent::Close,
+            ctx.with_message(Message::Close(close_frame)),
+        )
+        .await;
+    }
+}
+
+/// Writes a queued frame. Frames queued after a close frame are dropped, as
+/// nothing may follow it. Returns false when writing fails.
+async fn write(socket: &mut WebSocket, message: Message, closing: &mut bool) -> bool {
+    if *closing {
+        return true;
+    }
+
+    *closing = matches!(message, Message::Close(_));
+
+    socket.send(message).await.is_ok()
+}
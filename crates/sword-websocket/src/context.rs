@@ -0,0 +1,242 @@
+use crate::error::WebSocketError;
+
+use axum::{
+    extract::ws::{CloseFrame, Message, Utf8Bytes},
+    http::{Extensions, HeaderMap, Uri, request::Parts},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use std::{marker::PhantomData, sync::Arc};
+use tokio::sync::mpsc::{Sender, error::TrySendError};
+use uuid::Uuid;
+
+#[cfg(feature = "validation-validator")]
+use validator::Validate;
+
+/// The context passed to WebSocket interceptors and `#[on(...)]` handlers.
+///
+/// It holds the upgrade request parts, the frame that triggered the handler
+/// and a handle to send frames back to the client. Contexts are cheap to clone
+/// and can be moved into spawned tasks.
+#[derive(Clone)]
+pub struct WebSocketContext {
+    id: Uuid,
+    parts: Arc<Parts>,
+    outgoing: Sender<Message>,
+    message: Option<Message>,
+}
+
+impl WebSocketContext {
+    pub(crate) fn new(parts: Parts, outgoing: Sender<Message>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            parts: Arc::new(parts),
+            outgoing,
+            message: None,
+        }
+    }
+
+    pub(crate) fn with_message(&self, message: Message) -> Self {
+        Self {
+            message: Some(message),
+            ..self.clone()
+        }
+    }
+
+    /// Unique id of the connection.
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Headers of the upgrade request.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.parts.headers
+    }
+
+    /// Uri of the upgrade request.
+    pub fn uri(&self) -> &Uri {
+        &self.parts.uri
+    }
+
+    /// Extensions of the upgrade request, such as `ClientInfo` or `RequestId`.
+    pub fn http_extensions(&self) -> &Extensions {
+        &self.parts.extensions
+    }
+
+    /// The frame that triggered the handler. `None` in interceptors and
+    /// connection handlers.
+    pub fn message(&self) -> Option<&Message> {
+        self.message.as_ref()
+    }
+
+    /// The payload of a text frame.
+    pub fn text(&self) -> Option<&str> {
+        match &self.message {
+            Some(Message::Text(text)) => Some(text.as_str()),
+            _ => None,
+        }
+    }
+
+    /// The payload of a binary or ping frame.
+    pub fn bytes(&self) -> Option<&[u8]> {
+        match &self.message {
+            Some(Message::Binary(bytes) | Message::Ping(bytes)) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// The close frame sent by the client, in close handlers.
+    pub fn close_frame(&self) -> Option<&CloseFrame> {
+        match &self.message {
+            Some(Message::Close(frame)) => frame.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Deserializes the frame payload to the specified type.
+    ///
+    /// Text frames are decoded as JSON and binary frames as MessagePack.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handler was not triggered by a text or binary
+    /// frame, or if the payload cannot be decoded into `T`.
+    pub fn try_data<T: DeserializeOwned>(&self) -> Result<T, WebSocketError> {
+        match &self.message {
+            Some(Message::Text(text)) => Ok(serde_json::from_str(text)?),
+            Some(Message::Binary(bytes)) => Ok(rmp_serde::from_slice(bytes)?),
+            _ => Err(WebSocketError::NoData),
+        }
+    }
+
+    #[cfg(feature = "validation-validator")]
+    /// # Errors
+    ///
+    /// Returns an error if payload decoding fails or if the decoded value
+    /// does not satisfy its `validator` constraints.
+    pub fn try_validated_data<T>(&self) -> Result<T, WebSocketError>
+    where
+        T: DeserializeOwned + Validate,
+    {
+        let data: T = self.try_data()?;
+
+        data.validate()?;
+
+        Ok(data)
+    }
+
+    /// Returns a handle that sends `T` values to this client.
+    pub fn sender<T: Serialize>(&self) -> WebSocketSender<T> {
+        WebSocketSender {
+            outgoing: self.outgoing.clone(),
+            _message: PhantomData,
+        }
+    }
+
+    /// Starts the closing handshake with the given close code and reason.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection is already closed.
+    pub async fn close(&self, code: u16, reason: &str) -> Result<(), WebSocketError> {
+        self.sender::<()>().close(code, reason).await
+    }
+}
+
+/// A typed handle to send frames to one WebSocket client.
+///
+/// Frames are queued and written by the connection task in order, so the
+/// handle can be used from any task while the connection is open. The queue
+/// holds a bounded number of frames per connection: `send` waits while it is
+/// full and `try_send` fails with [`WebSocketError::Full`] instead.
+///
+/// ```rust,ignore
+/// #[on("connection")]
+/// async fn on_connection(&self, ctx: WebSocketContext) {
+///     let sender = ctx.sender::<PriceUpdate>();
+///     self.prices.subscribe(move |update| sender.try_send(&update).is_ok());
+/// }
+/// ```
+pub struct WebSocketSender<T> {
+    outgoing: Sender<Message>,
+    _message: PhantomData<fn(&T)>,
+}
+
+impl<T> Clone for WebSocketSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            outgoing: self.outgoing.clone(),
+            _message: PhantomData,
+        }
+    }
+}
+
+impl<T: Serialize> WebSocketSender<T> {
+    /// Sends the value as a JSON text frame, waiting while the outgoing
+    /// buffer is full.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails or the connection is closed.
+    pub async fn send(&self, value: &T) -> Result<(), WebSocketError> {
+        let text = serde_json::to_string(value)?;
+        self.send_raw(Message::Text(Utf8Bytes::from(text))).await
+    }
+
+    /// Sends the value as a JSON text frame without waiting.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails, the outgoing buffer is full
+    /// or the connection is closed.
+    pub fn try_send(&self, value: &T) -> Result<(), WebSocketError> {
+        let text = serde_json::to_string(value)?;
+
+        self.outgoing
+            .try_send(Message::Text(Utf8Bytes::from(text)))
+            .map_err(|error| match error {
+                TrySendError::Full(_) => WebSocketError::Full,
+                TrySendError::Closed(_) => WebSocketError::Closed,
+            })
+    }
+
+    /// Sends the value as a MessagePack binary frame, waiting while the
+    /// outgoing buffer is full.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails or the connection is closed.
+    pub async fn send_binary(&self, value: &T) -> Result<(), WebSocketError> {
+        let bytes = rmp_serde::to_vec_named(value)?;
+        self.send_raw(Message::Binary(bytes.into())).await
+    }
+
+    /// Sends a frame as is, waiting while the outgoing buffer is full.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection is closed.
+    pub async fn send_raw(&self, message: Message) -> Result<(), WebSocketError> {
+        self.outgoing
+            .send(message)
+            .await
+            .map_err(|_| WebSocketError::Closed)
+    }
+
+    /// Starts the closing handshake with the given close code and reason.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection is closed.
+    pub async fn close(&self, code: u16, reason: &str) -> Result<(), WebSocketError> {
+        self.send_raw(Message::Close(Some(CloseFrame {
+            code,
+            reason: Utf8Bytes::from(reason),
+        })))
+        .await
+    }
+
+    /// Whether the connection task has finished.
+    pub fn is_closed(&self) -> bool {
+        self.outgoing.is_closed()
+    }
+}
@@ -0,0 +1,78 @@
+use crate::context::WebSocketContext;
+
+use axum::response::Response;
+use std::any::{Any, TypeId};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use sword_core::{ControllerSpec, State};
+
+pub use sword_macros::on;
+
+/// Trait for providing plain WebSocket controller functionality.
+///
+/// This trait is implemented by types annotated with
+/// `#[controller(kind = Controller::WebSocket, path = "...")]`.
+pub trait WebSocketController: ControllerSpec {
+    fn path() -> &'static str;
+}
+
+/// Events a WebSocket controller can handle with `#[on("...")]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WebSocketEvent {
+    /// `#[on("connection")]`, called once the connection is upgraded.
+    Connection,
+
+    /// `#[on("text")]`, called for every text frame.
+    Text,
+
+    /// `#[on("binary")]`, called for every binary frame.
+    Binary,
+
+    /// `#[on("ping")]`, called for every ping frame. The pong is sent automatically.
+    Ping,
+
+    /// `#[on("close")]`, called once when the connection ends, for any reason.
+    Close,
+}
+
+pub(crate) type WebSocketCallFn =
+    fn(Arc<dyn Any + Send + Sync>, WebSocketContext) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+
+pub type UpgradeFuture = Pin<Box<dyn Future<Output = Result<(), Response>> + Send>>;
+
+/// A controller-level `OnUpgrade` interceptor, erased by the controller macro.
+pub type UpgradeInterceptor = Arc<dyn Fn(WebSocketContext) -> UpgradeFuture + Send + Sync>;
+
+/// Metadata for a WebSocket event handler registered via the `#[on]` attribute.
+pub struct WebSocketHandlerRegistrar {
+    /// TypeId of the controller for filtering during registration
+    pub controller_type_id: TypeId,
+
+    /// Event this handler responds to
+    pub event: WebSocketEvent,
+
+    /// Name of the handler method
+    pub method_name: &'static str,
+
+    /// Executes the handler with the controller instance.
+    pub call_fn: WebSocketCallFn,
+}
+
+/// The built controller and its interceptors, returned by the controller setup.
+pub struct WebSocketSetup {
+    pub controller: Arc<dyn Any + Send + Sync>,
+    pub interceptors: Vec<UpgradeInterceptor>,
+}
+
+/// Setup function that initializes a WebSocket controller at runtime.
+pub struct WebSocketControllerRegistrar {
+    pub controller_id: TypeId,
+    pub controller_name: &'static str,
+    pub path: &'static str,
+
+    pub setup_fn: fn(&State) -> WebSocketSetup,
+}
+
+inventory::collect!(WebSocketHandlerRegistrar);
+inventory::collect!(WebSocketControllerRegistrar);
@@ -0,0 +1,29 @@
+use thiserror::Error;
+
+#[cfg(feature = "validation-validator")]
+use validator::ValidationErrors;
+
+#[derive(Debug, Error)]
+pub enum WebSocketError {
+    #[error("WebSocket message has no data payload")]
+    NoData,
+
+    #[error("WebSocket connection is closed")]
+    Closed,
+
+    #[error("WebSocket outgoing buffer is full")]
+    Full,
+
+    #[error("WebSocket JSON Error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("WebSocket MessagePack decode Error: {0}")]
+    MsgPackDecode(#[from] rmp_serde::decode::Error),
+
+    #[error("WebSocket MessagePack encode Error: {0}")]
+    MsgPackEncode(#[from] rmp_serde::encode::Error),
+
+    #[cfg(feature = "validation-validator")]
+    #[error("WebSocket Validation Error: {0}")]
+    Validation(#[from] ValidationErrors),
+}
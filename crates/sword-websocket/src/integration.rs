@@ -0,0 +1,93 @@
+use crate::connection::WebSocketEndpoint;
+use crate::controller::{
+    WebSocketCallFn, WebSocketControllerRegistrar, WebSocketEvent, WebSocketHandlerRegistrar,
+};
+
+use axum::{Router, extract::Request, routing::get};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::Arc;
+use sword_core::{Config, Controller, ControllerRegistry, State, sword_error};
+use sword_web::{prelude::GracefulShutdown, router::WebRouterExtension};
+
+fn collect_handlers(
+    registrar: &WebSocketControllerRegistrar,
+) -> HashMap<WebSocketEvent, WebSocketCallFn> {
+    let mut handlers: HashMap<WebSocketEvent, &WebSocketHandlerRegistrar> = HashMap::new();
+
+    for handler in inventory::iter::<WebSocketHandlerRegistrar>()
+        .filter(|handler| handler.controller_type_id == registrar.controller_id)
+    {
+        if let Some(existing) = handlers.insert(handler.event, handler) {
+            sword_error! {
+                title: "Duplicate WebSocket event handler",
+                reason: format!("{:?} is handled by more than one method", handler.event),
+                context: {
+                    "controller" => registrar.controller_name,
+                    "handlers" => format!("{}, {}", existing.method_name, handler.method_name),
+                    "source" => "WebRouterExtension::apply_websocket_controllers",
+                },
+                hints: ["Each #[on(...)] event can only be handled once per controller"],
+            };
+        }
+    }
+
+    handlers
+        .into_iter()
+        .map(|(event, handler)| (event, handler.call_fn))
+        .collect()
+}
+
+fn apply_websocket_extension(
+    state: &State,
+    _: &Config,
+    mut router: Router<State>,
+    controller_registry: &ControllerRegistry,
+) -> Router<State> {
+    let controller_map = controller_registry.read();
+
+    let Some(controllers) = controller_map.get(&Controller::WebSocket) else {
+        return router;
+    };
+
+    let registrars: HashMap<TypeId, &WebSocketControllerRegistrar> =
+        inventory::iter::<WebSocketControllerRegistrar>()
+            .map(|registrar| (registrar.controller_id, registrar))
+            .collect();
+
+    let shutdown = state.get::<GracefulShutdown>().unwrap_or_default();
+
+    for controller_id in controllers {
+        let registrar = registrars.get(controller_id).copied().unwrap_or_else(|| {
+            sword_error! {
+                title: "Controller metadata not found",
+                reason: "No WebSocketControllerRegistrar entry was found for controller",
+                context: {
+                    "controller_id" => format!("{controller_id:?}"),
+                    "source" => "WebRouterExtension::apply_websocket_controllers",
+                },
+                hints: ["Verify #[controller(kind = Controller::WebSocket, path = \"...\")] is applied correctly"],
+            }
+        });
+
+        let endpoint = Arc::new(WebSocketEndpoint::new(
+            registrar,
+            (registrar.setup_fn)(state),
+            collect_handlers(registrar),
+            shutdown.clone(),
+        ));
+
+        router = router.route(
+            registrar.path,
+            get(move |req: Request| Arc::clone(&endpoint).upgrade(req)),
+        );
+    }
+
+    router
+}
+
+inventory::submit! {
+    WebRouterExtension {
+        apply: apply_websocket_extension,
+    }
+}
@@ -0,0 +1,42 @@
+use crate::context::WebSocketContext;
+use axum::response::IntoResponse;
+use sword_core::Interceptor;
+
+/// An interceptor that is called before a WebSocket connection is upgraded.
+///
+/// Returning an error rejects the handshake with the error response, so the
+/// connection never reaches the controller handlers.
+///
+/// ## Example
+/// ```rust,ignore
+/// use sword::prelude::*;
+///
+/// #[derive(Interceptor)]
+/// pub struct DeviceTokenInterceptor;
+///
+/// impl OnUpgrade for DeviceTokenInterceptor {
+///     type Error = JsonResponse;
+///
+///     async fn on_upgrade(&self, ctx: WebSocketContext) -> Result<(), Self::Error> {
+///         match ctx.headers().get("x-device-token") {
+///             Some(_) => Ok(()),
+///             None => Err(JsonResponse::Unauthorized()),
+///         }
+///     }
+/// }
+/// ```
+///
+/// ## Applying the Interceptor
+/// Apply it at controller level using `#[interceptor(...)]` on the struct
+/// annotated with `#[controller(kind = Controller::WebSocket, ...)]`.
+/// ```rust,ignore
+/// #[controller(kind = Controller::WebSocket, path = "/devices")]
+/// #[interceptor(DeviceTokenInterceptor)]
+/// pub struct DevicesController;
+/// ```
+#[allow(async_fn_in_trait)]
+pub trait OnUpgrade: Interceptor {
+    type Error: IntoResponse;
+
+    async fn on_upgrade(&self, ctx: WebSocketContext) -> Result<(), Self::Error>;
+}
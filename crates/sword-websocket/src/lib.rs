@@ -0,0 +1,23 @@
+mod connection;
+pub mod context;
+pub mod controller;
+pub mod error;
+mod integration;
+pub mod interceptor;
+
+pub mod prelude {
+    pub use crate::context::{WebSocketContext, WebSocketSender};
+    pub use crate::controller::{WebSocketController, WebSocketEvent, on};
+    pub use crate::error::WebSocketError;
+    pub use crate::interceptor::OnUpgrade;
+    pub use axum::extract::ws::{CloseFrame, Message, close_code};
+}
+
+#[doc(hidden)]
+pub mod internal {
+    pub use crate::controller::{
+        UpgradeFuture, UpgradeInterceptor, WebSocketController, WebSocketControllerRegistrar,
+        WebSocketEvent, WebSocketHandlerRegistrar, WebSocketSetup,
+    };
+    pub use axum::response::IntoResponse;
+}
@@ -1,5 +1,7 @@
 pub use sword_core::ApplicationConfig;
 
+// A single engine exists per application, so its size doesn't matter.
+#[allow(clippy::large_enum_variant)]
 pub enum ApplicationEngine {
     #[cfg(any(feature = "web", feature = "socketio"))]
     Web(sword_web::application::WebApplication),
@@ -95,6 +95,32 @@ impl Application {
         }
     }
 
+    #[cfg(all(feature = "testing", feature = "grpc"))]
+    pub(crate) fn grpc_engine(&self) -> &sword_grpc::application::GrpcApplication {
+        match &self.engine {
+            ApplicationEngine::Grpc(app) => app,
+
+            #[allow(unreachable_patterns)]
+            _ => unreachable!("gRPC engine requested from a web application"),
+        }
+    }
+
+    #[cfg(feature = "testing")]
+    pub(crate) fn state(&self) -> sword_core::State {
+        match &self.engine {
+            #[cfg(any(feature = "web", feature = "socketio"))]
+            ApplicationEngine::Web(app) => app.state.clone(),
+
+            #[cfg(feature = "grpc")]
+            ApplicationEngine::Grpc(app) => app.state.clone(),
+
+            #[allow(unreachable_patterns)]
+            _ => unreachable!(
+                "Invalid application engine configuration. Enable the appropriate feature flag to use the desired engine."
+            ),
+        }
+    }
+
     #[cfg(any(feature = "web", feature = "socketio"))]
     pub fn router(&self) -> axum::Router {
         #[cfg(any(feature = "web", feature = "socketio"))]
@@ -9,9 +9,45 @@ pub use sword_web::prelude as web;
 #[cfg(feature = "socketio")]
 pub use sword_socketio::prelude as socketio;
 
+#[cfg(feature = "websocket")]
+pub use sword_websocket::prelude as websocket;
+
 #[cfg(feature = "grpc")]
 pub use sword_grpc::prelude as grpc;
 
+#[cfg(feature = "metrics")]
+pub use sword_layers::metrics;
+
+#[cfg(feature = "opentelemetry")]
+pub use sword_layers::opentelemetry;
+
+#[cfg(feature = "openapi")]
+pub use sword_web::openapi;
+
+#[cfg(feature = "tls")]
+pub use sword_layers::tls;
+
+#[cfg(feature = "jwt")]
+pub use sword_layers::jwt;
+
+#[cfg(feature = "idempotency")]
+pub use sword_layers::idempotency;
+
+#[cfg(feature = "i18n")]
+pub use sword_web::i18n;
+
+#[cfg(feature = "sessions")]
+pub use sword_layers::sessions;
+
+#[cfg(feature = "csrf")]
+pub use sword_layers::csrf;
+
+#[cfg(feature = "rate-limit")]
+pub use sword_layers::rate_limit;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
 pub use application::*;
 pub use sword_core::Module;
 pub use sword_macros::main;
@@ -24,6 +60,9 @@ pub mod internal {
     #[cfg(feature = "socketio")]
     pub use sword_socketio::internal as socketio;
 
+    #[cfg(feature = "websocket")]
+    pub use sword_websocket::internal as websocket;
+
     #[cfg(feature = "grpc")]
     pub use sword_grpc::internal as grpc;
 
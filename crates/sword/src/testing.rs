@@ -0,0 +1,275 @@
+//! In-process test harness for Sword applications.
+//!
+//! A [`TestApp`] is built from inline TOML and modules, exactly like an application
+//! built with [`Application::from_config`](crate::Application::from_config), but it never binds a port:
+//!
+//! - web apps are driven through an in-memory HTTP client ([`TestApp::http`]),
+//! - Socket.IO namespaces through a polling client ([`TestApp::socketio`]),
+//! - gRPC controllers through an in-process tonic channel ([`TestApp::grpc_channel`]).
+//!
+//! Every `TestApp` owns its own state and records tracing events into its own
+//! [`TracingCapture`]. Events are captured while building the app and while handling
+//! requests; work spawned onto separate tasks (e.g. Socket.IO event handlers) is
+//! not attributed to the capture.
+
+use crate::application::ApplicationBuilder;
+
+use axum::{extract::Request as AxumRequest, response::IntoResponse, routing::Route};
+use std::{convert::Infallible, sync::Arc};
+use sword_core::{DependencyInjectionError, Module, Provider, sword_error};
+use sword_layers::tracing::{TracingConfig, TracingSubscriber};
+use tower::{Layer, Service};
+use tracing::dispatcher;
+
+pub use sword_core::{Config, State};
+pub use sword_layers::tracing::{CapturedEvent, TracingCapture};
+
+#[cfg(any(feature = "web", feature = "socketio"))]
+pub use axum_test::{TestRequest, TestResponse, TestServer};
+
+#[cfg(feature = "socketio")]
+pub use sword_socketio::testing::{SocketIoTestClient, SocketIoTestEvent};
+
+#[cfg(feature = "grpc")]
+pub use tonic::transport::Channel;
+
+/// Defaults applied before the user TOML. The global stdout subscriber is left
+/// uninstalled so test output goes to the per-app [`TracingCapture`] instead, and
+/// no OTLP exporter is started unless the test enables it explicitly.
+const BASE_TOML: &str = r#"
+[tracing]
+enabled = false
+use-env-filter = false
+
+[opentelemetry]
+enabled = false
+"#;
+
+pub struct TestAppBuilder {
+    builder: ApplicationBuilder,
+    tracing: TracingCapture,
+}
+
+impl TestAppBuilder {
+    fn new(toml: &str) -> Self {
+        let config = Config::builder()
+            .add_toml_str(BASE_TOML)
+            .add_toml_str(toml)
+            .build()
+            .unwrap_or_else(|err| {
+                sword_error! {
+                    title: "Failed to load test configuration",
+                    reason: err,
+                    source: "TestApp::from_toml",
+                    hints: ["Ensure the inline configuration is valid TOML"],
+                }
+            });
+
+        let filter = TracingSubscriber::from(config.get_or_default::<TracingConfig>()).env_filter();
+        let tracing = TracingCapture::new(filter);
+
+        let builder = dispatcher::with_default(tracing.dispatch(), || {
+            ApplicationBuilder::from_config(config)
+        });
+
+        Self { builder, tracing }
+    }
+
+    /// See [`ApplicationBuilder::with_module`].
+    pub fn with_module<M: Module>(self) -> Self {
+        let Self { builder, tracing } = self;
+        let builder = dispatcher::with_default(tracing.dispatch(), || builder.with_module::<M>());
+
+        Self { builder, tracing }
+    }
+
+    /// See [`ApplicationBuilder::with_provider`].
+    pub fn with_provider<T>(mut self, provider: T) -> Self
+    where
+        T: Provider + 'static,
+    {
+        self.builder = self.builder.with_provider(provider);
+        self
+    }
+
+    /// See [`ApplicationBuilder::with_layer`].
+    pub fn with_layer<L>(mut self, layer: L) -> Self
+    where
+        L: Layer<Route> + Clone + Send + Sync + 'static,
+        L::Service: Service<AxumRequest> + Clone + Send + Sync + 'static,
+        <L::Service as Service<AxumRequest>>::Response: IntoResponse + 'static,
+        <L::Service as Service<AxumRequest>>::Error: Into<Infallible> + 'static,
+        <L::Service as Service<AxumRequest>>::Future: Send + 'static,
+    {
+        self.builder = self.builder.with_layer(layer);
+        self
+    }
+
+    /// Builds the application and wires up the in-process clients.
+    ///
+    /// gRPC apps spawn their in-process server, so this must be called from within
+    /// a Tokio runtime (e.g. a `#[tokio::test]`).
+    pub fn build(self) -> TestApp {
+        let Self { builder, tracing } = self;
+
+        let app = Arc::new(dispatcher::with_default(tracing.dispatch(), || {
+            builder.build()
+        }));
+        let state = app.state();
+
+        #[cfg(any(feature = "web", feature = "socketio"))]
+        let router = dispatcher::with_default(tracing.dispatch(), || app.router()).layer(
+            sword_layers::tracing::DispatchLayer::new(tracing.dispatch().clone()),
+        );
+
+        #[cfg(any(feature = "web", feature = "socketio"))]
+        let http = TestServer::new(router.clone()).unwrap_or_else(|err| {
+            sword_error! {
+                title: "Failed to create in-memory HTTP client",
+                reason: err,
+                source: "TestAppBuilder::build",
+            }
+        });
+
+        #[cfg(feature = "grpc")]
+        let (grpc_channel, grpc_server) = {
+            let (channel, incoming) = sword_grpc::testing::in_process_channel();
+            let dispatch = tracing.dispatch().clone();
+            let app = Arc::clone(&app);
+
+            let server = tokio::spawn(async move {
+                app.grpc_engine().serve_in_process(incoming, dispatch).await;
+            });
+
+            (channel, server)
+        };
+
+        TestApp {
+            config: app.config.clone(),
+            state,
+            tracing,
+            #[cfg(any(feature = "web", feature = "socketio"))]
+            router,
+            #[cfg(any(feature = "web", feature = "socketio"))]
+            http,
+            #[cfg(feature = "grpc")]
+            grpc_channel,
+            #[cfg(feature = "grpc")]
+            grpc_server,
+        }
+    }
+}
+
+/// An application instance driven entirely in memory. See the [module docs](self).
+pub struct TestApp {
+    config: Config,
+    state: State,
+    tracing: TracingCapture,
+
+    #[cfg(any(feature = "web", feature = "socketio"))]
+    router: axum::Router,
+
+    #[cfg(any(feature = "web", feature = "socketio"))]
+    http: TestServer,
+
+    #[cfg(feature = "grpc")]
+    grpc_channel: Channel,
+
+    #[cfg(feature = "grpc")]
+    grpc_server: tokio::task::JoinHandle<()>,
+}
+
+impl TestApp {
+    /// Starts a test application with the default configuration.
+    pub fn builder() -> TestAppBuilder {
+        TestAppBuilder::new("")
+    }
+
+    /// Starts a test application configured from an inline TOML document.
+    ///
+    /// Sections that are not present use the same defaults as a regular application.
+    pub fn from_toml(toml: &str) -> TestAppBuilder {
+        TestAppBuilder::new(toml)
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// The state owned by this application instance.
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Shorthand for `self.state().get::<T>()`.
+    pub fn get<T>(&self) -> Result<T, DependencyInjectionError>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.state.get::<T>()
+    }
+
+    /// Tracing events recorded by this application.
+    pub fn tracing(&self) -> &TracingCapture {
+        &self.tracing
+    }
+
+    /// In-memory HTTP client backed by the application router.
+    #[cfg(any(feature = "web", feature = "socketio"))]
+    pub fn http(&self) -> &TestServer {
+        &self.http
+    }
+
+    /// The fully built application router, with tracing capture applied.
+    #[cfg(any(feature = "web", feature = "socketio"))]
+    pub fn router(&self) -> axum::Router {
+        self.router.clone()
+    }
+
+    /// Connects a Socket.IO client to `namespace`.
+    #[cfg(feature = "socketio")]
+    pub async fn socketio(&self, namespace: &str) -> SocketIoTestClient {
+        SocketIoTestClient::connect(self.router(), &self.socketio_path(), namespace).await
+    }
+
+    /// Connects a Socket.IO client to `namespace`, sending `auth` in the connect packet.
+    #[cfg(feature = "socketio")]
+    pub async fn socketio_with_auth(
+        &self,
+        namespace: &str,
+        auth: serde_json::Value,
+    ) -> SocketIoTestClient {
+        SocketIoTestClient::connect_with_auth(
+            self.router(),
+            &self.socketio_path(),
+            namespace,
+            Some(auth),
+        )
+        .await
+    }
+
+    #[cfg(feature = "socketio")]
+    fn socketio_path(&self) -> String {
+        use sword_socketio::prelude::SocketIoServerConfig;
+
+        self.config
+            .get_or_default::<SocketIoServerConfig>()
+            .req_path
+            .unwrap_or_else(|| "/socket.io".to_owned())
+    }
+
+    /// A tonic channel connected in-process to the application's gRPC controllers.
+    ///
+    /// Pass it to any generated client, e.g. `UserServiceClient::new(app.grpc_channel())`.
+    #[cfg(feature = "grpc")]
+    pub fn grpc_channel(&self) -> Channel {
+        self.grpc_channel.clone()
+    }
+}
+
+#[cfg(feature = "grpc")]
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        self.grpc_server.abort();
+    }
+}
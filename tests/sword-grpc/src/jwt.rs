@@ -0,0 +1,142 @@
+use jsonwebtoken::{EncodingKey, Header, encode};
+use serde_json::json;
+use std::net::TcpListener as StdTcpListener;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sword::grpc::*;
+use sword::prelude::*;
+use sword::testing::Config;
+use sword_grpc::prelude::{GrpcResponse, GrpcResult};
+use tokio::net::TcpStream;
+use tokio::time::{Duration, sleep};
+use tonic::metadata::MetadataValue;
+use tonic::{Request, Status};
+
+use crate::interceptors::proto::secure_service_client::SecureServiceClient;
+use crate::interceptors::proto::secure_service_server::{SecureService, SecureServiceServer};
+use crate::interceptors::proto::{PingReply, PingRequest};
+
+#[controller(kind = Controller::Grpc, service = SecureServiceServer)]
+#[interceptor(JwtAuth)]
+struct JwtController;
+
+#[sword::grpc::async_trait]
+impl SecureService for JwtController {
+    async fn ping(&self, req: Request<PingRequest>) -> GrpcResult<PingReply> {
+        let subject = req
+            .extensions()
+            .get::<JwtClaims>()
+            .and_then(|claims| claims.subject().map(str::to_string))
+            .ok_or_else(|| Status::internal("missing claims"))?;
+
+        Ok(GrpcResponse::message(PingReply {
+            message: format!("pong: {subject}"),
+        }))
+    }
+}
+
+struct JwtGrpcModule;
+
+impl Module for JwtGrpcModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<JwtController>();
+    }
+}
+
+fn ping(authorization: Option<String>) -> Request<PingRequest> {
+    let mut request = Request::new(PingRequest {
+        message: "hello".to_string(),
+    });
+
+    if let Some(authorization) = authorization {
+        request.metadata_mut().insert(
+            "authorization",
+            MetadataValue::try_from(authorization).unwrap(),
+        );
+    }
+
+    request
+}
+
+#[tokio::test]
+async fn jwt_auth_verifies_bearer_metadata() {
+    let port = StdTcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let config = Config::builder()
+        .add_toml_str(&format!(
+            r#"
+            [tracing]
+            enabled = false
+
+            [grpc]
+            host = "127.0.0.1"
+            port = {port}
+            body-limit = {{ max-decoding-message-size = "2 MiB", max-encoding-message-size = "2 MiB" }}
+
+            [auth.jwt]
+            secret = "test-secret"
+            "#
+        ))
+        .build()
+        .unwrap();
+
+    let app = Application::from_config(config)
+        .with_module::<JwtGrpcModule>()
+        .build();
+
+    let server = tokio::spawn(async move { app.run().await });
+
+    for _ in 0..100 {
+        if TcpStream::connect(("127.0.0.1", port)).await.is_ok() {
+            break;
+        }
+
+        sleep(Duration::from_millis(20)).await;
+    }
+
+    let mut client = SecureServiceClient::connect(format!("http://127.0.0.1:{port}"))
+        .await
+        .expect("client must connect");
+
+    let exp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        + 300;
+
+    let token = encode(
+        &Header::default(),
+        &json!({ "sub": "ana", "exp": exp }),
+        &EncodingKey::from_secret(b"test-secret"),
+    )
+    .unwrap();
+
+    let reply = client
+        .ping(ping(Some(format!("Bearer {token}"))))
+        .await
+        .expect("valid token must be accepted")
+        .into_inner();
+
+    assert_eq!(reply.message, "pong: ana");
+
+    let status = client
+        .ping(ping(None))
+        .await
+        .expect_err("missing token must be rejected");
+
+    assert_eq!(status.code(), tonic::Code::Unauthenticated);
+    assert_eq!(status.message(), "Missing bearer token");
+
+    let status = client
+        .ping(ping(Some("Bearer not-a-token".to_string())))
+        .await
+        .expect_err("invalid token must be rejected");
+
+    assert_eq!(status.code(), tonic::Code::Unauthenticated);
+    assert_eq!(status.message(), "Invalid token");
+
+    server.abort();
+}
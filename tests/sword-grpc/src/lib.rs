@@ -6,3 +6,12 @@ mod users;
 
 #[cfg(test)]
 mod errors;
+
+#[cfg(test)]
+mod jwt;
+
+#[cfg(test)]
+mod rate_limit;
+
+#[cfg(test)]
+mod tls;
@@ -0,0 +1,120 @@
+use std::net::TcpListener as StdTcpListener;
+use sword::grpc::*;
+use sword::prelude::*;
+use sword::testing::Config;
+use sword_grpc::prelude::{GrpcResponse, GrpcResult};
+use tokio::net::TcpStream;
+use tokio::time::{Duration, sleep};
+use tonic::metadata::MetadataValue;
+use tonic::{Code, Request};
+
+use crate::interceptors::proto::secure_service_client::SecureServiceClient;
+use crate::interceptors::proto::secure_service_server::{SecureService, SecureServiceServer};
+use crate::interceptors::proto::{PingReply, PingRequest};
+
+#[controller(kind = Controller::Grpc, service = SecureServiceServer)]
+#[interceptor(RateLimiter, config = "ping")]
+struct RateLimitedController;
+
+#[sword::grpc::async_trait]
+impl SecureService for RateLimitedController {
+    async fn ping(&self, req: Request<PingRequest>) -> GrpcResult<PingReply> {
+        Ok(GrpcResponse::message(PingReply {
+            message: format!("pong: {}", req.into_inner().message),
+        }))
+    }
+}
+
+struct RateLimitGrpcModule;
+
+impl Module for RateLimitGrpcModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<RateLimitedController>();
+    }
+}
+
+fn ping(client: &'static str) -> Request<PingRequest> {
+    let mut request = Request::new(PingRequest {
+        message: "hello".to_string(),
+    });
+
+    request
+        .metadata_mut()
+        .insert("x-client", MetadataValue::from_static(client));
+
+    request
+}
+
+#[tokio::test]
+async fn rate_limiter_rejects_calls_with_resource_exhausted() {
+    let port = StdTcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let config = Config::builder()
+        .add_toml_str(&format!(
+            r#"
+            [tracing]
+            enabled = false
+
+            [grpc]
+            host = "127.0.0.1"
+            port = {port}
+            body-limit = {{ max-decoding-message-size = "2 MiB", max-encoding-message-size = "2 MiB" }}
+
+            [rate-limit]
+            enabled = true
+
+            [rate-limit.policies.ping]
+            limit = 2
+            window = "1m"
+            key = "header:x-client"
+            "#
+        ))
+        .build()
+        .unwrap();
+
+    let app = Application::from_config(config)
+        .with_module::<RateLimitGrpcModule>()
+        .build();
+
+    let server = tokio::spawn(async move { app.run().await });
+
+    for _ in 0..100 {
+        if TcpStream::connect(("127.0.0.1", port)).await.is_ok() {
+            break;
+        }
+
+        sleep(Duration::from_millis(20)).await;
+    }
+
+    let mut client = SecureServiceClient::connect(format!("http://127.0.0.1:{port}"))
+        .await
+        .expect("client must connect");
+
+    for _ in 0..2 {
+        client
+            .ping(ping("a"))
+            .await
+            .expect("calls within the limit must be accepted");
+    }
+
+    let status = client
+        .ping(ping("a"))
+        .await
+        .expect_err("calls over the limit must be rejected");
+
+    assert_eq!(status.code(), Code::ResourceExhausted);
+    assert_eq!(status.message(), "Too many requests");
+    assert_eq!(status.metadata().get("ratelimit-remaining").unwrap(), "0");
+    assert_eq!(status.metadata().get("retry-after").unwrap(), "30");
+
+    client
+        .ping(ping("b"))
+        .await
+        .expect("other keys have their own quota");
+
+    server.abort();
+}
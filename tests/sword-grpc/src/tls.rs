@@ -0,0 +1,285 @@
+use hyper_util::rt::TokioIo;
+use rcgen::{BasicConstraints, CertificateParams, CertifiedIssuer, DnType, IsCa, KeyPair};
+use std::{net::TcpListener as StdTcpListener, path::PathBuf, sync::Arc};
+use sword::grpc::*;
+use sword::prelude::*;
+use sword::testing::Config;
+use sword_grpc::prelude::{GrpcResponse, GrpcResult};
+use tokio::net::TcpStream;
+use tokio::time::{Duration, sleep};
+use tokio_rustls::{
+    TlsConnector,
+    rustls::{
+        ClientConfig, RootCertStore,
+        crypto::ring,
+        pki_types::{CertificateDer, PrivateKeyDer, ServerName, pem::PemObject},
+    },
+};
+use tonic::transport::{Channel, Endpoint, Uri};
+use tonic::{Request, Status};
+
+use crate::interceptors::proto::secure_service_client::SecureServiceClient;
+use crate::interceptors::proto::secure_service_server::{SecureService, SecureServiceServer};
+use crate::interceptors::proto::{PingReply, PingRequest};
+
+#[derive(Interceptor)]
+struct RequireWorkerCertificate;
+
+impl OnRequest for RequireWorkerCertificate {
+    async fn on_request(&self, req: Request<()>) -> GrpcInterceptorResult {
+        let common_name = req
+            .extensions()
+            .get::<PeerIdentity>()
+            .and_then(PeerIdentity::common_name);
+
+        match common_name {
+            Some("worker") => Ok(req),
+            _ => Err(Status::permission_denied("unknown client certificate")),
+        }
+    }
+}
+
+#[controller(kind = Controller::Grpc, service = SecureServiceServer)]
+#[interceptor(RequireWorkerCertificate)]
+struct TlsController;
+
+#[sword::grpc::async_trait]
+impl SecureService for TlsController {
+    async fn ping(&self, req: Request<PingRequest>) -> GrpcResult<PingReply> {
+        let peer = req.remote_addr().is_some();
+        let identity = req.extensions().get::<PeerIdentity>().cloned();
+
+        Ok(GrpcResponse::message(PingReply {
+            message: format!(
+                "pong: {} {peer}",
+                identity
+                    .as_ref()
+                    .and_then(PeerIdentity::common_name)
+                    .unwrap_or_default()
+            ),
+        }))
+    }
+}
+
+struct TlsGrpcModule;
+
+impl Module for TlsGrpcModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<TlsController>();
+    }
+}
+
+struct Pki {
+    dir: PathBuf,
+    ca: CertifiedIssuer<'static, KeyPair>,
+}
+
+impl Pki {
+    fn new(name: &str) -> Self {
+        let dir =
+            std::env::temp_dir().join(format!("sword-grpc-tls-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+
+        let ca = CertifiedIssuer::self_signed(params, KeyPair::generate().unwrap()).unwrap();
+
+        Self { dir, ca }
+    }
+
+    fn issue(&self, common_name: &str, names: &[&str]) -> (String, String) {
+        let names = names.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let mut params = CertificateParams::new(names).unwrap();
+        params
+            .distinguished_name
+            .push(DnType::CommonName, common_name);
+
+        let key = KeyPair::generate().unwrap();
+        let certificate = params.signed_by(&key, &self.ca).unwrap();
+
+        (certificate.pem(), key.serialize_pem())
+    }
+
+    fn write(&self, name: &str, contents: &str) -> String {
+        let path = self.dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path.display().to_string()
+    }
+
+    fn client_config(&self, client: Option<(String, String)>) -> ClientConfig {
+        let mut roots = RootCertStore::empty();
+        roots.add(self.ca.der().clone()).unwrap();
+
+        let builder = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots);
+
+        let mut config = match client {
+            Some((cert, key)) => builder
+                .with_client_auth_cert(
+                    CertificateDer::pem_slice_iter(cert.as_bytes())
+                        .collect::<Result<_, _>>()
+                        .unwrap(),
+                    PrivateKeyDer::from_pem_slice(key.as_bytes()).unwrap(),
+                )
+                .unwrap(),
+            None => builder.with_no_client_auth(),
+        };
+
+        config.alpn_protocols = vec![b"h2".to_vec()];
+        config
+    }
+}
+
+impl Drop for Pki {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// A channel that performs the TLS handshake itself, with an optional client certificate.
+fn channel(port: u16, config: ClientConfig) -> Channel {
+    let connector = TlsConnector::from(Arc::new(config));
+
+    let connect = tower::service_fn(move |_: Uri| {
+        let connector = connector.clone();
+
+        async move {
+            let stream = TcpStream::connect(("127.0.0.1", port)).await?;
+            let stream = connector
+                .connect(ServerName::try_from("localhost").unwrap(), stream)
+                .await?;
+
+            Ok::<_, std::io::Error>(TokioIo::new(stream))
+        }
+    });
+
+    Endpoint::from_shared(format!("https://localhost:{port}"))
+        .unwrap()
+        .connect_with_connector_lazy(connect)
+}
+
+/// Starts a gRPC server with the given `grpc.tls` table and waits for its port.
+async fn start(tls: &str) -> (u16, tokio::task::JoinHandle<()>) {
+    let port = StdTcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let config = Config::builder()
+        .add_toml_str(&format!(
+            r#"
+            [tracing]
+            enabled = false
+
+            [grpc]
+            host = "127.0.0.1"
+            port = {port}
+            body-limit = {{ max-decoding-message-size = "2 MiB", max-encoding-message-size = "2 MiB" }}
+            tls = {tls}
+            "#
+        ))
+        .build()
+        .unwrap();
+
+    let app = Application::from_config(config)
+        .with_module::<TlsGrpcModule>()
+        .build();
+
+    let server = tokio::spawn(async move { app.run().await });
+
+    for _ in 0..100 {
+        if TcpStream::connect(("127.0.0.1", port)).await.is_ok() {
+            break;
+        }
+
+        sleep(Duration::from_millis(20)).await;
+    }
+
+    (port, server)
+}
+
+#[tokio::test]
+async fn grpc_tls_exposes_the_client_identity_to_interceptors() {
+    let pki = Pki::new("identity");
+    let (cert, key) = pki.issue("server", &["localhost"]);
+    let cert = pki.write("server.pem", &cert);
+    let key = pki.write("server.key", &key);
+    let ca = pki.write("ca.pem", &pki.ca.pem());
+
+    let (port, server) = start(&format!(
+        r#"{{ enabled = true, cert = "{cert}", key = "{key}", client-ca = "{ca}", client-auth = "optional" }}"#
+    ))
+    .await;
+
+    let ping = || {
+        Request::new(PingRequest {
+            message: "hello".to_string(),
+        })
+    };
+
+    let worker = pki.client_config(Some(pki.issue("worker", &[])));
+    let reply = SecureServiceClient::new(channel(port, worker))
+        .ping(ping())
+        .await
+        .expect("worker certificate must be accepted")
+        .into_inner();
+
+    assert_eq!(reply.message, "pong: worker true");
+
+    let anonymous = pki.client_config(None);
+    let status = SecureServiceClient::new(channel(port, anonymous))
+        .ping(ping())
+        .await
+        .expect_err("anonymous client must be rejected");
+
+    assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn grpc_certificates_are_reloaded_when_files_change() {
+    let pki = Pki::new("reload");
+    let (cert, key) = pki.issue("first", &["localhost"]);
+    let cert = pki.write("server.pem", &cert);
+    let key = pki.write("server.key", &key);
+
+    let (port, server) = start(&format!(
+        r#"{{ enabled = true, cert = "{cert}", key = "{key}", reload-interval = "50ms" }}"#
+    ))
+    .await;
+
+    let served_common_name = || async {
+        let stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        let stream = TlsConnector::from(Arc::new(pki.client_config(None)))
+            .connect(ServerName::try_from("localhost").unwrap(), stream)
+            .await
+            .unwrap();
+
+        let certificates = stream.get_ref().1.peer_certificates().unwrap();
+
+        PeerIdentity::from_certificates(certificates)
+            .and_then(|identity| identity.common_name().map(ToString::to_string))
+    };
+
+    assert_eq!(served_common_name().await.as_deref(), Some("first"));
+
+    let (cert, key) = pki.issue("second", &["localhost"]);
+    pki.write("server.pem", &cert);
+    pki.write("server.key", &key);
+
+    for _ in 0..100 {
+        if served_common_name().await.as_deref() == Some("second") {
+            server.abort();
+            return;
+        }
+
+        sleep(Duration::from_millis(20)).await;
+    }
+
+    panic!("the rotated certificate was never served");
+}
@@ -196,3 +196,63 @@ async fn stream_users_receives_items() {
 
     server.abort();
 }
+
+#[tokio::test]
+#[serial]
+async fn calls_are_recorded_in_metrics() {
+    let server = start_server_for_test().await;
+
+    let mut client =
+        proto::user_service_client::UserServiceClient::connect("http://127.0.0.1:50051")
+            .await
+            .expect("client must connect");
+
+    client
+        .list_users(Request::new(ListUsersRequest {}))
+        .await
+        .expect("list_users should succeed");
+
+    client
+        .get_user(Request::new(GetUserRequest {
+            id: "does-not-exist".to_string(),
+        }))
+        .await
+        .expect_err("request should fail with not found");
+
+    let body = sword::metrics::SwordMetrics::global().render();
+
+    assert!(body.contains(
+        r#"grpc_requests_total{code="OK",method="ListUsers",service="users.UserService"}"#
+    ));
+    assert!(body.contains(
+        r#"grpc_requests_total{code="NOT_FOUND",method="GetUser",service="users.UserService"}"#
+    ));
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn test_app_serves_controllers_in_process() {
+    let app = sword::testing::TestApp::builder()
+        .with_module::<GrpcModule>()
+        .build();
+
+    let mut client = proto::user_service_client::UserServiceClient::new(app.grpc_channel());
+
+    let response = client
+        .list_users(Request::new(ListUsersRequest {}))
+        .await
+        .expect("list_users should succeed")
+        .into_inner();
+
+    assert_eq!(response.users.len(), 2);
+
+    let status = client
+        .get_user(Request::new(GetUserRequest {
+            id: "does-not-exist".to_string(),
+        }))
+        .await
+        .expect_err("request should fail with not found");
+
+    assert_eq!(status.code(), tonic::Code::NotFound);
+}
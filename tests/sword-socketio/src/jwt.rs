@@ -0,0 +1,65 @@
+use jsonwebtoken::{EncodingKey, Header, encode};
+use serde_json::json;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sword::prelude::*;
+use sword::socketio::*;
+use sword::testing::TestApp;
+
+#[controller(kind = Controller::SocketIo, namespace = "/secure")]
+#[interceptor(JwtAuth)]
+struct SecureSocketController;
+
+impl SecureSocketController {
+    #[on("whoami")]
+    async fn on_whoami(&self, socket: SocketContext) {
+        let subject = socket
+            .extensions()
+            .get::<JwtClaims>()
+            .and_then(|claims| claims.subject().map(str::to_string));
+
+        socket.ack(&subject).ok();
+    }
+}
+
+struct SecureSocketModule;
+
+impl Module for SecureSocketModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<SecureSocketController>();
+    }
+}
+
+#[tokio::test]
+async fn jwt_auth_verifies_the_handshake_token() {
+    let app = TestApp::from_toml(
+        r#"
+        [auth.jwt]
+        secret = "test-secret"
+        "#,
+    )
+    .with_module::<SecureSocketModule>()
+    .build();
+
+    let exp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        + 300;
+
+    let token = encode(
+        &Header::default(),
+        &json!({ "sub": "ana", "exp": exp }),
+        &EncodingKey::from_secret(b"test-secret"),
+    )
+    .unwrap();
+
+    let mut client = app
+        .socketio_with_auth("/secure", json!({ "token": token }))
+        .await;
+
+    let ack = client.emit_with_ack("whoami", ()).await;
+
+    assert_eq!(ack, json!("ana"));
+
+    client.disconnect().await;
+}
@@ -1,2 +1,11 @@
 #[cfg(test)]
 mod cors;
+
+#[cfg(test)]
+mod jwt;
+
+#[cfg(test)]
+mod metrics;
+
+#[cfg(test)]
+mod testing;
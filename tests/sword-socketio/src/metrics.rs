@@ -0,0 +1,39 @@
+use axum_test::TestServer;
+use sword::prelude::*;
+use sword::socketio::*;
+
+#[controller(kind = Controller::SocketIo, namespace = "/metrics-socket")]
+struct MetricsSocketController;
+
+impl MetricsSocketController {
+    #[on("ping")]
+    async fn on_ping(&self, socket: SocketContext) {
+        socket.ack("pong").ok();
+    }
+}
+
+struct MetricsSocketModule;
+
+impl Module for MetricsSocketModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<MetricsSocketController>();
+    }
+}
+
+#[tokio::test]
+async fn metrics_expose_connected_sockets_per_namespace() {
+    let app = Application::builder()
+        .with_module::<MetricsSocketModule>()
+        .build();
+
+    let test = TestServer::new(app.router()).unwrap();
+
+    let response = test.get("/metrics").await;
+    let body = response.text();
+
+    assert_eq!(response.status_code(), 200);
+    assert!(
+        body.contains(r#"socketio_connected_sockets{namespace="/metrics-socket"} 0"#),
+        "{body}"
+    );
+}
@@ -0,0 +1,58 @@
+use serde_json::{Value, json};
+use sword::prelude::*;
+use sword::socketio::*;
+use sword::testing::TestApp;
+
+#[controller(kind = Controller::SocketIo, namespace = "/echo")]
+struct EchoController;
+
+impl EchoController {
+    #[on("echo")]
+    async fn on_echo(&self, socket: SocketContext) {
+        let data = socket.try_data::<Value>().unwrap_or_default();
+        socket.ack(&data).ok();
+    }
+
+    #[on("shout")]
+    async fn on_shout(&self, socket: SocketContext) {
+        let data = socket.try_data::<String>().unwrap_or_default();
+        socket.socket.emit("shouted", &data.to_uppercase()).ok();
+    }
+}
+
+struct EchoModule;
+
+impl Module for EchoModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<EchoController>();
+    }
+}
+
+fn echo_app() -> TestApp {
+    TestApp::builder().with_module::<EchoModule>().build()
+}
+
+#[tokio::test]
+async fn test_client_receives_acks() {
+    let app = echo_app();
+    let mut client = app.socketio("/echo").await;
+
+    let ack = client.emit_with_ack("echo", json!({ "n": 1 })).await;
+
+    assert_eq!(ack, json!({ "n": 1 }));
+
+    client.disconnect().await;
+}
+
+#[tokio::test]
+async fn test_client_receives_server_events() {
+    let app = echo_app();
+    let mut client = app.socketio("/echo").await;
+
+    client.emit("shout", "hello").await;
+
+    let event = client.recv().await;
+
+    assert_eq!(event.event, "shouted");
+    assert_eq!(event.data(), &json!("HELLO"));
+}
@@ -0,0 +1,250 @@
+use serde::Deserialize;
+use serde_json::json;
+use sword::prelude::*;
+use sword::testing::TestApp;
+use sword::web::*;
+
+use crate::test_app;
+
+#[derive(Deserialize)]
+struct CommentForm {
+    text: String,
+}
+
+#[controller(kind = Controller::Web, path = "/comments")]
+struct CommentsController;
+
+impl CommentsController {
+    #[get("/form")]
+    async fn form(&self, req: Request) -> WebResult {
+        Ok(JsonResponse::Ok().data(json!({ "csrf": req.csrf_token()? })))
+    }
+
+    #[post("/")]
+    async fn create(&self, req: Request) -> WebResult {
+        let form = req.body::<CommentForm>()?;
+        Ok(JsonResponse::Created().data(form.text))
+    }
+
+    #[delete("/{id}")]
+    async fn remove(&self) -> WebResult {
+        Ok(JsonResponse::Ok())
+    }
+
+    #[post("/protected")]
+    #[interceptor(CsrfProtection)]
+    async fn protected(&self) -> WebResult {
+        Ok(JsonResponse::Ok())
+    }
+}
+
+#[controller(kind = Controller::Web, path = "/webhooks")]
+struct WebhooksController;
+
+impl WebhooksController {
+    #[post("/github")]
+    async fn github(&self) -> WebResult {
+        Ok(JsonResponse::Ok())
+    }
+}
+
+struct CsrfModule;
+
+impl Module for CsrfModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<CommentsController>();
+        controllers.register::<WebhooksController>();
+    }
+}
+
+fn csrf_app(config: &str) -> TestApp {
+    test_app(config).with_module::<CsrfModule>().build()
+}
+
+fn message(response: &axum_test::TestResponse) -> String {
+    response.json::<JsonResponseBody>().message.to_string()
+}
+
+/// The `name=value` pair of the cookie named `name` set by a response.
+fn cookie(response: &axum_test::TestResponse, name: &str) -> String {
+    response
+        .iter_headers_by_name("set-cookie")
+        .map(|value| {
+            value
+                .to_str()
+                .unwrap()
+                .split(';')
+                .next()
+                .unwrap()
+                .to_string()
+        })
+        .find(|cookie| cookie.starts_with(&format!("{name}=")))
+        .unwrap()
+}
+
+fn token(response: &axum_test::TestResponse) -> String {
+    response.json::<JsonResponseBody>().data.unwrap()["csrf"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn double_submit_tokens_must_match_the_cookie() {
+    let app = csrf_app(
+        r#"
+        [web.csrf]
+        header = "X-XSRF-Token"
+        form-field = "token"
+        safe-methods = ["GET", "HEAD", "OPTIONS", "DELETE"]
+        exempt-routes = ["/webhooks"]
+        "#,
+    );
+
+    let response = app.http().get("/comments/form").await;
+    let token = token(&response);
+    let csrf_cookie = cookie(&response, "sword.csrf");
+
+    assert_eq!(csrf_cookie, format!("sword.csrf={token}"));
+
+    let response = app
+        .http()
+        .post("/comments")
+        .add_header("cookie", &csrf_cookie)
+        .form(&[("text", "hi")])
+        .await;
+
+    response.assert_status_forbidden();
+    assert_eq!(message(&response), "Missing CSRF token");
+
+    let response = app
+        .http()
+        .post("/comments")
+        .add_header("cookie", &csrf_cookie)
+        .add_header("x-xsrf-token", "0".repeat(64))
+        .form(&[("text", "hi")])
+        .await;
+
+    response.assert_status_forbidden();
+    assert_eq!(message(&response), "Invalid CSRF token");
+
+    let response = app
+        .http()
+        .post("/comments")
+        .add_header("cookie", &csrf_cookie)
+        .add_header("x-xsrf-token", &token)
+        .form(&[("text", "from header")])
+        .await;
+
+    response.assert_status(axum_test::http::StatusCode::CREATED);
+
+    // The form field is read without consuming the body.
+    let response = app
+        .http()
+        .post("/comments")
+        .add_header("cookie", &csrf_cookie)
+        .form(&[("text", "from form"), ("token", token.as_str())])
+        .await;
+
+    response.assert_status(axum_test::http::StatusCode::CREATED);
+    assert_eq!(
+        response.json::<JsonResponseBody>().data,
+        Some(json!("from form"))
+    );
+
+    // A token without its cookie is rejected.
+    let response = app
+        .http()
+        .post("/comments")
+        .add_header("x-xsrf-token", &token)
+        .form(&[("text", "hi")])
+        .await;
+
+    response.assert_status_forbidden();
+
+    app.http().delete("/comments/1").await.assert_status_ok();
+    app.http().post("/webhooks/github").await.assert_status_ok();
+}
+
+#[tokio::test]
+async fn synchronizer_tokens_are_kept_in_the_session() {
+    let app = csrf_app(
+        r#"
+        [web.session]
+        secret = "0123456789abcdef0123456789abcdef"
+
+        [web.csrf]
+        mode = "synchronizer"
+        "#,
+    );
+
+    let response = app.http().get("/comments/form").await;
+    let csrf = token(&response);
+    let session = cookie(&response, "sword.sid");
+
+    assert!(
+        response
+            .iter_headers_by_name("set-cookie")
+            .all(|value| !value.to_str().unwrap().starts_with("sword.csrf="))
+    );
+
+    let response = app
+        .http()
+        .get("/comments/form")
+        .add_header("cookie", &session)
+        .await;
+    assert_eq!(token(&response), csrf);
+
+    let response = app
+        .http()
+        .post("/comments")
+        .add_header("cookie", &session)
+        .add_header("x-csrf-token", &csrf)
+        .form(&[("text", "hi")])
+        .await;
+
+    response.assert_status(axum_test::http::StatusCode::CREATED);
+
+    // Another session has its own token.
+    let other = cookie(&app.http().get("/comments/form").await, "sword.sid");
+
+    let response = app
+        .http()
+        .post("/comments")
+        .add_header("cookie", &other)
+        .add_header("x-csrf-token", &csrf)
+        .form(&[("text", "hi")])
+        .await;
+
+    response.assert_status_forbidden();
+    assert_eq!(message(&response), "Invalid CSRF token");
+}
+
+#[tokio::test]
+async fn interceptor_protects_routes_when_not_global() {
+    let app = csrf_app(
+        r#"
+        [web.csrf]
+        global = false
+        "#,
+    );
+
+    let response = app.http().get("/comments/form").await;
+    let token = token(&response);
+    let csrf_cookie = cookie(&response, "sword.csrf");
+
+    let response = app.http().post("/comments").form(&[("text", "hi")]).await;
+    response.assert_status(axum_test::http::StatusCode::CREATED);
+
+    let response = app.http().post("/comments/protected").await;
+    response.assert_status_forbidden();
+
+    let response = app
+        .http()
+        .post("/comments/protected")
+        .add_header("cookie", &csrf_cookie)
+        .add_header("x-csrf-token", &token)
+        .await;
+
+    response.assert_status_ok();
+}
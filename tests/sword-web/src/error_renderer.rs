@@ -0,0 +1,153 @@
+use axum::{Json, response::IntoResponse};
+use axum_test::http::StatusCode;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use sword::prelude::*;
+use sword::testing::TestAppBuilder;
+use sword::web::*;
+use tokio::time::{Duration, sleep};
+use validator::Validate;
+
+use crate::test_app;
+
+#[derive(Debug, Deserialize, Validate)]
+struct TransferDto {
+    #[validate(range(min = 1, message = "Amount must be positive"))]
+    amount: i64,
+}
+
+#[controller(kind = Controller::Web, path = "/renderer")]
+struct RendererController;
+
+impl RendererController {
+    #[post("/transfers", body_limit = "64B")]
+    async fn transfer(&self, req: Request) -> WebResult {
+        let transfer = req.body_validator::<TransferDto>()?;
+        Ok(JsonResponse::Created().data(json!({ "amount": transfer.amount })))
+    }
+
+    #[get("/slow", timeout = "50ms")]
+    async fn slow(&self) -> WebResult {
+        sleep(Duration::from_millis(300)).await;
+        Ok(JsonResponse::Ok())
+    }
+
+    #[get("/conflict")]
+    async fn conflict(&self) -> WebResult {
+        Err(JsonResponse::Conflict().message("Transfer already exists"))
+    }
+}
+
+struct RendererModule;
+
+impl Module for RendererModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<RendererController>();
+    }
+}
+
+/// Renders framework errors in a company-wide schema, keeping the default
+/// envelope for oversized bodies.
+struct CompanyErrors;
+
+impl ErrorRenderer for CompanyErrors {
+    fn render(&self, error: FrameworkError) -> axum::response::Response {
+        if error.kind == FrameworkErrorKind::BodyTooLarge {
+            return JsonResponse::from(error).into_response();
+        }
+
+        let body = json!({
+            "kind": error.kind.as_str(),
+            "description": error.message,
+            "details": error.details,
+            "correlation_id": error.request_id,
+        });
+
+        (error.status, Json(body)).into_response()
+    }
+}
+
+fn renderer_app() -> TestAppBuilder {
+    test_app("").with_module::<RendererModule>()
+}
+
+#[tokio::test]
+async fn framework_errors_use_the_registered_renderer() {
+    let app = renderer_app()
+        .with_provider(ErrorRendererProvider::new(CompanyErrors))
+        .build();
+
+    let response = app.http().get("/renderer/unknown").await;
+    response.assert_status(StatusCode::NOT_FOUND);
+
+    let body = response.json::<Value>();
+    assert_eq!(body["kind"], "not-found");
+    assert_eq!(body["description"], "The requested resource was not found.");
+
+    let response = app
+        .http()
+        .post("/renderer/transfers")
+        .json(&json!({ "amount": 0 }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let request_id = response.header("x-request-id");
+    let body = response.json::<Value>();
+
+    assert_eq!(body["kind"], "validation");
+    assert_eq!(body["correlation_id"], request_id.to_str().unwrap());
+    assert_eq!(
+        body["details"]["amount"][0]["message"],
+        "Amount must be positive"
+    );
+
+    let response = app.http().get("/renderer/slow").await;
+    response.assert_status(StatusCode::REQUEST_TIMEOUT);
+    assert_eq!(response.json::<Value>()["kind"], "request-timeout");
+
+    let response = app
+        .http()
+        .post("/renderer/transfers")
+        .json(&json!({ "amount": 10, "note": "x".repeat(128) }))
+        .await;
+
+    response.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+
+    let request_id = response.header("x-request-id");
+    let body = response.json::<JsonResponseBody>();
+
+    assert_eq!(body.request_id.as_deref(), request_id.to_str().ok());
+    assert_eq!(
+        body.message.to_string(),
+        "The request body exceeds the maximum allowed size by the server"
+    );
+
+    // Errors returned by handlers are not rendered again.
+    let response = app.http().get("/renderer/conflict").await;
+    response.assert_status(StatusCode::CONFLICT);
+    assert_eq!(
+        response.json::<JsonResponseBody>().message.to_string(),
+        "Transfer already exists"
+    );
+}
+
+#[tokio::test]
+async fn framework_errors_keep_the_envelope_without_a_renderer() {
+    let app = renderer_app().build();
+
+    let response = app
+        .http()
+        .post("/renderer/transfers")
+        .json(&json!({ "amount": 0 }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let body = response.json::<JsonResponseBody>();
+    assert_eq!(body.message.to_string(), "Invalid request body");
+    assert!(body.request_id.is_none());
+
+    let response = app.http().get("/renderer/unknown").await;
+    response.assert_status(StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,185 @@
+use axum_test::http::StatusCode;
+use serde_json::json;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sword::prelude::*;
+use sword::testing::TestAppBuilder;
+use sword::web::*;
+
+use crate::test_app;
+
+const LAST_MODIFIED: &str = "Tue, 14 Nov 2023 22:13:20 GMT";
+const EARLIER: &str = "Tue, 14 Nov 2023 21:56:40 GMT";
+
+fn updated_at() -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+}
+
+#[controller(kind = Controller::Web, path = "/etag")]
+struct ETagController;
+
+impl ETagController {
+    #[get("/items")]
+    async fn items(&self) -> WebResult {
+        Ok(JsonResponse::Ok().data(json!([{ "id": 1 }, { "id": 2 }])))
+    }
+
+    #[get("/tagged", etag = true)]
+    async fn tagged(&self) -> WebResult {
+        Ok(JsonResponse::Ok().data(json!({ "id": 1 })))
+    }
+
+    #[get("/untagged", etag = false)]
+    async fn untagged(&self) -> WebResult {
+        Ok(JsonResponse::Ok().data(json!({ "id": 1 })))
+    }
+
+    #[get("/orders/{id}")]
+    async fn order(&self) -> WebResult {
+        Ok(JsonResponse::Ok()
+            .data(json!({ "id": 1, "version": 3 }))
+            .etag(&ETag::strong("v3"))
+            .last_modified(updated_at()))
+    }
+
+    #[put("/orders/{id}")]
+    async fn update_order(&self, req: Request) -> WebResult {
+        req.check_preconditions(Some(&ETag::strong("v3")), Some(updated_at()))?;
+
+        Ok(JsonResponse::Ok().data(json!({ "id": 1, "version": 4 })))
+    }
+}
+
+struct ETagModule;
+
+impl Module for ETagModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<ETagController>();
+    }
+}
+
+fn etag_app(enabled: bool) -> TestAppBuilder {
+    test_app(&format!(
+        r#"
+        [web.etag]
+        enabled = {enabled}
+        "#
+    ))
+    .with_module::<ETagModule>()
+}
+
+fn etag(response: &axum_test::TestResponse) -> Option<String> {
+    response
+        .maybe_header("etag")
+        .map(|value| value.to_str().unwrap().to_string())
+}
+
+#[tokio::test]
+async fn computed_etags_answer_if_none_match() {
+    let app = etag_app(true).build();
+
+    let response = app.http().get("/etag/items").await;
+    response.assert_status_ok();
+
+    let tag = etag(&response).expect("computed etag");
+    assert!(tag.starts_with("W/\""));
+
+    // The envelope timestamp is not part of the tag.
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_eq!(
+        etag(&app.http().get("/etag/items").await),
+        Some(tag.clone())
+    );
+
+    let response = app
+        .http()
+        .get("/etag/items")
+        .add_header("if-none-match", format!("\"other\", {tag}"))
+        .await;
+
+    response.assert_status(StatusCode::NOT_MODIFIED);
+    assert!(response.as_bytes().is_empty());
+    assert_eq!(etag(&response), Some(tag));
+
+    let response = app
+        .http()
+        .get("/etag/items")
+        .add_header("if-none-match", "\"other\"")
+        .await;
+
+    response.assert_status_ok();
+
+    assert_eq!(etag(&app.http().get("/etag/untagged").await), None);
+}
+
+#[tokio::test]
+async fn routes_override_the_global_setting() {
+    let app = etag_app(false).build();
+
+    assert_eq!(etag(&app.http().get("/etag/items").await), None);
+    assert!(etag(&app.http().get("/etag/tagged").await).is_some());
+}
+
+#[tokio::test]
+async fn handler_validators_take_precedence() {
+    let app = etag_app(true).build();
+
+    let response = app.http().get("/etag/orders/1").await;
+    response.assert_status_ok();
+    assert_eq!(etag(&response).as_deref(), Some("\"v3\""));
+    assert_eq!(response.header("last-modified"), LAST_MODIFIED);
+
+    app.http()
+        .get("/etag/orders/1")
+        .add_header("if-none-match", "W/\"v3\"")
+        .await
+        .assert_status(StatusCode::NOT_MODIFIED);
+
+    app.http()
+        .get("/etag/orders/1")
+        .add_header("if-modified-since", LAST_MODIFIED)
+        .await
+        .assert_status(StatusCode::NOT_MODIFIED);
+
+    app.http()
+        .get("/etag/orders/1")
+        .add_header("if-modified-since", EARLIER)
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn unmet_preconditions_fail_updates() {
+    let app = etag_app(false).build();
+
+    let update = |name: &'static str, value: &'static str| {
+        app.http().put("/etag/orders/1").add_header(name, value)
+    };
+
+    update("if-match", "\"v3\"").await.assert_status_ok();
+    update("if-match", "*").await.assert_status_ok();
+    update("if-unmodified-since", LAST_MODIFIED)
+        .await
+        .assert_status_ok();
+
+    let response = update("if-match", "\"v2\"").await;
+    response.assert_status(StatusCode::PRECONDITION_FAILED);
+    assert_eq!(
+        response.json::<JsonResponseBody>().message.to_string(),
+        "Precondition failed"
+    );
+
+    // Weak tags never match `If-Match`.
+    update("if-match", "W/\"v3\"")
+        .await
+        .assert_status(StatusCode::PRECONDITION_FAILED);
+
+    update("if-unmodified-since", EARLIER)
+        .await
+        .assert_status(StatusCode::PRECONDITION_FAILED);
+
+    update("if-none-match", "\"v3\"")
+        .await
+        .assert_status(StatusCode::PRECONDITION_FAILED);
+
+    app.http().put("/etag/orders/1").await.assert_status_ok();
+}
@@ -0,0 +1,215 @@
+use axum_test::http::StatusCode;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use sword::prelude::*;
+use sword::testing::TestApp;
+use sword::web::*;
+use thiserror::Error;
+use validator::Validate;
+
+use crate::test_app;
+
+#[derive(Debug, Deserialize, Validate)]
+struct DepositDto {
+    #[validate(range(min = 1))]
+    amount: i64,
+
+    #[validate(email)]
+    email: String,
+}
+
+#[derive(Debug, Error, HttpError)]
+enum DepositError {
+    #[error("Insufficient funds")]
+    #[http(code = 409, message = "payments.insufficient-funds")]
+    InsufficientFunds,
+
+    #[error("Account frozen")]
+    #[http(code = 423, message = "The account is frozen")]
+    AccountFrozen,
+}
+
+#[controller(kind = Controller::Web, path = "/i18n")]
+struct I18nController;
+
+impl I18nController {
+    #[post("/deposits")]
+    async fn deposit(&self, req: Request) -> WebResult {
+        let deposit = req.body_validator::<DepositDto>()?;
+
+        match deposit.amount {
+            1000.. => Err(DepositError::InsufficientFunds)?,
+            500.. => Err(DepositError::AccountFrozen)?,
+            _ => Ok(JsonResponse::Created()),
+        }
+    }
+
+    #[get("/locale")]
+    async fn locale(&self, req: Request) -> WebResult {
+        let locale = req.locale()?;
+
+        Ok(JsonResponse::Ok().data(json!({
+            "locale": locale.as_str(),
+            "language": locale.language(),
+        })))
+    }
+}
+
+struct I18nModule;
+
+impl Module for I18nModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<I18nController>();
+    }
+}
+
+fn catalogs(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sword-i18n-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let en = r#"
+        [errors]
+        not-found = "We couldn't find that"
+
+        [payments]
+        insufficient-funds = "Your balance is too low"
+    "#;
+
+    let es = r#"
+        [errors]
+        not-found = "No encontramos el recurso solicitado"
+        validation = "La solicitud tiene errores"
+
+        [validation]
+        range = "El campo {field} está fuera de rango"
+        email.email = "El correo no es válido"
+    "#;
+
+    std::fs::write(dir.join("en.toml"), en).unwrap();
+    std::fs::write(dir.join("es-CL.toml"), es).unwrap();
+    std::fs::write(dir.join("README.md"), "Not a catalog").unwrap();
+
+    dir
+}
+
+fn i18n_app(name: &str) -> TestApp {
+    test_app(&format!(
+        r#"
+        [web.i18n]
+        directory = "{}"
+        default-locale = "en"
+        "#,
+        catalogs(name).display()
+    ))
+    .with_module::<I18nModule>()
+    .build()
+}
+
+#[tokio::test]
+async fn error_messages_follow_accept_language() {
+    let app = i18n_app("errors");
+
+    let response = app
+        .http()
+        .get("/i18n/unknown")
+        .add_header("accept-language", "es, en;q=0.8")
+        .await;
+
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(response.header("content-language"), "es-CL");
+    assert_eq!(
+        response.json::<JsonResponseBody>().message.to_string(),
+        "No encontramos el recurso solicitado"
+    );
+
+    let response = app
+        .http()
+        .post("/i18n/deposits")
+        .add_header("accept-language", "es-CL")
+        .json(&json!({ "amount": 0, "email": "nope" }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let body = response.json::<JsonResponseBody>();
+    let errors = body.errors.unwrap();
+
+    assert_eq!(body.message.to_string(), "La solicitud tiene errores");
+    assert_eq!(errors["amount"][0]["code"], "range");
+    assert_eq!(
+        errors["amount"][0]["message"],
+        "El campo amount está fuera de rango"
+    );
+    assert_eq!(errors["email"][0]["message"], "El correo no es válido");
+
+    // Keys missing in the negotiated catalog use the default locale.
+    let deposit = |language: &'static str, amount: i64| {
+        app.http()
+            .post("/i18n/deposits")
+            .add_header("accept-language", language)
+            .json(&json!({ "amount": amount, "email": "user@example.com" }))
+    };
+
+    let response = deposit("es", 1000).await;
+    response.assert_status(StatusCode::CONFLICT);
+    assert_eq!(
+        response.json::<JsonResponseBody>().message.to_string(),
+        "Your balance is too low"
+    );
+
+    // Messages that aren't keys are kept as is.
+    let response = deposit("es", 500).await;
+    response.assert_status(StatusCode::LOCKED);
+    assert!(response.maybe_header("content-language").is_none());
+    assert_eq!(
+        response.json::<JsonResponseBody>().message.to_string(),
+        "The account is frozen"
+    );
+
+    // Without a translation, the default message is kept.
+    let response = app
+        .http()
+        .post("/i18n/deposits")
+        .json(&json!({ "amount": 0, "email": "user@example.com" }))
+        .await;
+
+    let body = response.json::<JsonResponseBody>();
+    assert_eq!(body.message.to_string(), "Invalid request body");
+    assert_eq!(body.errors.unwrap()["amount"][0]["message"], Value::Null);
+}
+
+#[tokio::test]
+async fn handlers_read_the_negotiated_locale() {
+    let app = i18n_app("locale");
+
+    let locale = |accept_language: &'static str| {
+        app.http()
+            .get("/i18n/locale")
+            .add_header("accept-language", accept_language)
+    };
+
+    let cases = [
+        ("es-cl", "es-CL"),
+        ("fr-CA, es;q=0.5", "es-CL"),
+        ("es;q=0.4, en-US;q=0.9", "en"),
+        ("fr, *", "en"),
+        ("es;q=0", "en"),
+    ];
+
+    for (accept_language, expected) in cases {
+        let data = locale(accept_language)
+            .await
+            .json::<JsonResponseBody>()
+            .data;
+
+        assert_eq!(
+            data.as_ref().unwrap()["locale"],
+            expected,
+            "{accept_language}"
+        );
+    }
+
+    let data = locale("es").await.json::<JsonResponseBody>().data.unwrap();
+    assert_eq!(data["language"], "es");
+}
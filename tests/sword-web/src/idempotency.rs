@@ -0,0 +1,257 @@
+use axum_test::http::StatusCode;
+use jsonwebtoken::{EncodingKey, Header, encode};
+use serde_json::{Value, json};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sword::prelude::*;
+use sword::testing::TestAppBuilder;
+use sword::web::*;
+
+use crate::test_app;
+
+static PAYMENTS: AtomicUsize = AtomicUsize::new(0);
+static ORDERS: AtomicUsize = AtomicUsize::new(0);
+static FAILURES: AtomicUsize = AtomicUsize::new(0);
+static TRANSFERS: AtomicUsize = AtomicUsize::new(0);
+
+const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+#[controller(kind = Controller::Web, path = "/idempotency")]
+struct IdempotencyController;
+
+impl IdempotencyController {
+    #[post("/payments")]
+    async fn pay(&self, req: Request) -> WebResult {
+        let body = req.body::<Value>()?;
+        let id = PAYMENTS.fetch_add(1, Ordering::SeqCst) + 1;
+
+        Ok(JsonResponse::Created().data(json!({ "id": id, "amount": body["amount"] })))
+    }
+
+    #[post("/orders")]
+    async fn order(&self) -> WebResult {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let id = ORDERS.fetch_add(1, Ordering::SeqCst) + 1;
+
+        Ok(JsonResponse::Created().data(json!({ "id": id })))
+    }
+
+    #[post("/failures")]
+    async fn fail(&self) -> WebResult {
+        FAILURES.fetch_add(1, Ordering::SeqCst);
+        Err(JsonResponse::InternalServerError())
+    }
+
+    #[post("/login")]
+    async fn login(&self, req: Request) -> WebResult {
+        req.session()?.insert("user", "ana")?;
+        Ok(JsonResponse::Ok())
+    }
+
+    #[post("/transfers")]
+    async fn transfer(&self) -> WebResult {
+        let id = TRANSFERS.fetch_add(1, Ordering::SeqCst) + 1;
+        Ok(JsonResponse::Created().data(json!({ "id": id })))
+    }
+}
+
+struct IdempotencyModule;
+
+impl Module for IdempotencyModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<IdempotencyController>();
+    }
+}
+
+fn idempotency_app(ttl: &str, extra: &str) -> TestAppBuilder {
+    test_app(&format!(
+        r#"
+        [web.idempotency]
+        ttl = "{ttl}"
+
+        {extra}
+        "#
+    ))
+    .with_module::<IdempotencyModule>()
+}
+
+#[tokio::test]
+async fn repeats_replay_the_first_response() {
+    let app = idempotency_app("1s", "").build();
+
+    let pay = |key: &'static str, amount: u64| {
+        app.http()
+            .post("/idempotency/payments")
+            .add_header("idempotency-key", key)
+            .json(&json!({ "amount": amount }))
+    };
+
+    let first = pay("payment-1", 10).await;
+    first.assert_status(StatusCode::CREATED);
+    assert!(first.maybe_header("idempotent-replayed").is_none());
+
+    let repeat = pay("payment-1", 10).await;
+    repeat.assert_status(StatusCode::CREATED);
+    assert_eq!(repeat.header("idempotent-replayed"), "true");
+    assert_eq!(repeat.text(), first.text());
+
+    let response = pay("payment-1", 20).await;
+    response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        response.json::<JsonResponseBody>().message.to_string(),
+        "The idempotency key was used for a different request"
+    );
+
+    // The negotiated format is part of the request.
+    let response = app
+        .http()
+        .post("/idempotency/payments")
+        .add_header("idempotency-key", "payment-1")
+        .add_header("accept", "application/xml")
+        .json(&json!({ "amount": 10 }))
+        .await;
+
+    response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+
+    // Keys are scoped to the credentials of the request.
+    app.http()
+        .post("/idempotency/payments")
+        .add_header("idempotency-key", "payment-1")
+        .add_header("authorization", "Bearer other")
+        .json(&json!({ "amount": 10 }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    assert_eq!(PAYMENTS.load(Ordering::SeqCst), 2);
+
+    // Expired keys run the request again.
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+
+    let response = pay("payment-1", 20).await;
+    response.assert_status(StatusCode::CREATED);
+    assert!(response.maybe_header("idempotent-replayed").is_none());
+    assert_eq!(PAYMENTS.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn concurrent_duplicates_conflict() {
+    let app = idempotency_app("1h", "").build();
+
+    let order = || {
+        app.http()
+            .post("/idempotency/orders")
+            .add_header("idempotency-key", "order-1")
+    };
+
+    let (first, second) = tokio::join!(order(), async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        order().await
+    });
+
+    first.assert_status(StatusCode::CREATED);
+    second.assert_status(StatusCode::CONFLICT);
+
+    order().await.assert_status(StatusCode::CREATED);
+    assert_eq!(ORDERS.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn server_errors_are_not_kept() {
+    let app = idempotency_app("1h", "").build();
+
+    for _ in 0..2 {
+        app.http()
+            .post("/idempotency/failures")
+            .add_header("idempotency-key", "failure-1")
+            .await
+            .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    assert_eq!(FAILURES.load(Ordering::SeqCst), 2);
+
+    app.http()
+        .post("/idempotency/failures")
+        .add_header("idempotency-key", "")
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+fn bearer(subject: &str, role: &str) -> String {
+    let exp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        + 300;
+    let claims = json!({ "sub": subject, "role": role, "exp": exp });
+    let key = EncodingKey::from_secret(SECRET.as_bytes());
+
+    format!(
+        "Bearer {}",
+        encode(&Header::default(), &claims, &key).unwrap()
+    )
+}
+
+#[tokio::test]
+async fn keys_are_scoped_to_the_client_identity() {
+    let app = idempotency_app(
+        "1h",
+        &format!(
+            r#"
+            [web.session]
+            secret = "{SECRET}"
+
+            [auth.jwt]
+            secret = "{SECRET}"
+            "#
+        ),
+    )
+    .build();
+
+    let transfer = |credentials: (&'static str, String)| {
+        app.http()
+            .post("/idempotency/transfers")
+            .add_header("idempotency-key", "transfer-1")
+            .add_header(credentials.0, credentials.1)
+    };
+
+    let login = || async {
+        let response = app.http().post("/idempotency/login").await;
+        let cookie = response.header("set-cookie");
+
+        cookie
+            .to_str()
+            .unwrap()
+            .split(';')
+            .next()
+            .unwrap()
+            .to_string()
+    };
+
+    // Tokens of the same subject share records, other subjects get their own.
+    let first = transfer(("authorization", bearer("ana", "admin"))).await;
+    first.assert_status(StatusCode::CREATED);
+
+    let repeat = transfer(("authorization", bearer("ana", "viewer"))).await;
+    assert_eq!(repeat.header("idempotent-replayed"), "true");
+    assert_eq!(repeat.text(), first.text());
+
+    let other = transfer(("authorization", bearer("bob", "admin"))).await;
+    other.assert_status(StatusCode::CREATED);
+    assert!(other.maybe_header("idempotent-replayed").is_none());
+
+    // Sessions are scoped by id, whatever other cookies are sent.
+    let (ana, bob) = (login().await, login().await);
+
+    let first = transfer(("cookie", ana.clone())).await;
+    first.assert_status(StatusCode::CREATED);
+    assert!(first.maybe_header("idempotent-replayed").is_none());
+
+    let repeat = transfer(("cookie", format!("{ana}; theme=dark"))).await;
+    assert_eq!(repeat.header("idempotent-replayed"), "true");
+
+    let other = transfer(("cookie", bob)).await;
+    other.assert_status(StatusCode::CREATED);
+    assert!(other.maybe_header("idempotent-replayed").is_none());
+
+    assert_eq!(TRANSFERS.load(Ordering::SeqCst), 4);
+}
@@ -221,18 +221,18 @@ async fn content_type_invalid() {
     assert!(!json.success);
     assert!(
         json.message
-            .contains("Expected Content-Type to be application/json")
+            .contains("Unsupported Content-Type for request body")
     );
 }
 
 #[tokio::test]
-async fn content_type_xml_invalid() {
+async fn content_type_yaml_invalid() {
     let test_app = test_server();
 
     let response = test_app
         .post("/test/content-type-any")
-        .bytes(Bytes::from("<xml>data</xml>"))
-        .content_type("application/xml")
+        .bytes(Bytes::from("key: value"))
+        .content_type("application/yaml")
         .await;
 
     assert_eq!(response.status_code(), 415);
@@ -243,12 +243,12 @@ async fn content_type_xml_invalid() {
     assert!(!json.success);
     assert!(
         json.message
-            .contains("Expected Content-Type to be application/json")
+            .contains("Unsupported Content-Type for request body")
     );
 }
 
 #[tokio::test]
-async fn content_type_form_urlencoded_invalid() {
+async fn content_type_form_urlencoded_is_decoded() {
     let test_app = test_server();
 
     let response = test_app
@@ -257,16 +257,14 @@ async fn content_type_form_urlencoded_invalid() {
         .content_type("application/x-www-form-urlencoded")
         .await;
 
-    assert_eq!(response.status_code(), 415);
+    // The form is decoded, but a key/value map can't be read as a string.
+    assert_eq!(response.status_code(), 400);
 
     let json = response.json::<JsonResponseBody>();
 
-    assert_eq!(json.code, 415);
+    assert_eq!(json.code, 400);
     assert!(!json.success);
-    assert!(
-        json.message
-            .contains("Expected Content-Type to be application/json")
-    );
+    assert!(json.message.contains("Invalid request body"));
 }
 
 #[tokio::test]
@@ -299,7 +297,7 @@ async fn content_type_missing_header_with_body() {
     assert!(!json.success);
     assert!(
         json.message
-            .contains("Expected Content-Type to be application/json")
+            .contains("Unsupported Content-Type for request body")
     );
 }
 
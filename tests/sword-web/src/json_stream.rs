@@ -0,0 +1,111 @@
+use futures_lite::{StreamExt, stream};
+use serde_json::{Value, json};
+use sword::prelude::*;
+use sword::testing::TestApp;
+use sword::web::*;
+
+use crate::test_app;
+
+#[controller(kind = Controller::Web, path = "/export")]
+struct ExportController;
+
+fn rows(count: u32) -> impl futures_lite::Stream<Item = Result<Value, String>> {
+    stream::iter(1..=count).map(|id| Ok(json!({ "id": id })))
+}
+
+impl ExportController {
+    #[get("/lines")]
+    async fn lines(&self) -> JsonStreamResponse {
+        JsonStreamResponse::lines(rows(3))
+    }
+
+    #[get("/array")]
+    async fn array(&self) -> JsonStreamResponse {
+        JsonStreamResponse::array(rows(3))
+    }
+
+    #[get("/empty")]
+    async fn empty(&self) -> JsonStreamResponse {
+        JsonStreamResponse::array(rows(0))
+    }
+
+    #[get("/failing")]
+    async fn failing(&self) -> JsonStreamResponse {
+        let items = rows(2).chain(stream::once(Err("database went away".to_string())));
+        JsonStreamResponse::array(items)
+    }
+
+    #[get("/stalled")]
+    async fn stalled(&self) -> JsonStreamResponse {
+        JsonStreamResponse::lines(rows(1).chain(stream::pending()))
+    }
+}
+
+struct ExportModule;
+
+impl Module for ExportModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<ExportController>();
+    }
+}
+
+fn app() -> TestApp {
+    test_app(
+        r#"
+        [web]
+        request-timeout = { enabled = true, timeout = "100ms" }
+        "#,
+    )
+    .with_module::<ExportModule>()
+    .build()
+}
+
+#[tokio::test]
+async fn items_are_streamed_as_ndjson() {
+    let app = app();
+    let response = app.http().get("/export/lines").await;
+
+    response.assert_status_ok();
+    assert_eq!(response.header("content-type"), "application/x-ndjson");
+    assert_eq!(response.text(), "{\"id\":1}\n{\"id\":2}\n{\"id\":3}\n");
+}
+
+#[tokio::test]
+async fn items_are_streamed_as_a_json_array() {
+    let app = app();
+    let response = app.http().get("/export/array").await;
+
+    response.assert_status_ok();
+    assert_eq!(response.header("content-type"), "application/json");
+    assert_eq!(
+        response.json::<Value>(),
+        json!([{ "id": 1 }, { "id": 2 }, { "id": 3 }])
+    );
+
+    let empty = app.http().get("/export/empty").await;
+    assert_eq!(empty.json::<Value>(), json!([]));
+}
+
+#[tokio::test]
+async fn stream_errors_end_the_body_with_an_error_object() {
+    let app = app();
+    let body = app.http().get("/export/failing").await.json::<Value>();
+
+    assert_eq!(body.as_array().unwrap().len(), 3);
+    assert_eq!(body[1], json!({ "id": 2 }));
+    assert_eq!(body[2]["error"]["code"], 500);
+}
+
+#[tokio::test]
+async fn request_timeout_bounds_the_body() {
+    let app = app();
+    let body = app.http().get("/export/stalled").await.text();
+
+    let lines = body.lines().collect::<Vec<_>>();
+
+    assert_eq!(lines[0], "{\"id\":1}");
+    assert_eq!(
+        serde_json::from_str::<Value>(lines[1]).unwrap()["error"]["code"],
+        408
+    );
+}
@@ -0,0 +1,236 @@
+use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
+use rcgen::KeyPair;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::time::{SystemTime, UNIX_EPOCH};
+use sword::prelude::*;
+use sword::testing::TestApp;
+use sword::web::*;
+
+use crate::test_app;
+
+const SECRET: &str = "test-secret";
+
+#[derive(Serialize, Deserialize)]
+struct UserClaims {
+    sub: String,
+    role: String,
+}
+
+#[controller(kind = Controller::Web, path = "/account")]
+#[interceptor(JwtAuth)]
+struct AccountController;
+
+impl AccountController {
+    #[get("/me")]
+    async fn me(&self, req: Request) -> WebResult {
+        let claims = req.claims::<UserClaims>()?;
+        Ok(JsonResponse::Ok().data(json!({ "sub": claims.sub, "role": claims.role })))
+    }
+}
+
+#[controller(kind = Controller::Web, path = "/uploads")]
+struct UploadsController;
+
+impl UploadsController {
+    #[post("/")]
+    #[interceptor(JwtAuth)]
+    async fn upload(&self, req: StreamRequest) -> WebResult {
+        let claims = req.claims::<UserClaims>()?;
+        Ok(JsonResponse::Ok().data(json!({ "sub": claims.sub })))
+    }
+
+    #[get("/public")]
+    async fn public(&self, req: Request) -> WebResult {
+        let claims = req.claims::<UserClaims>()?;
+        Ok(JsonResponse::Ok().data(json!({ "sub": claims.sub })))
+    }
+}
+
+struct JwtModule;
+
+impl Module for JwtModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<AccountController>();
+        controllers.register::<UploadsController>();
+    }
+}
+
+fn jwt_app(jwt: &str) -> TestApp {
+    test_app(&format!(
+        r#"
+        [auth.jwt]
+        {jwt}
+        "#
+    ))
+    .with_module::<JwtModule>()
+    .build()
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+fn claims(extra: Value) -> Value {
+    let mut claims = json!({
+        "sub": "ana",
+        "role": "admin",
+        "iss": "https://auth.example.com",
+        "aud": "orders-api",
+        "exp": now() + 300,
+    });
+
+    for (key, value) in extra.as_object().unwrap() {
+        claims[key] = value.clone();
+    }
+
+    claims
+}
+
+fn hs256(claims: &Value) -> String {
+    let key = EncodingKey::from_secret(SECRET.as_bytes());
+    format!(
+        "Bearer {}",
+        encode(&Header::default(), claims, &key).unwrap()
+    )
+}
+
+fn hmac_app() -> TestApp {
+    jwt_app(&format!(
+        r#"
+        secret = "{SECRET}"
+        issuer = "https://auth.example.com"
+        audience = "orders-api"
+        leeway = "5s"
+        "#
+    ))
+}
+
+fn message(response: &axum_test::TestResponse) -> String {
+    response.json::<JsonResponseBody>().message.to_string()
+}
+
+#[tokio::test]
+async fn valid_tokens_expose_typed_claims() {
+    let app = hmac_app();
+
+    let response = app
+        .http()
+        .get("/account/me")
+        .add_header("authorization", hs256(&claims(json!({}))))
+        .await;
+
+    response.assert_status_ok();
+    assert_eq!(
+        response.json::<JsonResponseBody>().data,
+        Some(json!({ "sub": "ana", "role": "admin" }))
+    );
+
+    let response = app
+        .http()
+        .post("/uploads")
+        .add_header("authorization", hs256(&claims(json!({}))))
+        .text("file contents")
+        .await;
+
+    response.assert_status_ok();
+}
+
+#[tokio::test]
+async fn invalid_tokens_are_rejected_with_401() {
+    let app = hmac_app();
+
+    let response = app.http().get("/account/me").await;
+    response.assert_status_unauthorized();
+    assert_eq!(response.header("www-authenticate"), "Bearer");
+    assert_eq!(message(&response), "Missing bearer token");
+
+    let expired = hs256(&claims(json!({ "exp": now() - 60 })));
+    let response = app
+        .http()
+        .get("/account/me")
+        .add_header("authorization", expired)
+        .await;
+
+    response.assert_status_unauthorized();
+    assert_eq!(message(&response), "Token has expired");
+
+    let rejected = [
+        hs256(&claims(json!({ "aud": "billing-api" }))),
+        hs256(&claims(json!({ "iss": "https://evil.example.com" }))),
+        "Bearer not-a-token".to_string(),
+        format!(
+            "Bearer {}",
+            encode(
+                &Header::default(),
+                &claims(json!({})),
+                &EncodingKey::from_secret(b"other-secret"),
+            )
+            .unwrap()
+        ),
+    ];
+
+    for token in rejected {
+        let response = app
+            .http()
+            .get("/account/me")
+            .add_header("authorization", token)
+            .await;
+
+        response.assert_status_unauthorized();
+        assert_eq!(message(&response), "Invalid token");
+    }
+
+    let response = app
+        .http()
+        .get("/account/me")
+        .add_header("authorization", hs256(&claims(json!({ "role": 7 }))))
+        .await;
+
+    response.assert_status_unauthorized();
+    assert_eq!(message(&response), "Invalid token claims");
+
+    let response = app.http().get("/uploads/public").await;
+    response.assert_status_internal_server_error();
+}
+
+#[tokio::test]
+async fn public_keys_verify_asymmetric_tokens() {
+    let key = KeyPair::generate().unwrap();
+    let path = std::env::temp_dir().join(format!("sword-jwt-{}.pem", std::process::id()));
+    std::fs::write(&path, key.public_key_pem()).unwrap();
+
+    let app = jwt_app(&format!(
+        r#"
+        algorithms = ["ES256"]
+        public-key = "{}"
+        "#,
+        path.display()
+    ));
+
+    let signing_key = EncodingKey::from_ec_pem(key.serialize_pem().as_bytes()).unwrap();
+    let claims = json!({ "sub": "ana", "role": "admin", "exp": now() + 300 });
+    let token = encode(&Header::new(Algorithm::ES256), &claims, &signing_key).unwrap();
+
+    let response = app
+        .http()
+        .get("/account/me")
+        .add_header("authorization", format!("Bearer {token}"))
+        .await;
+
+    response.assert_status_ok();
+
+    // HMAC tokens aren't accepted when only ES256 is configured.
+    let response = app
+        .http()
+        .get("/account/me")
+        .add_header("authorization", hs256(&claims))
+        .await;
+
+    response.assert_status_unauthorized();
+
+    let _ = std::fs::remove_file(path);
+}
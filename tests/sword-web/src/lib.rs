@@ -8,20 +8,128 @@ pub fn test_server(app: sword::Application) -> axum_test::TestServer {
     axum_test::TestServer::new(app.router()).unwrap()
 }
 
+/// Configuration shared by the test apps of this crate. Every `#[config]` in
+/// the crate is loaded by each app, so their sections must be present.
+#[cfg(test)]
+const BASE_TOML: &str = r#"
+[web]
+host = "0.0.0.0"
+port = 8080
+body-limit = "1MB"
+request-timeout = { enabled = false, timeout = "1s" }
+
+[greeting]
+text = "hello"
+
+[my-custom-section]
+custom_key = "value"
+env_user = "test"
+"#;
+
+/// Merges `extra_toml` into the shared configuration. Tables are merged key
+/// by key, so a test only writes the sections and keys it is about.
+#[cfg(test)]
+pub fn test_toml(extra_toml: &str) -> String {
+    fn merge(base: &mut toml::Table, extra: toml::Table) {
+        for (key, value) in extra {
+            match (base.get_mut(&key), value) {
+                (Some(toml::Value::Table(base)), toml::Value::Table(extra)) => merge(base, extra),
+                (_, value) => {
+                    base.insert(key, value);
+                }
+            }
+        }
+    }
+
+    let mut config: toml::Table = BASE_TOML.parse().unwrap();
+    merge(&mut config, extra_toml.parse().unwrap());
+
+    config.to_string()
+}
+
+/// A `TestApp` with the shared configuration plus `extra_toml`.
+#[cfg(test)]
+pub fn test_app(extra_toml: &str) -> sword::testing::TestAppBuilder {
+    sword::testing::TestApp::from_toml(&test_toml(extra_toml))
+}
+
 #[cfg(test)]
 mod request {
+    mod client_info;
     mod cookies;
+    mod decoders;
+    mod extractors;
     mod multipart;
     mod query;
     mod stream;
 }
 
+#[cfg(test)]
+mod csrf;
+
+#[cfg(test)]
+mod error_renderer;
+
 #[cfg(test)]
 mod errors;
 
+#[cfg(test)]
+mod etag;
+
 #[cfg(test)]
 mod http_methods;
 
+#[cfg(test)]
+mod i18n;
+
+#[cfg(test)]
+mod idempotency;
+
+#[cfg(test)]
+mod json_stream;
+
+#[cfg(test)]
+mod jwt;
+
+#[cfg(test)]
+mod listeners;
+
+#[cfg(test)]
+mod metrics;
+
+#[cfg(test)]
+mod negotiation;
+
+#[cfg(test)]
+mod openapi;
+
+#[cfg(test)]
+mod opentelemetry;
+
+#[cfg(test)]
+mod problem;
+
+#[cfg(test)]
+mod rate_limit;
+
+#[cfg(test)]
+mod route_limits;
+
+#[cfg(test)]
+mod sessions;
+
+#[cfg(test)]
+mod sse;
+
+#[cfg(test)]
+mod testing;
+
+#[cfg(test)]
+mod tls;
+
+#[cfg(test)]
+mod versioning;
+
 #[cfg(test)]
 mod interceptors {
     mod built_in;
@@ -0,0 +1,216 @@
+use std::{net::TcpListener as StdTcpListener, time::Duration};
+use sword::prelude::*;
+use sword::testing::Config;
+use sword::web::*;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use crate::test_toml;
+
+#[controller(kind = Controller::Web, path = "/public")]
+struct PublicController;
+
+impl PublicController {
+    #[get("/hello")]
+    async fn hello(&self) -> WebResult {
+        Ok(JsonResponse::Ok().message("hello"))
+    }
+}
+
+#[controller(kind = Controller::Web, path = "/admin")]
+struct AdminController;
+
+impl AdminController {
+    #[get("/stats")]
+    async fn stats(&self) -> WebResult {
+        Ok(JsonResponse::Ok().message("stats"))
+    }
+}
+
+struct ListenersModule;
+
+impl Module for ListenersModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<PublicController>();
+        controllers.register::<AdminController>();
+    }
+}
+
+fn free_port() -> u16 {
+    StdTcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+fn start(listeners: &str) {
+    let config = Config::builder()
+        .add_toml_str(&test_toml(&format!(
+            r#"
+            [tracing]
+            enabled = false
+
+            [web]
+            host = "127.0.0.1"
+            port = 0
+            {listeners}
+            "#
+        )))
+        .build()
+        .unwrap();
+
+    let app = Application::from_config(config)
+        .with_module::<ListenersModule>()
+        .build();
+
+    tokio::spawn(async move { app.run().await });
+}
+
+/// Sends an HTTP/1.1 GET over `stream` and returns the raw response.
+async fn get<S: AsyncRead + AsyncWrite + Unpin>(mut stream: S, path: &str) -> String {
+    stream
+        .write_all(
+            format!("GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                .as_bytes(),
+        )
+        .await
+        .unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    response
+}
+
+async fn connect(port: u16) -> TcpStream {
+    for _ in 0..100 {
+        if let Ok(stream) = TcpStream::connect(("127.0.0.1", port)).await {
+            return stream;
+        }
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    panic!("listener on port {port} did not start");
+}
+
+#[tokio::test]
+async fn listeners_serve_their_own_route_subsets() {
+    let public = free_port();
+    let internal = free_port();
+
+    start(&format!(
+        r#"
+        listeners = [
+            {{ bind = "127.0.0.1:{public}", exclude-routes = ["/admin"] }},
+            {{ bind = "127.0.0.1:{internal}", routes = ["/admin/"] }},
+        ]
+        "#
+    ));
+
+    let response = get(connect(public).await, "/public/hello").await;
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+    let response = get(connect(public).await, "/admin/stats").await;
+    assert!(response.starts_with("HTTP/1.1 404"), "{response}");
+
+    let response = get(connect(internal).await, "/admin/stats").await;
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+    assert!(response.contains("stats"), "{response}");
+
+    let response = get(connect(internal).await, "/public/hello").await;
+    assert!(response.starts_with("HTTP/1.1 404"), "{response}");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn unix_socket_listeners_apply_the_configured_mode() {
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::net::UnixStream;
+
+    let path = std::env::temp_dir().join(format!("sword-listener-{}.sock", std::process::id()));
+
+    // A stale socket file from a previous run must not prevent binding.
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+    start(&format!(
+        r#"
+        listeners = [{{ bind = "unix:{}", mode = "600" }}]
+        "#,
+        path.display()
+    ));
+
+    let mut stream = None;
+
+    for _ in 0..100 {
+        if let Ok(connected) = UnixStream::connect(&path).await {
+            stream = Some(connected);
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    let response = get(
+        stream.expect("unix listener did not start"),
+        "/public/hello",
+    )
+    .await;
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[test]
+fn listener_binds_are_parsed_from_strings() {
+    assert_eq!(
+        "127.0.0.1:8080".parse::<ListenerBind>(),
+        Ok(ListenerBind::Tcp("127.0.0.1:8080".parse().unwrap()))
+    );
+
+    assert_eq!(
+        "unix:/run/app.sock".parse::<ListenerBind>(),
+        Ok(ListenerBind::Unix("/run/app.sock".into()))
+    );
+
+    assert_eq!(
+        "systemd:1".parse::<ListenerBind>(),
+        Ok(ListenerBind::Systemd(1))
+    );
+
+    assert!("unix:".parse::<ListenerBind>().is_err());
+    assert!("systemd:web".parse::<ListenerBind>().is_err());
+    assert!("localhost".parse::<ListenerBind>().is_err());
+}
+
+#[tokio::test]
+async fn filtered_routes_are_rendered_like_unknown_routes() {
+    let port = free_port();
+
+    start(&format!(
+        r#"
+        listeners = [{{ bind = "127.0.0.1:{port}", exclude-routes = ["/admin"] }}]
+
+        [web.errors]
+        format = "problem"
+        "#
+    ));
+
+    for path in ["/admin/stats", "/unknown"] {
+        let response = get(connect(port).await, path).await;
+
+        assert!(response.starts_with("HTTP/1.1 404"), "{response}");
+        assert!(
+            response.contains("content-type: application/problem+json"),
+            "{response}"
+        );
+        assert!(
+            response.contains(&format!(r#""instance":"{path}""#)),
+            "{response}"
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use sword::prelude::*;
+use sword::web::*;
+
+use crate::application_builder;
+use crate::test_app;
+use crate::test_server;
+
+#[controller(kind = Controller::Web, path = "/metrics-test")]
+struct MetricsTestController;
+
+impl MetricsTestController {
+    #[get("/items/{id}")]
+    async fn item(&self, req: Request) -> WebResult {
+        let id = req.param::<u32>("id")?;
+        Ok(JsonResponse::Ok().data(id))
+    }
+
+    #[post("/items")]
+    async fn create(&self) -> WebResult {
+        Ok(JsonResponse::Created().message("created"))
+    }
+
+    #[post("/uploads", body_limit = "16B")]
+    async fn upload(&self, req: Request) -> WebResult {
+        let body = req.body::<serde_json::Value>()?;
+        Ok(JsonResponse::Ok().data(body))
+    }
+}
+
+#[controller(kind = Controller::Web, path = "/metrics-versioned", version = "2")]
+struct VersionedMetricsController;
+
+impl VersionedMetricsController {
+    #[get("/items/{id}")]
+    async fn item(&self) -> WebResult {
+        Ok(JsonResponse::Ok())
+    }
+}
+
+struct MetricsTestModule;
+
+impl Module for MetricsTestModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<MetricsTestController>();
+    }
+}
+
+struct PrefixedMetricsModule;
+
+impl Module for PrefixedMetricsModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<MetricsTestController>();
+        controllers.register::<VersionedMetricsController>();
+    }
+}
+
+#[tokio::test]
+async fn metrics_endpoint_exposes_prometheus_text() {
+    let app = application_builder()
+        .with_module::<MetricsTestModule>()
+        .build();
+
+    let app = test_server(app);
+
+    let response = app.get("/metrics").await;
+    let content_type = response.header("content-type");
+
+    assert_eq!(response.status_code().as_u16(), 200);
+    assert!(content_type.to_str().unwrap().starts_with("text/plain"));
+}
+
+#[tokio::test]
+async fn metrics_are_labelled_with_route_templates() {
+    let app = application_builder()
+        .with_module::<MetricsTestModule>()
+        .build();
+
+    let app = test_server(app);
+
+    app.get("/metrics-test/items/42").await;
+    app.get("/metrics-test/items/not-a-number").await;
+    app.post("/metrics-test/items").text("payload").await;
+
+    let body = app.get("/metrics").await.text();
+
+    let expected_ok = r#"http_requests_total{controller="MetricsTestController",method="GET",route="/metrics-test/items/{id}",status="200"}"#;
+    let expected_bad = r#"http_requests_total{controller="MetricsTestController",method="GET",route="/metrics-test/items/{id}",status="400"}"#;
+    let expected_post = r#"http_requests_total{controller="MetricsTestController",method="POST",route="/metrics-test/items",status="201"}"#;
+
+    assert!(body.contains(expected_ok), "{body}");
+    assert!(body.contains(expected_bad), "{body}");
+    assert!(body.contains(expected_post), "{body}");
+    assert!(body.contains("http_request_duration_seconds_bucket"));
+    assert!(body.contains("http_request_size_bytes_bucket"));
+    assert!(!body.contains("/metrics-test/items/42"));
+}
+
+#[tokio::test]
+async fn responses_from_route_layers_are_recorded() {
+    let app = application_builder()
+        .with_module::<MetricsTestModule>()
+        .build();
+
+    let app = test_server(app);
+
+    // A declared length over the limit is rejected before the handler runs.
+    let payload = r#"{"name":"a body well over the sixteen byte limit"}"#;
+
+    app.post("/metrics-test/uploads")
+        .content_type("application/json")
+        .add_header("content-length", payload.len().to_string())
+        .text(payload)
+        .await
+        .assert_status(axum_test::http::StatusCode::PAYLOAD_TOO_LARGE);
+
+    let body = app.get("/metrics").await.text();
+
+    let expected = r#"http_requests_total{controller="MetricsTestController",method="POST",route="/metrics-test/uploads",status="413"}"#;
+
+    assert!(body.contains(expected), "{body}");
+}
+
+#[tokio::test]
+async fn unmatched_routes_are_not_recorded() {
+    let app = application_builder()
+        .with_module::<MetricsTestModule>()
+        .build();
+
+    let app = test_server(app);
+
+    app.get("/metrics-test/unknown-route-for-metrics").await;
+
+    let body = app.get("/metrics").await.text();
+
+    assert!(!body.contains("unknown-route-for-metrics"));
+}
+
+#[tokio::test]
+async fn route_templates_include_the_router_and_version_prefixes() {
+    let app = test_app(
+        r#"
+        [web]
+        router-prefix = "/api"
+
+        [metrics]
+        enabled = true
+        "#,
+    )
+    .with_module::<PrefixedMetricsModule>()
+    .build();
+
+    app.http().get("/api/metrics-test/items/7").await;
+    app.http().get("/api/v2/metrics-versioned/items/7").await;
+
+    let body = app.http().get("/metrics").await.text();
+
+    let expected = r#"http_requests_total{controller="MetricsTestController",method="GET",route="/api/metrics-test/items/{id}",status="200"}"#;
+    let expected_versioned = r#"http_requests_total{controller="VersionedMetricsController",method="GET",route="/api/v2/metrics-versioned/items/{id}",status="200"}"#;
+
+    assert!(body.contains(expected), "{body}");
+    assert!(body.contains(expected_versioned), "{body}");
+}
@@ -0,0 +1,182 @@
+use axum_test::TestServer;
+use serde_json::{Value, json};
+use std::error::Error;
+use sword::prelude::*;
+use sword::web::*;
+use thiserror::Error;
+
+use crate::application_builder;
+use crate::test_server;
+
+#[derive(Debug, Error, HttpError)]
+enum NegotiationError {
+    #[error("Not found")]
+    #[http(code = 404, message = "Item not found")]
+    NotFound,
+}
+
+/// Encodes the response `message` as plain text.
+struct MessageEncoder;
+
+impl BodyEncoder for MessageEncoder {
+    fn media_type(&self) -> &'static str {
+        "text/x-sword-test-message"
+    }
+
+    fn encode(&self, value: &Value) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        let message = value["message"].as_str().ok_or("missing message")?;
+        Ok(message.as_bytes().to_vec())
+    }
+}
+
+register_body_encoder!(MessageEncoder);
+
+#[controller(kind = Controller::Web, path = "/negotiation")]
+struct NegotiationController;
+
+impl NegotiationController {
+    #[get("/item")]
+    async fn item(&self) -> WebResult<Negotiated> {
+        Ok(JsonResponse::Ok()
+            .message("Item found")
+            .data(json!({ "id": 7, "name": "Ada" }))
+            .into())
+    }
+
+    #[get("/plain")]
+    async fn plain(&self) -> WebResult {
+        Ok(JsonResponse::Ok().message("Always JSON"))
+    }
+
+    #[get("/missing")]
+    async fn missing(&self) -> WebResult<Negotiated, NegotiationError> {
+        Err(NegotiationError::NotFound)
+    }
+}
+
+struct NegotiationModule;
+
+impl Module for NegotiationModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<NegotiationController>();
+    }
+}
+
+fn server() -> TestServer {
+    let app = application_builder()
+        .with_module::<NegotiationModule>()
+        .build();
+
+    test_server(app)
+}
+
+async fn get(server: &TestServer, path: &str, accept: &'static str) -> axum_test::TestResponse {
+    server.get(path).add_header("accept", accept).await
+}
+
+fn content_type(response: &axum_test::TestResponse) -> String {
+    response
+        .header("content-type")
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn response_is_encoded_from_accept() {
+    let server = server();
+    let expected = server.get("/negotiation/item").await.json::<Value>();
+
+    assert_eq!(expected["data"], json!({ "id": 7, "name": "Ada" }));
+
+    let response = get(&server, "/negotiation/item", "application/msgpack").await;
+    response.assert_status_ok();
+
+    assert_eq!(content_type(&response), "application/msgpack");
+    assert_eq!(
+        rmp_serde::from_slice::<Value>(response.as_bytes()).unwrap(),
+        expected
+    );
+
+    let response = get(&server, "/negotiation/item", "application/cbor").await;
+
+    assert_eq!(content_type(&response), "application/cbor");
+    assert_eq!(
+        cbor4ii::serde::from_slice::<Value>(response.as_bytes()).unwrap(),
+        expected
+    );
+
+    let response = get(&server, "/negotiation/item", "text/xml").await;
+    let body = response.text();
+
+    assert_eq!(content_type(&response), "application/xml");
+    assert!(body.starts_with("<response>"), "{body}");
+    assert!(body.contains("<message>Item found</message>"), "{body}");
+    assert!(body.contains("<name>Ada</name>"), "{body}");
+}
+
+#[tokio::test]
+async fn accept_ranges_are_weighted() {
+    let server = server();
+
+    let cases = [
+        (
+            "application/cbor;q=0.5, application/msgpack",
+            "application/msgpack",
+        ),
+        ("text/html, application/cbor;q=0.9", "application/cbor"),
+        ("application/msgpack;q=0, */*;q=0.1", "application/json"),
+        ("application/*", "application/json"),
+        ("text/x-sword-test-message", "text/x-sword-test-message"),
+    ];
+
+    for (accept, expected) in cases {
+        let response = get(&server, "/negotiation/item", accept).await;
+
+        response.assert_status_ok();
+        assert_eq!(content_type(&response), expected, "{accept}");
+        assert_eq!(response.header("vary"), "accept", "{accept}");
+    }
+
+    let response = get(&server, "/negotiation/item", "text/x-sword-test-message").await;
+    assert_eq!(response.text(), "Item found");
+}
+
+#[tokio::test]
+async fn unacceptable_types_are_rejected() {
+    let server = server();
+
+    let response = get(&server, "/negotiation/item", "text/csv").await;
+    let json = response.json::<Value>();
+
+    response.assert_status(axum::http::StatusCode::NOT_ACCEPTABLE);
+    assert_eq!(json["code"], 406);
+    assert!(
+        json["error"]["supported"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("application/msgpack"))
+    );
+
+    let response = get(&server, "/negotiation/plain", "application/msgpack").await;
+
+    response.assert_status_ok();
+    assert_eq!(response.json::<Value>()["message"], "Always JSON");
+}
+
+#[tokio::test]
+async fn http_errors_follow_accept() {
+    let server = server();
+
+    let response = get(&server, "/negotiation/missing", "application/msgpack").await;
+    let body = rmp_serde::from_slice::<Value>(response.as_bytes()).unwrap();
+
+    response.assert_status_not_found();
+    assert_eq!(content_type(&response), "application/msgpack");
+    assert_eq!(body["message"], "Item not found");
+
+    let response = get(&server, "/negotiation/missing", "text/csv").await;
+
+    response.assert_status_not_found();
+    assert_eq!(response.json::<Value>()["message"], "Item not found");
+}
@@ -0,0 +1,267 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sword::openapi::{OpenApiDocument, OpenApiSecurity, SecurityScheme};
+use sword::prelude::*;
+use sword::web::*;
+use thiserror::Error;
+
+use crate::application_builder;
+use crate::test_app;
+use crate::test_server;
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+struct UserDto {
+    id: u32,
+    name: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, JsonSchema)]
+struct CreateUserDto {
+    #[validate(length(min = 3))]
+    name: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[allow(dead_code)]
+struct ListQuery {
+    page: u32,
+    search: Option<String>,
+}
+
+#[derive(Debug, Error, HttpError)]
+#[http_error(code = 500)]
+enum UserError {
+    #[error("User not found")]
+    #[http(code = 404, message = "User not found")]
+    NotFound,
+
+    #[allow(dead_code)]
+    #[error("Conflict")]
+    #[http(code = 409)]
+    Conflict,
+}
+
+#[derive(Interceptor)]
+struct BearerAuth;
+
+impl OnRequest for BearerAuth {
+    async fn on_request(&self, req: Request) -> WebInterceptorResult {
+        req.next().await
+    }
+}
+
+impl OpenApiSecurity for BearerAuth {
+    const SCHEME_NAME: &'static str = "bearerAuth";
+
+    fn security_scheme() -> SecurityScheme {
+        SecurityScheme::bearer_with_format("JWT")
+    }
+}
+
+/// User management.
+#[controller(kind = Controller::Web, path = "/users")]
+struct OpenApiUsersController;
+
+impl OpenApiUsersController {
+    /// Lists users.
+    ///
+    /// Results are paginated.
+    #[get("/")]
+    async fn list(&self, Query(_query): Query<ListQuery>) -> WebResult {
+        Ok(JsonResponse::Ok())
+    }
+
+    /// Finds a user by id.
+    #[get("/{id}")]
+    #[openapi(response = UserDto)]
+    async fn find(&self, Path(_id): Path<u32>) -> Result<JsonResponse, UserError> {
+        Err(UserError::NotFound)
+    }
+
+    #[post("/")]
+    #[interceptor(BearerAuth)]
+    #[openapi(response = UserDto, status = 201)]
+    async fn create(&self, Validated(Json(user)): Validated<Json<CreateUserDto>>) -> WebResult {
+        Ok(JsonResponse::Created().data(user))
+    }
+
+    #[get("/internal")]
+    #[openapi(skip)]
+    async fn internal(&self, req: Request) -> WebResult {
+        Ok(JsonResponse::Ok().data(req.uri()))
+    }
+}
+
+struct OpenApiModule;
+
+impl Module for OpenApiModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<OpenApiUsersController>();
+    }
+}
+
+async fn document() -> Value {
+    let app = application_builder().with_module::<OpenApiModule>().build();
+    let server = test_server(app);
+
+    let response = server.get("/openapi.json").await;
+    response.assert_status_ok();
+
+    response.json::<Value>()
+}
+
+#[tokio::test]
+async fn document_describes_registered_routes() {
+    let document = document().await;
+
+    assert_eq!(document["openapi"], "3.1.0");
+    assert_eq!(document["info"]["title"], "Sword API");
+    assert_eq!(
+        document["tags"],
+        json!([{ "name": "OpenApiUsersController", "description": "User management." }])
+    );
+
+    let paths = document["paths"].as_object().unwrap();
+    let mut keys = paths.keys().cloned().collect::<Vec<_>>();
+    keys.sort();
+
+    // Skipped routes and controllers of other modules are not documented.
+    assert_eq!(keys, ["/users", "/users/{id}"]);
+
+    let list = &document["paths"]["/users"]["get"];
+
+    assert_eq!(list["summary"], "Lists users.");
+    assert_eq!(list["description"], "Results are paginated.");
+    assert_eq!(list["operationId"], "OpenApiUsersController_list");
+    assert_eq!(
+        list["parameters"],
+        json!([
+            { "name": "page", "in": "query", "required": true, "schema": { "type": "integer", "format": "uint32", "minimum": 0 } },
+            { "name": "search", "in": "query", "required": false, "schema": { "type": ["string", "null"] } },
+        ])
+    );
+}
+
+#[tokio::test]
+async fn document_uses_schemas_errors_and_security() {
+    let document = document().await;
+
+    let find = &document["paths"]["/users/{id}"]["get"];
+
+    assert_eq!(find["parameters"][0]["name"], "id");
+    assert_eq!(find["parameters"][0]["in"], "path");
+    assert_eq!(find["parameters"][0]["schema"]["type"], "integer");
+    assert_eq!(
+        find["responses"]["200"]["content"]["application/json"]["schema"]["allOf"][1]["properties"]
+            ["data"],
+        json!({ "$ref": "#/components/schemas/UserDto" })
+    );
+    assert_eq!(find["responses"]["404"]["description"], "User not found");
+    assert_eq!(find["responses"]["409"]["description"], "Conflict");
+    assert!(find.get("security").is_none());
+
+    let create = &document["paths"]["/users"]["post"];
+
+    assert!(create["responses"]["201"].is_object());
+    assert!(create["responses"]["200"].is_null());
+    assert_eq!(
+        create["requestBody"]["content"]["application/json"]["schema"],
+        json!({ "$ref": "#/components/schemas/CreateUserDto" })
+    );
+    assert_eq!(create["security"], json!([{ "bearerAuth": [] }]));
+
+    let components = &document["components"];
+
+    assert_eq!(
+        components["securitySchemes"]["bearerAuth"],
+        json!({ "type": "http", "scheme": "bearer", "bearerFormat": "JWT" })
+    );
+    assert!(components["schemas"]["UserDto"]["properties"]["name"].is_object());
+    assert!(components["schemas"]["CreateUserDto"].is_object());
+    assert!(components["schemas"]["JsonResponseBody"].is_object());
+}
+
+#[tokio::test]
+async fn document_path_and_swagger_ui_are_configurable() {
+    let app = test_app(
+        r#"
+        [openapi]
+        enabled = true
+        path = "/api-docs/openapi.json"
+        title = "Users API"
+        swagger-ui = { enabled = true, path = "/api-docs" }
+
+        [web]
+        router-prefix = "/api"
+        "#,
+    )
+    .with_module::<OpenApiModule>()
+    .build();
+
+    let response = app.http().get("/api-docs/openapi.json").await;
+    response.assert_status_ok();
+
+    let document = response.json::<Value>();
+
+    assert_eq!(document["info"]["title"], "Users API");
+    assert!(document["paths"]["/api/users/{id}"].is_object());
+
+    let stored = app.get::<OpenApiDocument>().unwrap();
+    assert_eq!(stored.as_value(), &document);
+
+    let page = app.http().get("/api-docs").await;
+    page.assert_status_ok();
+
+    assert!(page.text().contains("SwaggerUIBundle"));
+    assert!(page.text().contains(r#"url: "/api-docs/openapi.json""#));
+    assert!(
+        page.text()
+            .contains(r#"src="/api-docs/swagger-ui-bundle.js""#)
+    );
+
+    let bundle = app.http().get("/api-docs/swagger-ui-bundle.js").await;
+    bundle.assert_status_ok();
+    assert!(
+        bundle
+            .header("content-type")
+            .to_str()
+            .unwrap()
+            .starts_with("text/javascript")
+    );
+    assert!(bundle.text().contains("SwaggerUIBundle"));
+
+    let stylesheet = app.http().get("/api-docs/swagger-ui.css").await;
+    stylesheet.assert_status_ok();
+    assert!(
+        stylesheet
+            .header("content-type")
+            .to_str()
+            .unwrap()
+            .starts_with("text/css")
+    );
+}
+
+#[tokio::test]
+async fn swagger_ui_assets_url_overrides_the_bundled_assets() {
+    let app = test_app(
+        r#"
+        [openapi]
+        enabled = true
+        swagger-ui = { enabled = true, assets-url = "https://unpkg.com/swagger-ui-dist@5/" }
+        "#,
+    )
+    .with_module::<OpenApiModule>()
+    .build();
+
+    let page = app.http().get("/docs").await;
+    page.assert_status_ok();
+
+    assert!(
+        page.text()
+            .contains(r#"src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js""#)
+    );
+
+    let bundle = app.http().get("/docs/swagger-ui-bundle.js").await;
+    bundle.assert_status_not_found();
+}
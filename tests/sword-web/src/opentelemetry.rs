@@ -0,0 +1,179 @@
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use opentelemetry_proto::tonic::{
+    collector::trace::v1::{
+        ExportTraceServiceRequest, ExportTraceServiceResponse,
+        trace_service_server::{TraceService, TraceServiceServer},
+    },
+    common::v1::any_value::Value,
+    trace::v1::Span,
+};
+use sword::prelude::*;
+use sword::web::*;
+use tonic::transport::server::TcpIncoming;
+
+use crate::test_server;
+
+const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+const PARENT_SPAN_ID: &str = "00f067aa0ba902b7";
+
+#[controller(kind = Controller::Web, path = "/otel-test")]
+struct OtelTestController;
+
+impl OtelTestController {
+    #[get("/items/{id}")]
+    async fn item(&self, req: Request) -> WebResult {
+        let id = req.param::<u32>("id")?;
+        Ok(JsonResponse::Ok().data(id))
+    }
+}
+
+struct OtelTestModule;
+
+impl Module for OtelTestModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<OtelTestController>();
+    }
+}
+
+#[derive(Clone, Default)]
+struct Collector {
+    spans: Arc<Mutex<Vec<Span>>>,
+}
+
+#[tonic::async_trait]
+impl TraceService for Collector {
+    async fn export(
+        &self,
+        request: tonic::Request<ExportTraceServiceRequest>,
+    ) -> Result<tonic::Response<ExportTraceServiceResponse>, tonic::Status> {
+        let spans = request
+            .into_inner()
+            .resource_spans
+            .into_iter()
+            .flat_map(|resource| resource.scope_spans)
+            .flat_map(|scope| scope.spans);
+
+        self.spans.lock().unwrap().extend(spans);
+
+        Ok(tonic::Response::new(ExportTraceServiceResponse::default()))
+    }
+}
+
+fn spawn_collector() -> (SocketAddr, Collector) {
+    let collector = Collector::default();
+    let incoming = TcpIncoming::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = incoming.local_addr().unwrap();
+
+    let service = TraceServiceServer::new(collector.clone());
+
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(service)
+            .serve_with_incoming(incoming),
+    );
+
+    (addr, collector)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn string_attribute(span: &Span, key: &str) -> Option<String> {
+    span.attributes
+        .iter()
+        .find(|kv| kv.key == key)
+        .and_then(|kv| kv.value.as_ref())
+        .and_then(|value| match &value.value {
+            Some(Value::StringValue(s)) => Some(s.clone()),
+            _ => None,
+        })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn request_spans_continue_the_incoming_trace() {
+    let (addr, collector) = spawn_collector();
+
+    let config = Config::builder()
+        .add_required_file("Config.toml")
+        .add_toml_str(&format!(
+            r#"
+            [opentelemetry]
+            enabled = true
+            endpoint = "http://{addr}"
+            service-name = "sword-web-tests"
+            export-interval = "100ms"
+            "#
+        ))
+        .build()
+        .unwrap();
+
+    let app = sword::Application::from_config(config)
+        .with_module::<OtelTestModule>()
+        .build();
+
+    let server = test_server(app);
+
+    let response = server
+        .get("/otel-test/items/7")
+        .add_header("traceparent", format!("00-{TRACE_ID}-{PARENT_SPAN_ID}-01"))
+        .add_header("x-request-id", "req-otel-1")
+        .await;
+
+    response.assert_status_ok();
+
+    let traceparent = response.header("traceparent");
+    let traceparent = traceparent.to_str().unwrap();
+
+    assert!(traceparent.starts_with(&format!("00-{TRACE_ID}-")));
+    assert!(!traceparent.contains(PARENT_SPAN_ID));
+
+    let mut exported = None;
+
+    for _ in 0..100 {
+        exported = collector
+            .spans
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|span| span.name == "GET /otel-test/items/{id}")
+            .cloned();
+
+        if exported.is_some() {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    let span = exported.expect("request span was not exported");
+
+    assert_eq!(hex(&span.trace_id), TRACE_ID);
+    assert_eq!(hex(&span.parent_span_id), PARENT_SPAN_ID);
+    assert_eq!(
+        string_attribute(&span, "http.request.header.x-request-id").as_deref(),
+        Some("req-otel-1")
+    );
+    assert_eq!(
+        string_attribute(&span, "http.route").as_deref(),
+        Some("/otel-test/items/{id}")
+    );
+}
+
+#[tokio::test]
+async fn disabled_telemetry_does_not_emit_traceparent() {
+    let app = crate::application_builder()
+        .with_module::<OtelTestModule>()
+        .build();
+
+    let server = test_server(app);
+    let response = server.get("/otel-test/items/1").await;
+
+    response.assert_status_ok();
+    assert!(response.maybe_header("traceparent").is_none());
+}
@@ -0,0 +1,272 @@
+use axum_test::http::StatusCode;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use sword::prelude::*;
+use sword::testing::TestAppBuilder;
+use sword::web::*;
+use thiserror::Error;
+use validator::Validate;
+
+use crate::test_app;
+
+#[derive(Debug, Deserialize, Validate)]
+struct PaymentDto {
+    #[validate(range(min = 1, message = "Amount must be positive"))]
+    amount: i64,
+}
+
+#[derive(Debug, Error, HttpError)]
+enum PaymentError {
+    #[error("Insufficient funds")]
+    #[http(
+        code = 409,
+        message = "Insufficient funds",
+        type = "https://api.example.com/problems/insufficient-funds"
+    )]
+    InsufficientFunds,
+
+    #[error("Card declined")]
+    #[http(code = 402)]
+    CardDeclined,
+
+    #[error("Teapot")]
+    #[http(code = 418, message = "Short and stout")]
+    Teapot,
+
+    #[error("Invalid report")]
+    #[http(code = 422, message = "Invalid report", error = rows)]
+    InvalidReport { rows: Vec<String> },
+}
+
+#[derive(Debug, Error, HttpError)]
+#[http_error(format = problem)]
+enum RefundError {
+    #[error("Refund window closed")]
+    #[http(code = 422, message = "The refund window is closed")]
+    WindowClosed,
+}
+
+#[controller(kind = Controller::Web, path = "/problems")]
+struct ProblemController;
+
+impl ProblemController {
+    #[post("/payments")]
+    async fn pay(&self, req: Request) -> WebResult {
+        let payment = req.body_validator::<PaymentDto>()?;
+
+        match payment.amount {
+            1000.. => Err(PaymentError::InsufficientFunds)?,
+            500.. => Err(PaymentError::CardDeclined)?,
+            _ => Ok(JsonResponse::Created().data(json!({ "amount": payment.amount }))),
+        }
+    }
+
+    #[post("/refunds")]
+    async fn refund(&self) -> WebResult<JsonResponse, RefundError> {
+        Err(RefundError::WindowClosed)
+    }
+
+    #[get("/teapot")]
+    async fn teapot(&self) -> WebResult {
+        Err(PaymentError::Teapot)?
+    }
+
+    #[get("/report")]
+    async fn report(&self) -> WebResult {
+        Err(PaymentError::InvalidReport {
+            rows: vec!["invalid".into(); 16 * 1024],
+        })?
+    }
+
+    #[get("/conflict")]
+    async fn conflict(&self) -> WebResult {
+        Err(JsonResponse::Conflict()
+            .message("Already exists")
+            .error(json!({ "id": 1 })))
+    }
+}
+
+struct ProblemModule;
+
+impl Module for ProblemModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<ProblemController>();
+    }
+}
+
+fn problem_app(errors: &str) -> TestAppBuilder {
+    test_app(&format!(
+        r#"
+        [web.errors]
+        {errors}
+        "#
+    ))
+    .with_module::<ProblemModule>()
+}
+
+fn content_type(response: &axum_test::TestResponse) -> String {
+    response
+        .header("content-type")
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn errors_are_rendered_as_problem_details() {
+    let app = problem_app(
+        r#"
+        format = "problem"
+        type-base = "https://api.example.com/problems/"
+        types = { 404 = "https://api.example.com/problems/missing" }
+        "#,
+    )
+    .build();
+
+    let response = app.http().get("/problems/unknown").await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(content_type(&response), "application/problem+json");
+
+    let problem = response.json::<Value>();
+
+    assert_eq!(problem["type"], "https://api.example.com/problems/missing");
+    assert_eq!(problem["title"], "Not Found");
+    assert_eq!(problem["status"], 404);
+    assert_eq!(problem["detail"], "The requested resource was not found.");
+    assert_eq!(problem["instance"], "/problems/unknown");
+    assert!(problem.get("success").is_none());
+
+    let response = app
+        .http()
+        .post("/problems/payments")
+        .json(&json!({ "amount": 0 }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let problem = response.json::<Value>();
+    let request_id = response.header("x-request-id");
+
+    assert_eq!(problem["request_id"], request_id.to_str().unwrap());
+    assert_eq!(
+        problem["type"],
+        "https://api.example.com/problems/bad-request"
+    );
+    assert_eq!(
+        problem["errors"]["amount"][0]["message"],
+        "Amount must be positive"
+    );
+
+    let response = app
+        .http()
+        .post("/problems/payments")
+        .json(&json!({ "amount": 1000 }))
+        .await;
+
+    response.assert_status(StatusCode::CONFLICT);
+
+    let problem = response.json::<Value>();
+    assert_eq!(
+        problem["type"],
+        "https://api.example.com/problems/insufficient-funds"
+    );
+    assert_eq!(problem["detail"], "Insufficient funds");
+
+    // Messages equal to the title add nothing as `detail`.
+    let problem = app
+        .http()
+        .post("/problems/payments")
+        .json(&json!({ "amount": 500 }))
+        .await
+        .json::<Value>();
+
+    assert_eq!(
+        problem["type"],
+        "https://api.example.com/problems/payment-required"
+    );
+    assert!(problem.get("detail").is_none());
+
+    let problem = app.http().get("/problems/teapot").await.json::<Value>();
+    assert_eq!(
+        problem["type"],
+        "https://api.example.com/problems/im-a-teapot"
+    );
+    assert_eq!(problem["detail"], "Short and stout");
+
+    // Successful responses keep the envelope.
+    let response = app
+        .http()
+        .post("/problems/payments")
+        .json(&json!({ "amount": 10 }))
+        .await;
+
+    response.assert_status(StatusCode::CREATED);
+    assert_eq!(
+        response.json::<JsonResponseBody>().data,
+        Some(json!({ "amount": 10 }))
+    );
+}
+
+#[tokio::test]
+async fn derives_select_their_own_format() {
+    let app = problem_app(
+        r#"
+        instance = false
+        request-id = false
+        "#,
+    )
+    .build();
+
+    let response = app.http().post("/problems/refunds").await;
+    response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(content_type(&response), "application/problem+json");
+
+    assert_eq!(
+        response.json::<Value>(),
+        json!({
+            "type": "about:blank",
+            "title": "Unprocessable Entity",
+            "status": 422,
+            "detail": "The refund window is closed",
+        })
+    );
+
+    let response = app
+        .http()
+        .post("/problems/payments")
+        .json(&json!({ "amount": 1000 }))
+        .await;
+
+    response.assert_status(StatusCode::CONFLICT);
+    assert_eq!(content_type(&response), "application/json");
+    assert_eq!(
+        response.json::<JsonResponseBody>().message.to_string(),
+        "Insufficient funds"
+    );
+}
+
+#[tokio::test]
+async fn large_error_bodies_are_rendered() {
+    let app = problem_app(r#"format = "problem""#).build();
+
+    let response = app.http().get("/problems/report").await;
+    response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(content_type(&response), "application/problem+json");
+
+    let problem = response.json::<Value>();
+    assert_eq!(problem["detail"], "Invalid report");
+    assert_eq!(problem["error"].as_array().unwrap().len(), 16 * 1024);
+}
+
+#[tokio::test]
+async fn handler_error_responses_are_sent_as_is() {
+    let app = problem_app(r#"format = "problem""#).build();
+
+    let response = app.http().get("/problems/conflict").await;
+    response.assert_status(StatusCode::CONFLICT);
+    assert_eq!(content_type(&response), "application/json");
+
+    let body = response.json::<JsonResponseBody>();
+    assert_eq!(body.message.to_string(), "Already exists");
+    assert_eq!(body.error, Some(json!({ "id": 1 })));
+}
@@ -0,0 +1,223 @@
+use jsonwebtoken::{EncodingKey, Header, encode};
+use serde_json::json;
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+use sword::prelude::*;
+use sword::testing::TestAppBuilder;
+use sword::web::*;
+
+use crate::test_app;
+
+const SECRET: &str = "test-secret";
+
+#[controller(kind = Controller::Web, path = "/api")]
+struct ApiController;
+
+impl ApiController {
+    #[get("/items")]
+    async fn items(&self) -> WebResult {
+        Ok(JsonResponse::Ok())
+    }
+
+    #[post("/login", rate_limit = "login")]
+    async fn login(&self) -> WebResult {
+        Ok(JsonResponse::Ok())
+    }
+
+    #[get("/health", rate_limit = "none")]
+    async fn health(&self) -> WebResult {
+        Ok(JsonResponse::Ok())
+    }
+}
+
+#[controller(kind = Controller::Web, path = "/reports", rate_limit = "tenants")]
+struct ReportsController;
+
+impl ReportsController {
+    #[get("/")]
+    async fn list(&self) -> WebResult {
+        Ok(JsonResponse::Ok())
+    }
+}
+
+struct TenantKey;
+
+impl RateLimitKeyExtractor for TenantKey {
+    fn extract(&self, req: &RateLimitRequest<'_>) -> Option<String> {
+        let tenant = req.headers().get("x-tenant")?.to_str().ok()?;
+        Some(tenant.to_lowercase())
+    }
+}
+
+register_rate_limit_key!("tenant", TenantKey);
+
+/// Counts hits and delegates to the in-memory store.
+#[derive(Clone, Default)]
+struct CountingStore {
+    hits: Arc<AtomicUsize>,
+    inner: MemoryRateLimitStore,
+}
+
+impl RateLimitStore for CountingStore {
+    fn hit<'a>(
+        &'a self,
+        key: &'a str,
+        policy: &'a RateLimitPolicy,
+    ) -> RateLimitFuture<'a, RateLimitDecision> {
+        self.hits.fetch_add(1, Ordering::SeqCst);
+        self.inner.hit(key, policy)
+    }
+}
+
+struct RateLimitModule;
+
+impl Module for RateLimitModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<ApiController>();
+        controllers.register::<ReportsController>();
+    }
+}
+
+fn rate_limit_app(config: &str) -> TestAppBuilder {
+    test_app(&format!(
+        r#"
+        [auth.jwt]
+        secret = "{SECRET}"
+
+        [rate-limit]
+        enabled = true
+        {config}
+
+        [rate-limit.policies.login]
+        algorithm = "sliding-window"
+        limit = 1
+        window = "1m"
+        key = "header:X-Api-Key"
+
+        [rate-limit.policies.tenants]
+        limit = 1
+        window = "1m"
+        key = "custom:tenant"
+        "#
+    ))
+    .with_module::<RateLimitModule>()
+}
+
+fn bearer(subject: &str) -> String {
+    let exp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        + 300;
+
+    let token = encode(
+        &Header::default(),
+        &json!({ "sub": subject, "exp": exp }),
+        &EncodingKey::from_secret(SECRET.as_bytes()),
+    )
+    .unwrap();
+
+    format!("Bearer {token}")
+}
+
+fn header(response: &axum_test::TestResponse, name: &str) -> String {
+    response.header(name).to_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn default_policy_limits_every_route() {
+    let app = rate_limit_app(
+        r#"
+        limit = 2
+        window = "1m"
+        "#,
+    )
+    .build();
+
+    let response = app.http().get("/api/items").await;
+    response.assert_status_ok();
+    assert_eq!(header(&response, "ratelimit-limit"), "2");
+    assert_eq!(header(&response, "ratelimit-remaining"), "1");
+    assert_eq!(header(&response, "ratelimit-policy"), "2;w=60");
+
+    app.http().get("/api/items").await.assert_status_ok();
+
+    let response = app.http().get("/api/items").await;
+    response.assert_status(axum_test::http::StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(
+        response.json::<JsonResponseBody>().message.to_string(),
+        "Too many requests"
+    );
+    assert_eq!(header(&response, "ratelimit-remaining"), "0");
+    assert_eq!(header(&response, "retry-after"), "30");
+
+    // Exempt routes are not counted.
+    for _ in 0..3 {
+        app.http().get("/api/health").await.assert_status_ok();
+    }
+}
+
+#[tokio::test]
+async fn routes_and_controllers_select_their_policy() {
+    let app = rate_limit_app(
+        r#"
+        global = false
+        "#,
+    )
+    .build();
+
+    for _ in 0..3 {
+        app.http().get("/api/items").await.assert_status_ok();
+    }
+
+    let login = |key: &'static str| app.http().post("/api/login").add_header("x-api-key", key);
+
+    login("a").await.assert_status_ok();
+    login("a")
+        .await
+        .assert_status(axum_test::http::StatusCode::TOO_MANY_REQUESTS);
+    login("b").await.assert_status_ok();
+
+    let reports = |tenant: &'static str| app.http().get("/reports").add_header("x-tenant", tenant);
+
+    reports("acme").await.assert_status_ok();
+    reports("ACME")
+        .await
+        .assert_status(axum_test::http::StatusCode::TOO_MANY_REQUESTS);
+    reports("globex").await.assert_status_ok();
+}
+
+#[tokio::test]
+async fn subjects_are_counted_separately_in_a_custom_store() {
+    let store = CountingStore::default();
+
+    let app = rate_limit_app(
+        r#"
+        algorithm = "sliding-window"
+        limit = 1
+        key = "subject"
+        "#,
+    )
+    .with_provider(RateLimitStoreProvider::new(store.clone()))
+    .build();
+
+    let items = |subject: &str| {
+        app.http()
+            .get("/api/items")
+            .add_header("authorization", bearer(subject))
+    };
+
+    items("ana").await.assert_status_ok();
+    items("ana")
+        .await
+        .assert_status(axum_test::http::StatusCode::TOO_MANY_REQUESTS);
+    items("bob").await.assert_status_ok();
+
+    // Anonymous requests are counted by client IP.
+    app.http().get("/api/items").await.assert_status_ok();
+
+    assert_eq!(store.hits.load(Ordering::SeqCst), 4);
+}
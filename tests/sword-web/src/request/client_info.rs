@@ -0,0 +1,214 @@
+use axum::extract::connect_info::MockConnectInfo;
+use axum_test::TestServer;
+use serde_json::{Value, json};
+use std::net::SocketAddr;
+use sword::prelude::*;
+use sword::web::*;
+
+use crate::test_app;
+
+#[controller(kind = Controller::Web, path = "/client")]
+struct ClientInfoController;
+
+impl ClientInfoController {
+    #[get("/")]
+    async fn client(&self, req: Request) -> WebResult {
+        Ok(JsonResponse::Ok().data(json!({
+            "ip": req.ip(),
+            "ips": req.ips(),
+            "protocol": req.protocol(),
+            "host": req.host(),
+            "peer": req.peer_addr(),
+        })))
+    }
+
+    #[get("/extract")]
+    async fn extract(&self, info: ClientInfo) -> WebResult {
+        Ok(JsonResponse::Ok().data(info.ip))
+    }
+}
+
+struct ClientInfoModule;
+
+impl Module for ClientInfoModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<ClientInfoController>();
+    }
+}
+
+fn server(peer: &str, header: &str) -> TestServer {
+    let app = test_app(&format!(
+        r#"
+        [web]
+        trusted-proxies = {{ cidrs = ["10.0.0.0/8", "2001:db8::1"], header = "{header}" }}
+        "#
+    ))
+    .with_module::<ClientInfoModule>()
+    .build();
+
+    let peer = peer.parse::<SocketAddr>().unwrap();
+
+    TestServer::new(app.router().layer(MockConnectInfo(peer))).unwrap()
+}
+
+async fn client(server: &TestServer, headers: &[(&'static str, &'static str)]) -> Value {
+    let mut request = server.get("/client");
+
+    for (name, value) in headers {
+        request = request.add_header(*name, *value);
+    }
+
+    let response = request.await;
+    response.assert_status_ok();
+
+    response.json::<Value>()["data"].clone()
+}
+
+#[tokio::test]
+async fn forwarding_headers_from_untrusted_peer_are_ignored() {
+    let server = server("203.0.113.7:5000", "x-forwarded");
+
+    let data = client(
+        &server,
+        &[
+            ("x-forwarded-for", "1.2.3.4"),
+            ("x-forwarded-proto", "https"),
+            ("x-forwarded-host", "spoofed.example"),
+            ("host", "api.example"),
+        ],
+    )
+    .await;
+
+    assert_eq!(data["ip"], "203.0.113.7");
+    assert_eq!(data["ips"], json!(["203.0.113.7"]));
+    assert_eq!(data["protocol"], "http");
+    assert_eq!(data["host"], "api.example");
+    assert_eq!(data["peer"], "203.0.113.7:5000");
+}
+
+#[tokio::test]
+async fn x_forwarded_headers_stop_at_first_untrusted_hop() {
+    let server = server("10.0.0.2:5000", "x-forwarded");
+
+    let data = client(
+        &server,
+        &[
+            ("x-forwarded-for", "1.2.3.4, 198.51.100.9, 10.0.0.1"),
+            ("x-forwarded-proto", "https"),
+            ("x-forwarded-host", "api.example"),
+        ],
+    )
+    .await;
+
+    // `1.2.3.4` was reported by `198.51.100.9`, which isn't trusted.
+    assert_eq!(data["ip"], "198.51.100.9");
+    assert_eq!(data["ips"], json!(["198.51.100.9", "10.0.0.1", "10.0.0.2"]));
+    assert_eq!(data["protocol"], "https");
+    assert_eq!(data["host"], "api.example");
+}
+
+#[tokio::test]
+async fn x_forwarded_proto_and_host_come_from_the_nearest_proxy() {
+    let server = server("10.0.0.2:5000", "x-forwarded");
+
+    // The proxy appended its own entries after the ones sent by the client.
+    let data = client(
+        &server,
+        &[
+            ("x-forwarded-for", "10.0.0.1, 192.0.2.60"),
+            ("x-forwarded-proto", "https, http"),
+            ("x-forwarded-host", "spoofed.example, api.example"),
+        ],
+    )
+    .await;
+
+    assert_eq!(data["ip"], "192.0.2.60");
+    assert_eq!(data["protocol"], "http");
+    assert_eq!(data["host"], "api.example");
+}
+
+#[tokio::test]
+async fn forwarded_header_is_ignored_behind_x_forwarded_proxies() {
+    let server = server("10.0.0.2:5000", "x-forwarded");
+
+    let data = client(
+        &server,
+        &[
+            (
+                "forwarded",
+                "for=1.2.3.4;proto=https;host=spoofed.example, for=10.0.0.9",
+            ),
+            ("x-forwarded-for", "192.0.2.60"),
+            ("host", "api.example"),
+        ],
+    )
+    .await;
+
+    assert_eq!(data["ip"], "192.0.2.60");
+    assert_eq!(data["ips"], json!(["192.0.2.60", "10.0.0.2"]));
+    assert_eq!(data["protocol"], "http");
+    assert_eq!(data["host"], "api.example");
+
+    // Without `X-Forwarded-For`, the peer is the client.
+    let data = client(
+        &server,
+        &[("forwarded", "for=1.2.3.4;proto=https;host=spoofed.example")],
+    )
+    .await;
+
+    assert_eq!(data["ip"], "10.0.0.2");
+    assert_eq!(data["protocol"], "http");
+    assert_ne!(data["host"], "spoofed.example");
+}
+
+#[tokio::test]
+async fn forwarded_header_is_read_when_configured() {
+    let server = server("[2001:db8::1]:443", "forwarded");
+
+    let data = client(
+        &server,
+        &[
+            (
+                "forwarded",
+                r#"for="[2001:db8:cafe::17]:4711";proto=https;host=shop.example"#,
+            ),
+            ("x-forwarded-for", "1.2.3.4"),
+        ],
+    )
+    .await;
+
+    assert_eq!(data["ip"], "2001:db8:cafe::17");
+    assert_eq!(data["protocol"], "https");
+    assert_eq!(data["host"], "shop.example");
+
+    // `X-Forwarded-*` headers are ignored behind `Forwarded` proxies.
+    let data = client(
+        &server,
+        &[
+            ("x-forwarded-for", "1.2.3.4"),
+            ("x-forwarded-proto", "https"),
+        ],
+    )
+    .await;
+
+    assert_eq!(data["ip"], "2001:db8::1");
+    assert_eq!(data["protocol"], "http");
+
+    let data = client(&server, &[("forwarded", "for=_hidden;proto=http")]).await;
+
+    assert_eq!(data["ip"], Value::Null);
+    assert_eq!(data["ips"], json!(["2001:db8::1"]));
+}
+
+#[tokio::test]
+async fn client_info_can_be_extracted() {
+    let server = server("10.1.2.3:5000", "x-forwarded");
+
+    let response = server
+        .get("/client/extract")
+        .add_header("x-forwarded-for", "192.0.2.60")
+        .await;
+
+    response.assert_status_ok();
+    assert_eq!(response.json::<Value>()["data"], "192.0.2.60");
+}
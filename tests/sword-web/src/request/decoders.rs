@@ -0,0 +1,215 @@
+use axum_test::TestServer;
+use serde::de::value::{Error as ValueError, SeqDeserializer};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::error::Error;
+use sword::prelude::*;
+use sword::web::*;
+use validator::Validate;
+
+use crate::application_builder;
+use crate::test_server;
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+struct UserDto {
+    #[validate(length(min = 3, message = "Name must be at least 3 characters"))]
+    name: String,
+    age: u32,
+}
+
+/// Decodes `name,age` lines, e.g. `Ada,36`.
+struct CsvLineDecoder;
+
+impl BodyDecoder for CsvLineDecoder {
+    fn accepts(&self, media_type: &str) -> bool {
+        media_type == "application/x-sword-test-line"
+    }
+
+    fn decode<'de>(
+        &self,
+        body: &'de [u8],
+        target: BodyTarget<'_, 'de>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let line = std::str::from_utf8(body)?.trim();
+        let fields = line.split(',').map(str::trim);
+
+        target.deserialize(SeqDeserializer::<_, ValueError>::new(fields))
+    }
+}
+
+register_body_decoder!(CsvLineDecoder);
+
+#[controller(kind = Controller::Web, path = "/decoders")]
+struct DecodersController;
+
+impl DecodersController {
+    #[post("/user")]
+    async fn user(&self, req: Request) -> WebResult {
+        let user = req.body::<UserDto>()?;
+        Ok(JsonResponse::Ok().data(user))
+    }
+
+    #[post("/validated")]
+    async fn validated(&self, req: Request) -> WebResult {
+        let user = req.body_validator::<UserDto>()?;
+        Ok(JsonResponse::Ok().data(user))
+    }
+
+    #[post("/line")]
+    async fn line(&self, req: Request) -> WebResult {
+        let (name, age) = req.body::<(String, String)>()?;
+        Ok(JsonResponse::Ok().data(json!({ "name": name, "age": age })))
+    }
+}
+
+struct DecodersModule;
+
+impl Module for DecodersModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<DecodersController>();
+    }
+}
+
+fn server() -> TestServer {
+    let app = application_builder()
+        .with_module::<DecodersModule>()
+        .build();
+
+    test_server(app)
+}
+
+fn user() -> UserDto {
+    UserDto {
+        name: "Ada".to_string(),
+        age: 36,
+    }
+}
+
+async fn post(server: &TestServer, path: &str, content_type: &str, body: Vec<u8>) -> Value {
+    server
+        .post(path)
+        .bytes(body.into())
+        .content_type(content_type)
+        .await
+        .json::<Value>()
+}
+
+#[tokio::test]
+async fn body_is_decoded_by_content_type() {
+    let server = server();
+    let expected = json!({ "name": "Ada", "age": 36 });
+
+    let cases = [
+        ("application/json", serde_json::to_vec(&user()).unwrap()),
+        (
+            "application/x-www-form-urlencoded; charset=utf-8",
+            b"name=Ada&age=36".to_vec(),
+        ),
+        (
+            "application/msgpack",
+            rmp_serde::to_vec_named(&user()).unwrap(),
+        ),
+        (
+            "application/cbor",
+            cbor4ii::serde::to_vec(Vec::new(), &user()).unwrap(),
+        ),
+        (
+            "application/xml",
+            b"<user><name>Ada</name><age>36</age></user>".to_vec(),
+        ),
+    ];
+
+    for (content_type, body) in cases {
+        let json = post(&server, "/decoders/user", content_type, body).await;
+
+        assert_eq!(json["code"], 200, "{content_type}: {json}");
+        assert_eq!(json["data"], expected, "{content_type}");
+    }
+}
+
+#[tokio::test]
+async fn body_validator_uses_decoders() {
+    let server = server();
+
+    let json = post(
+        &server,
+        "/decoders/validated",
+        "application/x-www-form-urlencoded",
+        b"name=Al&age=36".to_vec(),
+    )
+    .await;
+
+    assert_eq!(json["code"], 400);
+    assert_eq!(
+        json["errors"]["name"][0]["message"],
+        "Name must be at least 3 characters"
+    );
+}
+
+#[tokio::test]
+async fn custom_decoder_is_used() {
+    let server = server();
+
+    let json = post(
+        &server,
+        "/decoders/line",
+        "application/x-sword-test-line",
+        b"Ada, 36".to_vec(),
+    )
+    .await;
+
+    assert_eq!(json["code"], 200);
+    assert_eq!(json["data"], json!({ "name": "Ada", "age": "36" }));
+}
+
+#[tokio::test]
+async fn malformed_and_unsupported_bodies_are_rejected() {
+    let server = server();
+
+    let json = post(
+        &server,
+        "/decoders/user",
+        "application/msgpack",
+        vec![0xc1, 0x00],
+    )
+    .await;
+
+    assert_eq!(json["code"], 400);
+    assert_eq!(json["message"], "Invalid request body");
+
+    let json = post(
+        &server,
+        "/decoders/user",
+        "application/yaml",
+        b"name: Ada".to_vec(),
+    )
+    .await;
+
+    assert_eq!(json["code"], 415);
+}
+
+#[tokio::test]
+async fn trailing_bytes_after_the_body_are_rejected() {
+    let server = server();
+
+    let cases = [
+        ("application/json", serde_json::to_vec(&user()).unwrap()),
+        (
+            "application/msgpack",
+            rmp_serde::to_vec_named(&user()).unwrap(),
+        ),
+        (
+            "application/cbor",
+            cbor4ii::serde::to_vec(Vec::new(), &user()).unwrap(),
+        ),
+    ];
+
+    for (content_type, mut body) in cases {
+        body.extend_from_slice(&body.clone());
+
+        let json = post(&server, "/decoders/user", content_type, body).await;
+
+        assert_eq!(json["code"], 400, "{content_type}: {json}");
+        assert_eq!(json["message"], "Invalid request body", "{content_type}");
+    }
+}
@@ -0,0 +1,280 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sword::prelude::*;
+use sword::web::*;
+use validator::Validate;
+
+use crate::application_builder;
+use crate::test_server;
+
+#[derive(Debug, Deserialize, Serialize)]
+struct PostParams {
+    user_id: u32,
+    post_id: String,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct Pagination {
+    page: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+struct CreateUser {
+    #[validate(length(min = 3, message = "Name must be at least 3 characters"))]
+    name: String,
+}
+
+struct ApiKey(String);
+
+impl TypedHeader for ApiKey {
+    const NAME: &'static str = "x-api-key";
+
+    fn decode(value: &HeaderValue) -> Result<Self, RequestError> {
+        let value = value
+            .to_str()
+            .map_err(|_| RequestError::invalid_header_value(Self::NAME))?;
+
+        Ok(Self(value.to_owned()))
+    }
+}
+
+/// A user-defined extractor built on the sword `FromRequestParts` trait.
+struct Tenant(String);
+
+impl FromRequestParts for Tenant {
+    type Rejection = JsonResponse;
+
+    async fn from_request_parts(parts: &mut Parts, _: &State) -> Result<Self, Self::Rejection> {
+        parts
+            .headers
+            .get("x-tenant")
+            .and_then(|value| value.to_str().ok())
+            .map(|tenant| Self(tenant.to_owned()))
+            .ok_or_else(|| JsonResponse::Forbidden().message("Unknown tenant"))
+    }
+}
+
+#[derive(Interceptor)]
+struct TagInterceptor;
+
+impl OnRequest for TagInterceptor {
+    async fn on_request(&self, mut req: Request) -> WebInterceptorResult {
+        req.set_header("x-tenant", "intercepted")?;
+        req.next().await
+    }
+}
+
+#[controller(kind = Controller::Web, path = "/extractors")]
+struct ExtractorsController;
+
+impl ExtractorsController {
+    #[get("/single/{id}")]
+    async fn single(&self, Path(id): Path<u32>) -> WebResult {
+        Ok(JsonResponse::Ok().data(id))
+    }
+
+    #[get("/users/{user_id}/posts/{post_id}")]
+    async fn post(
+        &self,
+        Path(params): Path<PostParams>,
+        Query(query): Query<Pagination>,
+    ) -> WebResult {
+        Ok(JsonResponse::Ok().data(json!({ "params": params, "query": query })))
+    }
+
+    #[post("/users")]
+    async fn create(&self, Json(user): Json<CreateUser>) -> WebResult {
+        Ok(JsonResponse::Created().data(user))
+    }
+
+    #[post("/validated")]
+    async fn validated(&self, Validated(Json(user)): Validated<Json<CreateUser>>) -> WebResult {
+        Ok(JsonResponse::Created().data(user))
+    }
+
+    #[get("/header")]
+    async fn header(&self, Header(ApiKey(key)): Header<ApiKey>, id: RequestId) -> WebResult {
+        let id = id.header_value().to_str().unwrap_or_default().to_owned();
+
+        Ok(JsonResponse::Ok().data(json!({ "key": key, "request_id": id })))
+    }
+
+    #[get("/tenant")]
+    async fn tenant(&self, Tenant(tenant): Tenant) -> WebResult {
+        Ok(JsonResponse::Ok().data(tenant))
+    }
+
+    #[get("/intercepted/{id}")]
+    #[interceptor(TagInterceptor)]
+    async fn intercepted(&self, Path(id): Path<u32>, Tenant(tenant): Tenant) -> WebResult {
+        Ok(JsonResponse::Ok().data(json!({ "id": id, "tenant": tenant })))
+    }
+
+    #[post("/mixed/{id}")]
+    async fn mixed(&self, Path(id): Path<u32>, req: Request) -> WebResult {
+        let user: CreateUser = req.body()?;
+
+        Ok(JsonResponse::Ok().data(json!({ "id": id, "name": user.name })))
+    }
+}
+
+struct ExtractorsModule;
+
+impl Module for ExtractorsModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<ExtractorsController>();
+    }
+}
+
+fn server() -> axum_test::TestServer {
+    test_server(
+        application_builder()
+            .with_module::<ExtractorsModule>()
+            .build(),
+    )
+}
+
+#[tokio::test]
+async fn path_and_query_extractors() {
+    let server = server();
+
+    let response = server.get("/extractors/single/7").await;
+    assert_eq!(response.status_code().as_u16(), 200);
+    assert_eq!(response.json::<JsonResponseBody>().data, Some(json!(7)));
+
+    let response = server.get("/extractors/users/1/posts/abc?page=3").await;
+    assert_eq!(response.status_code().as_u16(), 200);
+    assert_eq!(
+        response.json::<JsonResponseBody>().data,
+        Some(json!({
+            "params": { "user_id": 1, "post_id": "abc" },
+            "query": { "page": 3 },
+        }))
+    );
+
+    let response = server.get("/extractors/users/1/posts/abc").await;
+    assert_eq!(
+        response.json::<JsonResponseBody>().data.unwrap()["query"],
+        json!({ "page": null })
+    );
+}
+
+#[tokio::test]
+async fn extraction_errors_map_through_request_error() {
+    let server = server();
+
+    let response = server.get("/extractors/single/not-a-number").await;
+    assert_eq!(response.status_code().as_u16(), 400);
+    assert_eq!(
+        response.json::<JsonResponseBody>().message.as_ref(),
+        "Invalid path parameters"
+    );
+
+    let response = server.get("/extractors/users/1/posts/abc?page=x").await;
+    assert_eq!(response.status_code().as_u16(), 400);
+    assert_eq!(
+        response.json::<JsonResponseBody>().message.as_ref(),
+        "Invalid query parameters"
+    );
+
+    let response = server.post("/extractors/users").await;
+    assert_eq!(response.status_code().as_u16(), 400);
+    assert_eq!(
+        response.json::<JsonResponseBody>().message.as_ref(),
+        "Request body is empty"
+    );
+
+    let response = server.post("/extractors/users").text("{}").await;
+    assert_eq!(response.status_code().as_u16(), 415);
+
+    let response = server.get("/extractors/header").await;
+    assert_eq!(response.status_code().as_u16(), 400);
+    assert_eq!(
+        response.json::<JsonResponseBody>().message.as_ref(),
+        "Missing required header"
+    );
+}
+
+#[tokio::test]
+async fn json_and_validated_extractors() {
+    let server = server();
+
+    let response = server
+        .post("/extractors/users")
+        .json(&json!({ "name": "al" }))
+        .await;
+    assert_eq!(response.status_code().as_u16(), 201);
+    assert_eq!(
+        response.json::<JsonResponseBody>().data,
+        Some(json!({ "name": "al" }))
+    );
+
+    let response = server
+        .post("/extractors/validated")
+        .json(&json!({ "name": "al" }))
+        .await;
+    assert_eq!(response.status_code().as_u16(), 400);
+
+    let json = response.json::<JsonResponseBody>();
+    assert_eq!(json.message.as_ref(), "Invalid request body");
+    assert_eq!(
+        json.errors.unwrap()["name"][0]["message"],
+        "Name must be at least 3 characters"
+    );
+
+    let response = server
+        .post("/extractors/validated")
+        .json(&json!({ "name": "alice" }))
+        .await;
+    assert_eq!(response.status_code().as_u16(), 201);
+}
+
+#[tokio::test]
+async fn header_and_request_id_extractors() {
+    let response = server()
+        .get("/extractors/header")
+        .add_header("x-api-key", "secret")
+        .await;
+
+    assert_eq!(response.status_code().as_u16(), 200);
+
+    let data = response.json::<JsonResponseBody>().data.unwrap();
+    assert_eq!(data["key"], "secret");
+    assert_eq!(
+        data["request_id"],
+        response.header("x-request-id").to_str().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn custom_extractors_and_interceptors() {
+    let server = server();
+
+    let response = server.get("/extractors/tenant").await;
+    assert_eq!(response.status_code().as_u16(), 403);
+
+    let response = server
+        .get("/extractors/tenant")
+        .add_header("x-tenant", "acme")
+        .await;
+    assert_eq!(
+        response.json::<JsonResponseBody>().data,
+        Some(json!("acme"))
+    );
+
+    let response = server.get("/extractors/intercepted/5").await;
+    assert_eq!(response.status_code().as_u16(), 200);
+    assert_eq!(
+        response.json::<JsonResponseBody>().data,
+        Some(json!({ "id": 5, "tenant": "intercepted" }))
+    );
+
+    let response = server
+        .post("/extractors/mixed/9")
+        .json(&json!({ "name": "bob" }))
+        .await;
+    assert_eq!(
+        response.json::<JsonResponseBody>().data,
+        Some(json!({ "id": 9, "name": "bob" }))
+    );
+}
@@ -1,9 +1,14 @@
-use axum::body::to_bytes;
-use serde_json::json;
+use axum::body::{Body, to_bytes};
+use futures_lite::{StreamExt, stream};
+use serde::Deserialize;
+use serde_json::{Value, json};
 use sword::prelude::*;
+use sword::testing::TestApp;
 use sword::web::*;
+use tower::ServiceExt;
 
 use crate::application_builder;
+use crate::test_app;
 use crate::test_server;
 
 #[derive(Interceptor)]
@@ -67,11 +72,60 @@ impl StreamController {
     }
 }
 
+#[derive(Deserialize, Validate)]
+struct Row {
+    id: u32,
+    #[validate(length(min = 1))]
+    name: String,
+}
+
+/// Collects decoded ids and item errors, stopping at the first fatal error.
+async fn collect_rows(mut rows: JsonItemStream<Row>) -> WebResult {
+    let mut ids = Vec::new();
+    let mut errors = Vec::new();
+
+    while let Some(row) = rows.next().await {
+        match row {
+            Ok(row) => ids.push(row.id),
+            Err(error) => {
+                if matches!(error.error, RequestError::BodyTooLarge) {
+                    return Err(error.into());
+                }
+
+                errors.push(json!({ "index": error.index, "offset": error.offset }));
+            }
+        }
+    }
+
+    Ok(JsonResponse::Ok().data(json!({ "ids": ids, "errors": errors })))
+}
+
+#[controller(kind = Controller::Web, path = "/import")]
+struct ImportController;
+
+impl ImportController {
+    #[post("/lines")]
+    async fn lines(&self, req: StreamRequest) -> WebResult {
+        collect_rows(req.json_lines()).await
+    }
+
+    #[post("/array")]
+    async fn array(&self, req: StreamRequest) -> WebResult {
+        collect_rows(req.json_array()).await
+    }
+
+    #[post("/validated")]
+    async fn validated(&self, req: StreamRequest) -> WebResult {
+        collect_rows(req.json_lines_validator()).await
+    }
+}
+
 struct StreamModule;
 
 impl Module for StreamModule {
     fn register_controllers(controllers: &ControllerRegistry) {
         controllers.register::<StreamController>();
+        controllers.register::<ImportController>();
     }
 }
 
@@ -104,3 +158,93 @@ async fn stream_route_with_config_interceptor_works() {
     assert_eq!(body.data.as_ref().unwrap()["len"], 17);
     assert_eq!(body.data.as_ref().unwrap()["tag"], "stream-config");
 }
+
+fn import_app(body_limit: &str) -> TestApp {
+    test_app(&format!(
+        r#"
+        [web]
+        body-limit = "{body_limit}"
+        "#
+    ))
+    .with_module::<StreamModule>()
+    .build()
+}
+
+/// Sends the body one byte per chunk, so items span several chunks.
+async fn post_chunked(app: &TestApp, path: &str, body: &'static str) -> (u16, Value) {
+    let chunks = stream::iter(body.as_bytes().chunks(1))
+        .map(|chunk| Ok::<_, std::io::Error>(chunk.to_vec()));
+
+    let request = axum::http::Request::post(path)
+        .body(Body::from_stream(chunks))
+        .unwrap();
+
+    let response = app.router().oneshot(request).await.unwrap();
+    let status = response.status().as_u16();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn json_lines_are_decoded_per_item() {
+    let app = import_app("1MB");
+
+    let body = "{\"id\":1,\"name\":\"a\"}\n\n{\"id\":\"x\"}\n{\"id\":3,\"name\":\"c\"}";
+    let (status, json) = post_chunked(&app, "/import/lines", body).await;
+
+    assert_eq!(status, 200);
+    assert_eq!(json["data"]["ids"], json!([1, 3]));
+    assert_eq!(
+        json["data"]["errors"],
+        json!([{ "index": 1, "offset": 21 }])
+    );
+}
+
+#[tokio::test]
+async fn json_array_elements_are_decoded_as_they_arrive() {
+    let app = import_app("1MB");
+
+    let body = r#" [ {"id": 1, "name": "a, [b]"}, {"id": 2, "name": "\"}"} ] "#;
+    let (status, json) = post_chunked(&app, "/import/array", body).await;
+
+    assert_eq!(status, 200);
+    assert_eq!(json["data"]["ids"], json!([1, 2]));
+    assert_eq!(json["data"]["errors"], json!([]));
+
+    let (_, json) = post_chunked(&app, "/import/array", r#"[{"id": 1, "name": "a"},]"#).await;
+
+    assert_eq!(json["data"]["ids"], json!([1]));
+    assert_eq!(
+        json["data"]["errors"],
+        json!([{ "index": 1, "offset": 24 }])
+    );
+
+    let (_, json) = post_chunked(&app, "/import/array", "[]").await;
+    assert_eq!(json["data"]["ids"], json!([]));
+}
+
+#[tokio::test]
+async fn json_items_respect_the_body_limit() {
+    let app = import_app("16B");
+
+    let (status, _) = post_chunked(
+        &app,
+        "/import/lines",
+        "{\"id\":1,\"name\":\"a\"}\n{\"id\":2,\"name\":\"b\"}",
+    )
+    .await;
+
+    assert_eq!(status, 413);
+}
+
+#[tokio::test]
+async fn json_items_can_be_validated() {
+    let app = import_app("1MB");
+
+    let body = "{\"id\":1,\"name\":\"\"}\n{\"id\":2,\"name\":\"b\"}";
+    let (_, json) = post_chunked(&app, "/import/validated", body).await;
+
+    assert_eq!(json["data"]["ids"], json!([2]));
+    assert_eq!(json["data"]["errors"], json!([{ "index": 0, "offset": 0 }]));
+}
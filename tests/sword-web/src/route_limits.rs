@@ -0,0 +1,162 @@
+use axum::body::to_bytes;
+use serde_json::{Value, json};
+use sword::prelude::*;
+use sword::testing::TestApp;
+use sword::web::*;
+use tokio::time::{Duration, sleep};
+
+use crate::test_app;
+
+#[controller(kind = Controller::Web, path = "/limits")]
+struct LimitsController;
+
+impl LimitsController {
+    #[post("/default")]
+    async fn default(&self, req: Request) -> WebResult {
+        let body = req.body::<Value>()?;
+        Ok(JsonResponse::Ok().data(body))
+    }
+
+    #[post("/upload", body_limit = "1KB")]
+    async fn upload(&self, req: Request) -> WebResult {
+        let body = req.body::<Value>()?;
+        Ok(JsonResponse::Ok().data(body))
+    }
+
+    #[post("/stream-upload", body_limit = "1KB")]
+    async fn stream_upload(&self, req: StreamRequest) -> WebResult {
+        let body_limit = req.body_limit();
+
+        let body = to_bytes(req.into_body(), body_limit)
+            .await
+            .map_err(|_| JsonResponse::PayloadTooLarge())?;
+
+        Ok(JsonResponse::Ok().data(json!({ "len": body.len(), "limit": body_limit })))
+    }
+
+    #[get("/slow")]
+    async fn slow(&self) -> WebResult {
+        sleep(Duration::from_millis(300)).await;
+        Ok(JsonResponse::Ok())
+    }
+
+    #[get("/slow-allowed", timeout = "2s")]
+    async fn slow_allowed(&self) -> WebResult {
+        sleep(Duration::from_millis(300)).await;
+        Ok(JsonResponse::Ok())
+    }
+}
+
+#[controller(
+    kind = Controller::Web,
+    path = "/reports",
+    body_limit = "1KB",
+    timeout = "2s"
+)]
+struct ReportsController;
+
+impl ReportsController {
+    #[post("/")]
+    async fn create(&self, req: Request) -> WebResult {
+        sleep(Duration::from_millis(300)).await;
+
+        let body = req.body::<Value>()?;
+        Ok(JsonResponse::Ok().data(body))
+    }
+
+    #[post("/strict", body_limit = "16B", timeout = "100ms")]
+    async fn strict(&self, req: Request) -> WebResult {
+        let body = req.body::<Value>()?;
+
+        sleep(Duration::from_millis(300)).await;
+        Ok(JsonResponse::Ok().data(body))
+    }
+}
+
+struct RouteLimitsModule;
+
+impl Module for RouteLimitsModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<LimitsController>();
+        controllers.register::<ReportsController>();
+    }
+}
+
+fn limits_app() -> TestApp {
+    test_app(
+        r#"
+        [web]
+        body-limit = "32B"
+        request-timeout = { enabled = true, timeout = "100ms" }
+        "#,
+    )
+    .with_module::<RouteLimitsModule>()
+    .build()
+}
+
+fn payload(len: usize) -> Value {
+    json!({ "data": "x".repeat(len) })
+}
+
+#[tokio::test]
+async fn route_body_limit_overrides_the_global_limit() {
+    let app = limits_app();
+
+    let response = app.http().post("/limits/default").json(&payload(64)).await;
+    assert_eq!(response.status_code().as_u16(), 413);
+
+    let response = app.http().post("/limits/upload").json(&payload(64)).await;
+    assert_eq!(response.status_code().as_u16(), 200);
+
+    let response = app.http().post("/limits/upload").json(&payload(2048)).await;
+    assert_eq!(response.status_code().as_u16(), 413);
+}
+
+#[tokio::test]
+async fn route_body_limit_applies_to_stream_requests() {
+    let app = limits_app();
+
+    let response = app
+        .http()
+        .post("/limits/stream-upload")
+        .text("x".repeat(512))
+        .await;
+
+    let body = response.json::<JsonResponseBody>();
+
+    assert_eq!(response.status_code().as_u16(), 200);
+    assert_eq!(body.data, Some(json!({ "len": 512, "limit": 1000 })));
+
+    let response = app
+        .http()
+        .post("/limits/stream-upload")
+        .text("x".repeat(2048))
+        .await;
+
+    assert_eq!(response.status_code().as_u16(), 413);
+}
+
+#[tokio::test]
+async fn route_timeout_overrides_the_global_timeout() {
+    let app = limits_app();
+
+    let response = app.http().get("/limits/slow").await;
+    assert_eq!(response.status_code().as_u16(), 408);
+
+    let response = app.http().get("/limits/slow-allowed").await;
+    assert_eq!(response.status_code().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn controller_limits_apply_to_its_routes_unless_overridden() {
+    let app = limits_app();
+
+    let response = app.http().post("/reports").json(&payload(64)).await;
+    assert_eq!(response.status_code().as_u16(), 200);
+
+    let response = app.http().post("/reports/strict").json(&payload(64)).await;
+    assert_eq!(response.status_code().as_u16(), 413);
+
+    let response = app.http().post("/reports/strict").json(&json!({})).await;
+    assert_eq!(response.status_code().as_u16(), 408);
+}
@@ -0,0 +1,365 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sword::prelude::*;
+use sword::testing::{TestApp, TestAppBuilder};
+use sword::web::*;
+
+use crate::test_app;
+
+const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+#[derive(Serialize, Deserialize)]
+struct User {
+    id: u32,
+    name: String,
+}
+
+#[controller(kind = Controller::Web, path = "/account")]
+struct AccountController;
+
+impl AccountController {
+    #[post("/login")]
+    async fn login(&self, req: Request) -> WebResult {
+        let session = req.session()?;
+
+        session.rotate_id();
+        session.insert(
+            "user",
+            User {
+                id: 1,
+                name: "ana".into(),
+            },
+        )?;
+
+        Ok(JsonResponse::Ok())
+    }
+
+    #[get("/me")]
+    async fn me(&self, req: Request) -> WebResult {
+        let user = req
+            .session()?
+            .get::<User>("user")?
+            .ok_or_else(|| JsonResponse::Unauthorized().message("Not logged in"))?;
+
+        Ok(JsonResponse::Ok().data(json!({ "id": user.id, "name": user.name })))
+    }
+
+    #[post("/visits")]
+    async fn visits(&self, req: StreamRequest) -> WebResult {
+        let session = req.session()?;
+        let visits = session.get::<u32>("visits")?.unwrap_or_default() + 1;
+
+        session.insert("visits", visits)?;
+
+        Ok(JsonResponse::Ok().data(visits))
+    }
+
+    #[post("/logout")]
+    async fn logout(&self, req: Request) -> WebResult {
+        let session = req.session()?;
+        let user = session.remove::<User>("user")?;
+
+        session.destroy();
+
+        Ok(JsonResponse::Ok().data(user.map(|user| user.name)))
+    }
+}
+
+struct SessionsModule;
+
+impl Module for SessionsModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<AccountController>();
+    }
+}
+
+fn session_app(session: &str) -> TestAppBuilder {
+    test_app(&format!(
+        r#"
+        [web.session]
+        secret = "{SECRET}"
+        {session}
+        "#
+    ))
+    .with_module::<SessionsModule>()
+}
+
+/// The `name=value` pair of the session cookie set by a response.
+fn session_cookie(response: &axum_test::TestResponse) -> String {
+    let header = response.header("set-cookie");
+    let header = header.to_str().unwrap();
+
+    assert!(header.starts_with("sword.sid="));
+    assert!(header.contains("HttpOnly"));
+
+    header.split(';').next().unwrap().to_string()
+}
+
+async fn me(app: &TestApp, cookie: &str) -> axum_test::TestResponse {
+    app.http()
+        .get("/account/me")
+        .add_header("cookie", cookie)
+        .await
+}
+
+/// Login, typed reads, id rotation and logout against the configured store.
+async fn assert_session_lifecycle(app: &TestApp) {
+    let response = app.http().post("/account/visits").await;
+    assert_eq!(response.json::<JsonResponseBody>().data, Some(json!(1)));
+    let anonymous = session_cookie(&response);
+
+    let response = app
+        .http()
+        .post("/account/visits")
+        .add_header("cookie", &anonymous)
+        .await;
+
+    assert_eq!(response.json::<JsonResponseBody>().data, Some(json!(2)));
+    assert!(response.maybe_header("set-cookie").is_none());
+
+    let response = app
+        .http()
+        .post("/account/login")
+        .add_header("cookie", &anonymous)
+        .await;
+
+    let cookie = session_cookie(&response);
+    assert_ne!(cookie, anonymous);
+
+    let response = me(app, &cookie).await;
+    response.assert_status_ok();
+    assert_eq!(
+        response.json::<JsonResponseBody>().data,
+        Some(json!({ "id": 1, "name": "ana" }))
+    );
+
+    // Data survives the rotation, the old id doesn't.
+    let response = app
+        .http()
+        .post("/account/visits")
+        .add_header("cookie", &cookie)
+        .await;
+
+    assert_eq!(response.json::<JsonResponseBody>().data, Some(json!(3)));
+    me(app, &anonymous).await.assert_status_unauthorized();
+
+    let response = app
+        .http()
+        .post("/account/logout")
+        .add_header("cookie", &cookie)
+        .await;
+
+    assert_eq!(response.json::<JsonResponseBody>().data, Some(json!("ana")));
+    assert!(
+        response
+            .header("set-cookie")
+            .to_str()
+            .unwrap()
+            .contains("Max-Age=0")
+    );
+
+    me(app, &cookie).await.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn memory_store_keeps_sessions_across_requests() {
+    let app = session_app("").build();
+
+    assert_session_lifecycle(&app).await;
+
+    app.http()
+        .get("/account/me")
+        .add_header("cookie", "sword.sid=forged")
+        .await
+        .assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn file_and_sql_stores_keep_sessions() {
+    let dir = std::env::temp_dir().join(format!("sword-sessions-{}", std::process::id()));
+
+    let app = session_app(&format!(
+        r#"
+        store = "file"
+        path = "{}"
+        "#,
+        dir.join("files").display()
+    ))
+    .build();
+
+    assert_session_lifecycle(&app).await;
+
+    let app = session_app(&format!(
+        r#"
+        store = "sql"
+        url = "sqlite://{}?mode=rwc"
+        "#,
+        dir.join("sessions.db").display()
+    ))
+    .build();
+
+    assert_session_lifecycle(&app).await;
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn sessions_expire_after_idle_and_absolute_timeouts() {
+    let app = session_app(r#"idle-timeout = "1s""#).build();
+
+    let cookie = session_cookie(&app.http().post("/account/login").await);
+    me(&app, &cookie).await.assert_status_ok();
+
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    me(&app, &cookie).await.assert_status_unauthorized();
+
+    let app = session_app(r#"absolute-timeout = "1s""#).build();
+
+    let cookie = session_cookie(&app.http().post("/account/login").await);
+
+    for _ in 0..3 {
+        me(&app, &cookie).await.assert_status_ok();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    me(&app, &cookie).await.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn custom_stores_replace_the_configured_one() {
+    let store = MemoryStore::new();
+
+    let app = session_app(r#"store = "file""#)
+        .with_provider(SessionStoreProvider::new(store.clone()))
+        .build();
+
+    let cookie = session_cookie(&app.http().post("/account/login").await);
+    me(&app, &cookie).await.assert_status_ok();
+
+    // Signed cookie values end with the session id.
+    let id = &cookie[cookie.len() - 32..];
+    let record = store.load(id).await.unwrap().unwrap();
+
+    assert_eq!(record.data["user"], json!({ "id": 1, "name": "ana" }));
+    assert!(record.expires_at > record.created_at);
+    assert!(!std::path::Path::new("sessions").exists());
+}
+
+/// A record expiring `ttl` from now, at millisecond precision like `SqlStore`.
+fn record(user: &str, ttl: Duration) -> SessionRecord {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    let created_at = UNIX_EPOCH + Duration::from_millis(now.as_millis() as u64);
+
+    let mut data = serde_json::Map::new();
+    data.insert("user".into(), json!(user));
+
+    SessionRecord {
+        data,
+        created_at,
+        expires_at: created_at + ttl,
+    }
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("sword-{name}-{}", std::process::id()))
+}
+
+#[tokio::test]
+async fn file_store_round_trips_records() {
+    let dir = temp_dir("file-store");
+    let store = FileStore::new(&dir).unwrap();
+    let saved = record("ana", Duration::from_secs(60));
+
+    store.save("abc123", &saved).await.unwrap();
+    assert_eq!(store.load("abc123").await.unwrap(), Some(saved));
+
+    // A second store on the same directory sees the record.
+    let reopened = FileStore::new(&dir).unwrap();
+    assert!(reopened.load("abc123").await.unwrap().is_some());
+
+    store.delete("abc123").await.unwrap();
+    assert_eq!(store.load("abc123").await.unwrap(), None);
+    store.delete("abc123").await.unwrap();
+
+    // Ids that could escape the directory are never read or written.
+    assert_eq!(store.load("../abc123").await.unwrap(), None);
+    assert!(
+        store
+            .save("../abc123", &record("ana", Duration::from_secs(60)))
+            .await
+            .is_err()
+    );
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn sql_store_loads_commits_and_expires_records() {
+    let dir = temp_dir("sql-store");
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let url = format!("sqlite://{}?mode=rwc", dir.join("sessions.db").display());
+    let store = SqlStore::new(&url).unwrap();
+
+    assert_eq!(store.load("abc123").await.unwrap(), None);
+
+    let saved = record("ana", Duration::from_secs(60));
+    store.save("abc123", &saved).await.unwrap();
+    assert_eq!(store.load("abc123").await.unwrap(), Some(saved.clone()));
+
+    // Committing again replaces the data and expiry but keeps the creation time.
+    let mut updated = record("bea", Duration::from_secs(120));
+    updated.created_at = SystemTime::now() + Duration::from_secs(30);
+    store.save("abc123", &updated).await.unwrap();
+
+    let loaded = store.load("abc123").await.unwrap().unwrap();
+    assert_eq!(loaded.data["user"], json!("bea"));
+    assert_eq!(loaded.expires_at, updated.expires_at);
+    assert_eq!(loaded.created_at, saved.created_at);
+
+    // Expired rows are removed as sessions are written.
+    let mut expired = record("cid", Duration::ZERO);
+    expired.expires_at -= Duration::from_secs(1);
+    assert!(expired.is_expired());
+
+    store.save("expired", &expired).await.unwrap();
+    assert_eq!(store.load("expired").await.unwrap(), None);
+    assert!(store.load("abc123").await.unwrap().is_some());
+
+    store.delete("abc123").await.unwrap();
+    assert_eq!(store.load("abc123").await.unwrap(), None);
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn rotate_id_deletes_the_previous_record() {
+    let store = MemoryStore::new();
+
+    let app = session_app("")
+        .with_provider(SessionStoreProvider::new(store.clone()))
+        .build();
+
+    let anonymous = session_cookie(&app.http().post("/account/visits").await);
+    let anonymous_id = &anonymous[anonymous.len() - 32..];
+    assert!(store.load(anonymous_id).await.unwrap().is_some());
+
+    let response = app
+        .http()
+        .post("/account/login")
+        .add_header("cookie", &anonymous)
+        .await;
+
+    let cookie = session_cookie(&response);
+    let id = &cookie[cookie.len() - 32..];
+
+    assert_ne!(id, anonymous_id);
+    assert_eq!(store.load(anonymous_id).await.unwrap(), None);
+
+    let record = store.load(id).await.unwrap().unwrap();
+    assert_eq!(record.data["visits"], json!(1));
+    assert_eq!(record.data["user"], json!({ "id": 1, "name": "ana" }));
+}
@@ -0,0 +1,144 @@
+use axum::body::Body;
+use futures_lite::{StreamExt, stream};
+use serde_json::json;
+use std::time::Duration;
+use sword::prelude::*;
+use sword::testing::TestApp;
+use sword::web::*;
+use tower::ServiceExt;
+
+use crate::test_app;
+
+#[controller(kind = Controller::Web, path = "/sse")]
+struct SseController;
+
+impl SseController {
+    #[sse("/ticks")]
+    async fn ticks(&self, LastEventId(last_id): LastEventId) -> SseResponse {
+        let start = last_id.and_then(|id| id.parse::<u32>().ok()).unwrap_or(0) + 1;
+
+        let events = stream::iter(start..=3).map(|tick| {
+            SseEvent::new(json!({ "tick": tick }))
+                .event("tick")
+                .id(tick.to_string())
+        });
+
+        SseResponse::new(events)
+            .retry(Duration::from_secs(3))
+            .without_keep_alive()
+    }
+
+    #[sse("/slow")]
+    async fn slow(&self) -> SseResponse {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        SseResponse::new(stream::once(SseEvent::new("ready")))
+    }
+
+    #[get("/slow-json")]
+    async fn slow_json(&self) -> WebResult {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        Ok(JsonResponse::Ok())
+    }
+
+    #[sse("/endless")]
+    async fn endless(&self) -> SseResponse {
+        let events = stream::once(SseEvent::new("first")).chain(stream::pending());
+        SseResponse::new(events).without_keep_alive()
+    }
+}
+
+struct SseModule;
+
+impl Module for SseModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<SseController>();
+    }
+}
+
+fn app() -> TestApp {
+    test_app(
+        r#"
+        [web]
+        request-timeout = { enabled = true, timeout = "100ms" }
+        "#,
+    )
+    .with_module::<SseModule>()
+    .build()
+}
+
+#[tokio::test]
+async fn events_are_streamed_as_event_stream() {
+    let app = app();
+    let response = app.http().get("/sse/ticks").await;
+
+    response.assert_status_ok();
+    assert_eq!(response.header("content-type"), "text/event-stream");
+    assert_eq!(response.header("cache-control"), "no-cache");
+
+    let events = response
+        .text()
+        .split("\n\n")
+        .filter(|event| !event.is_empty())
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+
+    assert_eq!(events[0], "retry: 3000");
+    assert_eq!(events[1], "data: {\"tick\":1}\nevent: tick\nid: 1");
+    assert_eq!(events.len(), 4);
+}
+
+#[tokio::test]
+async fn last_event_id_resumes_the_stream() {
+    let app = app();
+
+    let body = app
+        .http()
+        .get("/sse/ticks")
+        .add_header("last-event-id", "2")
+        .await
+        .text();
+
+    assert!(!body.contains("id: 2"), "{body}");
+    assert!(
+        body.contains("data: {\"tick\":3}\nevent: tick\nid: 3"),
+        "{body}"
+    );
+}
+
+#[tokio::test]
+async fn sse_routes_are_exempt_from_request_timeout() {
+    let app = app();
+
+    let response = app.http().get("/sse/slow").await;
+
+    response.assert_status_ok();
+    assert!(response.text().contains("data: \"ready\""));
+
+    app.http()
+        .get("/sse/slow-json")
+        .await
+        .assert_status(axum::http::StatusCode::REQUEST_TIMEOUT);
+}
+
+#[tokio::test]
+async fn streams_end_on_graceful_shutdown() {
+    let app = app();
+
+    let request = axum::http::Request::get("/sse/endless")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.router().oneshot(request).await.unwrap();
+    let mut body = response.into_body().into_data_stream();
+
+    let first = body.next().await.unwrap().unwrap();
+    assert_eq!(first, "data: \"first\"\n\n");
+
+    app.get::<GracefulShutdown>().unwrap().trigger();
+
+    let next = tokio::time::timeout(Duration::from_secs(1), body.next())
+        .await
+        .expect("stream should end after shutdown");
+
+    assert!(next.is_none());
+}
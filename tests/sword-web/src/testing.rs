@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use sword::prelude::*;
+use sword::testing::TestApp;
+use sword::web::*;
+
+use crate::test_app;
+
+#[config(key = "greeting")]
+#[derive(Clone, Serialize, Deserialize)]
+struct GreetingConfig {
+    text: String,
+}
+
+#[controller(kind = Controller::Web, path = "/greet")]
+struct GreetingController {
+    greeting: GreetingConfig,
+}
+
+impl GreetingController {
+    #[get("/")]
+    async fn greet(&self) -> JsonResponse {
+        tracing::info!(target: "greeting", text = %self.greeting.text, "greeting sent");
+
+        JsonResponse::Ok().data(&self.greeting.text)
+    }
+}
+
+struct GreetingModule;
+
+impl Module for GreetingModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<GreetingController>();
+    }
+}
+
+fn greeting_app(text: &str) -> TestApp {
+    test_app(&format!(
+        r#"
+        [greeting]
+        text = "{text}"
+        "#
+    ))
+    .with_module::<GreetingModule>()
+    .build()
+}
+
+#[tokio::test]
+async fn test_app_serves_requests_in_memory() {
+    let app = greeting_app("hello");
+
+    let response = app.http().get("/greet").await;
+    let body = response.json::<JsonResponseBody>();
+
+    response.assert_status_ok();
+    assert_eq!(body.data, Some(serde_json::json!("hello")));
+}
+
+#[tokio::test]
+async fn test_apps_have_isolated_state() {
+    let first = greeting_app("first");
+    let second = greeting_app("second");
+
+    assert_eq!(first.get::<GreetingConfig>().unwrap().text, "first");
+    assert_eq!(second.get::<GreetingConfig>().unwrap().text, "second");
+
+    let body = second.http().get("/greet").await.json::<JsonResponseBody>();
+    assert_eq!(body.data, Some(serde_json::json!("second")));
+}
+
+#[tokio::test]
+async fn test_apps_capture_their_own_tracing_events() {
+    let first = greeting_app("first");
+    let second = greeting_app("second");
+
+    first.http().get("/greet").await.assert_status_ok();
+
+    let events = first.tracing().events();
+    let greeting = events
+        .iter()
+        .find(|event| event.target == "greeting")
+        .expect("handler event must be captured");
+
+    assert_eq!(greeting.message, "greeting sent");
+    assert_eq!(
+        greeting.fields.get("text").map(String::as_str),
+        Some("first")
+    );
+
+    assert!(!second.tracing().contains("greeting sent"));
+}
@@ -0,0 +1,358 @@
+use rcgen::{BasicConstraints, CertificateParams, CertifiedIssuer, DnType, IsCa, KeyPair};
+use serde_json::json;
+use std::{
+    net::TcpListener as StdTcpListener,
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+use sword::prelude::*;
+use sword::testing::Config;
+use sword::web::*;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+use tokio_rustls::{
+    TlsConnector,
+    client::TlsStream,
+    rustls::{
+        ClientConfig, RootCertStore,
+        crypto::ring,
+        pki_types::{CertificateDer, PrivateKeyDer, ServerName, pem::PemObject},
+    },
+};
+
+use crate::test_toml;
+
+#[derive(Interceptor)]
+struct RequireClientCertificate;
+
+impl OnRequest for RequireClientCertificate {
+    async fn on_request(&self, req: Request) -> WebInterceptorResult {
+        match req.peer_identity() {
+            Some(identity) if identity.common_name() == Some("worker") => req.next().await,
+            _ => Err(JsonResponse::Forbidden().message("Unknown client certificate")),
+        }
+    }
+}
+
+#[controller(kind = Controller::Web, path = "/tls")]
+struct TlsController;
+
+impl TlsController {
+    #[get("/whoami")]
+    async fn whoami(&self, req: Request) -> WebResult {
+        let identity = req.peer_identity();
+
+        Ok(JsonResponse::Ok().data(json!({
+            "cn": identity.and_then(PeerIdentity::common_name),
+            "dns": identity.map(PeerIdentity::dns_names),
+            "peer": req.peer_addr().is_some(),
+        })))
+    }
+
+    #[get("/workers")]
+    #[interceptor(RequireClientCertificate)]
+    async fn workers(&self) -> WebResult {
+        Ok(JsonResponse::Ok().message("worker accepted"))
+    }
+}
+
+struct TlsModule;
+
+impl Module for TlsModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<TlsController>();
+    }
+}
+
+/// A throwaway CA that issues certificates into a temporary directory.
+struct Pki {
+    dir: PathBuf,
+    ca: CertifiedIssuer<'static, KeyPair>,
+}
+
+impl Pki {
+    fn new() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+        let dir = std::env::temp_dir().join(format!(
+            "sword-tls-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "sword test CA");
+
+        let ca = CertifiedIssuer::self_signed(params, KeyPair::generate().unwrap()).unwrap();
+
+        Self { dir, ca }
+    }
+
+    /// Issues a certificate and returns its PEM chain and key.
+    fn issue(&self, common_name: &str, names: &[&str]) -> (String, String) {
+        let names = names.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let mut params = CertificateParams::new(names).unwrap();
+        params
+            .distinguished_name
+            .push(DnType::CommonName, common_name);
+
+        let key = KeyPair::generate().unwrap();
+        let certificate = params.signed_by(&key, &self.ca).unwrap();
+
+        (certificate.pem(), key.serialize_pem())
+    }
+
+    fn write(&self, name: &str, contents: &str) -> String {
+        let path = self.dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path.display().to_string()
+    }
+
+    /// Writes a server certificate for `localhost` and returns the `[web.tls]` paths.
+    fn server_files(&self, common_name: &str) -> (String, String) {
+        let (cert, key) = self.issue(common_name, &["localhost"]);
+        (
+            self.write("server.pem", &cert),
+            self.write("server.key", &key),
+        )
+    }
+
+    fn client_config(&self, alpn: &[&[u8]], client: Option<(String, String)>) -> ClientConfig {
+        let mut roots = RootCertStore::empty();
+        roots.add(self.ca.der().clone()).unwrap();
+
+        let builder = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots);
+
+        let mut config = match client {
+            Some((cert, key)) => builder
+                .with_client_auth_cert(
+                    CertificateDer::pem_slice_iter(cert.as_bytes())
+                        .collect::<Result<_, _>>()
+                        .unwrap(),
+                    PrivateKeyDer::from_pem_slice(key.as_bytes()).unwrap(),
+                )
+                .unwrap(),
+            None => builder.with_no_client_auth(),
+        };
+
+        config.alpn_protocols = alpn.iter().map(|protocol| protocol.to_vec()).collect();
+        config
+    }
+}
+
+impl Drop for Pki {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Runs the application on a free port and waits until it accepts connections.
+async fn start(tls: &str) -> u16 {
+    let port = StdTcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let config = Config::builder()
+        .add_toml_str(&test_toml(&format!(
+            r#"
+            [tracing]
+            enabled = false
+
+            [web]
+            host = "127.0.0.1"
+            port = {port}
+            tls = {tls}
+            "#
+        )))
+        .build()
+        .unwrap();
+
+    let app = Application::from_config(config)
+        .with_module::<TlsModule>()
+        .build();
+
+    tokio::spawn(async move { app.run().await });
+
+    for _ in 0..100 {
+        if TcpStream::connect(("127.0.0.1", port)).await.is_ok() {
+            return port;
+        }
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    panic!("TLS test server did not start");
+}
+
+async fn connect(port: u16, config: ClientConfig) -> std::io::Result<TlsStream<TcpStream>> {
+    let stream = TcpStream::connect(("127.0.0.1", port)).await?;
+
+    TlsConnector::from(Arc::new(config))
+        .connect(ServerName::try_from("localhost").unwrap(), stream)
+        .await
+}
+
+/// Sends an HTTP/1.1 GET over a TLS connection and returns the raw response.
+async fn get(port: u16, config: ClientConfig, path: &str) -> std::io::Result<String> {
+    let mut stream = connect(port, config).await?;
+
+    stream
+        .write_all(
+            format!("GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                .as_bytes(),
+        )
+        .await?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+
+    Ok(response)
+}
+
+#[tokio::test]
+async fn https_requests_are_served_with_http2_alpn() {
+    let pki = Pki::new();
+    let (cert, key) = pki.server_files("server");
+
+    let port = start(&format!(
+        r#"{{ enabled = true, cert = "{cert}", key = "{key}" }}"#
+    ))
+    .await;
+
+    let stream = connect(port, pki.client_config(&[b"h2", b"http/1.1"], None))
+        .await
+        .unwrap();
+
+    assert_eq!(stream.get_ref().1.alpn_protocol(), Some(&b"h2"[..]));
+
+    let response = get(port, pki.client_config(&[b"http/1.1"], None), "/tls/whoami")
+        .await
+        .unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+    assert!(response.contains(r#""cn":null"#), "{response}");
+    assert!(response.contains(r#""peer":true"#), "{response}");
+}
+
+#[tokio::test]
+async fn client_certificates_reach_interceptors() {
+    let pki = Pki::new();
+    let (cert, key) = pki.server_files("server");
+    let ca = pki.write("ca.pem", &pki.ca.pem());
+
+    let port = start(&format!(
+        r#"{{ enabled = true, cert = "{cert}", key = "{key}", client-ca = "{ca}", client-auth = "optional" }}"#
+    ))
+    .await;
+
+    let worker = pki.issue("worker", &["worker.internal"]);
+    let response = get(
+        port,
+        pki.client_config(&[], Some(worker.clone())),
+        "/tls/whoami",
+    )
+    .await
+    .unwrap();
+
+    assert!(response.contains(r#""cn":"worker""#), "{response}");
+    assert!(
+        response.contains(r#""dns":["worker.internal"]"#),
+        "{response}"
+    );
+
+    let response = get(port, pki.client_config(&[], Some(worker)), "/tls/workers")
+        .await
+        .unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+    let other = pki.issue("other", &[]);
+    let response = get(port, pki.client_config(&[], Some(other)), "/tls/workers")
+        .await
+        .unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 403"), "{response}");
+
+    let response = get(port, pki.client_config(&[], None), "/tls/workers")
+        .await
+        .unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 403"), "{response}");
+}
+
+#[tokio::test]
+async fn required_client_certificates_reject_anonymous_clients() {
+    let pki = Pki::new();
+    let (cert, key) = pki.server_files("server");
+    let ca = pki.write("ca.pem", &pki.ca.pem());
+
+    let port = start(&format!(
+        r#"{{ enabled = true, cert = "{cert}", key = "{key}", client-ca = "{ca}" }}"#
+    ))
+    .await;
+
+    let anonymous = get(port, pki.client_config(&[], None), "/tls/whoami").await;
+    assert!(anonymous.is_err() || anonymous.unwrap().is_empty());
+
+    let worker = pki.issue("worker", &[]);
+    let response = get(port, pki.client_config(&[], Some(worker)), "/tls/whoami")
+        .await
+        .unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+}
+
+#[tokio::test]
+async fn certificates_are_reloaded_when_files_change() {
+    let pki = Pki::new();
+    let (cert, key) = pki.server_files("first");
+
+    let port = start(&format!(
+        r#"{{ enabled = true, cert = "{cert}", key = "{key}", reload-interval = "50ms" }}"#
+    ))
+    .await;
+
+    let served_common_name = || async {
+        let stream = connect(port, pki.client_config(&[], None)).await.unwrap();
+        let certificates = stream.get_ref().1.peer_certificates().unwrap();
+
+        PeerIdentity::from_certificates(certificates)
+            .and_then(|identity| identity.common_name().map(ToString::to_string))
+    };
+
+    assert_eq!(served_common_name().await.as_deref(), Some("first"));
+
+    // Rotating to files that don't parse keeps the current certificate.
+    pki.write("server.pem", "not a certificate");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(served_common_name().await.as_deref(), Some("first"));
+
+    pki.server_files("second");
+
+    for _ in 0..100 {
+        if served_common_name().await.as_deref() == Some("second") {
+            return;
+        }
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    panic!("the rotated certificate was never served");
+}
@@ -0,0 +1,190 @@
+use serde_json::json;
+use sword::prelude::*;
+use sword::testing::TestApp;
+use sword::web::*;
+
+use crate::test_app;
+
+#[controller(kind = Controller::Web, path = "/users", version = "1")]
+struct UsersV1Controller;
+
+impl UsersV1Controller {
+    #[get("/")]
+    async fn list(&self, version: ApiVersion) -> WebResult {
+        Ok(JsonResponse::Ok().data(json!({ "version": version.as_str(), "users": ["ana"] })))
+    }
+}
+
+#[controller(kind = Controller::Web, path = "/users", version = "2")]
+struct UsersV2Controller;
+
+impl UsersV2Controller {
+    #[get("/")]
+    async fn list(&self, version: ApiVersion) -> WebResult {
+        Ok(JsonResponse::Ok().data(json!({
+            "version": version.as_str(),
+            "users": [{ "name": "ana" }],
+        })))
+    }
+}
+
+#[controller(kind = Controller::Web, path = "/status")]
+struct StatusController;
+
+impl StatusController {
+    #[get("/")]
+    async fn status(&self) -> WebResult {
+        Ok(JsonResponse::Ok().message("unversioned"))
+    }
+}
+
+struct VersioningModule;
+
+impl Module for VersioningModule {
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<UsersV1Controller>();
+        controllers.register::<UsersV2Controller>();
+        controllers.register::<StatusController>();
+    }
+}
+
+fn versioned_app(versioning: &str) -> TestApp {
+    test_app(&format!(
+        r#"
+        [web.versioning]
+        {versioning}
+
+        [web.versioning.versions."1"]
+        deprecation = "Wed, 01 Jan 2025 00:00:00 GMT"
+        sunset = "Thu, 01 Jan 2026 00:00:00 GMT"
+        link = "https://example.com/migrating-to-v2"
+        "#
+    ))
+    .with_module::<VersioningModule>()
+    .build()
+}
+
+fn served_version(response: &axum_test::TestResponse) -> serde_json::Value {
+    response.json::<JsonResponseBody>().data.unwrap()["version"].clone()
+}
+
+#[tokio::test]
+async fn path_strategy_serves_versions_under_url_segments() {
+    let app = versioned_app(r#"default-version = "1""#);
+
+    let response = app.http().get("/v2/users").await;
+    response.assert_status_ok();
+    assert_eq!(served_version(&response), json!("2"));
+    assert!(response.maybe_header("deprecation").is_none());
+
+    let response = app.http().get("/v1/users").await;
+    assert_eq!(served_version(&response), json!("1"));
+
+    let response = app.http().get("/users").await;
+    assert_eq!(served_version(&response), json!("1"));
+
+    let response = app.http().get("/status").await;
+    response.assert_status_ok();
+
+    let response = app.http().get("/v3/users").await;
+    response.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn deprecated_versions_send_deprecation_headers() {
+    let app = versioned_app("");
+
+    let response = app.http().get("/v1/users").await;
+
+    assert_eq!(response.header("deprecation"), "@1735689600");
+    assert_eq!(response.header("sunset"), "Thu, 01 Jan 2026 00:00:00 GMT");
+    assert_eq!(
+        response.header("link"),
+        r#"<https://example.com/migrating-to-v2>; rel="deprecation""#
+    );
+
+    app.http().get("/users").await.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn header_strategy_selects_the_requested_version() {
+    let app = versioned_app(
+        r#"
+        strategy = "header"
+        default-version = "2"
+        "#,
+    );
+
+    let response = app
+        .http()
+        .get("/users")
+        .add_header("accept-version", "1")
+        .await;
+
+    assert_eq!(served_version(&response), json!("1"));
+    assert_eq!(response.header("vary"), "accept-version");
+    assert_eq!(response.header("deprecation"), "@1735689600");
+
+    let response = app.http().get("/users").await;
+    assert_eq!(served_version(&response), json!("2"));
+
+    let response = app
+        .http()
+        .get("/users")
+        .add_header("accept-version", "9")
+        .await;
+
+    response.assert_status_bad_request();
+
+    let response = app
+        .http()
+        .get("/status")
+        .add_header("accept-version", "9")
+        .await;
+
+    response.assert_status_ok();
+}
+
+#[tokio::test]
+async fn media_type_strategy_reads_the_accept_parameter() {
+    let app = versioned_app(r#"strategy = "media-type""#);
+
+    let response = app
+        .http()
+        .get("/users")
+        .add_header("accept", "application/json; version=2")
+        .await;
+
+    assert_eq!(served_version(&response), json!("2"));
+
+    let response = app
+        .http()
+        .get("/users")
+        .add_header("accept", "application/json")
+        .await;
+
+    response.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn unknown_paths_are_not_found_without_a_version() {
+    let app = versioned_app(r#"strategy = "header""#);
+
+    let response = app.http().get("/favicon.ico").await;
+    response.assert_status_not_found();
+
+    let response = app
+        .http()
+        .get("/favicon.ico")
+        .add_header("accept-version", "9")
+        .await;
+
+    response.assert_status_not_found();
+    assert_eq!(
+        response.json::<JsonResponseBody>().message.to_string(),
+        "The requested resource was not found."
+    );
+
+    let response = app.http().get("/users").await;
+    response.assert_status_bad_request();
+}
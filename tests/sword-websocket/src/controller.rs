@@ -0,0 +1,306 @@
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use sword::prelude::*;
+use sword::testing::TestApp;
+use sword::web::{GracefulShutdown, JsonResponse};
+use sword::websocket::*;
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, connect_async,
+    tungstenite::{self, client::IntoClientRequest},
+};
+
+type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Reading {
+    device: String,
+    value: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Ack {
+    device: String,
+    accepted: bool,
+}
+
+#[injectable(provider)]
+struct CloseLog {
+    codes: Arc<Mutex<Vec<u16>>>,
+}
+
+#[derive(Interceptor)]
+struct DeviceTokenInterceptor;
+
+impl OnUpgrade for DeviceTokenInterceptor {
+    type Error = JsonResponse;
+
+    async fn on_upgrade(&self, ctx: WebSocketContext) -> Result<(), Self::Error> {
+        match ctx.headers().get("x-device-token") {
+            Some(token) if token == "secret" => Ok(()),
+            _ => Err(JsonResponse::Unauthorized().message("Missing device token")),
+        }
+    }
+}
+
+#[controller(kind = Controller::WebSocket, path = "/devices")]
+#[interceptor(DeviceTokenInterceptor)]
+struct DevicesController {
+    closes: CloseLog,
+}
+
+impl DevicesController {
+    #[on("connection")]
+    async fn on_connection(&self, ctx: WebSocketContext) {
+        ctx.sender::<&str>().send(&"welcome").await.unwrap();
+    }
+
+    #[on("text")]
+    async fn on_text(&self, ctx: WebSocketContext) {
+        if ctx.text() == Some("flood") {
+            flood(ctx.sender()).await;
+            ctx.sender::<&str>().send(&"done").await.unwrap();
+
+            return;
+        }
+
+        let ack = match ctx.try_data::<Reading>() {
+            Ok(reading) => Ack {
+                device: reading.device,
+                accepted: reading.value >= 0.0,
+            },
+            Err(_) => Ack {
+                device: ctx.text().unwrap_or_default().to_string(),
+                accepted: false,
+            },
+        };
+
+        ctx.sender::<Ack>().send(&ack).await.unwrap();
+    }
+
+    #[on("binary")]
+    async fn on_binary(&self, ctx: WebSocketContext) {
+        let reading = ctx.try_data::<Reading>().unwrap();
+
+        ctx.sender::<Ack>()
+            .send_binary(&Ack {
+                device: reading.device,
+                accepted: true,
+            })
+            .await
+            .unwrap();
+    }
+
+    #[on("close")]
+    async fn on_close(&self, ctx: WebSocketContext) {
+        let code = ctx.close_frame().map(|frame| frame.code).unwrap_or(0);
+        self.closes.codes.lock().await.push(code);
+    }
+}
+
+/// Queues numbered frames until the outgoing buffer is full, then sends
+/// `FLOOD_AFTER_FULL` more that have to wait for room.
+async fn flood(sender: WebSocketSender<usize>) {
+    let mut sent = 0;
+
+    loop {
+        match sender.try_send(&sent) {
+            Ok(()) => sent += 1,
+            Err(WebSocketError::Full) => break,
+            Err(error) => panic!("unexpected send error: {error}"),
+        }
+    }
+
+    for n in sent..sent + FLOOD_AFTER_FULL {
+        sender.send(&n).await.unwrap();
+    }
+}
+
+const FLOOD_AFTER_FULL: usize = 200;
+
+struct DevicesModule;
+
+impl Module for DevicesModule {
+    async fn register_providers(_: &Config, providers: &ProviderRegistry) {
+        providers.register(CloseLog {
+            codes: Arc::default(),
+        });
+    }
+
+    fn register_controllers(controllers: &ControllerRegistry) {
+        controllers.register::<DevicesController>();
+    }
+}
+
+fn app() -> TestApp {
+    TestApp::from_toml(
+        r#"
+        [web]
+        host = "0.0.0.0"
+        port = 8080
+        body-limit = "1MB"
+        request-timeout = { enabled = true, timeout = "100ms" }
+        "#,
+    )
+    .with_module::<DevicesModule>()
+    .build()
+}
+
+async fn serve(app: &TestApp) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    tokio::spawn(axum::serve(listener, app.router()).into_future());
+
+    format!("ws://{address}/devices")
+}
+
+async fn connect(url: &str) -> Client {
+    let mut request = url.into_client_request().unwrap();
+    request
+        .headers_mut()
+        .insert("x-device-token", "secret".parse().unwrap());
+
+    let (mut client, _) = connect_async(request).await.unwrap();
+
+    assert_eq!(next(&mut client).await.into_text().unwrap(), "\"welcome\"");
+
+    client
+}
+
+async fn next(client: &mut Client) -> tungstenite::Message {
+    tokio::time::timeout(Duration::from_secs(1), client.next())
+        .await
+        .expect("expected a frame")
+        .expect("connection ended")
+        .unwrap()
+}
+
+#[tokio::test]
+async fn text_frames_are_decoded_and_answered() {
+    let app = app();
+    let mut client = connect(&serve(&app).await).await;
+
+    let reading = serde_json::json!({ "device": "sensor-1", "value": 21.5 });
+    client
+        .send(tungstenite::Message::text(reading.to_string()))
+        .await
+        .unwrap();
+
+    let ack: Ack = serde_json::from_str(&next(&mut client).await.into_text().unwrap()).unwrap();
+
+    assert_eq!(
+        ack,
+        Ack {
+            device: "sensor-1".into(),
+            accepted: true
+        }
+    );
+
+    client
+        .send(tungstenite::Message::text("not json"))
+        .await
+        .unwrap();
+
+    let ack: Ack = serde_json::from_str(&next(&mut client).await.into_text().unwrap()).unwrap();
+    assert!(!ack.accepted);
+}
+
+#[tokio::test]
+async fn binary_frames_use_msgpack() {
+    let app = app();
+    let mut client = connect(&serve(&app).await).await;
+
+    let reading = Reading {
+        device: "sensor-2".into(),
+        value: 3.0,
+    };
+
+    client
+        .send(tungstenite::Message::binary(
+            rmp_serde::to_vec_named(&reading).unwrap(),
+        ))
+        .await
+        .unwrap();
+
+    let ack: Ack = rmp_serde::from_slice(&next(&mut client).await.into_data()).unwrap();
+    assert_eq!(ack.device, "sensor-2");
+}
+
+#[tokio::test]
+async fn upgrade_interceptor_rejects_the_handshake() {
+    let app = app();
+    let url = serve(&app).await;
+
+    let error = connect_async(url.as_str()).await.unwrap_err();
+
+    let tungstenite::Error::Http(response) = error else {
+        panic!("expected an HTTP rejection, got {error:?}");
+    };
+
+    assert_eq!(response.status(), 401);
+}
+
+#[tokio::test]
+async fn close_handler_receives_the_client_frame() {
+    let app = app();
+    let mut client = connect(&serve(&app).await).await;
+
+    client
+        .close(Some(tungstenite::protocol::CloseFrame {
+            code: tungstenite::protocol::frame::coding::CloseCode::Normal,
+            reason: "bye".into(),
+        }))
+        .await
+        .unwrap();
+
+    while client.next().await.is_some() {}
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let closes = app.get::<CloseLog>().unwrap();
+    assert_eq!(*closes.codes.lock().await, vec![1000]);
+}
+
+#[tokio::test]
+async fn graceful_shutdown_closes_open_connections() {
+    let app = app();
+    let mut client = connect(&serve(&app).await).await;
+
+    app.get::<GracefulShutdown>().unwrap().trigger();
+
+    let tungstenite::Message::Close(Some(frame)) = next(&mut client).await else {
+        panic!("expected a close frame");
+    };
+
+    assert_eq!(u16::from(frame.code), close_code::AWAY);
+}
+
+#[tokio::test]
+async fn senders_wait_for_room_in_the_outgoing_buffer() {
+    let app = app();
+    let mut client = connect(&serve(&app).await).await;
+
+    client
+        .send(tungstenite::Message::text("flood"))
+        .await
+        .unwrap();
+
+    let mut expected = 0;
+
+    loop {
+        let frame = next(&mut client).await.into_text().unwrap();
+
+        if frame == "\"done\"" {
+            break;
+        }
+
+        assert_eq!(frame.parse::<usize>().unwrap(), expected);
+        expected += 1;
+    }
+
+    // Some frames were queued before the buffer was full.
+    assert!(expected > FLOOD_AFTER_FULL);
+}